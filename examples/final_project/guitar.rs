@@ -12,15 +12,15 @@ lazy_static!(
     static ref GUITAR_V_7_SUS_4: Chord = TUNING.get_chord(&Frets::new_full([0, 0, 2, 0, 0, 0]));
 );
 
-fn groove(note: NotePitch) -> Line {
+fn groove(note: NotePitch, _voice: usize) -> Line {
     electric_guitar(eighth(REST) + eighth(note) + (sixteenth(REST) + sixteenth(note)) * 2)
 }
 
-fn chord_strike(note: NotePitch) -> Line {
+fn chord_strike(note: NotePitch, _voice: usize) -> Line {
     electric_guitar(eighth(REST) + sixteenth(note) * 2)
 }
 
-fn epic_chord_strike(note: NotePitch) -> Line {
+fn epic_chord_strike(note: NotePitch, _voice: usize) -> Line {
     electric_guitar(quarter(note)).into()
 }
 
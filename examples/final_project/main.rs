@@ -6,7 +6,7 @@
 #![cfg_attr(rustfmt, rustfmt_skip)]
 
 use symphoxy::prelude::*;
-use symphoxy::InteractiveTui;
+use symphoxy::{InteractiveTui, Metadata, Section, Song};
 
 // Moved to separate files for better organization,
 // but you could inline them and still maintain a reasonable file size
@@ -26,7 +26,7 @@ fn get_final_song() -> Piece {
         * (double_whole(REST) + drums::drums_verse() * 2).volume(DRUM_VOL)
     };
 
-    let verse_1 = {
+    let verse = {
         (bass::brain_stew(true) * 4).volume(BASS_VOL)
         * (drums::drums_verse() * 7 + drums::drums_prechorus() + half(REST)).volume(DRUM_VOL)
         * (guitar::dings() * 3 + guitar::dings_prechorus()).volume(GUITAR_VOL)
@@ -36,23 +36,17 @@ fn get_final_song() -> Piece {
         ).volume(MELODY_VOL)
     };
 
-    let chorus_1 = {
+    let chorus = {
         bass::brain_stew(false).volume(BASS_VOL)
         * drums::groovy_drums().volume(DRUM_VOL)
         * guitar::groovy_dings_prechorus().volume(GUITAR_VOL)
     };
 
-    let verse_2 = verse_1.clone();
-
-    let chorus_2 = chorus_1.clone();
-
     let bridge = {
         bass::bridge_bass().volume(BASS_VOL)
         * drums::groovy_drums_alt().volume(DRUM_VOL)
         * guitar::groovy_dings().volume(GUITAR_VOL)
-    } * 2;
-
-    let verse_3 = verse_1.clone();
+    };
 
     let ending = {
         (bass::brain_stew(true) * 2).volume(BASS_VOL)
@@ -60,9 +54,27 @@ fn get_final_song() -> Piece {
         * (double_whole(REST) + melody::im_sad_your_back() + melody::tired_of_being_alone()).volume(MELODY_VOL)
     };
 
-    intro + verse_1 + chorus_1 + verse_2 + chorus_2 + bridge + verse_3 + ending
+    Song::new(300)
+        .with_section(Section::new("intro", intro))
+        .with_section(Section::new("verse", verse))
+        .with_section(Section::new("chorus", chorus))
+        .with_section(Section::new("bridge", bridge))
+        .with_section(Section::new("ending", ending))
+        .then("intro")
+        .then("verse")
+        .then("chorus")
+        .then("verse")
+        .then("chorus")
+        .then_repeat("bridge", 2)
+        .then("verse")
+        .then("ending")
+        .to_piece()
+        .expect("Every section is in the song")
 }
 
 fn main() {
-    InteractiveTui::start(get_final_song());
+    InteractiveTui::start_with_metadata(
+        get_final_song(),
+        Metadata::new("Final Song").with_artist("Jack Papel").with_tempo(300),
+    );
 }
\ No newline at end of file
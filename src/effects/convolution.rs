@@ -0,0 +1,339 @@
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    fs::File,
+    io::BufReader,
+    path::Path,
+    sync::Arc,
+};
+
+use rodio::{Decoder, Source};
+
+use crate::{
+    effects::{Effect, EffectProcessor},
+    play::fft::{fft, inverse_fft},
+};
+
+/// A reverb made by convolving the sound with an impulse response - a recording of how a real
+/// room (or hall, or spring reverb) rings after a single click.
+///
+/// This sounds more realistic than [`Reverb`](crate::Reverb), especially on sampled instruments,
+/// but takes longer to render. Impulse responses can be loaded from any audio file which can be
+/// played as a custom timbre. A mono impulse response is applied to every channel, and a stereo
+/// one to the left and right channels. The impulse response is scaled so the reverb is about as
+/// loud as the sound which made it.
+///
+/// # Examples
+/// ```no_run
+/// # #[cfg(feature = "wav-output")] {
+/// use symphoxy::prelude::*;
+/// use symphoxy::MusicPlayer;
+///
+/// let hall = ConvolutionReverb::from_ir_file("impulses/concert_hall.wav").unwrap();
+///
+/// // On one line
+/// let strings_line = strings(whole(C4) + whole(G4)).with_effect(hall.clone());
+///
+/// // Or on the whole mix
+/// let player = MusicPlayer::new_file(300, 1.0, 44100).with_master_effect(hall);
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConvolutionReverb {
+    /// The impulse response, with one buffer of samples per channel
+    impulse_response: Arc<Vec<Vec<f32>>>,
+    /// The sample rate of the impulse response, in Hz
+    sample_rate: u32,
+    /// How much of the reverb is heard, from 0.0 (none) to 1.0 (only the reverb) (default: 0.3)
+    pub mix: f32,
+}
+
+impl ConvolutionReverb {
+    /// Loads an impulse response from an audio file.
+    ///
+    /// # Parameters
+    /// - `path`: The path of the audio file, such as a WAV file
+    ///
+    /// # Errors
+    /// Returns an error if the file can't be opened or decoded, or has no audio in it.
+    pub fn from_ir_file(path: impl AsRef<Path>) -> Result<ConvolutionReverb, ImpulseResponseError> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|error| ImpulseResponseError::Open(error.to_string()))?;
+        let decoder =
+            Decoder::new(BufReader::new(file)).map_err(|error| ImpulseResponseError::Decode(error.to_string()))?;
+
+        let channels = usize::from(decoder.channels().max(1));
+        let sample_rate = decoder.sample_rate();
+        let samples: Vec<f32> = decoder.convert_samples().collect();
+
+        let mut impulse_response =
+            vec![Vec::with_capacity(samples.len().checked_div(channels).unwrap_or_default()); channels];
+        for frame in samples.chunks_exact(channels) {
+            for (channel, &sample) in impulse_response.iter_mut().zip(frame) {
+                channel.push(sample);
+            }
+        }
+
+        let reverb = ConvolutionReverb::from_samples(impulse_response, sample_rate);
+        if reverb
+            .impulse_response
+            .iter()
+            .all(|channel| channel.iter().all(|&sample| sample == 0.0))
+        {
+            return Err(ImpulseResponseError::Empty);
+        }
+        Ok(reverb)
+    }
+
+    /// Creates a convolution reverb from the samples of an impulse response.
+    ///
+    /// # Parameters
+    /// - `impulse_response`: One buffer of samples per channel
+    /// - `sample_rate`: The sample rate of the impulse response, in Hz
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// // A slap-back echo, 50 ms after the sound, at 8 kHz
+    /// let mut impulse = vec![0.0; 401];
+    /// impulse[400] = 1.0;
+    /// let slap = ConvolutionReverb::from_samples(vec![impulse], 8000);
+    /// let melody = piano(quarter(C4) + quarter(E4)).with_effect(slap);
+    /// ```
+    pub fn from_samples(impulse_response: Vec<Vec<f32>>, sample_rate: u32) -> ConvolutionReverb {
+        ConvolutionReverb {
+            impulse_response: Arc::new(impulse_response),
+            sample_rate,
+            mix: 0.3,
+        }
+    }
+}
+
+/// The number of samples convolved at a time. Larger blocks are faster for long impulse responses,
+/// but convolve more of the impulse response directly.
+const BLOCK_SIZE: usize = 512;
+
+impl Effect for ConvolutionReverb {
+    fn processor(&self, sample_rate: u32, channels: usize) -> Box<dyn EffectProcessor> {
+        let impulse_responses: Vec<Vec<f32>> = self
+            .impulse_response
+            .iter()
+            .map(|channel| resample(channel, self.sample_rate, sample_rate))
+            .collect();
+
+        // Scaled so white noise comes out as loud as it went in
+        let energy: f32 = impulse_responses.iter().flatten().map(|sample| sample * sample).sum();
+        #[expect(clippy::cast_precision_loss, reason = "There are only a few channels")]
+        let scale = if energy > 0.0 {
+            (impulse_responses.len() as f32 / energy).sqrt()
+        } else {
+            0.0
+        };
+
+        let convolvers = (0..channels)
+            .map(|channel| {
+                impulse_responses
+                    .get(channel.checked_rem(impulse_responses.len()).unwrap_or_default())
+                    .map_or_else(|| Convolver::new(&[]), |response| Convolver::new(response))
+            })
+            .collect();
+
+        Box::new(ConvolutionProcessor {
+            convolvers,
+            scale,
+            mix: self.mix.clamp(0.0, 1.0),
+        })
+    }
+}
+
+/// Resamples a buffer with linear interpolation.
+#[expect(clippy::cast_precision_loss, reason = "Impulse responses are short")]
+#[expect(clippy::cast_possible_truncation, reason = "Impulse responses are short")]
+#[expect(clippy::cast_sign_loss, reason = "Lengths and positions are positive")]
+fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || from_rate == 0 {
+        return samples.to_vec();
+    }
+    let step = from_rate as f32 / to_rate as f32;
+    let length = (samples.len() as f32 / step).ceil() as usize;
+    (0..length)
+        .map(|index| {
+            let position = index as f32 * step;
+            let before = position.floor() as usize;
+            let fraction = position.fract();
+            let at = |index: usize| samples.get(index).copied().unwrap_or_default();
+            at(before) + (at(before.saturating_add(1)) - at(before)) * fraction
+        })
+        .collect()
+}
+
+struct ConvolutionProcessor {
+    convolvers: Vec<Convolver>,
+    scale: f32,
+    mix: f32,
+}
+
+impl EffectProcessor for ConvolutionProcessor {
+    fn process(&mut self, frame: &mut [f32]) {
+        for (sample, convolver) in frame.iter_mut().zip(&mut self.convolvers) {
+            let wet = convolver.process(*sample) * self.scale;
+            *sample += (wet - *sample) * self.mix;
+        }
+    }
+}
+
+/// Convolves one channel with an impulse response, without any delay.
+///
+/// The first block of the impulse response is convolved directly, one sample at a time. The rest is
+/// split into blocks, which are convolved with whole blocks of the input using the FFT. Since the
+/// rest of the impulse response starts a block late, a block's output from it isn't needed until
+/// the block has been fully read.
+struct Convolver {
+    /// The first block of the impulse response
+    head: Vec<f32>,
+    /// The spectrum of each later block of the impulse response, as real and imaginary parts
+    partitions: Vec<(Vec<f32>, Vec<f32>)>,
+    /// The last block of input, as a ring buffer, for convolving with the head
+    history: Vec<f32>,
+    /// The spectra of the most recent blocks of input, newest first
+    input_spectra: Vec<(Vec<f32>, Vec<f32>)>,
+    /// The input read so far in this block
+    block: Vec<f32>,
+    /// The output of the later blocks of the impulse response for the current block
+    tail_output: Vec<f32>,
+    /// The output of the later blocks of the impulse response which spills into the next block
+    overlap: Vec<f32>,
+}
+
+#[expect(clippy::arithmetic_side_effects, reason = "Signal processing, with checked bounds")]
+impl Convolver {
+    fn new(impulse_response: &[f32]) -> Self {
+        let fft_size = 2 * BLOCK_SIZE;
+        let head = impulse_response.iter().take(BLOCK_SIZE).copied().collect();
+        let partitions: Vec<_> = impulse_response
+            .chunks(BLOCK_SIZE)
+            .skip(1)
+            .map(|chunk| {
+                let mut real = chunk.to_vec();
+                real.resize(fft_size, 0.0);
+                let mut imaginary = vec![0.0; fft_size];
+                fft(&mut real, &mut imaginary);
+                (real, imaginary)
+            })
+            .collect();
+
+        Convolver {
+            head,
+            input_spectra: vec![(vec![0.0; fft_size], vec![0.0; fft_size]); partitions.len()],
+            partitions,
+            history: vec![0.0; BLOCK_SIZE],
+            block: Vec::with_capacity(BLOCK_SIZE),
+            tail_output: vec![0.0; BLOCK_SIZE],
+            overlap: vec![0.0; BLOCK_SIZE],
+        }
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        let position = self.block.len();
+        self.history[position] = input;
+        self.block.push(input);
+
+        // The history holds the current block up to this sample, then the end of the last block
+        let head: f32 = self
+            .head
+            .iter()
+            .enumerate()
+            .map(|(delay, tap)| tap * self.history[(position + BLOCK_SIZE - delay) % BLOCK_SIZE])
+            .sum();
+        let output = head + self.tail_output[position];
+
+        if self.block.len() == BLOCK_SIZE {
+            self.finish_block();
+        }
+        output
+    }
+
+    /// Convolves the block just read with the later blocks of the impulse response, giving the
+    /// output for the next block.
+    fn finish_block(&mut self) {
+        let fft_size = 2 * BLOCK_SIZE;
+        if self.partitions.is_empty() {
+            self.block.clear();
+            return;
+        }
+
+        let mut real = std::mem::take(&mut self.block);
+        real.resize(fft_size, 0.0);
+        let mut imaginary = vec![0.0; fft_size];
+        fft(&mut real, &mut imaginary);
+        self.input_spectra.rotate_right(1);
+        self.input_spectra[0] = (real, imaginary);
+
+        // The block read `index` blocks ago meets the impulse response `index + 1` blocks in
+        let mut sum_real = vec![0.0; fft_size];
+        let mut sum_imaginary = vec![0.0; fft_size];
+        for ((input_real, input_imaginary), (partition_real, partition_imaginary)) in
+            self.input_spectra.iter().zip(&self.partitions)
+        {
+            for bin in 0..fft_size {
+                sum_real[bin] +=
+                    input_real[bin] * partition_real[bin] - input_imaginary[bin] * partition_imaginary[bin];
+                sum_imaginary[bin] +=
+                    input_real[bin] * partition_imaginary[bin] + input_imaginary[bin] * partition_real[bin];
+            }
+        }
+        inverse_fft(&mut sum_real, &mut sum_imaginary);
+
+        for (index, output) in self.tail_output.iter_mut().enumerate() {
+            *output = sum_real[index] + self.overlap[index];
+        }
+        self.overlap.copy_from_slice(&sum_real[BLOCK_SIZE..]);
+        self.block = Vec::with_capacity(BLOCK_SIZE);
+    }
+}
+
+/// The error returned when an impulse response can't be loaded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ImpulseResponseError {
+    /// The file couldn't be opened, for this reason
+    Open(String),
+    /// The file couldn't be decoded as audio, for this reason
+    Decode(String),
+    /// The file has no sound in it
+    Empty,
+}
+
+impl Display for ImpulseResponseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            ImpulseResponseError::Open(reason) => write!(f, "couldn't open the impulse response: {reason}"),
+            ImpulseResponseError::Decode(reason) => write!(f, "couldn't decode the impulse response: {reason}"),
+            ImpulseResponseError::Empty => write!(f, "the impulse response is silent"),
+        }
+    }
+}
+
+impl std::error::Error for ImpulseResponseError {}
+
+#[test]
+#[expect(clippy::cast_precision_loss, reason = "Test signals are small")]
+fn test_convolution_matches_direct_convolution() {
+    // Long enough to use several blocks of the impulse response
+    let impulse_response: Vec<f32> = (0..1500)
+        .map(|index| ((index * 7919) % 13) as f32 / 13.0 - 0.5)
+        .collect();
+    let input: Vec<f32> = (0..3000)
+        .map(|index| ((index * 104_729) % 17) as f32 / 17.0 - 0.5)
+        .collect();
+
+    let mut convolver = Convolver::new(&impulse_response);
+    for (index, &sample) in input.iter().enumerate() {
+        let expected: f32 = impulse_response
+            .iter()
+            .enumerate()
+            .filter(|&(delay, _)| delay <= index)
+            .map(|(delay, tap)| tap * input[index - delay])
+            .sum();
+        assert!((convolver.process(sample) - expected).abs() < 1e-3, "sample {index}");
+    }
+}
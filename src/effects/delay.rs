@@ -0,0 +1,74 @@
+use std::time::Duration;
+
+use crate::effects::{samples_in, Effect, EffectProcessor};
+
+/// An echo effect, which repeats the sound after a delay, each repeat quieter than the last.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use std::time::Duration;
+///
+/// // A dotted eighth note echo at 120 BPM, which fades out over a few repeats
+/// let echo = Delay {
+///     time: Duration::from_millis(375),
+///     feedback: 0.4,
+///     mix: 0.35,
+/// };
+/// let melody = piano(quarter(C4) + quarter(G4)).with_effect(echo);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Delay {
+    /// How long after the sound each echo is heard (default: 300 ms)
+    pub time: Duration,
+    /// How loud each echo is compared to the one before, from 0.0 to just under 1.0 (default: 0.35)
+    pub feedback: f32,
+    /// How loud the echoes are compared to the original sound, from 0.0 to 1.0 (default: 0.3)
+    pub mix: f32,
+}
+
+impl Default for Delay {
+    fn default() -> Self {
+        Delay {
+            time: Duration::from_millis(300),
+            feedback: 0.35,
+            mix: 0.3,
+        }
+    }
+}
+
+impl Effect for Delay {
+    fn processor(&self, sample_rate: u32, channels: usize) -> Box<dyn EffectProcessor> {
+        let length = samples_in(self.time.as_secs_f32(), sample_rate);
+        Box::new(DelayProcessor {
+            buffers: vec![vec![0.0; length]; channels],
+            position: 0,
+            feedback: self.feedback.clamp(0.0, 0.99),
+            mix: self.mix.clamp(0.0, 1.0),
+        })
+    }
+}
+
+struct DelayProcessor {
+    /// The last `time` of audio for each channel, as a ring buffer
+    buffers: Vec<Vec<f32>>,
+    position: usize,
+    feedback: f32,
+    mix: f32,
+}
+
+impl EffectProcessor for DelayProcessor {
+    fn process(&mut self, frame: &mut [f32]) {
+        for (sample, buffer) in frame.iter_mut().zip(&mut self.buffers) {
+            let Some(delayed) = buffer.get_mut(self.position) else {
+                continue;
+            };
+            let echo = *delayed;
+            *delayed = *sample + echo * self.feedback;
+            *sample += echo * self.mix;
+        }
+
+        let length = self.buffers.first().map_or(1, Vec::len);
+        self.position = self.position.saturating_add(1).checked_rem(length).unwrap_or_default();
+    }
+}
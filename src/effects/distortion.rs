@@ -0,0 +1,44 @@
+use crate::effects::{Effect, EffectProcessor};
+
+/// A distortion effect, which overdrives the sound into soft clipping, adding grit and harmonics.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let crunchy = electric_guitar(half(E2) + half(A2)).with_effect(Distortion { drive: 8.0, mix: 1.0 });
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Distortion {
+    /// How hard the sound is driven into clipping. 1.0 barely changes it, and higher values
+    /// are harsher (default: 4.0)
+    pub drive: f32,
+    /// How much of the distorted sound is heard, from 0.0 (none) to 1.0 (only the distorted sound)
+    /// (default: 1.0)
+    pub mix: f32,
+}
+
+impl Default for Distortion {
+    fn default() -> Self {
+        Distortion { drive: 4.0, mix: 1.0 }
+    }
+}
+
+impl Effect for Distortion {
+    fn processor(&self, _sample_rate: u32, _channels: usize) -> Box<dyn EffectProcessor> {
+        Box::new(*self)
+    }
+}
+
+impl EffectProcessor for Distortion {
+    fn process(&mut self, frame: &mut [f32]) {
+        let drive = self.drive.max(1.0);
+        let mix = self.mix.clamp(0.0, 1.0);
+        // Scaled so full scale stays at full scale, however hard it's driven
+        let scale = drive.tanh().recip();
+        for sample in frame {
+            let distorted = (*sample * drive).tanh() * scale;
+            *sample += (distorted - *sample) * mix;
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use std::f32::consts::{PI, SQRT_2};
+
+use crate::effects::{Effect, EffectProcessor};
+
+/// A three band equalizer, which turns the lows, mids, and highs of the sound up or down.
+///
+/// The lows are shelved below 250 Hz, the mids are centred on 1 kHz, and the highs are shelved
+/// above 4 kHz. Gains are in decibels, so 0.0 leaves a band unchanged, 6.0 about doubles it, and
+/// -6.0 about halves it.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// // Warm up a harsh sound
+/// let warmer = Equalizer {
+///     low_db: 3.0,
+///     mid_db: 0.0,
+///     high_db: -6.0,
+/// };
+/// let melody = electric_guitar(quarter(E4) + quarter(G4)).with_effect(warmer);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Equalizer {
+    /// How much the lows are turned up, in decibels (default: 0.0)
+    pub low_db: f32,
+    /// How much the mids are turned up, in decibels (default: 0.0)
+    pub mid_db: f32,
+    /// How much the highs are turned up, in decibels (default: 0.0)
+    pub high_db: f32,
+}
+
+/// The frequency the low shelf starts at, in Hz.
+const LOW_HZ: f32 = 250.0;
+
+/// The centre frequency of the mid band, in Hz.
+const MID_HZ: f32 = 1000.0;
+
+/// The frequency the high shelf starts at, in Hz.
+const HIGH_HZ: f32 = 4000.0;
+
+impl Effect for Equalizer {
+    #[expect(clippy::cast_precision_loss, reason = "Sample rates are exactly representable")]
+    fn processor(&self, sample_rate: u32, channels: usize) -> Box<dyn EffectProcessor> {
+        let sample_rate = sample_rate as f32;
+        let bands = [
+            (BandKind::LowShelf, LOW_HZ, self.low_db),
+            (BandKind::Peak, MID_HZ, self.mid_db),
+            (BandKind::HighShelf, HIGH_HZ, self.high_db),
+        ];
+
+        Box::new(EqualizerProcessor {
+            filters: bands
+                .into_iter()
+                // Bands which don't change the sound are skipped
+                .filter(|(_, _, gain_db)| *gain_db != 0.0)
+                .map(|(kind, frequency, gain_db)| Biquad::new(kind, frequency, gain_db, sample_rate, channels))
+                .collect(),
+        })
+    }
+}
+
+struct EqualizerProcessor {
+    filters: Vec<Biquad>,
+}
+
+impl EffectProcessor for EqualizerProcessor {
+    fn process(&mut self, frame: &mut [f32]) {
+        for filter in &mut self.filters {
+            filter.process(frame);
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum BandKind {
+    LowShelf,
+    Peak,
+    HighShelf,
+}
+
+/// A second order filter, with the coefficients from Robert Bristow-Johnson's Audio EQ Cookbook.
+struct Biquad {
+    /// The feedforward coefficients, divided by a0
+    b: [f32; 3],
+    /// The feedback coefficients a1 and a2, divided by a0
+    a: [f32; 2],
+    /// The last two inputs and outputs of each channel
+    history: Vec<[f32; 4]>,
+}
+
+impl Biquad {
+    fn new(kind: BandKind, frequency: f32, gain_db: f32, sample_rate: f32, channels: usize) -> Self {
+        // Bands can't be above the highest frequency the sample rate can hold
+        let frequency = frequency.min(sample_rate * 0.45);
+        let amplitude = 10.0_f32.powf(gain_db / 40.0);
+        let omega = 2.0 * PI * frequency / sample_rate;
+        let (sin, cos) = omega.sin_cos();
+
+        let (b, a) = match kind {
+            BandKind::Peak => {
+                // A Q of 0.7 makes the band about two octaves wide
+                let alpha = sin / (2.0 * 0.7);
+                (
+                    [1.0 + alpha * amplitude, -2.0 * cos, 1.0 - alpha * amplitude],
+                    [1.0 + alpha / amplitude, -2.0 * cos, 1.0 - alpha / amplitude],
+                )
+            }
+            BandKind::LowShelf | BandKind::HighShelf => {
+                // The steepest shelf which doesn't overshoot
+                let shelf = amplitude.sqrt() * sin * SQRT_2;
+                let (up, down) = (amplitude + 1.0, amplitude - 1.0);
+                // A high shelf is a low shelf with the sign of cos flipped
+                let sign = if matches!(kind, BandKind::LowShelf) { 1.0 } else { -1.0 };
+                let cos = cos * sign;
+                (
+                    [
+                        amplitude * (up - down * cos + shelf),
+                        2.0 * sign * amplitude * (down - up * cos),
+                        amplitude * (up - down * cos - shelf),
+                    ],
+                    [
+                        up + down * cos + shelf,
+                        -2.0 * sign * (down + up * cos),
+                        up + down * cos - shelf,
+                    ],
+                )
+            }
+        };
+
+        let [a0, a1, a2] = a;
+        Biquad {
+            b: b.map(|coefficient| coefficient / a0),
+            a: [a1 / a0, a2 / a0],
+            history: vec![[0.0; 4]; channels],
+        }
+    }
+
+    fn process(&mut self, frame: &mut [f32]) {
+        let ([b0, b1, b2], [a1, a2]) = (self.b, self.a);
+        for (sample, history) in frame.iter_mut().zip(&mut self.history) {
+            let [x1, x2, y1, y2] = *history;
+            let output = b0 * *sample + b1 * x1 + b2 * x2 - a1 * y1 - a2 * y2;
+            *history = [*sample, x1, output, y1];
+            *sample = output;
+        }
+    }
+}
+
+#[test]
+#[expect(clippy::cast_precision_loss, reason = "Sample indices are small")]
+fn test_equalizer_shelves_lows_and_highs() {
+    // The level of a sine wave after it's been through an equalizer
+    let level_after = |equalizer: Equalizer, frequency: f32| {
+        let mut processor = equalizer.processor(48_000, 1);
+        (0..48_000)
+            .map(|index| {
+                let mut frame = [(2.0 * PI * frequency * index as f32 / 48_000.0).sin()];
+                processor.process(&mut frame);
+                frame[0].abs()
+            })
+            .skip(24_000)
+            .fold(0.0_f32, f32::max)
+    };
+
+    let cut_highs = Equalizer {
+        high_db: -12.0,
+        ..Equalizer::default()
+    };
+    assert!((level_after(cut_highs, 50.0) - 1.0).abs() < 0.05);
+    assert!(level_after(cut_highs, 15_000.0) < 0.3);
+
+    let boost_lows = Equalizer {
+        low_db: 6.0,
+        ..Equalizer::default()
+    };
+    assert!(level_after(boost_lows, 50.0) > 1.8);
+    assert!((level_after(boost_lows, 15_000.0) - 1.0).abs() < 0.05);
+}
@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+use crate::effects::{samples_in, Effect, EffectProcessor};
+
+/// A limiter, which turns the sound down just enough to keep its peaks under a ceiling.
+///
+/// The limiter reacts instantly to peaks, so nothing gets past the ceiling, then turns the sound
+/// back up smoothly. Every channel is turned down together, so panning is kept. It's most useful
+/// at the end of the master effects chain, to keep louder effects from clipping.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use std::time::Duration;
+///
+/// // Keep peaks 1 dB under full scale
+/// let limiter = Limiter {
+///     ceiling: 0.89,
+///     release: Duration::from_millis(50),
+/// };
+/// let drum_line = drums(quarter(C4) + quarter(C4)).with_effect(limiter);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Limiter {
+    /// The loudest a sample can be, as a linear amplitude where 1.0 is full scale (default: 0.95)
+    pub ceiling: f32,
+    /// How long the limiter takes to recover after a peak (default: 100 ms)
+    pub release: Duration,
+}
+
+impl Default for Limiter {
+    fn default() -> Self {
+        Limiter {
+            ceiling: 0.95,
+            release: Duration::from_millis(100),
+        }
+    }
+}
+
+impl Effect for Limiter {
+    #[expect(clippy::cast_precision_loss, reason = "Release times are short")]
+    fn processor(&self, sample_rate: u32, _channels: usize) -> Box<dyn EffectProcessor> {
+        let release_samples = samples_in(self.release.as_secs_f32(), sample_rate) as f32;
+        Box::new(LimiterProcessor {
+            ceiling: self.ceiling.max(0.0),
+            release_coefficient: (-1.0 / release_samples).exp(),
+            gain: 1.0,
+        })
+    }
+}
+
+struct LimiterProcessor {
+    ceiling: f32,
+    release_coefficient: f32,
+    /// How much the sound is being turned down
+    gain: f32,
+}
+
+impl EffectProcessor for LimiterProcessor {
+    fn process(&mut self, frame: &mut [f32]) {
+        let peak = frame.iter().fold(0.0_f32, |peak, sample| peak.max(sample.abs()));
+        let target = if peak > self.ceiling { self.ceiling / peak } else { 1.0 };
+        self.gain = if target < self.gain {
+            target
+        } else {
+            target + (self.gain - target) * self.release_coefficient
+        };
+        for sample in frame {
+            *sample *= self.gain;
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+#[cfg(any(feature = "wav-output", feature = "live-output"))]
+use std::sync::Arc;
+
+#[cfg(any(feature = "wav-output", feature = "live-output"))]
+mod convolution;
+mod delay;
+mod distortion;
+mod equalizer;
+mod limiter;
+mod reverb;
+
+#[cfg(any(feature = "wav-output", feature = "live-output"))]
+pub use convolution::*;
+pub use delay::*;
+pub use distortion::*;
+pub use equalizer::*;
+pub use limiter::*;
+pub use reverb::*;
+
+/// An audio effect, such as reverb or distortion, which processes the sound of a whole line.
+///
+/// Effects are attached to lines with [`Line::with_effects`](crate::Line::with_effects), and are
+/// applied when rendering to a WAV file and when playing live. Each line's effects process the
+/// mix of just that line's notes, in order, before it's mixed with the other lines.
+///
+/// An effect is a description of the sound, which can be shared between lines. It creates an
+/// [`EffectProcessor`] for each stream of audio it's applied to, which holds any state the effect
+/// needs, such as the echoes of a delay.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// /// Flips the left and right channels
+/// struct SwapChannels;
+///
+/// struct SwapChannelsProcessor;
+///
+/// impl EffectProcessor for SwapChannelsProcessor {
+///     fn process(&mut self, frame: &mut [f32]) {
+///         frame.reverse();
+///     }
+/// }
+///
+/// impl Effect for SwapChannels {
+///     fn processor(&self, _sample_rate: u32, _channels: usize) -> Box<dyn EffectProcessor> {
+///         Box::new(SwapChannelsProcessor)
+///     }
+/// }
+///
+/// let melody = piano(quarter(C4) + quarter(E4)).pan(-1.0).with_effect(SwapChannels);
+/// ```
+pub trait Effect: Send + Sync {
+    /// Creates a processor which applies the effect to one stream of audio.
+    ///
+    /// # Parameters
+    /// - `sample_rate`: The sample rate of the audio, in Hz
+    /// - `channels`: The number of channels in each frame of the audio
+    fn processor(&self, sample_rate: u32, channels: usize) -> Box<dyn EffectProcessor>;
+}
+
+/// Applies an [`Effect`] to a stream of audio, one frame at a time.
+pub trait EffectProcessor: Send {
+    /// Processes one frame of audio in place. The frame holds one sample per channel.
+    fn process(&mut self, frame: &mut [f32]);
+}
+
+impl Debug for dyn Effect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str("Effect")
+    }
+}
+
+/// Effects are equal only if they're the same shared effect.
+impl PartialEq for dyn Effect {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(
+            (self as *const dyn Effect).cast::<()>(),
+            (other as *const dyn Effect).cast::<()>(),
+        )
+    }
+}
+
+/// Applies a chain of effects, in order, to audio with one buffer of samples per channel.
+#[cfg(feature = "wav-output")]
+pub(crate) fn apply_effects(effects: &[Arc<dyn Effect>], channels: &mut [Vec<f32>], sample_rate: u32) {
+    let mut processors = processors(effects, sample_rate, channels.len());
+    let length = channels.first().map_or(0, Vec::len);
+    let mut frame = vec![0.0; channels.len()];
+    for index in 0..length {
+        for (sample, channel) in frame.iter_mut().zip(channels.iter()) {
+            *sample = channel[index];
+        }
+        for processor in &mut processors {
+            processor.process(&mut frame);
+        }
+        for (sample, channel) in frame.iter().zip(channels.iter_mut()) {
+            channel[index] = *sample;
+        }
+    }
+}
+
+/// Creates a processor for each effect in a chain.
+#[cfg(any(feature = "wav-output", feature = "live-output"))]
+pub(crate) fn processors(
+    effects: &[Arc<dyn Effect>],
+    sample_rate: u32,
+    channels: usize,
+) -> Vec<Box<dyn EffectProcessor>> {
+    effects
+        .iter()
+        .map(|effect| effect.processor(sample_rate, channels))
+        .collect()
+}
+
+/// Returns how many samples at a sample rate last for a number of seconds, with at least one.
+#[expect(clippy::cast_possible_truncation, reason = "Effect times are short")]
+#[expect(clippy::cast_sign_loss, reason = "The length is clamped to be positive")]
+#[expect(clippy::cast_precision_loss, reason = "Sample rates are exactly representable")]
+fn samples_in(secs: f32, sample_rate: u32) -> usize {
+    (secs * sample_rate as f32).round().max(1.0) as usize
+}
@@ -0,0 +1,175 @@
+use crate::effects::{samples_in, Effect, EffectProcessor};
+
+/// A reverb effect, which places the sound in a room by adding a wash of reflections after it.
+///
+/// This is a Schroeder-Moorer reverb, like the well-known Freeverb: parallel comb filters
+/// make the dense tail of reflections, and all-pass filters diffuse it. Each channel's
+/// reflections are spaced slightly differently, so stereo lines sound wide.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// // A large, bright hall
+/// let hall = Reverb {
+///     room_size: 0.9,
+///     damping: 0.2,
+///     mix: 0.4,
+/// };
+/// let melody = piano(quarter(C4) + quarter(E4) + half(G4)).with_effect(hall);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Reverb {
+    /// How large the room sounds, from 0.0 (a small room) to 1.0 (a huge hall) (default: 0.6)
+    pub room_size: f32,
+    /// How quickly high frequencies die away in the tail, from 0.0 (bright) to 1.0 (dark) (default: 0.4)
+    pub damping: f32,
+    /// How much of the reverb is heard, from 0.0 (none) to 1.0 (only the reverb) (default: 0.3)
+    pub mix: f32,
+}
+
+impl Default for Reverb {
+    fn default() -> Self {
+        Reverb {
+            room_size: 0.6,
+            damping: 0.4,
+            mix: 0.3,
+        }
+    }
+}
+
+/// Lengths of Freeverb's comb filters, in seconds.
+const COMB_SECS: [f32; 4] = [0.025_306, 0.026_939, 0.028_957, 0.030_748];
+
+/// Lengths of Freeverb's all-pass filters, in seconds.
+const ALL_PASS_SECS: [f32; 2] = [0.012_608, 0.01];
+
+/// How much longer each channel's filters are than the last channel's, in seconds.
+const STEREO_SPREAD_SECS: f32 = 0.000_522;
+
+impl Effect for Reverb {
+    fn processor(&self, sample_rate: u32, channels: usize) -> Box<dyn EffectProcessor> {
+        let room_size = self.room_size.clamp(0.0, 1.0);
+        let damping = self.damping.clamp(0.0, 1.0);
+
+        let channels = (0..channels)
+            .map(|channel| {
+                #[expect(clippy::cast_precision_loss, reason = "There are only a few channels")]
+                let spread = STEREO_SPREAD_SECS * channel as f32;
+                ReverbChannel {
+                    combs: COMB_SECS
+                        .iter()
+                        .map(|secs| Comb {
+                            buffer: vec![0.0; samples_in(secs + spread, sample_rate)],
+                            position: 0,
+                            filtered: 0.0,
+                            feedback: 0.7 + 0.28 * room_size,
+                            damping,
+                        })
+                        .collect(),
+                    all_passes: ALL_PASS_SECS
+                        .iter()
+                        .map(|secs| AllPass {
+                            buffer: vec![0.0; samples_in(secs + spread, sample_rate)],
+                            position: 0,
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        Box::new(ReverbProcessor {
+            channels,
+            mix: self.mix.clamp(0.0, 1.0),
+        })
+    }
+}
+
+struct ReverbProcessor {
+    channels: Vec<ReverbChannel>,
+    mix: f32,
+}
+
+struct ReverbChannel {
+    combs: Vec<Comb>,
+    all_passes: Vec<AllPass>,
+}
+
+/// A feedback comb filter, with a low-pass filter in its feedback which darkens the echoes.
+struct Comb {
+    buffer: Vec<f32>,
+    position: usize,
+    filtered: f32,
+    feedback: f32,
+    damping: f32,
+}
+
+impl Comb {
+    fn process(&mut self, input: f32) -> f32 {
+        let Some(delayed) = self.buffer.get_mut(self.position) else {
+            return 0.0;
+        };
+        let output = *delayed;
+        self.filtered = output + (self.filtered - output) * self.damping;
+        *delayed = input + self.filtered * self.feedback;
+        self.position = self
+            .position
+            .saturating_add(1)
+            .checked_rem(self.buffer.len())
+            .unwrap_or_default();
+        output
+    }
+}
+
+/// An all-pass filter, which smears the echoes in time without changing their tone.
+struct AllPass {
+    buffer: Vec<f32>,
+    position: usize,
+}
+
+impl AllPass {
+    fn process(&mut self, input: f32) -> f32 {
+        let Some(delayed) = self.buffer.get_mut(self.position) else {
+            return input;
+        };
+        let buffered = *delayed;
+        *delayed = input + buffered * 0.5;
+        self.position = self
+            .position
+            .saturating_add(1)
+            .checked_rem(self.buffer.len())
+            .unwrap_or_default();
+        buffered - input
+    }
+}
+
+impl EffectProcessor for ReverbProcessor {
+    fn process(&mut self, frame: &mut [f32]) {
+        for (sample, channel) in frame.iter_mut().zip(&mut self.channels) {
+            // Scaled so the tail is about as loud as the sound which made it
+            let input = *sample * 0.06;
+            let mut wet: f32 = channel.combs.iter_mut().map(|comb| comb.process(input)).sum();
+            for all_pass in &mut channel.all_passes {
+                wet = all_pass.process(wet);
+            }
+            *sample += (wet - *sample) * self.mix;
+        }
+    }
+}
+
+#[test]
+fn test_reverb_rings_after_input_stops() {
+    let mut processor = Reverb::default().processor(8000, 2);
+    let mut tail = vec![];
+    for index in 0..8000 {
+        let mut frame = [if index < 800 { 0.5 } else { 0.0 }; 2];
+        processor.process(&mut frame);
+        tail.push(frame[0]);
+    }
+
+    let peak = |samples: &[f32]| samples.iter().fold(0.0_f32, |peak, s| peak.max(s.abs()));
+    // Still ringing just after the input stops, and decaying away after that
+    assert!(peak(&tail[800..1600]) > 0.01);
+    assert!(peak(&tail[7200..]) < peak(&tail[800..1600]));
+    assert!(peak(&tail) < 1.0);
+}
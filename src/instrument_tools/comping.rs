@@ -0,0 +1,206 @@
+use std::ops::Add;
+
+use crate::{
+    note::{chord::Chord, progression::Progression},
+    Line, Note, NoteKind, NoteLength, NotePitch, Piece, Tet12, REST, TICKS_PER_SIXTEENTH,
+};
+
+/// The length of one cycle of a comping pattern, in ticks (one bar of 4/4).
+const PATTERN_LENGTH: u16 = 16 * TICKS_PER_SIXTEENTH;
+
+/// A rhythm-section comping pattern, used to turn a [`Progression`] into an accompaniment.
+///
+/// Each pattern is a one-bar rhythm which repeats for as long as each chord lasts,
+/// and is cut short when the chord changes. The resulting piece has a bass line (an octave below
+/// the root of each chord, even when the chord is inverted) as its first line, followed by one
+/// line per chord tone.
+///
+/// No timbre is applied, so wrap the result in a timbre function like `piano()`.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let scale = MajorScale(C4);
+/// let progression = Progression::new([
+///     (Chord::from_degrees(&scale, &[1, 3, 5]), NoteLength::new(16)),
+///     (Chord::from_degrees(&scale, &[5, 7, 9]), NoteLength::new(16)),
+/// ]);
+///
+/// let backing_track = piano(progression.comp(CompingPattern::PopPiano));
+/// assert_eq!(backing_track.length(), progression.length());
+/// assert_eq!(backing_track.0.len(), 4); // Bass line + three chord tones
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CompingPattern {
+    /// Pop piano - steady eighth-note block chords over a half-note root in the bass.
+    PopPiano,
+    /// Reggae skank - short chord stabs on beats 2 and 4, with the bass dropping on beat 3.
+    ReggaeSkank,
+    /// Bossa nova - syncopated chord hits over a bass alternating between the root and the fifth.
+    Bossa,
+    /// Four-to-the-floor pads - chords held for their full length over a root pulsing on every beat.
+    FourOnTheFloorPads,
+}
+
+/// Which chord tone a bass hit plays.
+#[derive(Clone, Copy)]
+enum BassTone {
+    Root,
+    Fifth,
+}
+
+/// A single hit within a pattern, as an offset and length in sixteenth notes.
+type Hit = (u16, u16);
+
+impl CompingPattern {
+    /// The hits of the chord voices, or `None` if the chord is held for its full length.
+    fn chord_hits(self) -> Option<&'static [Hit]> {
+        match self {
+            CompingPattern::PopPiano => Some(&[(0, 2), (2, 2), (4, 2), (6, 2), (8, 2), (10, 2), (12, 2), (14, 2)]),
+            CompingPattern::ReggaeSkank => Some(&[(4, 1), (12, 1)]),
+            CompingPattern::Bossa => Some(&[(0, 2), (3, 2), (6, 2), (10, 2), (12, 2)]),
+            CompingPattern::FourOnTheFloorPads => None,
+        }
+    }
+
+    fn bass_hits(self) -> &'static [(Hit, BassTone)] {
+        match self {
+            CompingPattern::PopPiano => &[((0, 8), BassTone::Root), ((8, 8), BassTone::Root)],
+            CompingPattern::ReggaeSkank => &[((8, 4), BassTone::Root)],
+            CompingPattern::Bossa => &[
+                ((0, 6), BassTone::Root),
+                ((6, 2), BassTone::Root),
+                ((8, 6), BassTone::Fifth),
+                ((14, 2), BassTone::Fifth),
+            ],
+            CompingPattern::FourOnTheFloorPads => &[
+                ((0, 4), BassTone::Root),
+                ((4, 4), BassTone::Root),
+                ((8, 4), BassTone::Root),
+                ((12, 4), BassTone::Root),
+            ],
+        }
+    }
+
+    /// Comps a single chord for the given length.
+    fn comp_chord(self, chord: &Chord, length: NoteLength) -> Piece {
+        let root = chord.bass_root();
+
+        let bass_hits = self.bass_hits();
+        let bass_line = match root {
+            Some(root) => hits_to_line(bass_hits.iter().map(|&(hit, _)| hit), length.0, |index| {
+                let pitch = match bass_hits[index].1 {
+                    BassTone::Root => root,
+                    BassTone::Fifth => root.semitone(7),
+                };
+                pitch.octave(-1).into()
+            }),
+            None => Line::new().extend(usize::from(length.0)),
+        };
+
+        let voices = chord.0.iter().map(|&pitch: &NotePitch| match self.chord_hits() {
+            Some(hits) => hits_to_line(hits.iter().copied(), length.0, |_| pitch.into()),
+            None => Line::from(Note(length, pitch.into())),
+        });
+
+        Piece([vec![bass_line], voices.collect()].concat())
+    }
+}
+
+/// Lays out a repeating one-bar rhythm over `length` ticks, filling the gaps with rests.
+#[expect(
+    clippy::arithmetic_side_effects,
+    reason = "Times are u32s, which can't overflow while they're at most a u16 length plus a bar"
+)]
+fn hits_to_line(hits: impl Iterator<Item = Hit> + Clone, length: u16, mut kind: impl FnMut(usize) -> NoteKind) -> Line {
+    // Every note pushed ends by `length`, so its length fits in a u16
+    let note_length = |ticks: u32| NoteLength(u16::try_from(ticks).unwrap_or(u16::MAX));
+    let (length, ticks_per_sixteenth) = (u32::from(length), u32::from(TICKS_PER_SIXTEENTH));
+    let mut notes = vec![];
+    let mut time = 0;
+
+    for bar_start in (0..length).step_by(PATTERN_LENGTH as usize) {
+        for (index, (offset, hit_length)) in hits.clone().enumerate() {
+            let (offset, hit_length) = (
+                u32::from(offset) * ticks_per_sixteenth,
+                u32::from(hit_length) * ticks_per_sixteenth,
+            );
+            let start = bar_start + offset;
+            if start >= length {
+                break;
+            }
+            if start > time {
+                notes.push(Note(note_length(start - time), REST));
+            }
+            let hit_length = hit_length.min(length - start);
+            notes.push(Note(note_length(hit_length), kind(index)));
+            time = start + hit_length;
+        }
+    }
+
+    if time < length {
+        notes.push(Note(note_length(length - time), REST));
+    }
+
+    Line::from(notes)
+}
+
+impl Progression {
+    /// Turns this progression into an accompaniment using a comping pattern.
+    ///
+    /// See [`CompingPattern`] for details on the structure of the returned piece.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let [c4, f4, g4] = MajorScale(C4).get_degrees([1, 4, 5]);
+    /// let progression = Progression::new([
+    ///     (c4.with_chord_shape(&Chord::shape_from_semitone_offsets([4, 7])), NoteLength::new(16)),
+    ///     (f4.with_chord_shape(&Chord::shape_from_semitone_offsets([4, 7])), NoteLength::new(8)),
+    ///     (g4.with_chord_shape(&Chord::shape_from_semitone_offsets([4, 7])), NoteLength::new(8)),
+    /// ]);
+    ///
+    /// let skank = electric_guitar(progression.comp(CompingPattern::ReggaeSkank));
+    /// let bossa = piano(progression.comp(CompingPattern::Bossa));
+    /// assert_eq!(skank.length(), bossa.length());
+    /// ```
+    pub fn comp(&self, pattern: CompingPattern) -> Piece {
+        self.0
+            .iter()
+            .map(|(chord, length)| pattern.comp_chord(chord, *length))
+            .reduce(Add::add)
+            .unwrap_or_default()
+    }
+}
+
+#[test]
+fn test_comping_plays_the_root_of_inverted_chords() {
+    use crate::prelude::*;
+
+    let c_major = Chord::from_degrees(&MajorScale(C4), &[1, 3, 5]);
+    let progression = Progression::new([(c_major.invert(1), NoteLength::new(16))]);
+    let bossa = progression.comp(CompingPattern::Bossa);
+
+    let bass: Vec<_> = bossa.0[0]
+        .notes
+        .iter()
+        .filter_map(|note| match note.1 {
+            NoteKind::Pitched { pitch, .. } => Some(crate::scales::tet12::get_note_name_with_octave(pitch, A4)),
+            NoteKind::Rest => None,
+        })
+        .collect();
+    assert_eq!(bass, ["C3", "C3", "G3", "G3"]);
+}
+
+#[test]
+fn test_comping_chords_longer_than_the_last_whole_bar() {
+    use crate::prelude::*;
+
+    let c_major = Chord::from_degrees(&MajorScale(C4), &[1, 3, 5]);
+    let length = NoteLength::from_ticks(u16::MAX);
+    let pop = Progression::new([(c_major, length)]).comp(CompingPattern::PopPiano);
+
+    assert_eq!(pop.length(), usize::from(u16::MAX));
+}
@@ -1,5 +1,17 @@
+/// Comping patterns for turning chord progressions into accompaniment.
+///
+/// Contains the `CompingPattern` type and the `Progression::comp` method.
+pub mod comping;
+/// Step sequencing, for writing beat-oriented patterns on a grid.
+///
+/// Contains the `Sequencer` type and the `Step` notes placed on its grid.
+pub mod sequencer;
 /// String instrument tools and utilities.
 ///
 /// Contains fret mapping, string tuning systems, and chord generation
 /// tools for string instruments like guitars.
 pub mod strings;
+/// Transposing instruments, such as B♭ trumpet and E♭ alto sax.
+///
+/// Contains the `TransposingInstrument` type, which converts between written and concert pitch.
+pub mod transposing;
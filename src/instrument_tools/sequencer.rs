@@ -0,0 +1,267 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use crate::{Line, Note, NoteKind, NoteLength, NotePitch, REST, TICKS_PER_SIXTEENTH};
+
+/// The number of ticks in a whole note, which a bar of the sequencer's grid lasts.
+const BAR_LENGTH: u16 = 16 * TICKS_PER_SIXTEENTH;
+
+/// A step sequencer: a grid of equally spaced steps, each of which is either silent or plays a
+/// note, as on a drum machine.
+///
+/// Where the [`Line`] API builds music by adding notes one after another, the sequencer is for
+/// beat-oriented writing: it has a fixed number of steps in each bar of 4/4, and notes are
+/// placed on steps by their position in the bar. Each step has its own pitch, velocity, and
+/// probability, so patterns can have accents, and ghost notes which only play some of the time.
+///
+/// The grid is turned into a line with [`Sequencer::to_line`]. Each note lasts one step.
+/// No timbre is applied, so wrap the result in a timbre function like `drums()`, and layer
+/// sequencers to build up a kit.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let kick = Sequencer::new(16, 2)?.with_pattern(C4, "x...x...x...x...");
+/// let hats = Sequencer::new(16, 2)?
+///     .with_pattern(C3, "..x...x...x...x.")
+///     .with_step(15, Step::new(C3).with_velocity(0.4).with_probability(0.5));
+///
+/// let beat = drums(kick.to_line()) * drums(hats.to_line());
+/// assert_eq!(beat.length(), 2 * whole(C4).0.ticks() as usize);
+/// # Ok::<(), SequencerError>(())
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sequencer {
+    /// How many steps there are in each bar, which always divides a whole note into a whole
+    /// number of ticks
+    steps_per_bar: u16,
+    /// Every step of the grid, in order, with `None` for steps which are silent
+    pub steps: Vec<Option<Step>>,
+}
+
+/// A note played on a step of a [`Sequencer`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Step {
+    /// The pitch of the note
+    pub pitch: NotePitch,
+    /// How hard the note is played, as its volume
+    pub velocity: f32,
+    /// The chance of the note being played each time, from 0.0 (never) to 1.0 (always)
+    pub probability: f32,
+}
+
+impl Sequencer {
+    /// Creates a sequencer with every step silent.
+    ///
+    /// Every step lasts the same whole number of ticks, so the number of steps in a bar has to
+    /// divide a whole note evenly, such as `16` for sixteenth notes or `12` for eighth-note
+    /// triplets.
+    ///
+    /// # Parameters
+    /// - `steps_per_bar`: How many steps there are in each bar of 4/4
+    /// - `bars`: How many bars long the grid is
+    ///
+    /// # Errors
+    /// Returns an error if a whole note can't be split into `steps_per_bar` steps of a whole
+    /// number of ticks, including when there are no steps.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// assert!(Sequencer::new(12, 1).is_ok());
+    /// assert_eq!(Sequencer::new(10, 1), Err(SequencerError::UnevenSteps(10)));
+    /// ```
+    pub fn new(steps_per_bar: u16, bars: usize) -> Result<Self, SequencerError> {
+        if BAR_LENGTH.checked_rem(steps_per_bar) != Some(0) {
+            return Err(SequencerError::UnevenSteps(steps_per_bar));
+        }
+        Ok(Sequencer {
+            steps_per_bar,
+            steps: vec![None; usize::from(steps_per_bar).saturating_mul(bars)],
+        })
+    }
+
+    /// Returns how many steps there are in each bar.
+    pub fn steps_per_bar(&self) -> u16 {
+        self.steps_per_bar
+    }
+
+    /// Returns the length of one step.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// assert_eq!(Sequencer::new(16, 1)?.step_length(), NoteLength::new(1));
+    /// assert_eq!(Sequencer::new(12, 1)?.step_length(), triplet(eighth)(C4).0);
+    /// # Ok::<(), SequencerError>(())
+    /// ```
+    pub fn step_length(&self) -> NoteLength {
+        NoteLength(BAR_LENGTH.checked_div(self.steps_per_bar).unwrap_or_default())
+    }
+
+    /// Plays a note on the given step, replacing anything already on it.
+    /// Steps outside the grid are ignored.
+    ///
+    /// # Parameters
+    /// - `index`: The step, counted from 0 at the start of the first bar
+    /// - `step`: The note to play on it
+    pub fn with_step(mut self, index: usize, step: Step) -> Self {
+        if let Some(slot) = self.steps.get_mut(index) {
+            *slot = Some(step);
+        }
+        self
+    }
+
+    /// Silences the given step. Steps outside the grid are ignored.
+    pub fn without_step(mut self, index: usize) -> Self {
+        if let Some(slot) = self.steps.get_mut(index) {
+            *slot = None;
+        }
+        self
+    }
+
+    /// Plays the given pitch on the steps marked in a pattern, such as `"x...x...x...x..."`.
+    ///
+    /// Each character other than whitespace and `|` is one step: `x` or `X` plays the pitch,
+    /// with `X` as an accent at full velocity and `x` at a velocity of `0.7`, and any other
+    /// character leaves the step as it was. The pattern repeats until it reaches the end of
+    /// the grid, so a one-bar pattern fills every bar.
+    ///
+    /// # Parameters
+    /// - `pitch`: The pitch played on the marked steps
+    /// - `pattern`: The pattern, with one character per step
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let snare = Sequencer::new(16, 4)?.with_pattern(D4, "....X... | ....X..x");
+    /// assert_eq!(snare.steps.iter().flatten().count(), 4 * 3);
+    /// # Ok::<(), SequencerError>(())
+    /// ```
+    pub fn with_pattern(mut self, pitch: NotePitch, pattern: &str) -> Self {
+        let marks: Vec<char> = pattern
+            .chars()
+            .filter(|mark| !mark.is_whitespace() && *mark != '|')
+            .collect();
+        for (slot, mark) in self.steps.iter_mut().zip(marks.iter().cycle()) {
+            match mark {
+                'X' => *slot = Some(Step::new(pitch)),
+                'x' => *slot = Some(Step::new(pitch).with_velocity(0.7)),
+                _ => {}
+            }
+        }
+        self
+    }
+
+    /// Turns the grid into a line, with each note lasting one step and rests on silent steps.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let line = Sequencer::new(4, 1)?.with_step(1, Step::new(C4).with_velocity(0.5)).to_line();
+    /// assert_eq!(line, quarter(REST) + quarter(C4).volume(0.5) + half(REST));
+    /// # Ok::<(), SequencerError>(())
+    /// ```
+    pub fn to_line(&self) -> Line {
+        let step_length = self.step_length();
+        let mut notes: Vec<Note> = vec![];
+        for step in &self.steps {
+            match (step, notes.last_mut()) {
+                (None, Some(Note(length, NoteKind::Rest))) => length.0 = length.0.saturating_add(step_length.0),
+                (None, _) => notes.push(Note(step_length, REST)),
+                (Some(step), _) => notes.push(
+                    Note(step_length, step.pitch.into())
+                        .volume(step.velocity)
+                        .with_probability(step.probability),
+                ),
+            }
+        }
+        Line::from(notes)
+    }
+}
+
+impl Step {
+    /// Creates a step which plays the given pitch at full velocity, every time.
+    pub fn new(pitch: NotePitch) -> Self {
+        Step {
+            pitch,
+            velocity: 1.0,
+            probability: 1.0,
+        }
+    }
+
+    /// Sets how hard the note is played, as its volume.
+    pub fn with_velocity(mut self, velocity: f32) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
+    /// Sets the chance of the note being played each time, from 0.0 (never) to 1.0 (always).
+    pub fn with_probability(mut self, probability: f32) -> Self {
+        self.probability = probability;
+        self
+    }
+}
+
+/// The error returned when a bar can't be split into the given number of steps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SequencerError {
+    /// A whole note can't be split into this many steps of a whole number of ticks
+    UnevenSteps(u16),
+}
+
+impl Display for SequencerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            SequencerError::UnevenSteps(steps) => {
+                write!(f, "a bar of {BAR_LENGTH} ticks can't be split into {steps} equal steps")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SequencerError {}
+
+#[test]
+fn test_sequencer_places_notes_on_the_grid() {
+    use crate::prelude::*;
+
+    let line = Sequencer::new(8, 2)
+        .unwrap()
+        .with_pattern(C4, "X..x")
+        .with_step(3, Step::new(E4).with_probability(0.25))
+        .without_step(12)
+        .to_line();
+
+    assert_eq!(
+        line,
+        Line::from(eighth(C4))
+            + quarter(REST)
+            + eighth(E4).with_probability(0.25)
+            + eighth(C4)
+            + quarter(REST)
+            + eighth(C4).volume(0.7)
+            + eighth(C4)
+            + quarter(REST)
+            + eighth(C4).volume(0.7)
+            + dotted(quarter)(REST)
+            + eighth(C4).volume(0.7)
+    );
+}
+
+#[test]
+fn test_sequencer_rejects_steps_which_dont_divide_a_bar() {
+    assert_eq!(Sequencer::new(0, 1), Err(SequencerError::UnevenSteps(0)));
+    assert_eq!(Sequencer::new(7, 1), Err(SequencerError::UnevenSteps(7)));
+    assert_eq!(
+        Sequencer::new(BAR_LENGTH.saturating_mul(2), 1),
+        Err(SequencerError::UnevenSteps(BAR_LENGTH.saturating_mul(2)))
+    );
+
+    let finest = Sequencer::new(BAR_LENGTH, 1).unwrap();
+    assert_eq!(finest.step_length().ticks(), 1);
+}
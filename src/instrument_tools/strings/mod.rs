@@ -1,5 +1,9 @@
 use crate::{note::chord::Chord, NotePitch, Tet12};
 
+mod tab;
+
+pub use tab::{FingeringConstraints, OpenStrings};
+
 /// Represents fret positions on a string instrument.
 ///
 /// This type stores the fret positions for each string, where `None` indicates
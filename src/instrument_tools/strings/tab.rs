@@ -0,0 +1,267 @@
+use crate::{get_note_name, instrument_tools::strings::StringTuning, Line, NoteKind, NotePitch, A4};
+
+/// Whether open strings are used when fingering a line, for [`FingeringConstraints`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OpenStrings {
+    /// Open strings are never played
+    Avoid,
+    /// Open strings are played when they're the easiest way to reach a note
+    #[default]
+    Allow,
+    /// Open strings are played wherever they can be, so they ring out
+    Prefer,
+}
+
+/// Limits on where a line is played, used by [`StringTuning::finger_line`] and [`StringTuning::to_tab`].
+///
+/// Within the limits, notes are placed so the hand moves as little as possible.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::instrument_tools::strings::*;
+///
+/// // Stay between frets 5 and 9, without open strings
+/// let fifth_position = FingeringConstraints {
+///     min_fret: 5,
+///     max_fret: 9,
+///     open_strings: OpenStrings::Avoid,
+/// };
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FingeringConstraints {
+    /// The lowest fret which can be pressed (default: 1)
+    pub min_fret: i16,
+    /// The highest fret which can be pressed (default: 24)
+    pub max_fret: i16,
+    /// Whether open strings are played. They're played wherever the hand is, so they aren't limited
+    /// by `min_fret` and `max_fret` (default: allowed)
+    pub open_strings: OpenStrings,
+}
+
+impl Default for FingeringConstraints {
+    fn default() -> Self {
+        FingeringConstraints {
+            min_fret: 1,
+            max_fret: 24,
+            open_strings: OpenStrings::Allow,
+        }
+    }
+}
+
+/// How much a fingering costs for every string crossed between notes, compared to every fret moved.
+const STRING_CROSSING_COST: f32 = 0.5;
+
+/// How much a fingering costs for every note played on a string which isn't preferred.
+const UNPREFERRED_STRING_COST: f32 = 0.5;
+
+/// How far a pitch can be from a fret, in semitones, and still be played there.
+const FRET_TOLERANCE: f32 = 0.25;
+
+impl<const N: usize> StringTuning<N> {
+    /// Finds every place a pitch can be played within the constraints.
+    ///
+    /// # Parameters
+    /// - `pitch`: The pitch to find
+    /// - `constraints`: Which frets, and whether open strings, can be played
+    ///
+    /// # Returns
+    /// The string (counting from the first string of the tuning) and fret of each place.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::instrument_tools::strings::*;
+    ///
+    /// let tuning = GuitarTuning::DEFAULT_GUITAR_TUNING;
+    /// let positions = tuning.positions_of(C4, &FingeringConstraints::default());
+    ///
+    /// // C4 is on the first fret of the B string, the fifth fret of the G string, and so on
+    /// assert_eq!(positions, vec![(1, 1), (2, 5), (3, 10), (4, 15), (5, 20)]);
+    /// ```
+    pub fn positions_of(&self, pitch: NotePitch, constraints: &FingeringConstraints) -> Vec<(usize, i16)> {
+        self.0
+            .iter()
+            .enumerate()
+            .filter_map(|(string, open)| {
+                let semitones = 12.0 * (pitch.0 / open.0).log2();
+                if (semitones - semitones.round()).abs() > FRET_TOLERANCE || !semitones.is_finite() {
+                    return None;
+                }
+                #[expect(clippy::cast_possible_truncation, reason = "Frets are checked to be in range")]
+                let fret = semitones.round() as i16;
+                let playable = match fret {
+                    0 => constraints.open_strings != OpenStrings::Avoid,
+                    _ => (constraints.min_fret.max(1)..=constraints.max_fret).contains(&fret),
+                };
+                playable.then_some((string, fret))
+            })
+            .collect()
+    }
+
+    /// Chooses where each note of a line is played, within the constraints.
+    ///
+    /// Of all the ways to play the line, the one chosen moves the hand along the neck and across
+    /// the strings the least, so the fingering stays in one position where it can.
+    /// Only the main sequence of notes is fingered, not the pickup.
+    ///
+    /// # Parameters
+    /// - `line`: The line to finger
+    /// - `constraints`: Which frets, and whether open strings, can be played
+    ///
+    /// # Returns
+    /// The string and fret of each note of the line. Rests, and notes which can't be played
+    /// within the constraints, are `None`.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::instrument_tools::strings::*;
+    ///
+    /// let tuning = GuitarTuning::DEFAULT_GUITAR_TUNING;
+    /// let riff = Line::from(vec![quarter(A3), quarter(C4), quarter(D4), quarter(E4)]);
+    ///
+    /// // In the fifth position, the riff stays between frets 5 and 7
+    /// let constraints = FingeringConstraints { min_fret: 5, max_fret: 8, open_strings: OpenStrings::Avoid };
+    /// let fingering = tuning.finger_line(&riff, &constraints);
+    /// assert_eq!(fingering, vec![Some((3, 7)), Some((2, 5)), Some((2, 7)), Some((1, 5))]);
+    ///
+    /// // Preferring open strings lets the top E ring out
+    /// let constraints = FingeringConstraints { open_strings: OpenStrings::Prefer, ..constraints };
+    /// assert_eq!(tuning.finger_line(&riff, &constraints)[3], Some((0, 0)));
+    /// ```
+    pub fn finger_line(&self, line: &Line, constraints: &FingeringConstraints) -> Vec<Option<(usize, i16)>> {
+        let candidates: Vec<Vec<(usize, i16)>> = line
+            .notes
+            .iter()
+            .map(|note| match note.1 {
+                NoteKind::Pitched { pitch, .. } => self.positions_of(pitch, constraints),
+                NoteKind::Rest => vec![],
+            })
+            .collect();
+
+        // The cheapest way of reaching each place of each note
+        let mut best: Vec<Vec<Step>> = vec![];
+        let mut previous: Option<usize> = None;
+        for (index, places) in candidates.iter().enumerate() {
+            let costs = places
+                .iter()
+                .map(|&place| {
+                    let cost = place_cost(place, constraints);
+                    previous
+                        .and_then(|before| {
+                            best[before]
+                                .iter()
+                                .zip(&candidates[before])
+                                .enumerate()
+                                .map(|(choice, (step, &from))| Step {
+                                    cost: step.cost + cost + movement_cost(from, place),
+                                    from: Some((before, choice)),
+                                })
+                                .min_by(|a, b| a.cost.total_cmp(&b.cost))
+                        })
+                        .unwrap_or(Step { cost, from: None })
+                })
+                .collect();
+            best.push(costs);
+            if !places.is_empty() {
+                previous = Some(index);
+            }
+        }
+
+        // Follow the cheapest fingering back from the last note
+        let mut fingering = vec![None; candidates.len()];
+        let mut choice = previous.and_then(|last| {
+            best[last]
+                .iter()
+                .enumerate()
+                .min_by(|a, b| a.1.cost.total_cmp(&b.1.cost))
+                .map(|(choice, _)| (last, choice))
+        });
+        while let Some((index, place)) = choice {
+            fingering[index] = Some(candidates[index][place]);
+            choice = best[index][place].from;
+        }
+        fingering
+    }
+
+    /// Writes a line as plain-text tablature, fingered within the constraints.
+    ///
+    /// Each row is a string, labelled with the name of its open note, and each note is a
+    /// column with its fret written on the string it's played on. Rests, and notes which can't be
+    /// played within the constraints, are written as empty columns. Lengths aren't written.
+    ///
+    /// # Parameters
+    /// - `line`: The line to write
+    /// - `constraints`: Which frets, and whether open strings, can be played
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::instrument_tools::strings::*;
+    ///
+    /// let tuning = GuitarTuning::DEFAULT_GUITAR_TUNING;
+    /// let riff = Line::from(vec![quarter(A3), quarter(C4), quarter(D4), quarter(E4)]);
+    /// let constraints = FingeringConstraints { min_fret: 5, max_fret: 8, open_strings: OpenStrings::Avoid };
+    ///
+    /// assert_eq!(
+    ///     tuning.to_tab(&riff, &constraints),
+    ///     "E|---------|\n\
+    ///      B|-------5-|\n\
+    ///      G|---5-7---|\n\
+    ///      D|-7-------|\n\
+    ///      A|---------|\n\
+    ///      E|---------|\n"
+    /// );
+    /// ```
+    pub fn to_tab(&self, line: &Line, constraints: &FingeringConstraints) -> String {
+        let fingering = self.finger_line(line, constraints);
+        let labels: Vec<String> = self.0.iter().map(|&open| get_note_name(open, A4)).collect();
+        let label_width = labels.iter().map(String::len).max().unwrap_or_default();
+
+        let mut tab = String::new();
+        for (string, label) in labels.iter().enumerate() {
+            tab.push_str(&format!("{label:<label_width$}|-"));
+            for place in &fingering {
+                let width = place.map_or(1, |(_, fret)| fret.to_string().len());
+                let fret = match place {
+                    Some((on, fret)) if *on == string => fret.to_string(),
+                    _ => String::new(),
+                };
+                tab.push_str(&format!("{fret:-<width$}-"));
+            }
+            tab.push_str("|\n");
+        }
+        tab
+    }
+}
+
+/// The cheapest way found of playing a note at one place.
+struct Step {
+    /// The cost of playing the line up to and including the note
+    cost: f32,
+    /// The index of the note before, and which of its places it's played at
+    from: Option<(usize, usize)>,
+}
+
+/// The cost of playing a note at a place, apart from moving to it.
+fn place_cost(place: (usize, i16), constraints: &FingeringConstraints) -> f32 {
+    let open = place.1 == 0;
+    match constraints.open_strings {
+        OpenStrings::Prefer if !open => UNPREFERRED_STRING_COST,
+        OpenStrings::Allow | OpenStrings::Avoid if open => UNPREFERRED_STRING_COST,
+        _ => 0.0,
+    }
+}
+
+/// The cost of moving the hand from one place to another. Open strings can be played from anywhere.
+fn movement_cost(from: (usize, i16), to: (usize, i16)) -> f32 {
+    let frets = if from.1 == 0 || to.1 == 0 {
+        0.0
+    } else {
+        (f32::from(from.1) - f32::from(to.1)).abs()
+    };
+    #[expect(clippy::cast_precision_loss, reason = "There are only a few strings")]
+    let strings = from.0.abs_diff(to.0) as f32;
+    frets + strings * STRING_CROSSING_COST
+}
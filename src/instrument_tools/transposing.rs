@@ -0,0 +1,191 @@
+use crate::{
+    scales::tet12::{
+        get_note_name_with_octave,
+        pitches::{BF3, C6, C7, D6, E3, F6, FS2, FS3},
+    },
+    Line, NoteKind, NotePitch, Tet12, Timbre, A4,
+};
+
+/// An instrument whose music is written at a different pitch from how it sounds, such as a
+/// B♭ trumpet, which sounds a whole tone lower than written.
+///
+/// Lines are always kept at concert pitch, so they're played and rendered as they sound.
+/// The instrument converts them to written pitch for display, notation, and range checks,
+/// and converts written music to concert pitch so it can be played.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::TransposingInstrument;
+///
+/// let trumpet = TransposingInstrument::BB_TRUMPET;
+///
+/// // A concert C is written as a D for a B♭ trumpet
+/// assert_eq!(trumpet.written_name(C4), "D4");
+///
+/// // Music read from a trumpet part is converted to concert pitch to be played
+/// let part = brass(quarter(D4) + quarter(E4) + half(FS4));
+/// let concert = trumpet.from_written(&part);
+/// assert_eq!(concert.to_lilypond(), brass(quarter(C4) + quarter(D4) + half(E4)).to_lilypond());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TransposingInstrument {
+    /// The instrument's name, written beside its staff in notation
+    pub name: &'static str,
+    /// How many semitones the instrument sounds above its written pitch. This is negative for
+    /// instruments which sound lower than written, such as -2 for a B♭ trumpet.
+    pub transposition: i16,
+    /// The lowest written pitch the instrument can play
+    pub lowest_written: NotePitch,
+    /// The highest written pitch the instrument can play
+    pub highest_written: NotePitch,
+}
+
+/// How far a pitch can be past the end of a range, as a ratio of frequencies, and still be in it.
+const RANGE_TOLERANCE: f32 = 1.01;
+
+impl TransposingInstrument {
+    /// A trumpet in B♭, which sounds a whole tone lower than written.
+    pub const BB_TRUMPET: TransposingInstrument = TransposingInstrument {
+        name: "Trumpet in B♭",
+        transposition: -2,
+        lowest_written: FS3,
+        highest_written: D6,
+    };
+
+    /// A clarinet in B♭, which sounds a whole tone lower than written.
+    pub const BB_CLARINET: TransposingInstrument = TransposingInstrument {
+        name: "Clarinet in B♭",
+        transposition: -2,
+        lowest_written: E3,
+        highest_written: C7,
+    };
+
+    /// An alto saxophone in E♭, which sounds a major sixth lower than written.
+    pub const EB_ALTO_SAX: TransposingInstrument = TransposingInstrument {
+        name: "Alto Sax in E♭",
+        transposition: -9,
+        lowest_written: BF3,
+        highest_written: F6,
+    };
+
+    /// A tenor saxophone in B♭, which sounds a major ninth lower than written.
+    pub const BB_TENOR_SAX: TransposingInstrument = TransposingInstrument {
+        name: "Tenor Sax in B♭",
+        transposition: -14,
+        lowest_written: BF3,
+        highest_written: F6,
+    };
+
+    /// A horn in F, which sounds a perfect fifth lower than written.
+    pub const F_HORN: TransposingInstrument = TransposingInstrument {
+        name: "Horn in F",
+        transposition: -7,
+        lowest_written: FS2,
+        highest_written: C6,
+    };
+
+    /// Returns the pitch written for a note which sounds at the given concert pitch.
+    ///
+    /// # Parameters
+    /// - `concert`: The pitch the note sounds at
+    pub fn written_pitch(&self, concert: NotePitch) -> NotePitch {
+        concert.semitone(self.transposition.saturating_neg())
+    }
+
+    /// Returns the pitch a written note sounds at.
+    ///
+    /// # Parameters
+    /// - `written`: The pitch the note is written at
+    pub fn sounding_pitch(&self, written: NotePitch) -> NotePitch {
+        written.semitone(self.transposition)
+    }
+
+    /// Returns the name, with octave, of the note written for a concert pitch, such as "D4".
+    ///
+    /// # Parameters
+    /// - `concert`: The pitch the note sounds at
+    pub fn written_name(&self, concert: NotePitch) -> String {
+        get_note_name_with_octave(self.written_pitch(concert), A4)
+    }
+
+    /// Converts a line at concert pitch to the pitch it's written at for this instrument.
+    ///
+    /// The result is only meant to be displayed or exported. Playing it would sound at the
+    /// wrong pitch.
+    ///
+    /// # Parameters
+    /// - `line`: The line, at concert pitch
+    pub fn to_written(&self, line: &Line) -> Line {
+        line.semitone(self.transposition.saturating_neg())
+    }
+
+    /// Converts a line written for this instrument to concert pitch, so it can be played.
+    ///
+    /// # Parameters
+    /// - `line`: The line, at written pitch
+    pub fn from_written(&self, line: &Line) -> Line {
+        line.semitone(self.transposition)
+    }
+
+    /// Exports a line at concert pitch as a LilyPond part for this instrument.
+    ///
+    /// The part is written at the instrument's written pitch, with its name beside the staff.
+    /// See [`Piece::to_lilypond`](crate::Piece::to_lilypond) for how the notes are written.
+    ///
+    /// # Parameters
+    /// - `line`: The line, at concert pitch
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::TransposingInstrument;
+    ///
+    /// let melody = brass(quarter(C4) + quarter(D4) + half(E4));
+    /// let part = TransposingInstrument::EB_ALTO_SAX.to_lilypond(&melody);
+    ///
+    /// assert!(part.contains("instrumentName = \"Alto Sax in E♭\""));
+    /// assert!(part.contains("a'4 b'4 cis''2 |"));
+    /// ```
+    pub fn to_lilypond(&self, line: &Line) -> String {
+        self.to_written(line).to_lilypond_named(self.name)
+    }
+
+    /// Finds the notes of a line which the instrument can't play.
+    ///
+    /// Drums and other unpitched notes are never out of range.
+    ///
+    /// # Parameters
+    /// - `line`: The line, at concert pitch
+    ///
+    /// # Returns
+    /// The indices of the notes, in the main sequence of notes, whose written pitch is outside the
+    /// instrument's range.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::TransposingInstrument;
+    ///
+    /// // A B♭ trumpet's lowest note sounds as E3, so the D3 is too low
+    /// let line = brass(quarter(G3) + quarter(E3) + quarter(D3) + quarter(G4));
+    /// assert_eq!(TransposingInstrument::BB_TRUMPET.out_of_range(&line), vec![2]);
+    /// ```
+    pub fn out_of_range(&self, line: &Line) -> Vec<usize> {
+        line.notes
+            .iter()
+            .enumerate()
+            .filter(|(_, note)| match &note.1 {
+                NoteKind::Pitched { pitch, timbre, .. } => {
+                    let unpitched = matches!(timbre.unreversed(), Timbre::Drums | Timbre::CustomSourceUnpitched(_));
+                    let written = self.written_pitch(*pitch).0;
+                    !unpitched
+                        && (written * RANGE_TOLERANCE < self.lowest_written.0
+                            || written > self.highest_written.0 * RANGE_TOLERANCE)
+                }
+                NoteKind::Rest => false,
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+}
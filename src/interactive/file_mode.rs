@@ -1,12 +1,13 @@
 use crate::{
     interactive::{InteractiveTui, PlayResult, SelectionInfo, Selections, TuiSelectable},
     play::FileOutputConfig,
-    MusicPlayer, Piece,
+    Metadata, MusicPlayer, Piece,
 };
 
 impl InteractiveTui {
-    pub(super) fn handle_file_mode(piece: &Piece) -> PlayResult {
-        let mut player = MusicPlayer::new_file(300, 1.0, 44100);
+    pub(super) fn handle_file_mode(piece: &Piece, metadata: &Metadata) -> PlayResult {
+        let mut player =
+            MusicPlayer::new_file(InteractiveTui::starting_tempo(metadata), 1.0, 44100).with_metadata(metadata.clone());
         let mut path = InteractiveTui::get_absolute_path("./output.wav");
 
         loop {
@@ -20,7 +21,9 @@ impl InteractiveTui {
                 FileModeSelection::Render => {
                     if let Ok(ref path) = path.as_ref() {
                         println!("Rendering piece to {path}.");
-                        player.render_to_wav(piece.clone(), path);
+                        player.render_to_wav_with_progress(piece.clone(), path, |progress| {
+                            InteractiveTui::print_progress("Rendering", progress);
+                        });
                         println!("Rendering complete. Saved to {path}.");
                     } else {
                         println!("No valid output path set. Please set a valid path first.");
@@ -2,18 +2,18 @@ use std::sync::Arc;
 
 use crate::{
     interactive::{InteractiveTui, PlayResult, SelectionInfo, Selections, TuiSelectable},
-    MusicPlayer, Piece,
+    Metadata, MusicPlayer, Piece,
 };
 
 impl InteractiveTui {
-    pub(super) fn handle_live_mode(piece: &Piece) -> PlayResult {
+    pub(super) fn handle_live_mode(piece: &Piece, metadata: &Metadata) -> PlayResult {
         let Ok((_output_stream, output_handle)) = rodio::OutputStream::try_default() else {
             println!("Failed to get default output stream. Please ensure your audio output is configured correctly.");
             return PlayResult::Continue;
         };
         let output_handle = Arc::new(output_handle);
 
-        let mut player = MusicPlayer::new_live(300, output_handle);
+        let mut player = MusicPlayer::new_live(InteractiveTui::starting_tempo(metadata), output_handle);
         let mut show_score = false;
         loop {
             let choice = InteractiveTui::get_input::<LiveModeSelection>(LiveModeSelectionContext {
@@ -35,8 +35,24 @@ impl InteractiveTui {
                     } else {
                         println!("Playing piece without score display.");
                     }
-                    player.play(piece.clone()).join().expect("Failed to play piece");
+                    player
+                        .play_with_progress(piece.clone(), |progress| {
+                            InteractiveTui::print_progress("Playing", progress);
+                        })
+                        .join()
+                        .expect("Failed to play piece");
                 }
+                LiveModeSelection::PreviewLine => {
+                    let line_count = piece.0.len();
+                    if line_count == 0 {
+                        println!("This piece has no lines to preview.");
+                        continue;
+                    }
+                    let index = InteractiveTui::get_index_input("Enter the number of the line to preview", line_count);
+                    println!("Previewing line {}.", index.saturating_add(1));
+                    player.preview(&piece.0[index]).join().expect("Failed to play line");
+                }
+                LiveModeSelection::Practice => InteractiveTui::handle_practice(piece, &player),
                 LiveModeSelection::ToggleScore => {
                     show_score = !show_score;
                 }
@@ -52,6 +68,8 @@ enum LiveModeSelection {
     ChangeTempo,
     ToggleScore,
     Play,
+    PreviewLine,
+    Practice,
     Exit,
     Continue,
 }
@@ -75,6 +93,20 @@ impl TuiSelectable for LiveModeSelection {
                     },
                     Self::Play,
                 ),
+                (
+                    SelectionInfo {
+                        name: "Preview Line".to_string(),
+                        description: "Play one line of the piece on its own".to_string(),
+                    },
+                    Self::PreviewLine,
+                ),
+                (
+                    SelectionInfo {
+                        name: "Practice Line".to_string(),
+                        description: "Follow one line of the piece note by note".to_string(),
+                    },
+                    Self::Practice,
+                ),
                 (
                     SelectionInfo {
                         name: "Change Tempo".to_string(),
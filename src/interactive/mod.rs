@@ -1,10 +1,14 @@
-use crate::Piece;
+use std::io::Write;
+
+use crate::{Metadata, Piece, RenderProgress};
 
 #[cfg(feature = "wav-output")]
 mod file_mode;
 
 #[cfg(feature = "live-output")]
 mod live_mode;
+#[cfg(feature = "live-output")]
+mod practice_mode;
 
 /// Interactive TUI for playing music pieces in a terminal interface.
 /// Allows users to select modes and configure playback options interactively.
@@ -35,14 +39,42 @@ impl InteractiveTui {
     /// InteractiveTui::start(piece);
     /// ```
     pub fn start(piece: Piece) {
+        InteractiveTui::start_with_metadata(piece, Metadata::default());
+    }
+
+    /// Starts the interactive TUI for playing a music piece, showing its metadata.
+    ///
+    /// The title and artist are shown when the TUI starts, the piece's tempo is used as the
+    /// starting tempo, and rendered files are tagged with the metadata.
+    ///
+    /// # Arguments
+    /// * `piece` - The music piece to be played interactively.
+    /// * `metadata` - Information about the piece, such as its title.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{InteractiveTui, Metadata};
+    ///
+    /// let piece = Piece::from(piano(quarter(C4) + quarter(A4)));
+    /// InteractiveTui::start_with_metadata(piece, Metadata::new("Two Notes").with_tempo(240));
+    /// ```
+    pub fn start_with_metadata(piece: Piece, metadata: Metadata) {
+        if !metadata.is_empty() {
+            println!("{metadata}");
+            if let Some(copyright) = &metadata.copyright {
+                println!("{copyright}");
+            }
+        }
+
         loop {
             let mode = InteractiveTui::get_input::<Mode>(());
 
             let result = match mode {
                 #[cfg(feature = "live-output")]
-                Mode::Live => InteractiveTui::handle_live_mode(&piece),
+                Mode::Live => InteractiveTui::handle_live_mode(&piece, &metadata),
                 #[cfg(feature = "wav-output")]
-                Mode::File => InteractiveTui::handle_file_mode(&piece),
+                Mode::File => InteractiveTui::handle_file_mode(&piece, &metadata),
             };
 
             match result {
@@ -111,6 +143,22 @@ impl InteractiveTui {
         }
     }
 
+    /// Asks for a number from 1 to `count`, and returns it as a zero-based index.
+    #[cfg(feature = "live-output")]
+    fn get_index_input(ask: &str, count: usize) -> usize {
+        println!("{ask} (Between 1 and {count}):");
+        loop {
+            let mut input = String::new();
+            std::io::stdin().read_line(&mut input).expect("Failed to read line");
+
+            match input.trim().parse::<usize>() {
+                Ok(value) if (1..=count).contains(&value) => return value.saturating_sub(1),
+                Ok(_) => println!("Please enter a value between 1 and {count}."),
+                Err(_) => println!("Invalid input. Please enter a number."),
+            }
+        }
+    }
+
     #[cfg(feature = "wav-output")]
     fn get_positive_float_input(ask: &str) -> f32 {
         println!("{ask} (Between 0.0 and infinity):");
@@ -176,6 +224,34 @@ impl InteractiveTui {
         };
         Ok(output)
     }
+
+    /// Returns the tempo the TUI starts at: the piece's own, if its metadata has one.
+    fn starting_tempo(metadata: &Metadata) -> u32 {
+        metadata.tempo_bpm.unwrap_or(300)
+    }
+
+    /// Redraws a progress bar on the current line, then moves to the next line once it's full.
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss,
+        reason = "The fraction is from 0 to 1, and the bar is short"
+    )]
+    fn print_progress(label: &str, progress: RenderProgress) {
+        const WIDTH: usize = 40;
+        let filled = ((progress.fraction() * WIDTH as f32).round() as usize).min(WIDTH);
+        print!(
+            "\r{label} [{}{}] {}/{} beats",
+            "#".repeat(filled),
+            " ".repeat(WIDTH.saturating_sub(filled)),
+            progress.beat,
+            progress.total_beats
+        );
+        if progress.beat >= progress.total_beats {
+            println!();
+        }
+        let _ = std::io::stdout().flush();
+    }
 }
 
 enum PlayResult {
@@ -0,0 +1,202 @@
+use std::{io::Write, time::Duration};
+
+use crate::{
+    interactive::{InteractiveTui, SelectionInfo, Selections, TuiSelectable},
+    note::NoteKind,
+    play::LiveOutputConfig,
+    scales::tet12::get_note_name_with_octave,
+    Line, MusicPlayer, Note, NotePitch, Piece, A4, TICKS_PER_SIXTEENTH,
+};
+
+/// How many notes either side of the current one are shown while practicing.
+const CONTEXT_NOTES: usize = 3;
+
+impl InteractiveTui {
+    /// Practices one line of the piece, either following it as the piece plays, or stepping
+    /// through it one note at a time, waiting for the right pitch to be entered.
+    pub(super) fn handle_practice(piece: &Piece, player: &MusicPlayer<LiveOutputConfig>) {
+        let line_count = piece.0.len();
+        if line_count == 0 {
+            println!("This piece has no lines to practice.");
+            return;
+        }
+        let index = InteractiveTui::get_index_input("Enter the number of the line to practice", line_count);
+        let line = &piece.0[index];
+        if line.notes.is_empty() {
+            println!("Line {} has no notes to practice.", index.saturating_add(1));
+            return;
+        }
+
+        match InteractiveTui::get_input::<PracticeStyle>(()) {
+            PracticeStyle::Follow => InteractiveTui::follow_line(piece, line, player),
+            PracticeStyle::Wait => InteractiveTui::wait_for_each_note(line, player),
+        }
+    }
+
+    /// Plays the whole piece, highlighting the note of the line which is currently playing.
+    fn follow_line(piece: &Piece, line: &Line, player: &MusicPlayer<LiveOutputConfig>) {
+        let playback = player.play(piece.clone());
+        let mut shown = None;
+        while !playback.is_finished() {
+            let tick = playback.current_beat().saturating_mul(usize::from(TICKS_PER_SIXTEENTH));
+            let current = note_index_at(line, tick);
+            if current != shown {
+                if let Some(current) = current {
+                    // The score is redrawn in place, so the highlight moves along it
+                    print!("\r\x1b[2K{}", score_around(line, current));
+                    let _ = std::io::stdout().flush();
+                }
+                shown = current;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        println!();
+        let _ = playback.join();
+    }
+
+    /// Steps through the line, playing each note once its pitch has been entered.
+    fn wait_for_each_note(line: &Line, player: &MusicPlayer<LiveOutputConfig>) {
+        println!("Enter the name of each highlighted note, such as \"C#4\" or \"Eb3\".");
+        println!("Press enter on its own to hear the note, or enter \"q\" to stop.");
+
+        let mut index = 0;
+        while let Some(note) = line.notes.get(index) {
+            let NoteKind::Pitched { pitch, .. } = &note.1 else {
+                // Rests are waited out rather than entered
+                player
+                    .play(Line::from(note.clone()))
+                    .join()
+                    .expect("Failed to play rest");
+                index = index.saturating_add(1);
+                continue;
+            };
+            println!("{}", score_around(line, index));
+
+            let mut input = String::new();
+            std::io::stdin().read_line(&mut input).expect("Failed to read line");
+            let input = input.trim();
+            if input.eq_ignore_ascii_case("q") {
+                return;
+            }
+            if input.is_empty() {
+                player
+                    .play(Line::from(note.clone()))
+                    .join()
+                    .expect("Failed to play note");
+                continue;
+            }
+
+            match input.parse::<NotePitch>() {
+                Ok(entered) if same_note(entered, *pitch) => {
+                    player
+                        .play(Line::from(note.clone()))
+                        .join()
+                        .expect("Failed to play note");
+                    index = index.saturating_add(1);
+                }
+                Ok(entered) => println!("That was {entered:?}, please try again."),
+                Err(error) => println!("{error}, please try again."),
+            }
+        }
+        println!("Line complete!");
+    }
+}
+
+/// Returns the index of the note of the line which is sounding at the given tick, if any.
+fn note_index_at(line: &Line, tick: usize) -> Option<usize> {
+    let mut end = 0usize;
+    line.notes.iter().position(|note| {
+        end = end.saturating_add(usize::from(note.0 .0));
+        tick < end
+    })
+}
+
+/// Returns whether two pitches are the same note, so either spelling of a note is accepted.
+fn same_note(a: NotePitch, b: NotePitch) -> bool {
+    (12.0 * (a.0 / b.0).log2()).abs() < 0.5
+}
+
+/// Returns the name of a note as it's shown while practicing.
+fn note_name(note: &Note) -> String {
+    match &note.1 {
+        NoteKind::Pitched { pitch, .. } => get_note_name_with_octave(*pitch, A4),
+        NoteKind::Rest => "-".to_string(),
+    }
+}
+
+/// Returns the notes around the given one, with the given one highlighted in brackets.
+fn score_around(line: &Line, index: usize) -> String {
+    let first = index.saturating_sub(CONTEXT_NOTES);
+    let last = index
+        .saturating_add(CONTEXT_NOTES)
+        .min(line.notes.len().saturating_sub(1));
+    let notes: Vec<String> = (first..=last)
+        .filter_map(|i| line.notes.get(i).map(|note| (i, note_name(note))))
+        .map(|(i, name)| if i == index { format!("[{name}]") } else { name })
+        .collect();
+    format!(
+        "{}{}{}",
+        if first > 0 { "... " } else { "" },
+        notes.join(" "),
+        if last.saturating_add(1) < line.notes.len() {
+            " ..."
+        } else {
+            ""
+        }
+    )
+}
+
+#[derive(Clone, Copy)]
+enum PracticeStyle {
+    Follow,
+    Wait,
+}
+
+impl TuiSelectable for PracticeStyle {
+    type Context = ();
+
+    fn get_selections(_context: Self::Context) -> Selections<Self> {
+        Selections {
+            description: "How would you like to practice?".to_string(),
+            options: vec![
+                (
+                    SelectionInfo {
+                        name: "Follow".to_string(),
+                        description: "Play the piece, highlighting each note of the line".to_string(),
+                    },
+                    Self::Follow,
+                ),
+                (
+                    SelectionInfo {
+                        name: "Wait".to_string(),
+                        description: "Wait for each note of the line to be entered".to_string(),
+                    },
+                    Self::Wait,
+                ),
+            ],
+            default: Some(0),
+        }
+    }
+}
+
+#[test]
+fn test_practice_follows_the_line() {
+    use crate::prelude::*;
+
+    let line = piano(quarter(C4) + eighth(REST) + eighth(D4) + half(E4) + quarter(F4) + quarter(G4));
+    let tick = |sixteenths: usize| sixteenths.saturating_mul(usize::from(TICKS_PER_SIXTEENTH));
+    assert_eq!(note_index_at(&line, 0), Some(0));
+    assert_eq!(note_index_at(&line, tick(5)), Some(1));
+    assert_eq!(note_index_at(&line, tick(8)), Some(3));
+    assert_eq!(note_index_at(&line, tick(20)), Some(5));
+    assert_eq!(note_index_at(&line, tick(24)), None);
+
+    assert_eq!(score_around(&line, 0), "[C4] - D4 E4 ...");
+    assert_eq!(score_around(&line, 5), "... D4 E4 F4 [G4]");
+    assert!(same_note(
+        NotePitch::from_name("Gb4").unwrap(),
+        NotePitch::from_name("F#4").unwrap()
+    ));
+    assert!(!same_note(C4, C5));
+    assert_eq!(score_around(&Line::from(quarter(C5)), 0), "[C5]");
+}
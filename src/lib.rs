@@ -9,6 +9,7 @@
 //! - **Lines**: Sequences of notes played one after another (melodies/rhythms)  
 //! - **Pieces**: Multiple lines played simultaneously (harmony/polyphony)
 //! - **Chords**: Groups of pitches played together
+//! - **Progressions**: Sequences of chords, each held for a duration
 //!
 //! ## Quick Start
 //!
@@ -79,7 +80,7 @@
 //! let simultaneous = piano(quarter(chord.clone()));
 //!
 //! // You can also use a "striker" function to play it with a specific pattern
-//! fn striker_fn(pitch: NotePitch) -> Line {
+//! fn striker_fn(pitch: NotePitch, _voice: usize) -> Line {
 //!     piano(quarter(pitch) + eighth(REST) + eighth(pitch))
 //! }
 //!
@@ -107,6 +108,8 @@
 //! - `interactive-tui`: Interactive terminal interface for playback and file export
 //! - `wav-output`: Export compositions to WAV audio files  
 //! - `live-output`: Real-time audio playback
+//! - `midi-live`: Real-time playback over MIDI, to drive hardware synths and DAWs
+//! - `ticks-24`, `ticks-48`: Finer time resolution (see [`TICKS_PER_SIXTEENTH`])
 //!
 //! ## Philosophy
 //!
@@ -147,6 +150,11 @@
 #[cfg(all(feature = "interactive-tui", not(any(feature = "wav-output", feature = "live-output"))))]
 compile_error!("The `interactive-tui` feature requires either the `wav-output` or `live-output` feature to be enabled. Please enable one of them in your Cargo.toml.");
 
+/// Audio effects, such as reverb, delay, distortion, EQ, and limiting.
+///
+/// Contains the `Effect` trait, and the built-in effects which can be applied to lines or to the whole mix.
+pub mod effects;
+
 /// Instrument-specific tools and utilities.
 ///
 /// Contains guitar fretting tools, tuning systems, and other instrument helpers.
@@ -168,21 +176,58 @@ pub mod piece;
 #[cfg(any(feature = "wav-output", feature = "live-output"))]
 mod play;
 
+mod rng;
+
 /// Musical scales and tuning systems.
 ///
 /// Contains the `Scale` trait and implementations for various musical scales.
 pub mod scales;
 
-pub use instrument_tools::strings::{Frets, GuitarFrets, GuitarTuning, StringTuning};
-pub use note::chord::{Chord, ChordFluid};
-pub use note::{bass, drums, electric_guitar, piano, sine};
-pub use note::{dotted, double_whole, eighth, half, quarter, sixteenth, tie, whole};
+/// Ear-training and sight-reading exercise generation.
+///
+/// Contains `EarTrainer`, which generates random intervals, chords, and scales along with their answers,
+/// and `SightReader`, which generates graded melodies for sight-reading.
+pub mod training;
+
+#[cfg(any(feature = "wav-output", feature = "live-output"))]
+pub use effects::{ConvolutionReverb, ImpulseResponseError};
+pub use effects::{Delay, Distortion, Effect, EffectProcessor, Equalizer, Limiter, Reverb};
+pub use instrument_tools::comping::CompingPattern;
+pub use instrument_tools::sequencer::{Sequencer, SequencerError, Step};
+pub use instrument_tools::strings::{
+    FingeringConstraints, Frets, GuitarFrets, GuitarTuning, OpenStrings, StringTuning,
+};
+pub use instrument_tools::transposing::TransposingInstrument;
+pub use note::chord::{ArpPattern, Chord, ChordFluid, ParseChordError};
+pub use note::progression::Progression;
+pub use note::with_defaults;
+pub use note::{
+    bass, brass, drums, electric_guitar, flute, organ, piano, saw, sine, square, strings, synth_lead, synth_pad,
+    triangle,
+};
+pub use note::{
+    beats_to_ticks, dotted, double_whole, eighth, half, quarter, sixteenth, sixty_fourth, thirty_second, tie, triplet,
+    tuplet, whole,
+};
+pub use note::{
+    Envelope, FmModulator, FmPatch, Meter, Note, NoteKind, NoteLength, NotePitch, OffPitchError, ParsePitchError,
+    Ratio, SampleInstrument, SampledInstrument, Style, Timbre, TimeSignature, REST, TICKS_PER_SIXTEENTH,
+};
 pub use note::{LengthFluid, TimbreFluid};
-pub use note::{Note, NoteKind, NoteLength, NotePitch, Timbre, REST};
 pub use piece::line::Line;
-pub use piece::Piece;
-pub use scales::tet12::{get_note_name, get_note_name_with_octave, Tet12, A4, C4};
-pub use scales::Scale;
+pub use piece::{
+    zip_rhythm, Metadata, Movement, NotationOptions, NoteEvent, Notehead, ParseMelodyError, Part, PercussionMap,
+    PercussionNotation, Piece, PieceDiff, PieceFileError, Project, ScheduledPiece, Section, Song, SongError,
+    StretchError, VariationRules, PIECE_FILE_VERSION,
+};
+
+#[cfg(any(feature = "wav-output", feature = "live-output"))]
+pub use piece::AssetError;
+pub use scales::tet12::{
+    format_note_name, format_note_name_with_octave, get_note_name, get_note_name_with_octave, Key, KeySpelling, Mode,
+    NoteNameOptions, NoteNameStyle, ParseKeyError, PitchClassSet, Spelling, Tet12, A4, C4,
+};
+pub use scales::{CustomScale, Interval, IntervalScale, Scale, ScaleStepsError};
 
 /// Commonly used types and functions for music composition.
 ///
@@ -197,13 +242,18 @@ pub use scales::Scale;
 /// let piece = melody * bass(half(C4));
 /// ```
 pub mod prelude {
+    pub use crate::effects::*;
+    pub use crate::instrument_tools::comping::*;
+    pub use crate::instrument_tools::sequencer::*;
     pub use crate::instrument_tools::strings::*;
     pub use crate::note::chord::*;
+    pub use crate::note::progression::*;
     pub use crate::note::*;
+    pub use crate::scales::tet12::pitches::*;
     pub use crate::scales::*;
-    pub use crate::{Line, Piece};
+    pub use crate::{notes, zip_rhythm, Line, Piece, StretchError, VariationRules};
     pub use crate::{Note, NoteKind, NotePitch, REST};
-    pub use crate::{Scale, Tet12};
+    pub use crate::{PitchClassSet, Scale, Tet12};
     pub use crate::{A4, C4};
 }
 
@@ -211,4 +261,22 @@ pub mod prelude {
 pub use crate::interactive::InteractiveTui;
 
 #[cfg(any(feature = "wav-output", feature = "live-output"))]
-pub use crate::play::MusicPlayer;
+pub use crate::play::{
+    clear_decode_cache, Beat, BeatMap, LineStrip, Mixer, MusicOutput, MusicPlayer, OutputBackend, OutputNote, Playable,
+    RenderProgress,
+};
+
+#[cfg(feature = "live-output")]
+pub use crate::play::{ExternalClock, Levels, PlaybackHandle, VoiceLimit, VoiceStealing};
+
+#[cfg(feature = "midi-live")]
+pub use crate::play::{MidiChannel, MidiClock, MidiPlayback};
+
+#[cfg(feature = "wav-output")]
+pub use crate::play::{Clipping, NormalizationMode, OutputBus, RenderTail, ResamplingQuality};
+
+#[cfg(feature = "wav-output")]
+pub use crate::play::{SpectrogramOptions, StemGrouping};
+
+#[cfg(feature = "wav-output")]
+pub use crate::play::{compare_renders, AudioBuffer, BalanceProfile, Loudness, RenderDifference, RenderReport};
@@ -1,6 +1,75 @@
 use std::ops::Add;
 
-use crate::{Line, Note, NoteKind, NotePitch, Piece, Scale, Tet12, C4};
+use itertools::Itertools;
+
+use crate::{
+    format_note_name, rng::Rng, Key, LengthFluid, Line, Note, NoteKind, NoteLength, NoteNameOptions, NotePitch, Piece,
+    Scale, Spelling, Tet12, A4, C4,
+};
+
+/// Chord qualities recognized by [`Chord::symbol`], as semitones above the root, with their suffixes.
+const CHORD_SYMBOLS: [(&[u8], &str); 21] = [
+    (&[0], ""),
+    (&[0, 7], "5"),
+    (&[0, 4, 7], ""),
+    (&[0, 3, 7], "m"),
+    (&[0, 3, 6], "dim"),
+    (&[0, 4, 8], "aug"),
+    (&[0, 2, 7], "sus2"),
+    (&[0, 5, 7], "sus4"),
+    (&[0, 4, 7, 9], "6"),
+    (&[0, 3, 7, 9], "m6"),
+    (&[0, 4, 7, 10], "7"),
+    (&[0, 4, 7, 11], "maj7"),
+    (&[0, 3, 7, 10], "m7"),
+    (&[0, 3, 7, 11], "m(maj7)"),
+    (&[0, 3, 6, 10], "m7b5"),
+    (&[0, 3, 6, 9], "dim7"),
+    (&[0, 5, 7, 10], "7sus4"),
+    (&[0, 2, 4, 7], "add9"),
+    (&[0, 2, 4, 7, 10], "9"),
+    (&[0, 2, 4, 7, 11], "maj9"),
+    (&[0, 2, 3, 7, 10], "m9"),
+];
+
+/// Other ways of writing chord qualities, read by [`Chord::from_symbol`] as well as the suffixes
+/// in [`CHORD_SYMBOLS`].
+const CHORD_SYMBOL_ALIASES: [(&[u8], &[&str]); 15] = [
+    (&[0, 4, 7], &["maj", "M"]),
+    (&[0, 3, 7], &["min", "-"]),
+    (&[0, 3, 6], &["°", "o"]),
+    (&[0, 4, 8], &["+"]),
+    (&[0, 5, 7], &["sus"]),
+    (&[0, 4, 7, 11], &["M7", "Δ", "Δ7", "ma7"]),
+    (&[0, 3, 7, 10], &["min7", "-7"]),
+    (&[0, 3, 7, 11], &["mM7", "-maj7"]),
+    (&[0, 3, 6, 10], &["ø", "ø7", "m7-5"]),
+    (&[0, 3, 6, 9], &["°7", "o7"]),
+    (&[0, 5, 7, 10], &["7sus"]),
+    (&[0, 4, 8, 10], &["aug7", "+7", "7#5"]),
+    (&[0, 2, 4, 7], &["add2"]),
+    (&[0, 2, 3, 7], &["madd9", "m(add9)"]),
+    (&[0, 2, 4, 7, 9], &["6/9", "69"]),
+];
+
+/// The root, shape, and bass of a recognized chord. The root and bass are pitch classes.
+struct ChordQuality {
+    root: i16,
+    shape: &'static [u8],
+    suffix: &'static str,
+    bass: i16,
+}
+
+/// Returns the number of semitones from C4 to a pitch, rounded to the nearest semitone.
+#[expect(clippy::cast_possible_truncation, reason = "Audible pitches are within a few hundred semitones")]
+fn semitones_from_c4(pitch: NotePitch) -> i16 {
+    (12.0 * f32::log2(pitch.0 / C4.0)).round() as i16
+}
+
+/// Returns the pitch class of a pitch, from 0 (C) to 11 (B).
+fn pitch_class(pitch: NotePitch) -> i16 {
+    semitones_from_c4(pitch).rem_euclid(12)
+}
 
 /// Represents a musical chord - a collection of pitches played simultaneously.
 ///
@@ -68,6 +137,10 @@ impl Chord {
     /// then applies it to each pitch in the chord and combines them into
     /// a piece where all lines play at the same time.
     ///
+    /// The striker is also given the index of the voice it's playing, counting from the first
+    /// pitch of the chord, so different voices can play different rhythms. Since it can be
+    /// a closure, it can also use variables such as the timbre or rhythm to play with.
+    ///
     /// # Examples
     /// ```
     /// use symphoxy::prelude::*;
@@ -75,15 +148,315 @@ impl Chord {
     /// let chord = Chord::new([C4, NotePitch::new(329.63), NotePitch::new(392.00)]);
     ///
     /// // Strike all notes as quarter notes with piano timbre
-    /// let piece = chord.strike(|pitch| Line::from(piano(quarter(pitch))));
+    /// let piece = chord.strike(|pitch, _| Line::from(piano(quarter(pitch))));
     ///
     /// // Or with a more complex pattern
-    /// let piece = chord.strike(|pitch| {
+    /// let piece = chord.strike(|pitch, _| {
     ///     piano(quarter(pitch)) + piano(eighth(pitch)) + piano(eighth(REST))
     /// });
+    ///
+    /// // Sustain the lowest voice, and play the others with a captured rhythm
+    /// let rhythm = [eighth, eighth, quarter];
+    /// let piece = chord.strike(|pitch, voice| match voice {
+    ///     0 => Line::from(electric_guitar(half(pitch))),
+    ///     _ => electric_guitar(Line::from(rhythm.map(|length| length(pitch)).to_vec())),
+    /// });
+    /// assert_eq!(piece.0[0].notes.len(), 1);
+    /// assert_eq!(piece.0[1].notes.len(), 3);
+    /// ```
+    pub fn strike(&self, striker: impl Fn(NotePitch, usize) -> Line) -> Piece {
+        Piece(
+            self.0
+                .iter()
+                .enumerate()
+                .map(|(voice, &pitch)| striker(pitch, voice))
+                .collect(),
+        )
+    }
+
+    /// Plays each note in the chord with its own striker, combining them into a piece.
+    ///
+    /// The first striker plays the first pitch of the chord, the second striker plays the
+    /// second pitch, and so on. If there are more pitches than strikers, the last striker plays
+    /// the rest, so `[bass, upper]` strikes the first pitch with `bass` and all others with `upper`.
+    ///
+    /// # Parameters
+    /// - `strikers`: The function for each voice, which converts a pitch into a line of music
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let chord = Chord::from_degrees(&MajorScale(C3), &[1, 5, 8, 10]);
+    ///
+    /// // A sustained bass note under rhythmic upper voices
+    /// let strikers: [&dyn Fn(NotePitch) -> Line; 2] = [
+    ///     &|pitch| Line::from(piano(whole(pitch))),
+    ///     &|pitch| piano((quarter(REST) + quarter(pitch)) * 2),
+    /// ];
+    /// let piece = chord.strike_voices(&strikers);
+    ///
+    /// assert_eq!(piece.0.len(), 4);
+    /// assert_eq!(piece.0[0].notes.len(), 1);
+    /// assert_eq!(piece.0[3].notes.len(), 4);
+    /// ```
+    pub fn strike_voices(&self, strikers: &[impl Fn(NotePitch) -> Line]) -> Piece {
+        Piece(
+            self.0
+                .iter()
+                .enumerate()
+                .filter_map(|(voice, &pitch)| {
+                    let striker = strikers.get(voice).or(strikers.last())?;
+                    Some(striker(pitch))
+                })
+                .collect(),
+        )
+    }
+
+    /// Creates a block chord followed by an arpeggio of it, for hearing how the voicing sounds.
+    ///
+    /// Every pitch is played together for `length`, then each pitch is played on its own for
+    /// `length`, from the first pitch of the chord to the last. Each voice is its own line, and
+    /// notes have the default timbre, which can be changed with functions like [`piano`](crate::prelude::piano).
+    ///
+    /// # Parameters
+    /// - `length`: How long the block chord, and each note of the arpeggio, lasts
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let chord = Chord::from_degrees(&MajorScale(C4), &[1, 3, 5]);
+    /// let demo = piano(chord.demo(quarter(C4).0));
+    ///
+    /// // A quarter for the block chord, then three for the arpeggio
+    /// assert_eq!(demo.0.len(), 3);
+    /// assert_eq!(demo.length(), (quarter(C4) * 4).length());
+    /// ```
+    pub fn demo(&self, length: NoteLength) -> Piece {
+        let voices = self.0.len();
+        let rests = |count: usize| std::iter::repeat_n(Note(length, NoteKind::Rest), count);
+        Piece(
+            self.0
+                .iter()
+                .enumerate()
+                .map(|(voice, &pitch)| {
+                    let note = pitch.with_length(length);
+                    let notes = std::iter::once(note.clone())
+                        .chain(rests(voice))
+                        .chain(std::iter::once(note))
+                        .chain(rests(voices.saturating_sub(voice).saturating_sub(1)))
+                        .collect::<Vec<_>>();
+                    Line::from(notes)
+                })
+                .collect(),
+        )
+    }
+
+    /// Plays the pitches of the chord one at a time, in the order given by an arpeggio pattern.
+    ///
+    /// The pitches are arranged from lowest to highest, whatever order the chord has them in,
+    /// and each is played with `length_fn`, such as [`eighth`](crate::eighth). Notes have the
+    /// default timbre, which can be changed with functions like [`piano`](crate::prelude::piano).
+    /// Repeat the line for a longer arpeggio.
+    ///
+    /// # Parameters
+    /// - `pattern`: The order the pitches are played in
+    /// - `length_fn`: The function which gives each pitch its length
+    ///
+    /// # Examples
     /// ```
-    pub fn strike(&self, striker: fn(NotePitch) -> Line) -> Piece {
-        Piece(self.0.iter().map(|&pitch| striker(pitch)).collect())
+    /// use symphoxy::prelude::*;
+    ///
+    /// let chord = Chord::new([G4, C4, E4]);
+    ///
+    /// assert_eq!(chord.arpeggiate(ArpPattern::Up, eighth), eighth(C4) + eighth(E4) + eighth(G4));
+    /// assert_eq!(chord.arpeggiate(ArpPattern::UpDown, eighth), eighth(C4) + eighth(E4) + eighth(G4) + eighth(E4));
+    ///
+    /// // Indices past the top of the chord carry on an octave up
+    /// let alberti = chord.arpeggiate(ArpPattern::Custom(vec![0, 2, 1, 2, 3]), sixteenth);
+    /// assert_eq!(alberti.notes[4], sixteenth(C4.octave(1)));
+    ///
+    /// let arpeggio = piano(chord.arpeggiate(ArpPattern::Random { seed: 7 }, eighth) * 4);
+    /// assert_eq!(arpeggio.notes.len(), 12);
+    /// ```
+    pub fn arpeggiate(&self, pattern: ArpPattern, length_fn: impl Fn(NotePitch) -> Note) -> Line {
+        let pitches = self.sorted().0;
+        let count = pitches.len();
+        let indices: Vec<usize> = match pattern {
+            ArpPattern::Up => (0..count).collect(),
+            ArpPattern::Down => (0..count).rev().collect(),
+            ArpPattern::UpDown => (0..count).chain((1..count.saturating_sub(1)).rev()).collect(),
+            ArpPattern::Random { seed } => {
+                let mut rng = Rng::new(seed);
+                (0..count).map(|_| rng.below(count)).collect()
+            }
+            ArpPattern::Custom(indices) => indices,
+        };
+        Line::from(
+            indices
+                .into_iter()
+                .filter_map(|index| {
+                    let pitch = pitches.get(index.checked_rem(count)?)?;
+                    let octave = i32::try_from(index.checked_div(count)?).unwrap_or(i32::MAX);
+                    Some(length_fn(pitch.octave(octave)))
+                })
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Returns the root of the chord.
+    ///
+    /// For chords recognized by [`Chord::symbol`], this is the lowest pitch of the chord's root,
+    /// even if it isn't in the bass, so the root of C/E is C. For other chords, it's the lowest
+    /// pitch. Returns `None` if the chord is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let first_inversion = Chord::new([E4, G4, C4.octave(1)]);
+    /// assert_eq!(first_inversion.root(), Some(C4.octave(1)));
+    ///
+    /// assert_eq!(Chord::new([]).root(), None);
+    /// ```
+    pub fn root(&self) -> Option<NotePitch> {
+        let pitches = self.sorted().0;
+        self.quality()
+            .and_then(|quality| {
+                pitches
+                    .iter()
+                    .copied()
+                    .find(|&pitch| pitch_class(pitch) == quality.root)
+            })
+            .or_else(|| pitches.first().copied())
+    }
+
+    /// Returns the root of the chord, moved down by octaves until it's no higher than the
+    /// chord's lowest pitch, for playing under the chord in a bass line.
+    pub(crate) fn bass_root(&self) -> Option<NotePitch> {
+        let lowest = self.sorted().0.first().copied()?;
+        let mut root = self.root()?;
+        while root.0 > lowest.0 {
+            root = root.octave(-1);
+        }
+        Some(root)
+    }
+
+    /// Inverts the chord `n` times, each time moving its lowest pitch up an octave.
+    ///
+    /// The first inversion of a triad puts its third in the bass, and the second its fifth.
+    /// The pitches of the inverted chord are in order from lowest to highest.
+    ///
+    /// # Parameters
+    /// - `n`: How many times to invert the chord. Inverting a chord as many times as it has
+    ///   pitches gives the chord an octave higher.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let c_major = Chord::from_degrees(&MajorScale(C4), &[1, 3, 5]);
+    /// assert_eq!(c_major.invert(1).symbol().as_deref(), Some("C/E"));
+    /// assert_eq!(c_major.invert(2).symbol().as_deref(), Some("C/G"));
+    /// assert_eq!(c_major.invert(3), Chord::new(c_major.iter().map(|pitch| pitch.octave(1))));
+    /// ```
+    pub fn invert(&self, n: usize) -> Self {
+        let mut pitches = self.sorted().0;
+        if pitches.is_empty() {
+            return Chord(pitches);
+        }
+        for _ in 0..n {
+            let lowest = pitches.remove(0);
+            pitches.push(lowest.octave(1));
+        }
+        Chord(pitches)
+    }
+
+    /// Drops the second-highest pitch of the chord down an octave, for a "drop 2" voicing.
+    ///
+    /// Drop 2 voicings spread close-position chords out, and are common on guitar and in jazz
+    /// piano. Chords with fewer than two pitches are returned unchanged. The pitches of the new
+    /// chord are in order from lowest to highest.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let c_major_seventh = Chord::from_degrees(&MajorScale(C4), &[1, 3, 5, 7]);
+    /// let [c, e, g, b] = [0, 1, 2, 3].map(|voice| c_major_seventh[voice]);
+    ///
+    /// assert_eq!(c_major_seventh.drop2(), Chord::new([g.octave(-1), c, e, b]));
+    /// ```
+    pub fn drop2(&self) -> Self {
+        let mut pitches = self.sorted().0;
+        if let Some(index) = pitches.len().checked_sub(2) {
+            pitches[index] = pitches[index].octave(-1);
+        }
+        Chord(pitches).sorted()
+    }
+
+    /// Spreads the chord into an open voicing, moving every other pitch up an octave.
+    ///
+    /// The lowest pitch stays where it is, and the second, fourth, and so on, counting up from
+    /// it, move up an octave, so a close-position C major triad becomes C, G, and the E above.
+    /// The pitches of the new chord are in order from lowest to highest.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let c_major = Chord::from_degrees(&MajorScale(C4), &[1, 3, 5]);
+    /// let [c, e, g] = [0, 1, 2].map(|voice| c_major[voice]);
+    ///
+    /// assert_eq!(c_major.spread_over_octaves(), Chord::new([c, g, e.octave(1)]));
+    /// ```
+    pub fn spread_over_octaves(&self) -> Self {
+        Chord(
+            self.sorted()
+                .0
+                .into_iter()
+                .enumerate()
+                .map(|(voice, pitch)| if voice % 2 == 1 { pitch.octave(1) } else { pitch })
+                .collect(),
+        )
+        .sorted()
+    }
+
+    /// Adds the root of the chord an octave below its lowest pitch, to fill out the bass.
+    ///
+    /// The root is found with [`Chord::root`]. The pitches of the new chord are in order from
+    /// lowest to highest.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let c_major = Chord::from_degrees(&MajorScale(C4), &[1, 3, 5]);
+    /// let with_bass = c_major.add_octave_bass();
+    ///
+    /// assert_eq!(with_bass.len(), 4);
+    /// assert_eq!(with_bass[0], c_major[0].octave(-1));
+    ///
+    /// // The root of C/E is C, so it goes below the E
+    /// assert_eq!(c_major.invert(1).add_octave_bass()[0], c_major[0]);
+    /// ```
+    pub fn add_octave_bass(&self) -> Self {
+        let mut pitches = self.sorted().0;
+        let (Some(root), Some(&lowest)) = (self.root(), pitches.first()) else {
+            return Chord(pitches);
+        };
+        let mut bass = root.octave(-1);
+        while bass.0 >= lowest.0 && bass.0 > 0.0 {
+            bass = bass.octave(-1);
+        }
+        pitches.insert(0, bass);
+        Chord(pitches)
+    }
+
+    /// Returns the chord with its pitches in order from lowest to highest.
+    fn sorted(&self) -> Self {
+        Chord(self.0.iter().copied().sorted_by(|a, b| a.0.total_cmp(&b.0)).collect())
     }
 
     /// Transposes the chord to a new target pitch.
@@ -98,6 +471,341 @@ impl Chord {
         Chord(self.0.iter().map(|&pitch| NotePitch(pitch.0 * offset)).collect())
     }
 
+    /// Creates a chord from a chord symbol, such as `"Am7"`, `"F#m7b5"`, or `"C/E"`.
+    ///
+    /// The symbol is a root, such as `C`, `F#`, or `Bb`, followed by the chord's quality:
+    /// triads (`""`, `"m"`, `"dim"`, `"aug"`), suspended chords (`"sus2"`, `"sus4"`, `"7sus4"`),
+    /// sixths (`"6"`, `"6/9"`), sevenths (`"7"`, `"maj7"`, `"m7"`, `"m7b5"`, `"dim7"`), ninths, and added notes
+    /// (`"add9"`). Common alternatives such as `"-7"`, `"Δ7"`, and `"ø"` are read too. A slash
+    /// and another note name after the symbol puts that note in the bass.
+    ///
+    /// The root is placed in the given octave, with the other chord tones above it. The bass
+    /// note of a slash chord is placed below the root.
+    ///
+    /// # Parameters
+    /// - `symbol`: The chord symbol
+    /// - `octave`: The octave of the root, where `4` is the octave starting at middle C
+    ///
+    /// # Errors
+    /// Returns an error if the root or bass isn't a note name, or if the chord's quality isn't
+    /// one listed above.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// // A3, C4, E4, and G4
+    /// let a_minor_seventh = Chord::from_symbol("Am7", 3).unwrap();
+    /// assert_eq!(a_minor_seventh.len(), 4);
+    /// assert_eq!(a_minor_seventh.symbol().as_deref(), Some("Am7"));
+    ///
+    /// let first_inversion = Chord::from_symbol("C/E", 4).unwrap();
+    /// assert_eq!(first_inversion.symbol().as_deref(), Some("C/E"));
+    ///
+    /// assert_eq!(Chord::from_symbol("F#m7b5", 4).unwrap().symbol().as_deref(), Some("F#m7b5"));
+    /// assert_eq!(Chord::from_symbol("C6/9", 4).unwrap().len(), 5);
+    /// assert!(Chord::from_symbol("Hmaj7", 4).is_err());
+    /// ```
+    pub fn from_symbol(symbol: &str, octave: i32) -> Result<Self, ParseChordError> {
+        let error = || ParseChordError(symbol.to_string());
+        let trimmed = symbol.trim();
+        // Symbols such as "6/9" have slashes of their own, so only a note name counts as a bass
+        let (chord, bass) = match trimmed.rsplit_once('/') {
+            Some((chord, bass)) if bass.starts_with(|letter: char| letter.is_ascii_alphabetic()) => (chord, Some(bass)),
+            _ => (trimmed, None),
+        };
+
+        let root_length = chord
+            .char_indices()
+            .skip(1)
+            .find(|(_, char)| !matches!(char, '#' | '♯' | 'b' | '♭'))
+            .map_or(chord.len(), |(index, _)| index);
+        let (root, suffix) = chord.split_at(root_length);
+        let root = NotePitch::from_name(&format!("{root}{octave}")).ok_or_else(error)?;
+
+        // A lone note has no suffix either, but a symbol with no suffix is a major triad
+        let shape = CHORD_SYMBOLS
+            .iter()
+            .find(|(shape, known)| shape.len() > 1 && *known == suffix)
+            .map(|(shape, _)| *shape)
+            .or_else(|| {
+                CHORD_SYMBOL_ALIASES
+                    .iter()
+                    .find(|(_, aliases)| aliases.contains(&suffix))
+                    .map(|(shape, _)| *shape)
+            })
+            .ok_or_else(error)?;
+        let mut pitches: Vec<NotePitch> = shape.iter().map(|&offset| root.semitone(i16::from(offset))).collect();
+
+        if let Some(bass) = bass {
+            let bass = NotePitch::from_name(&format!("{bass}{octave}")).ok_or_else(error)?;
+            let bass = if bass.0 < root.0 { bass } else { bass.octave(-1) };
+            pitches.insert(0, bass);
+        }
+        Ok(Chord(pitches))
+    }
+
+    /// Returns the chord symbol of the chord, such as `"Am7"` or `"C/E"`.
+    ///
+    /// Octave doublings and the order of the pitches don't matter, except that the lowest pitch
+    /// is preferred as the root. If the root isn't the lowest pitch, the symbol is written as
+    /// a slash chord over the lowest pitch. Returns `None` if the chord is empty, or isn't a
+    /// triad, sixth, seventh, ninth, or suspended chord.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let scale = MajorScale(C4);
+    /// assert_eq!(Chord::from_degrees(&scale, &[1, 3, 5]).symbol().as_deref(), Some("C"));
+    /// assert_eq!(Chord::from_degrees(&scale, &[2, 4, 6, 8]).symbol().as_deref(), Some("Dm7"));
+    /// assert_eq!(Chord::from_degrees(&scale, &[5, 7, 9, 11]).symbol().as_deref(), Some("G7"));
+    ///
+    /// // First inversion
+    /// assert_eq!(Chord::from_degrees(&scale, &[3, 5, 8]).symbol().as_deref(), Some("C/E"));
+    ///
+    /// assert_eq!(Chord::new([C4, C4.semitone(1), C4.semitone(2)]).symbol(), None);
+    /// ```
+    ///
+    /// See [`Chord::from_symbol`] to create a chord from its symbol.
+    pub fn symbol(&self) -> Option<String> {
+        self.spelled_symbol(Spelling::Sharps)
+    }
+
+    /// Returns the chord symbol of the chord, like [`Chord::symbol`], with its notes spelled as
+    /// the key spells them, such as `"Eb"` rather than `"D#"` in B♭ major.
+    ///
+    /// # Parameters
+    /// - `key`: The key whose signature decides the spelling
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let chord = Chord::new([DS4, G4, AS4]);
+    /// assert_eq!(chord.symbol_in_key(&Key::new(AS3, Mode::Major)).as_deref(), Some("Eb"));
+    /// assert_eq!(chord.symbol_in_key(&Key::new(GS4, Mode::Minor)).as_deref(), Some("D#"));
+    /// ```
+    pub fn symbol_in_key(&self, key: &Key) -> Option<String> {
+        self.spelled_symbol(key.note_names().spelling)
+    }
+
+    /// Returns the chord symbol of the chord, with its notes spelled with sharps or flats.
+    pub(crate) fn spelled_symbol(&self, spelling: Spelling) -> Option<String> {
+        let ChordQuality { root, suffix, bass, .. } = self.quality()?;
+
+        let options = NoteNameOptions {
+            spelling,
+            ..NoteNameOptions::default()
+        };
+        let mut symbol = format!("{}{suffix}", format_note_name(C4.semitone(root), A4, &options));
+        if root != bass {
+            symbol.push('/');
+            symbol.push_str(&format_note_name(C4.semitone(bass), A4, &options));
+        }
+        Some(symbol)
+    }
+
+    /// Returns the Roman numeral of the chord in a key, such as `"V7"` or `"ii"`, with figures
+    /// for its inversion.
+    ///
+    /// The numeral is uppercase for major and augmented chords, and lowercase for minor and
+    /// diminished ones. Inversions are marked with figured bass: `6` and `64` for triads, and
+    /// `65`, `43`, and `42` for seventh chords. Returns `None` if the chord isn't a triad or
+    /// seventh chord, or if its root isn't in the key.
+    ///
+    /// # Parameters
+    /// - `key`: The scale the chord is analysed in
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let key = MajorScale(C4);
+    /// assert_eq!(Chord::from_degrees(&key, &[1, 3, 5]).roman_numeral(&key).as_deref(), Some("I"));
+    /// assert_eq!(Chord::from_degrees(&key, &[2, 4, 6]).roman_numeral(&key).as_deref(), Some("ii"));
+    /// assert_eq!(Chord::from_degrees(&key, &[7, 9, 11]).roman_numeral(&key).as_deref(), Some("vii°"));
+    ///
+    /// // G7 over B is the dominant seventh in first inversion
+    /// assert_eq!(Chord::from_degrees(&key, &[7, 9, 11, 12]).roman_numeral(&key).as_deref(), Some("V65"));
+    ///
+    /// // C minor has no root in the key of D major
+    /// assert_eq!(Chord::new([C4, DS4, G4]).roman_numeral(&MajorScale(D4)), None);
+    /// ```
+    ///
+    /// See [`Chord::from_roman`] to create a chord from its Roman numeral.
+    pub fn roman_numeral(&self, key: &impl Scale) -> Option<String> {
+        self.roman_numeral_in(&key.get_degrees([1, 2, 3, 4, 5, 6, 7]))
+    }
+
+    /// Creates a chord from its Roman numeral in a key, such as `"V7"`, `"ii"`, or `"bVII"`.
+    ///
+    /// This reads the numerals written by [`Chord::roman_numeral`]. Uppercase numerals are
+    /// major chords and lowercase numerals are minor chords, unless they're marked `°`
+    /// (diminished), `ø` (half-diminished), or `+` (augmented). A `b` or `#` before the numeral
+    /// lowers or raises the root by a semitone, for chords borrowed from outside the key.
+    ///
+    /// Figures after the numeral give the inversion: `6` and `64` for triads, and `7`, `65`,
+    /// `43`, and `42` for seventh chords. Seventh chords are dominant sevenths when uppercase,
+    /// and minor sevenths when lowercase, and `M7` (or `maj7`) makes the seventh major.
+    ///
+    /// # Parameters
+    /// - `key`: The scale the numeral is read in. The root is the numeral's degree of the scale.
+    /// - `numeral`: The Roman numeral
+    ///
+    /// # Errors
+    /// Returns an error if the numeral isn't one described above.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let key = MajorScale(C4);
+    /// assert_eq!(Chord::from_roman(&key, "vi").unwrap().symbol().as_deref(), Some("Am"));
+    /// assert_eq!(Chord::from_roman(&key, "V7").unwrap().symbol().as_deref(), Some("G7"));
+    /// assert_eq!(Chord::from_roman(&key, "IM7").unwrap().symbol().as_deref(), Some("Cmaj7"));
+    /// assert_eq!(Chord::from_roman(&key, "bVII").unwrap().symbol().as_deref(), Some("A#"));
+    ///
+    /// // Numerals round-trip through `roman_numeral`
+    /// let dominant = Chord::from_roman(&key, "V65").unwrap();
+    /// assert_eq!(dominant.roman_numeral(&key).as_deref(), Some("V65"));
+    ///
+    /// assert!(Chord::from_roman(&key, "VIII").is_err());
+    /// ```
+    pub fn from_roman(key: &impl Scale, numeral: &str) -> Result<Self, ParseChordError> {
+        let error = || ParseChordError(numeral.to_string());
+        let text = numeral.trim();
+
+        let (alteration, text) = match text.strip_prefix(|char| matches!(char, 'b' | '♭')) {
+            Some(text) => (-1, text),
+            None => match text.strip_prefix(|char| matches!(char, '#' | '♯')) {
+                Some(text) => (1, text),
+                None => (0, text),
+            },
+        };
+        let numeral_length = text
+            .find(|char| !matches!(char, 'I' | 'V' | 'i' | 'v'))
+            .unwrap_or(text.len());
+        let (letters, suffix) = text.split_at(numeral_length);
+        let minor = letters.chars().all(|char| char.is_ascii_lowercase());
+        if !minor && !letters.chars().all(|char| char.is_ascii_uppercase()) {
+            return Err(error());
+        }
+        let degree = ["I", "II", "III", "IV", "V", "VI", "VII"]
+            .iter()
+            .position(|known| known.eq_ignore_ascii_case(letters))
+            .ok_or_else(error)?;
+
+        let (mark, figures) = ["°", "o", "ø", "+", "maj", "M"]
+            .iter()
+            .find_map(|mark| Some((*mark, suffix.strip_prefix(mark)?)))
+            .unwrap_or(("", suffix));
+        // Half-diminished chords are always sevenths, so their 7 can be left out
+        let figures = if mark == "ø" && figures.is_empty() {
+            "7"
+        } else {
+            figures
+        };
+        let (seventh, inversion) = match figures {
+            "" => (false, 0),
+            "6" => (false, 1),
+            "64" => (false, 2),
+            "7" => (true, 0),
+            "65" => (true, 1),
+            "43" => (true, 2),
+            "42" => (true, 3),
+            _ => return Err(error()),
+        };
+        let shape: &[i16] = match (mark, minor, seventh) {
+            ("", false, false) => &[0, 4, 7],
+            ("", true, false) => &[0, 3, 7],
+            ("°" | "o", _, false) => &[0, 3, 6],
+            ("+", _, false) => &[0, 4, 8],
+            ("", false, true) => &[0, 4, 7, 10],
+            ("", true, true) => &[0, 3, 7, 10],
+            ("maj" | "M", false, true) => &[0, 4, 7, 11],
+            ("maj" | "M", true, true) => &[0, 3, 7, 11],
+            ("°" | "o", _, true) => &[0, 3, 6, 9],
+            ("ø", _, true) => &[0, 3, 6, 10],
+            ("+", _, true) => &[0, 4, 8, 10],
+            _ => return Err(error()),
+        };
+
+        let degree = isize::try_from(degree).map_err(|_| error())?.saturating_add(1);
+        let root = key.get_degree(degree).semitone(alteration);
+        let mut pitches: Vec<NotePitch> = shape.iter().map(|&offset| root.semitone(offset)).collect();
+        for pitch in pitches.iter_mut().take(inversion) {
+            *pitch = pitch.octave(1);
+        }
+        pitches.rotate_left(inversion);
+        Ok(Chord(pitches))
+    }
+
+    /// Returns the Roman numeral of the chord in a key given by the pitches of its seven degrees.
+    pub(crate) fn roman_numeral_in(&self, degrees: &[NotePitch]) -> Option<String> {
+        let ChordQuality { root, shape, bass, .. } = self.quality()?;
+        let degree = degrees.iter().position(|&pitch| pitch_class(pitch) == root)?;
+        let numeral = ["I", "II", "III", "IV", "V", "VI", "VII"].get(degree)?;
+
+        let (minor, mark, seventh) = match shape {
+            [0, 4, 7] => (false, "", false),
+            [0, 3, 7] => (true, "", false),
+            [0, 3, 6] => (true, "°", false),
+            [0, 4, 8] => (false, "+", false),
+            [0, 4, 7, 10] => (false, "", true),
+            [0, 4, 7, 11] => (false, "M", true),
+            [0, 3, 7, 10] => (true, "", true),
+            [0, 3, 6, 10] => (true, "ø", true),
+            [0, 3, 6, 9] => (true, "°", true),
+            _ => return None,
+        };
+
+        // Which chord tone is in the bass
+        let figures = match (bass.saturating_sub(root).rem_euclid(12), seventh) {
+            (0, false) => "",
+            (3 | 4, false) => "6",
+            (_, false) => "64",
+            (0, true) => "7",
+            (3 | 4, true) => "65",
+            (6..=8, true) => "43",
+            (_, true) => "42",
+        };
+
+        let numeral = if minor {
+            numeral.to_lowercase()
+        } else {
+            (*numeral).to_string()
+        };
+        Some(format!("{numeral}{mark}{figures}"))
+    }
+
+    /// Finds the root, shape, and bass of the chord, as pitch classes, if it's a chord
+    /// recognized by [`Chord::symbol`].
+    fn quality(&self) -> Option<ChordQuality> {
+        let semitones: Vec<i16> = self.0.iter().map(|&pitch| semitones_from_c4(pitch)).sorted().collect();
+        let bass = semitones.first()?.rem_euclid(12);
+        let pitch_classes: Vec<i16> = semitones
+            .iter()
+            .map(|semitone| semitone.rem_euclid(12))
+            .unique()
+            .collect();
+
+        pitch_classes.iter().find_map(|&root| {
+            let intervals: Vec<u8> = pitch_classes
+                .iter()
+                .map(|pitch_class| u8::try_from(pitch_class.saturating_sub(root).rem_euclid(12)).unwrap_or_default())
+                .sorted()
+                .collect();
+            let (shape, suffix) = CHORD_SYMBOLS.iter().find(|(shape, _)| *shape == intervals.as_slice())?;
+            Some(ChordQuality {
+                root,
+                shape,
+                suffix,
+                bass,
+            })
+        })
+    }
+
     /// Creates a chord from a shape defined by semitone offsets from C4.
     /// C4 is already included, so only the offsets are needed.
     pub fn shape_from_semitone_offsets<const I: usize>(semitones: [u8; I]) -> Self {
@@ -135,29 +843,35 @@ impl ChordFluid for Note {
 
     fn with_chord_shape(self, chord_shape: &Chord) -> Self::Output {
         match self.1 {
-            NoteKind::Rest => Piece(vec![Line {
-                notes: vec![self],
-                pickup: vec![],
-                hold_pickup: false,
-            }]),
-            NoteKind::Pitched { pitch, timbre, volume } => {
+            NoteKind::Rest => Piece(vec![Line::from(self)]),
+            NoteKind::Pitched {
+                pitch,
+                timbre,
+                volume,
+                envelope,
+                pan,
+                probability,
+                ratchet,
+            } => {
                 let chord = pitch.with_chord_shape(chord_shape);
 
                 Piece(
                     chord
                         .0
                         .into_iter()
-                        .map(|note_pitch| Line {
-                            notes: vec![Note(
+                        .map(|note_pitch| {
+                            Line::from(Note(
                                 self.0,
                                 NoteKind::Pitched {
                                     pitch: note_pitch,
-                                    timbre,
+                                    timbre: timbre.clone(),
                                     volume,
+                                    envelope,
+                                    pan,
+                                    probability,
+                                    ratchet,
                                 },
-                            )],
-                            pickup: vec![],
-                            hold_pickup: false,
+                            ))
                         })
                         .collect(),
                 )
@@ -178,6 +892,29 @@ impl ChordFluid for Line {
     }
 }
 
+/// The order [`Chord::arpeggiate`] plays the pitches of a chord in.
+///
+/// Pitches are counted from 0, the lowest pitch of the chord, upwards.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ArpPattern {
+    /// From the lowest pitch to the highest
+    Up,
+    /// From the highest pitch to the lowest
+    Down,
+    /// Up from the lowest pitch to the highest, then back down, without repeating the highest
+    /// or lowest, so the pattern can be repeated smoothly
+    UpDown,
+    /// As many pitches as the chord has, each chosen at random. The same seed always gives
+    /// the same order.
+    Random {
+        /// The seed the order is chosen with
+        seed: u64,
+    },
+    /// The pitches with the given indices, in order. Indices past the highest pitch carry on
+    /// an octave up, so in a triad, `3` is the lowest pitch an octave higher.
+    Custom(Vec<usize>),
+}
+
 // From implementations for ergonomic chord creation
 impl From<Vec<NotePitch>> for Chord {
     fn from(pitches: Vec<NotePitch>) -> Self {
@@ -233,3 +970,15 @@ impl Display for Chord {
         }
     }
 }
+
+/// The error returned when a [`Chord`] can't be created from a chord symbol.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseChordError(pub String);
+
+impl Display for ParseChordError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "invalid chord symbol: \"{}\"", self.0)
+    }
+}
+
+impl std::error::Error for ParseChordError {}
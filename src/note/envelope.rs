@@ -0,0 +1,194 @@
+use std::time::Duration;
+
+use crate::{Line, Note, NoteKind, Piece};
+
+/// An ADSR (attack, decay, sustain, release) volume envelope.
+///
+/// An envelope shapes the volume of a note over time:
+/// - **Attack**: The time taken to rise from silence to full volume when the note starts
+/// - **Decay**: The time taken to fall (exponentially) from full volume to the sustain level
+/// - **Sustain**: The volume level held until the note ends, from `0.0` to `1.0`
+/// - **Release**: The time taken to fade to silence after the note ends
+///
+/// The release happens *after* the note's length, so notes with a release ring slightly
+/// into whatever follows them.
+///
+/// Every built-in synthesized timbre has a default envelope (see [`Timbre::default_envelope`]),
+/// which can be overridden per note with `envelope()`.
+///
+/// [`Timbre::default_envelope`]: crate::Timbre::default_envelope
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// // A plucky sound: instant attack, quick decay, no sustain
+/// let pluck = Envelope::from_millis(2, 150, 0.0, 30);
+/// let plucked_line = piano(quarter(C4) + quarter(A4)).envelope(pluck);
+///
+/// // A slow swell
+/// let swell = Envelope::from_millis(800, 0, 1.0, 400);
+/// let pad = sine(whole(C4)).envelope(swell);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Envelope {
+    /// Time taken to rise from silence to full volume
+    pub attack: Duration,
+    /// Time taken to fall from full volume to the sustain level
+    pub decay: Duration,
+    /// Volume level held until the note ends (0.0 = silent, 1.0 = full volume)
+    pub sustain: f32,
+    /// Time taken to fade to silence after the note ends
+    pub release: Duration,
+}
+
+impl Envelope {
+    /// Creates a new envelope.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let envelope = Envelope::new(
+    ///     Duration::from_millis(10),
+    ///     Duration::from_millis(200),
+    ///     0.6,
+    ///     Duration::from_millis(100),
+    /// );
+    /// ```
+    pub fn new(attack: Duration, decay: Duration, sustain: f32, release: Duration) -> Self {
+        Envelope {
+            attack,
+            decay,
+            sustain,
+            release,
+        }
+    }
+
+    /// Creates a new envelope, with the attack, decay, and release given in milliseconds.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let envelope = Envelope::from_millis(10, 200, 0.6, 100);
+    /// assert_eq!(envelope.release.as_millis(), 100);
+    /// ```
+    pub fn from_millis(attack_ms: u64, decay_ms: u64, sustain: f32, release_ms: u64) -> Self {
+        Envelope::new(
+            Duration::from_millis(attack_ms),
+            Duration::from_millis(decay_ms),
+            sustain,
+            Duration::from_millis(release_ms),
+        )
+    }
+
+    /// Returns the volume multiplier of the envelope at a point in time.
+    ///
+    /// # Parameters
+    /// - `elapsed`: Time since the note started
+    /// - `note_duration`: How long the note is held before it is released
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let envelope = Envelope::from_millis(100, 0, 0.5, 100);
+    /// let note_duration = Duration::from_millis(1000);
+    ///
+    /// assert_eq!(envelope.amplitude_at(Duration::ZERO, note_duration), 0.0); // Start of the attack
+    /// assert!((envelope.amplitude_at(Duration::from_millis(50), note_duration) - 0.5).abs() < 0.001);
+    /// assert_eq!(envelope.amplitude_at(Duration::from_millis(500), note_duration), 0.5); // Sustaining
+    /// assert_eq!(envelope.amplitude_at(Duration::from_millis(1100), note_duration), 0.0); // Fully released
+    /// ```
+    pub fn amplitude_at(&self, elapsed: Duration, note_duration: Duration) -> f32 {
+        self.amplitude_at_secs(elapsed.as_secs_f32(), note_duration.as_secs_f32())
+    }
+
+    /// Like `amplitude_at`, but with times in seconds, so it can be called for every sample cheaply.
+    pub(crate) fn amplitude_at_secs(&self, elapsed: f32, note_duration: f32) -> f32 {
+        if elapsed < note_duration {
+            return self.held_amplitude_at(elapsed);
+        }
+
+        let release = self.release.as_secs_f32();
+        if release <= 0.0 {
+            return 0.0;
+        }
+        let released_fraction = ((elapsed - note_duration) / release).clamp(0.0, 1.0);
+        self.held_amplitude_at(note_duration) * (1.0 - released_fraction)
+    }
+
+    /// The amplitude of the attack, decay and sustain stages, before the note is released.
+    fn held_amplitude_at(&self, elapsed: f32) -> f32 {
+        let attack = self.attack.as_secs_f32();
+        let decay = self.decay.as_secs_f32();
+
+        if elapsed < attack {
+            return elapsed / attack;
+        }
+
+        if elapsed < attack + decay {
+            // Exponential decay, reaching 0.1% of the way to the sustain level by the end of the decay
+            let decayed_fraction = (elapsed - attack) / decay;
+            return self.sustain + (1.0 - self.sustain) * (-6.9 * decayed_fraction).exp();
+        }
+
+        self.sustain
+    }
+}
+
+impl Note {
+    /// Creates a new note with the specified volume envelope.
+    ///
+    /// This replaces the default envelope of the note's timbre. For rests, this has no effect.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let staccato = piano(quarter(C4)).envelope(Envelope::from_millis(2, 80, 0.0, 20));
+    /// ```
+    pub fn envelope(&self, envelope: Envelope) -> Note {
+        let mut note = self.clone();
+        if let NoteKind::Pitched {
+            envelope: note_envelope,
+            ..
+        } = &mut note.1
+        {
+            *note_envelope = Some(envelope);
+        }
+
+        note
+    }
+}
+
+impl Line {
+    /// Creates a new line with all notes set to the specified volume envelope.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let line = electric_guitar(quarter(C4) + quarter(A4)).envelope(Envelope::from_millis(5, 300, 0.4, 50));
+    /// ```
+    pub fn envelope(&self, envelope: Envelope) -> Line {
+        self.map_notes(|note| note.envelope(envelope))
+    }
+}
+
+impl Piece {
+    /// Creates a new piece with all notes set to the specified volume envelope.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let piece = (piano(quarter(C4)) * bass(quarter(C4))).envelope(Envelope::from_millis(5, 0, 1.0, 200));
+    /// ```
+    pub fn envelope(&self, envelope: Envelope) -> Piece {
+        Piece(self.0.iter().map(|line| line.envelope(envelope)).collect())
+    }
+}
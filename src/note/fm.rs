@@ -0,0 +1,200 @@
+use std::{
+    hash::{Hash, Hasher},
+    time::Duration,
+};
+
+use crate::{note::float_key, Envelope};
+
+/// A modulator of an [`FmPatch`], a sine wave which bends the phase of the carrier.
+///
+/// Each modulator adds sidebands around the carrier, spaced by the modulator's frequency.
+/// Whole-number ratios give harmonic tones, while other ratios give clangorous, bell-like ones.
+/// The higher the index, the more sidebands, and the brighter the tone.
+#[derive(Clone, Copy, Debug)]
+pub struct FmModulator {
+    /// The modulator's frequency, as a multiple of the note's frequency
+    pub ratio: f32,
+    /// How strongly the modulator bends the carrier at the start of the note
+    pub index: f32,
+    /// How long the index takes to fall to about a third of its starting value. The index
+    /// keeps falling for as long as the note sounds, so the tone mellows over time.
+    /// If this is zero, the index stays the same.
+    pub decay: Duration,
+}
+
+impl FmModulator {
+    /// Creates a modulator whose index stays the same for the whole note.
+    ///
+    /// # Parameters
+    /// - `ratio`: The modulator's frequency, as a multiple of the note's frequency
+    /// - `index`: How strongly the modulator bends the carrier
+    pub fn new(ratio: f32, index: f32) -> Self {
+        FmModulator {
+            ratio,
+            index,
+            decay: Duration::ZERO,
+        }
+    }
+
+    /// Returns this modulator, with its index falling away over time.
+    ///
+    /// # Parameters
+    /// - `decay`: How long the index takes to fall to about a third of its starting value
+    pub fn with_decay(self, decay: Duration) -> Self {
+        FmModulator { decay, ..self }
+    }
+
+    /// Returns the modulator's index at a time since the start of the note.
+    pub fn index_at_secs(&self, secs: f32) -> f32 {
+        if self.decay.is_zero() {
+            self.index
+        } else {
+            self.index * (-secs / self.decay.as_secs_f32()).exp()
+        }
+    }
+}
+
+impl PartialEq for FmModulator {
+    fn eq(&self, other: &Self) -> bool {
+        float_key(self.ratio) == float_key(other.ratio)
+            && float_key(self.index) == float_key(other.index)
+            && self.decay == other.decay
+    }
+}
+
+impl Eq for FmModulator {}
+
+impl Hash for FmModulator {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        float_key(self.ratio).hash(state);
+        float_key(self.index).hash(state);
+        self.decay.hash(state);
+    }
+}
+
+/// A sound made with frequency modulation (FM) synthesis, played with [`Timbre::Fm`](crate::Timbre::Fm).
+///
+/// A carrier sine wave is played at the note's pitch (times its ratio), and each modulator
+/// bends its phase. Unlike stacking harmonics, this can make inharmonic tones like bells,
+/// and tones whose brightness changes as the note goes on, like an electric piano.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use std::time::Duration;
+///
+/// // A ready-made patch
+/// let keys = (quarter(C4) + quarter(E4)).with_timbre(Timbre::Fm(FmPatch::electric_piano()));
+///
+/// // A custom patch: a bright tone which mellows over half a second
+/// let pluck = FmPatch::new(1.0, Envelope::from_millis(2, 400, 0.3, 100))
+///     .with_modulator(FmModulator::new(2.0, 3.0).with_decay(Duration::from_millis(500)));
+/// let melody = (quarter(G4) + half(A4)).with_timbre(Timbre::Fm(pluck));
+/// ```
+#[derive(Clone, Debug)]
+pub struct FmPatch {
+    /// The carrier's frequency, as a multiple of the note's frequency
+    pub carrier_ratio: f32,
+    /// The modulators, which all bend the carrier's phase together
+    pub modulators: Vec<FmModulator>,
+    /// The volume envelope used for notes which don't specify their own
+    pub envelope: Envelope,
+}
+
+impl FmPatch {
+    /// Creates a patch with no modulators, which plays a plain sine wave.
+    ///
+    /// # Parameters
+    /// - `carrier_ratio`: The carrier's frequency, as a multiple of the note's frequency
+    /// - `envelope`: The volume envelope used for notes which don't specify their own
+    pub fn new(carrier_ratio: f32, envelope: Envelope) -> Self {
+        FmPatch {
+            carrier_ratio,
+            modulators: vec![],
+            envelope,
+        }
+    }
+
+    /// Returns this patch with another modulator.
+    pub fn with_modulator(mut self, modulator: FmModulator) -> Self {
+        self.modulators.push(modulator);
+        self
+    }
+
+    /// An electric piano, with a bright, bell-like strike which mellows into a round tone.
+    pub fn electric_piano() -> Self {
+        FmPatch::new(1.0, Envelope::from_millis(2, 1500, 0.15, 200))
+            .with_modulator(FmModulator::new(1.0, 1.8).with_decay(Duration::from_millis(700)))
+            .with_modulator(FmModulator::new(14.0, 0.5).with_decay(Duration::from_millis(60)))
+    }
+
+    /// A bell, with inharmonic overtones which ring for a long time.
+    pub fn bell() -> Self {
+        FmPatch::new(1.0, Envelope::from_millis(1, 3000, 0.0, 1500))
+            .with_modulator(FmModulator::new(3.5, 4.0).with_decay(Duration::from_millis(1500)))
+    }
+
+    /// A punchy bass, whose bright attack quickly settles into a deep tone.
+    pub fn bass() -> Self {
+        FmPatch::new(1.0, Envelope::from_millis(3, 600, 0.6, 60))
+            .with_modulator(FmModulator::new(1.0, 2.5).with_decay(Duration::from_millis(150)))
+            .with_modulator(FmModulator::new(0.5, 0.6))
+    }
+}
+
+impl FmPatch {
+    /// The envelope's fields, with its sustain level as a [`float_key`], for comparing and hashing.
+    fn envelope_key(&self) -> (Duration, Duration, u32, Duration) {
+        let Envelope {
+            attack,
+            decay,
+            sustain,
+            release,
+        } = self.envelope;
+        (attack, decay, float_key(sustain), release)
+    }
+}
+
+impl PartialEq for FmPatch {
+    fn eq(&self, other: &Self) -> bool {
+        float_key(self.carrier_ratio) == float_key(other.carrier_ratio)
+            && self.modulators == other.modulators
+            && self.envelope_key() == other.envelope_key()
+    }
+}
+
+impl Eq for FmPatch {}
+
+impl Hash for FmPatch {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        float_key(self.carrier_ratio).hash(state);
+        self.modulators.hash(state);
+        self.envelope_key().hash(state);
+    }
+}
+
+#[test]
+fn test_fm_patches_hash_like_they_compare() {
+    use std::collections::hash_map::DefaultHasher;
+
+    let hash = |patch: &FmPatch| {
+        let mut hasher = DefaultHasher::new();
+        patch.hash(&mut hasher);
+        hasher.finish()
+    };
+    let envelope = Envelope::from_millis(2, 400, 0.0, 100);
+    let positive = FmPatch::new(0.0, envelope).with_modulator(FmModulator::new(0.0, 1.0));
+    let negative = FmPatch::new(
+        -0.0,
+        Envelope {
+            sustain: -0.0,
+            ..envelope
+        },
+    )
+    .with_modulator(FmModulator::new(-0.0, 1.0));
+    assert_eq!(positive, negative);
+    assert_eq!(hash(&positive), hash(&negative));
+
+    let nan = FmPatch::new(f32::NAN, envelope);
+    assert_eq!(nan, nan.clone());
+}
@@ -1,19 +1,49 @@
-use crate::{
-    note::{chord::Chord, Timbre},
-    Line, Note, NoteKind, NotePitch, Piece,
+use crate::{note::chord::Chord, Line, Note, NoteKind, NotePitch, Piece};
+
+/// The number of ticks in a sixteenth note.
+///
+/// Ticks are the time units which note lengths, line lengths, and piece lengths are measured in.
+/// A sixteenth note is split into several ticks so that triplets and other tuplets have exact lengths.
+///
+/// This is 12 by default. The `ticks-24` and `ticks-48` features raise it to 24 or 48, for finer
+/// swing and shorter tuplets, at the cost of a shorter longest note (see [`NoteLength::MAX_SIXTEENTHS`]).
+/// If both are enabled, the finer resolution is used.
+pub const TICKS_PER_SIXTEENTH: u16 = if cfg!(feature = "ticks-48") {
+    48
+} else if cfg!(feature = "ticks-24") {
+    24
+} else {
+    12
 };
 
-/// Represents the duration of a musical note in abstract time units.
+/// Converts a range of beats (sixteenth notes) into a range of ticks, for functions which take
+/// ranges of ticks, such as [`MusicPlayer::render_range_to_wav`](crate::MusicPlayer::render_range_to_wav).
 ///
-/// The values for the unit system are based on common musical notation:
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// assert_eq!(beats_to_ticks(16..32), 16 * TICKS_PER_SIXTEENTH as usize..32 * TICKS_PER_SIXTEENTH as usize);
+/// ```
+pub fn beats_to_ticks(beats: std::ops::Range<usize>) -> std::ops::Range<usize> {
+    let ticks = usize::from(TICKS_PER_SIXTEENTH);
+    beats.start.saturating_mul(ticks)..beats.end.saturating_mul(ticks)
+}
+
+/// Represents the duration of a musical note in ticks.
+///
+/// There are [`TICKS_PER_SIXTEENTH`] ticks in a sixteenth note. The ticks are private, so that
+/// code doesn't depend on the tick resolution. Note lengths are created with note length functions
+/// such as [`quarter`], or with [`NoteLength::new`], which both count in sixteenth notes:
 /// - `1` for sixteenth notes
-/// - `2` for eighth notes  
+/// - `2` for eighth notes
 /// - `4` for quarter notes
 /// - `8` for half notes
 /// - `16` for whole notes
 ///
-/// If you want thirty-second notes, or further subdivisions this is not currently supported,
-/// but you can increase the playback BPM to achieve a similar effect.
+/// For lengths shorter than a sixteenth, use [`thirty_second`] and [`sixty_fourth`].
+/// For lengths which aren't a whole number of sixteenths (such as triplets),
+/// use [`tuplet`] or [`NoteLength::from_ticks`].
 ///
 /// # Examples
 /// Manually using `NoteLength`:
@@ -27,9 +57,10 @@ use crate::{
 /// // Use with note creation functions
 /// let note = Note(quarter_len, NoteKind::Rest);
 ///
-/// // Convert from u16
+/// // Convert from a number of sixteenth notes
 /// let length: NoteLength = 4.into();
 /// assert_eq!(length.duration(), 4);
+/// assert_eq!(length.ticks(), 4 * TICKS_PER_SIXTEENTH);
 /// ```
 /// A more typical usage is through the `LengthFluid` trait:
 /// ```
@@ -48,7 +79,7 @@ use crate::{
 /// let tied_note = tie(quarter, eighth)(A4); // Tied quarter and eighth note
 /// ```
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
-pub struct NoteLength(pub u16);
+pub struct NoteLength(pub(crate) u16);
 
 /// A trait for types that can have their note length/duration modified.
 ///
@@ -88,7 +119,7 @@ macro_rules! note_length_fn {
         $(
             #[doc = $doc]
             pub fn $name<N: LengthFluid>(kind: N) -> N::Output {
-                kind.with_length(NoteLength($value))
+                kind.with_length(NoteLength::new($value))
             }
         )*
     }
@@ -123,24 +154,17 @@ impl LengthFluid for Chord {
         Piece(
             self.0
                 .into_iter()
-                .map(|note| note.with_length(length))
-                .map(|note| Line {
-                    notes: vec![note],
-                    pickup: vec![],
-                    hold_pickup: false,
-                })
+                .map(|note| Line::from(note.with_length(length)))
                 .collect(),
         )
     }
 }
 
+/// This is intended only to be used by note length functions, which only produce pieces as long
+/// as a single note. Longer pieces are given the longest length a note can have.
 impl HasNoteLength for Piece {
-    #[expect(
-        clippy::cast_possible_truncation,
-        reason = "This is intended only to be used by note length functions, which will only ever produce u16-sized things"
-    )]
     fn length(&self) -> NoteLength {
-        NoteLength(self.length() as u16)
+        NoteLength(u16::try_from(self.length()).unwrap_or(u16::MAX))
     }
 }
 
@@ -148,26 +172,45 @@ impl LengthFluid for NotePitch {
     type Output = Note;
 
     fn with_length(self, length: NoteLength) -> Self::Output {
-        Note(
-            length,
-            NoteKind::Pitched {
-                pitch: self,
-                timbre: Timbre::Sine,
-                volume: 1.0,
-            },
-        )
+        Note(length, NoteKind::from(self))
     }
 }
 
 note_length_fn!(
-    sixteenth, 1, "Creates a sixteenth note (1 time unit) from the given musical element.";
-    eighth, 2, "Creates an eighth note (2 time units) from the given musical element.";
-    quarter, 4, "Creates a quarter note (4 time units) from the given musical element.";
-    half, 8, "Creates a half note (8 time units) from the given musical element.";
-    whole, 16, "Creates a whole note (16 time units) from the given musical element.";
-    double_whole, 32, "Creates a double whole note (32 time units) from the given musical element."
+    sixteenth, 1, "Creates a sixteenth note (1 sixteenth) from the given musical element.";
+    eighth, 2, "Creates an eighth note (2 sixteenths) from the given musical element.";
+    quarter, 4, "Creates a quarter note (4 sixteenths) from the given musical element.";
+    half, 8, "Creates a half note (8 sixteenths) from the given musical element.";
+    whole, 16, "Creates a whole note (16 sixteenths) from the given musical element.";
+    double_whole, 32, "Creates a double whole note (32 sixteenths) from the given musical element."
 );
 
+/// Creates a thirty-second note (half a sixteenth) from the given musical element.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let run = piano(thirty_second(C4) * 4);
+/// assert_eq!(run.length(), eighth(C4).0.ticks() as usize);
+/// ```
+pub fn thirty_second<N: LengthFluid>(kind: N) -> N::Output {
+    kind.with_length(NoteLength::from_ticks(TICKS_PER_SIXTEENTH / 2))
+}
+
+/// Creates a sixty-fourth note (a quarter of a sixteenth) from the given musical element.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let ornament = piano(sixty_fourth(C4) + sixty_fourth(C4.semitone(2)));
+/// assert_eq!(ornament.length(), thirty_second(C4).0.ticks() as usize);
+/// ```
+pub fn sixty_fourth<N: LengthFluid>(kind: N) -> N::Output {
+    kind.with_length(NoteLength::from_ticks(TICKS_PER_SIXTEENTH / 4))
+}
+
 /// Creates a dotted note with 1.5x the duration of the base note.
 ///
 /// In music notation, a dot after a note increases its duration by half.
@@ -181,8 +224,9 @@ note_length_fn!(
 /// let dotted_quarter = dotted(quarter)(C4);
 /// let dotted_half = dotted(half)(REST);
 ///
-/// // Dotted quarter = 4 + 2 = 6 time units
-/// // Dotted half = 8 + 4 = 12 time units
+/// // Dotted quarter = 4 + 2 = 6 sixteenths
+/// // Dotted half = 8 + 4 = 12 sixteenths
+/// assert_eq!(dotted_quarter.0, NoteLength::new(6));
 /// ```
 #[expect(clippy::arithmetic_side_effects, reason = "User's fault")]
 pub fn dotted<T: LengthFluid + Clone>(len_fn: impl Fn(T) -> T::Output) -> impl Fn(T) -> T::Output {
@@ -203,11 +247,11 @@ pub fn dotted<T: LengthFluid + Clone>(len_fn: impl Fn(T) -> T::Output) -> impl F
 ///
 /// // Tie a quarter note and eighth note together
 /// let tied_note = tie(quarter, eighth)(C4);
-/// // Duration = 4 + 2 = 6 time units
+/// // Duration = 4 + 2 = 6 sixteenths
 ///
 /// // Tie two half notes for a whole note (or you could just use the "whole" function)
 /// let whole_via_tie = tie(half, half)(A4);
-/// // Duration = 8 + 8 = 16 time units
+/// // Duration = 8 + 8 = 16 sixteenths
 /// ```
 #[expect(clippy::arithmetic_side_effects, reason = "User's fault")]
 pub fn tie<T: LengthFluid + Clone>(
@@ -220,3 +264,100 @@ pub fn tie<T: LengthFluid + Clone>(
         kind.with_length(NoteLength(len1.length().0 + len2.length().0))
     })
 }
+
+/// Creates a triplet note, lasting two thirds of the duration of the base note.
+///
+/// Three triplet notes take the same time as two of the base note. For example, three triplet
+/// eighth notes together last as long as a quarter note. This is the same as `tuplet(3, 2, len_fn)`.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let triplets = piano(triplet(eighth)(C4) + triplet(eighth)(C4.semitone(4)) + triplet(eighth)(C4.semitone(7)));
+/// assert_eq!(triplets.length(), quarter(C4).0.ticks() as usize);
+/// ```
+pub fn triplet<T: LengthFluid + Clone>(len_fn: impl Fn(T) -> T::Output) -> impl Fn(T) -> T::Output {
+    tuplet(3, 2, len_fn)
+}
+
+/// Creates a tuplet note, where `n` notes take the same time as `m` of the base note.
+///
+/// For example, `tuplet(5, 4, sixteenth)` creates a quintuplet sixteenth, five of which
+/// last as long as a quarter note. If the resulting length isn't a whole number of ticks,
+/// it is rounded to the nearest tick. Lengths divided into 2, 3, 4, 6, or 12 are always exact.
+///
+/// # Parameters
+/// - `n`: The number of notes in the tuplet
+/// - `m`: The number of base notes the tuplet takes the time of
+/// - `len_fn`: The note length function of the base note, such as `eighth`
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let [c4, d4, e4, f4, g4, a4] = MajorScale(C4).get_degrees([1, 2, 3, 4, 5, 6]);
+///
+/// // Six sextuplet sixteenths in the time of four sixteenths
+/// let sextuplet = tuplet(6, 4, sixteenth);
+/// let run = piano(sextuplet(c4) + sextuplet(d4) + sextuplet(e4) + sextuplet(f4) + sextuplet(g4) + sextuplet(a4));
+/// assert_eq!(run.length(), quarter(C4).0.ticks() as usize);
+/// ```
+pub fn tuplet<T: LengthFluid + Clone>(n: u16, m: u16, len_fn: impl Fn(T) -> T::Output) -> impl Fn(T) -> T::Output {
+    Box::new(move |kind: T| {
+        let base_length = u32::from(len_fn(kind.clone()).length().0);
+        let n = u32::from(n.max(1));
+        let ticks = base_length
+            .saturating_mul(u32::from(m))
+            .saturating_add(n / 2)
+            .checked_div(n)
+            .unwrap_or_default();
+        kind.with_length(NoteLength(u16::try_from(ticks).unwrap_or(u16::MAX)))
+    })
+}
+
+/// A fraction used to scale note lengths, such as `2` to double them or `1 / 2` to halve them.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let double = Ratio::from(2);
+/// let halve = Ratio::new(1, 2);
+///
+/// assert_eq!(double.apply(quarter(C4).0), Some(half(C4).0));
+/// assert_eq!(halve.apply(quarter(C4).0), Some(eighth(C4).0));
+/// assert_eq!(Ratio::new(1, 7).apply(quarter(C4).0), None); // Not a whole number of ticks
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Ratio {
+    /// The number lengths are multiplied by
+    pub numerator: u32,
+    /// The number lengths are divided by
+    pub denominator: u32,
+}
+
+impl Ratio {
+    /// Creates a ratio of `numerator / denominator`.
+    pub fn new(numerator: u32, denominator: u32) -> Self {
+        Ratio { numerator, denominator }
+    }
+
+    /// Scales a note length by this ratio.
+    ///
+    /// Returns `None` if the result isn't a whole number of ticks, or is too long to be a note length.
+    pub fn apply(&self, length: NoteLength) -> Option<NoteLength> {
+        let scaled = u32::from(length.0).checked_mul(self.numerator)?;
+        if scaled.checked_rem(self.denominator)? != 0 {
+            return None;
+        }
+        let ticks = scaled.checked_div(self.denominator)?;
+        Some(NoteLength(u16::try_from(ticks).ok()?))
+    }
+}
+
+impl From<u32> for Ratio {
+    fn from(factor: u32) -> Self {
+        Ratio::new(factor, 1)
+    }
+}
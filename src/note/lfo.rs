@@ -0,0 +1,118 @@
+use std::f32::consts::TAU;
+
+use crate::NoteLength;
+
+/// The shape of an [`Lfo`]'s wave.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum LfoShape {
+    /// A smooth sine wave
+    #[default]
+    Sine,
+    /// Rises and falls in straight lines
+    Triangle,
+    /// Jumps between the highest and lowest values
+    Square,
+    /// Rises in a straight line, then drops back down
+    SawUp,
+    /// Drops in a straight line, then jumps back up
+    SawDown,
+}
+
+/// The synthesis parameter an [`Lfo`] modulates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LfoTarget {
+    /// Vibrato. The depth is in semitones either side of the note's pitch.
+    Pitch,
+    /// Tremolo. The depth is how much the volume dips, from 0.0 (not at all) to 1.0 (down to silence).
+    Amplitude,
+    /// A low-pass filter whose cutoff sweeps around a center frequency. The depth is in octaves either side.
+    FilterCutoff {
+        /// The center cutoff frequency in Hz
+        cutoff: f32,
+    },
+}
+
+/// A low-frequency oscillator, which moves a synthesis parameter back and forth over the course of a note.
+///
+/// The rate is given as a note length, so the movement stays in time with the music at any tempo.
+/// LFOs are attached to custom timbres with [`Synth::with_lfo`](crate::note::Synth::with_lfo),
+/// and are evaluated when the note is played or rendered. Each note starts at the beginning of the LFO's cycle.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use rodio::{source::SineWave, Source};
+/// use std::{sync::Arc, time::Duration};
+///
+/// let organ = |frequency: f32, duration_ms: u64| {
+///     Box::new(SineWave::new(frequency).take_duration(Duration::from_millis(duration_ms)))
+///         as Box<dyn Source<Item = f32> + Send>
+/// };
+///
+/// // A gentle vibrato, one cycle every eighth note
+/// let vibrato = Lfo::new(LfoTarget::Pitch, LfoShape::Sine, NoteLength::new(2), 0.2);
+/// // A filter sweep lasting a whole bar
+/// let sweep = Lfo::new(LfoTarget::FilterCutoff { cutoff: 800.0 }, LfoShape::Triangle, NoteLength::new(16), 2.0);
+///
+/// let timbre = Timbre::Custom(Arc::new(organ.with_lfo(vibrato).with_lfo(sweep)));
+/// let melody = (whole(C4) + whole(A4)).with_timbre(timbre);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Lfo {
+    /// The parameter which is modulated
+    pub target: LfoTarget,
+    /// The shape of the wave
+    pub shape: LfoShape,
+    /// The length of one cycle of the wave
+    pub rate: NoteLength,
+    /// How far the parameter is moved. The units depend on the target.
+    pub depth: f32,
+}
+
+impl Lfo {
+    /// Creates a new LFO.
+    ///
+    /// # Parameters
+    /// - `target`: The parameter to modulate
+    /// - `shape`: The shape of the wave
+    /// - `rate`: The length of one cycle of the wave
+    /// - `depth`: How far the parameter is moved. See [`LfoTarget`] for the units of each target.
+    pub fn new(target: LfoTarget, shape: LfoShape, rate: NoteLength, depth: f32) -> Self {
+        Lfo {
+            target,
+            shape,
+            rate,
+            depth,
+        }
+    }
+
+    /// Returns the value of the wave after the given number of cycles, from -1.0 to 1.0.
+    ///
+    /// The depth is not applied.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let lfo = Lfo::new(LfoTarget::Amplitude, LfoShape::Triangle, NoteLength::new(4), 0.5);
+    /// assert_eq!(lfo.value_at(0.0), -1.0);
+    /// assert_eq!(lfo.value_at(0.5), 1.0);
+    /// assert_eq!(lfo.value_at(1.25), 0.0);
+    /// ```
+    pub fn value_at(&self, cycles: f32) -> f32 {
+        let phase = cycles.rem_euclid(1.0);
+        match self.shape {
+            LfoShape::Sine => (phase * TAU).sin(),
+            LfoShape::Triangle => 1.0 - 4.0 * (phase - 0.5).abs(),
+            LfoShape::Square => {
+                if phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            LfoShape::SawUp => 2.0 * phase - 1.0,
+            LfoShape::SawDown => 1.0 - 2.0 * phase,
+        }
+    }
+}
@@ -2,18 +2,41 @@
 ///
 /// Contains the `Chord` type for representing groups of pitches played simultaneously.
 pub mod chord;
+mod envelope;
+mod fm;
 mod length;
+#[cfg(any(feature = "wav-output", feature = "live-output"))]
+mod lfo;
+/// Chord progression types.
+///
+/// Contains the `Progression` type for representing sequences of chords with durations.
+pub mod progression;
+mod sample;
+mod style;
+#[cfg(any(feature = "wav-output", feature = "live-output"))]
+mod synth;
 mod timbre;
+mod time_signature;
 
+pub use envelope::*;
+pub use fm::*;
 pub use length::*;
+#[cfg(any(feature = "wav-output", feature = "live-output"))]
+pub use lfo::*;
+pub use sample::*;
+pub use style::*;
+#[cfg(any(feature = "wav-output", feature = "live-output"))]
+pub use synth::*;
 pub use timbre::*;
+pub use time_signature::*;
 
 use std::{
     fmt::Debug,
     ops::{Add, Mul},
+    str::FromStr,
 };
 
-use crate::{Line, Piece, A4};
+use crate::{Line, Piece, Tet12, A4};
 
 /// Represents a musical note with duration, pitch/rest, and timbre
 ///
@@ -24,7 +47,7 @@ use crate::{Line, Piece, A4};
 /// // Create a quarter note C4 with piano timbre
 /// let note = piano(quarter(NotePitch(261.626)));
 /// ```
-#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[derive(Clone, PartialEq, Debug, Default)]
 pub struct Note(pub NoteLength, pub NoteKind);
 
 impl Note {
@@ -61,12 +84,107 @@ impl Note {
     /// assert!(matches!(loud_note.1, NoteKind::Pitched { volume: 2.0, .. })); // Volume is now 2.0, not 1.0
     /// ```
     pub fn volume(&self, volume: f32) -> Note {
-        let new_note_kind = match self.1 {
-            NoteKind::Pitched { pitch, timbre, .. } => NoteKind::Pitched { pitch, timbre, volume },
-            NoteKind::Rest => NoteKind::Rest,
-        };
+        let mut note = self.clone();
+        if let NoteKind::Pitched {
+            volume: note_volume, ..
+        } = &mut note.1
+        {
+            *note_volume = volume;
+        }
+
+        note
+    }
+
+    /// Creates a new note with the specified stereo position.
+    ///
+    /// # Parameters
+    /// - `pan`: The position of the note, from -1.0 (hard left) through 0.0 (center) to 1.0 (hard right).
+    ///   Values outside this range are clamped when the note is played.
+    ///
+    /// Panning is honored by both live playback and WAV rendering. A rendered file is
+    /// written in stereo if any of its notes are panned. For rests, this has no effect.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let left = piano(quarter(C4)).pan(-1.0);
+    /// assert!(matches!(left.1, NoteKind::Pitched { pan: -1.0, .. }));
+    ///
+    /// let rest = quarter(REST);
+    /// assert_eq!(rest.pan(0.5), rest);
+    /// ```
+    pub fn pan(&self, pan: f32) -> Note {
+        let mut note = self.clone();
+        if let NoteKind::Pitched { pan: note_pan, .. } = &mut note.1 {
+            *note_pan = pan;
+        }
+
+        note
+    }
+
+    /// Creates a new note which is only played some of the time.
+    ///
+    /// Whether the note plays is decided afresh each time the piece is played or rendered,
+    /// so ghost notes and fills come and go, and loops don't sound mechanical. To get the
+    /// same result every time, give the player a seed with `MusicPlayer::with_seed`.
+    /// For rests, this has no effect.
+    ///
+    /// # Parameters
+    /// - `probability`: The chance of the note being played, from 0.0 (never) to 1.0 (always)
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// // Hi-hats, with a ghost note which only plays some of the time
+    /// let hats = drums(quarter(C3) + eighth(C3) + eighth(C3).with_probability(0.6).volume(0.4));
+    ///
+    /// let ghost = eighth(C3).with_probability(0.6);
+    /// assert!(matches!(ghost.1, NoteKind::Pitched { probability: 0.6, .. }));
+    /// ```
+    pub fn with_probability(&self, probability: f32) -> Note {
+        let mut note = self.clone();
+        if let NoteKind::Pitched {
+            probability: note_probability,
+            ..
+        } = &mut note.1
+        {
+            *note_probability = probability;
+        }
+
+        note
+    }
+
+    /// Creates a new note which is retriggered `n` times in quick succession, like a ratchet
+    /// on a step sequencer.
+    ///
+    /// The note keeps its length, which is split evenly between the retriggers when it's
+    /// played or rendered, so it still lines up with the rest of the piece and works with
+    /// anything which changes note lengths. Each retrigger uses the note's envelope.
+    /// A ratchet of 0 or 1 plays the note once. For rests, this has no effect.
+    ///
+    /// # Parameters
+    /// - `n`: How many times the note is played within its length
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// // A hi-hat pattern which rolls into the next bar
+    /// let hats = drums(quarter(C3) * 3 + quarter(C3).ratchet(4));
+    ///
+    /// let roll = quarter(C3).ratchet(4);
+    /// assert_eq!(roll.0, quarter(C3).0);
+    /// assert!(matches!(roll.1, NoteKind::Pitched { ratchet: 4, .. }));
+    /// ```
+    pub fn ratchet(&self, n: u8) -> Note {
+        let mut note = self.clone();
+        if let NoteKind::Pitched { ratchet, .. } = &mut note.1 {
+            *ratchet = n.max(1);
+        }
 
-        Note(self.0, new_note_kind)
+        note
     }
 }
 
@@ -95,7 +213,7 @@ impl Mul<usize> for Note {
     type Output = Line;
 
     fn mul(self, rhs: usize) -> Self::Output {
-        Line::from((0..rhs).map(|_| self).collect::<Vec<_>>())
+        Line::from((0..rhs).map(|_| self.clone()).collect::<Vec<_>>())
     }
 }
 
@@ -118,11 +236,12 @@ impl Mul<Note> for Note {
 ///
 /// // Manually create a pitched note at 440Hz (A4) with piano timbre
 /// // Usually, you would just do `piano(quarter(A4))`
-/// let a4_note = NoteKind::Pitched {
-///     pitch: NotePitch(440.0),
-///     timbre: Timbre::Piano,
-///     volume: 1.0
-/// };
+/// let a4_note = NoteKind::pitched(NotePitch(440.0), Timbre::Piano);
+///
+/// // Its other settings can be read by matching, with `..` for the rest
+/// if let NoteKind::Pitched { volume, pan, .. } = a4_note {
+///     assert_eq!((volume, pan), (1.0, 0.0));
+/// }
 ///
 /// // Create a rest
 /// let rest = NoteKind::Rest;
@@ -130,12 +249,17 @@ impl Mul<Note> for Note {
 /// // Or use the constant
 /// let rest2 = REST;
 /// ```
-#[derive(Clone, Copy, Debug, PartialEq, Default)]
+#[derive(Clone, Debug, PartialEq, Default)]
 pub enum NoteKind {
     /// A rest - produces no sound for the duration specified
     #[default]
     Rest,
-    /// A pitched note with frequency, timbre, and volume
+    /// A pitched note with frequency, timbre, volume, an optional envelope, a stereo position,
+    /// the chance of it being played, and how many times it's retriggered.
+    ///
+    /// More settings may be added, so pitched notes are created with [`NoteKind::pitched`] or
+    /// note functions such as [`quarter`], and changed with methods such as [`Note::volume`].
+    #[non_exhaustive]
     Pitched {
         /// The fundamental frequency of the note in Hz
         pitch: NotePitch,
@@ -143,15 +267,56 @@ pub enum NoteKind {
         timbre: Timbre,
         /// Volume level (0.0 = silent, 1.0 = full volume, can exceed 1.0)
         volume: f32,
+        /// The volume envelope of the note. If `None`, the timbre's default envelope is used.
+        envelope: Option<Envelope>,
+        /// Stereo position (-1.0 = hard left, 0.0 = center, 1.0 = hard right)
+        pan: f32,
+        /// The chance of the note being played each time the piece is played or rendered
+        /// (0.0 = never, 1.0 = always). See [`Note::with_probability`].
+        probability: f32,
+        /// How many times the note is retriggered within its length (1 = played once).
+        /// See [`Note::ratchet`].
+        ratchet: u8,
     },
 }
 
+impl NoteKind {
+    /// Creates a pitched note with the given timbre, at full volume, centered, and with the
+    /// timbre's default envelope. It's always played, and played once.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let note = Note(NoteLength::new(4), NoteKind::pitched(A4, Timbre::Piano));
+    /// assert_eq!(note, piano(quarter(A4)));
+    /// ```
+    pub fn pitched(pitch: NotePitch, timbre: Timbre) -> Self {
+        NoteKind::Pitched {
+            pitch,
+            timbre,
+            volume: 1.0,
+            envelope: None,
+            pan: 0.0,
+            probability: 1.0,
+            ratchet: 1,
+        }
+    }
+}
+
 impl From<NotePitch> for NoteKind {
+    /// Creates a note with the timbre and volume of the current [`Style`], which is a sine wave
+    /// at full volume outside of [`with_defaults`].
     fn from(value: NotePitch) -> Self {
+        let Style { timbre, volume } = Style::current();
         NoteKind::Pitched {
             pitch: value,
-            timbre: Timbre::default(),
-            volume: 1.0,
+            timbre,
+            volume,
+            envelope: None,
+            pan: 0.0,
+            probability: 1.0,
+            ratchet: 1,
         }
     }
 }
@@ -200,6 +365,20 @@ impl From<NotePitch> for f32 {
     }
 }
 
+/// Returns the bits of a float with `-0.0` counted as `0.0`, and every NaN counted as the same NaN.
+///
+/// Types which hold floats but implement `Eq` and `Hash` compare and hash floats by this, so
+/// that equal values always hash the same, and every value is equal to itself.
+pub(crate) fn float_key(value: f32) -> u32 {
+    if value == 0.0 {
+        0.0f32.to_bits()
+    } else if value.is_nan() {
+        f32::NAN.to_bits()
+    } else {
+        value.to_bits()
+    }
+}
+
 impl NotePitch {
     /// Creates a new `NotePitch` from a frequency in Hz.
     ///
@@ -218,17 +397,169 @@ impl NotePitch {
     pub fn frequency(&self) -> f32 {
         self.0
     }
+
+    /// Creates a pitch from a note name with octave number, such as "C#4", "Eb3", or "g2",
+    /// tuned relative to A4 at 440 Hz.
+    ///
+    /// Sharps are written `#` and flats `b`. Returns `None` if the name can't be parsed.
+    /// Pitches can also be parsed with [`str::parse`].
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let g_sharp_2 = NotePitch::from_name("G#2").unwrap();
+    /// assert_eq!(format!("{g_sharp_2:?}"), "G#2");
+    ///
+    /// let a_flat_2: NotePitch = "Ab2".parse().unwrap();
+    /// assert_eq!(a_flat_2, g_sharp_2);
+    ///
+    /// assert!("C".parse::<NotePitch>().is_err()); // The octave is required
+    /// ```
+    pub fn from_name(name: &str) -> Option<Self> {
+        crate::scales::tet12::get_pitch_from_name(name, A4)
+    }
+
+    /// Finds the equal-tempered pitch nearest to a frequency, tuned relative to the given A4.
+    ///
+    /// Returns the pitch, and how far the frequency is from it in cents (hundredths of a
+    /// semitone). The distance is positive if the frequency is sharp of the pitch, and negative
+    /// if it's flat. Frequencies which aren't positive have no nearest pitch, so they're returned
+    /// as they are, with a distance of NaN.
+    ///
+    /// # Parameters
+    /// - `frequency`: The frequency in Hz
+    /// - `a4`: The pitch of A4, usually [`A4`] (440 Hz)
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let (pitch, cents) = NotePitch::nearest_tet12(293.66, A4);
+    /// assert_eq!(format!("{pitch:?}"), "D4");
+    /// assert!(cents.abs() < 0.1);
+    ///
+    /// // A typo lands between notes
+    /// let (pitch, cents) = NotePitch::nearest_tet12(239.66, A4);
+    /// assert_eq!(format!("{pitch:?}"), "A#3");
+    /// assert!(cents > 40.0);
+    /// ```
+    pub fn nearest_tet12(frequency: f32, a4: NotePitch) -> (NotePitch, f32) {
+        if !(frequency > 0.0 && frequency.is_finite()) {
+            return (NotePitch(frequency), f32::NAN);
+        }
+        let semitones = 12.0 * (frequency / a4.0).log2();
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "Audible pitches are a few hundred semitones from A4 at most"
+        )]
+        let nearest = a4.semitone(semitones.round().clamp(f32::from(i16::MIN), f32::from(i16::MAX)) as i16);
+        (nearest, 1200.0 * (frequency / nearest.0).log2())
+    }
+
+    /// Creates a pitch from a frequency, checking that it's within a number of cents of an
+    /// equal-tempered pitch, tuned relative to A4 at 440 Hz.
+    ///
+    /// Pitches written as frequencies are easy to mistype, and a typo like `239.66` for `293.66`
+    /// only sounds subtly wrong. This catches them. The frequency isn't changed: use
+    /// [`NotePitch::nearest_tet12`] to snap it to the nearest pitch instead.
+    ///
+    /// # Parameters
+    /// - `frequency`: The frequency in Hz
+    /// - `max_cents`: How far the frequency can be from the nearest pitch, in cents (hundredths of a semitone)
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// assert_eq!(NotePitch::new_strict(293.66, 5.0), Ok(NotePitch(293.66)));
+    ///
+    /// let error = NotePitch::new_strict(239.66, 5.0).unwrap_err();
+    /// assert_eq!(format!("{:?}", error.nearest), "A#3");
+    /// assert_eq!(error.to_string(), "239.66 Hz is 48.2 cents sharp of A#3");
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the frequency is further than `max_cents` from every equal-tempered
+    /// pitch, or isn't a positive number.
+    pub fn new_strict(frequency: f32, max_cents: f32) -> Result<Self, OffPitchError> {
+        let (nearest, cents) = NotePitch::nearest_tet12(frequency, A4);
+        if cents.abs() <= max_cents {
+            Ok(NotePitch(frequency))
+        } else {
+            Err(OffPitchError {
+                frequency,
+                nearest,
+                cents,
+            })
+        }
+    }
+}
+
+/// The error returned by [`NotePitch::new_strict`] when a frequency is too far from any
+/// equal-tempered pitch.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OffPitchError {
+    /// The frequency which was given, in Hz
+    pub frequency: f32,
+    /// The equal-tempered pitch nearest to the frequency
+    pub nearest: NotePitch,
+    /// How far the frequency is from the nearest pitch, in cents. Positive if it's sharp, and
+    /// NaN if the frequency isn't a positive number.
+    pub cents: f32,
+}
+
+impl Display for OffPitchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        if self.cents.is_nan() {
+            return write!(f, "{} Hz isn't a positive frequency", self.frequency);
+        }
+        let direction = if self.cents > 0.0 { "sharp" } else { "flat" };
+        write!(
+            f,
+            "{} Hz is {:.1} cents {direction} of {:?}",
+            self.frequency,
+            self.cents.abs(),
+            self.nearest
+        )
+    }
+}
+
+impl std::error::Error for OffPitchError {}
+
+/// The error returned when a [`NotePitch`] can't be parsed from a note name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParsePitchError(pub String);
+
+impl Display for ParsePitchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "invalid note name: \"{}\"", self.0)
+    }
+}
+
+impl std::error::Error for ParsePitchError {}
+
+impl FromStr for NotePitch {
+    type Err = ParsePitchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NotePitch::from_name(s).ok_or_else(|| ParsePitchError(s.to_string()))
+    }
 }
 
 impl From<NoteLength> for u16 {
+    /// Returns the length in sixteenth notes, rounded down.
     fn from(length: NoteLength) -> Self {
-        length.0
+        length.duration()
     }
 }
 
 impl From<u16> for NoteLength {
-    fn from(length: u16) -> Self {
-        NoteLength(length)
+    /// Creates a length of the given number of sixteenth notes. Lengths longer than
+    /// [`NoteLength::MAX_SIXTEENTHS`] are shortened to the longest a note can last, as with
+    /// [`NoteLength::new`].
+    fn from(sixteenths: u16) -> Self {
+        NoteLength::new(sixteenths)
     }
 }
 
@@ -248,12 +579,23 @@ impl Display for NotePitch {
 
 impl Display for NoteLength {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-        write!(f, "{} beats", self.0)
+        if self.0.is_multiple_of(TICKS_PER_SIXTEENTH) {
+            write!(f, "{} beats", self.duration())
+        } else {
+            write!(f, "{} ticks", self.0)
+        }
     }
 }
 
 impl NoteLength {
-    /// Creates a new `NoteLength` from a duration value.
+    /// The most sixteenth notes a single note can last.
+    pub const MAX_SIXTEENTHS: u16 = u16::MAX / TICKS_PER_SIXTEENTH;
+
+    /// Creates a new `NoteLength` lasting the given number of sixteenth notes.
+    ///
+    /// A note can last at most [`NoteLength::MAX_SIXTEENTHS`] sixteenth notes (just over 341 bars
+    /// of 4/4 at the default tick resolution), and longer lengths are shortened to the longest a note can last. Use
+    /// [`NoteLength::try_new`] to find out when a length is too long instead.
     ///
     /// # Examples
     /// ```
@@ -265,13 +607,49 @@ impl NoteLength {
     /// // Check that they work as expected
     /// let length = quarter_len.clone();
     /// assert_eq!(length.duration(), 4);
+    /// assert_eq!(quarter(C4).0, quarter_len);
+    /// ```
+    #[expect(clippy::arithmetic_side_effects, reason = "At most MAX_SIXTEENTHS sixteenths fit in a u16 of ticks")]
+    pub fn new(sixteenths: u16) -> Self {
+        NoteLength(sixteenths.min(Self::MAX_SIXTEENTHS) * TICKS_PER_SIXTEENTH)
+    }
+
+    /// Creates a new `NoteLength` lasting the given number of sixteenth notes, or returns `None`
+    /// if that's longer than a note can last (see [`NoteLength::MAX_SIXTEENTHS`]).
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// assert_eq!(NoteLength::try_new(4), Some(NoteLength::new(4)));
+    /// assert_eq!(NoteLength::try_new(NoteLength::MAX_SIXTEENTHS + 1), None);
+    /// ```
+    pub fn try_new(sixteenths: u16) -> Option<Self> {
+        sixteenths.checked_mul(TICKS_PER_SIXTEENTH).map(NoteLength)
+    }
+
+    /// Creates a new `NoteLength` lasting the given number of ticks.
+    ///
+    /// See [`TICKS_PER_SIXTEENTH`] for how long a tick is.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let triplet_sixteenth = NoteLength::from_ticks(TICKS_PER_SIXTEENTH * 2 / 3);
+    /// assert_eq!(triplet(sixteenth)(C4).0, triplet_sixteenth);
     /// ```
-    pub fn new(duration: u16) -> Self {
-        NoteLength(duration)
+    pub fn from_ticks(ticks: u16) -> Self {
+        NoteLength(ticks)
     }
 
-    /// Gets the duration value of this note length.
+    /// Gets the duration of this note length in sixteenth notes, rounded down.
     pub fn duration(&self) -> u16 {
+        self.0 / TICKS_PER_SIXTEENTH
+    }
+
+    /// Gets the duration of this note length in ticks.
+    pub fn ticks(&self) -> u16 {
         self.0
     }
 }
@@ -293,3 +671,12 @@ impl NoteLength {
 /// let quarter_rest = quarter(REST);
 /// ```
 pub const REST: NoteKind = NoteKind::Rest;
+
+#[test]
+fn test_overlong_lengths_are_shortened_to_whole_sixteenths() {
+    let longest = NoteLength::new(u16::MAX);
+    assert_eq!(longest, NoteLength::new(NoteLength::MAX_SIXTEENTHS));
+    assert_eq!(longest.ticks(), NoteLength::MAX_SIXTEENTHS * TICKS_PER_SIXTEENTH);
+    assert_eq!(longest.duration(), NoteLength::MAX_SIXTEENTHS);
+    assert_eq!(u16::from(longest), NoteLength::MAX_SIXTEENTHS);
+}
@@ -0,0 +1,343 @@
+use std::{fmt::Write, ops::Add};
+
+use itertools::Itertools;
+
+use crate::{
+    note::chord::{ArpPattern, Chord, ParseChordError},
+    LengthFluid, Line, Note, NoteLength, NotePitch, Piece, Scale, Tet12, TimeSignature, REST,
+};
+
+/// The number of bars written on each row of a chord chart.
+const BARS_PER_ROW: usize = 4;
+
+/// Represents a chord progression - a sequence of chords, each held for a duration.
+///
+/// A progression describes the harmony of a passage without saying how it is played.
+/// Tools such as comping patterns turn a progression into an accompaniment `Piece`.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let scale = MajorScale(C4);
+/// let progression = Progression::new([
+///     (Chord::from_degrees(&scale, &[1, 3, 5]), NoteLength::new(16)), // I for a bar
+///     (Chord::from_degrees(&scale, &[4, 6, 8]), NoteLength::new(8)),  // IV for half a bar
+///     (Chord::from_degrees(&scale, &[5, 7, 9]), NoteLength::new(8)),  // V for half a bar
+/// ]);
+///
+/// assert_eq!(progression.length(), NoteLength::new(32).ticks() as usize);
+/// ```
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Progression(pub Vec<(Chord, NoteLength)>);
+
+impl Progression {
+    /// Creates a new progression from an iterator of chords and their durations.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let c_major = Chord::new([C4, C4.semitone(4), C4.semitone(7)]);
+    /// let progression = Progression::new([(c_major, NoteLength::new(16))]);
+    /// ```
+    pub fn new(chords: impl IntoIterator<Item = (Chord, NoteLength)>) -> Self {
+        Progression(chords.into_iter().collect())
+    }
+
+    /// Returns the total duration of the progression in ticks.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let chord = Chord::new([C4, C4.semitone(4), C4.semitone(7)]);
+    /// let progression = Progression::new([(chord.clone(), NoteLength::new(16)), (chord, NoteLength::new(8))]);
+    /// assert_eq!(progression.length(), NoteLength::new(24).ticks() as usize);
+    /// ```
+    pub fn length(&self) -> usize {
+        self.0.iter().map(|(_, length)| length.0 as usize).sum()
+    }
+
+    /// Returns an iterator over the chords of the progression, ignoring their durations.
+    pub fn chords(&self) -> impl Iterator<Item = &Chord> {
+        self.0.iter().map(|(chord, _)| chord)
+    }
+
+    /// Creates a progression from Roman numerals in a key, such as `"I vi ii V7"`, with each
+    /// chord held for the same length.
+    ///
+    /// Numerals are separated by spaces, and `|` can be written between bars to make the
+    /// progression easier to read. Each numeral is read with [`Chord::from_roman`].
+    ///
+    /// # Parameters
+    /// - `key`: The scale the numerals are read in
+    /// - `numerals`: The Roman numerals of the chords, in order
+    /// - `length`: How long each chord is held
+    ///
+    /// # Errors
+    /// Returns an error if any numeral can't be read.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let progression = Progression::from_roman(&MajorScale(C4), "I vi | ii V7", NoteLength::new(8)).unwrap();
+    ///
+    /// let symbols: Vec<_> = progression.chords().filter_map(Chord::symbol).collect();
+    /// assert_eq!(symbols, ["C", "Am", "Dm", "G7"]);
+    /// assert_eq!(progression.length(), NoteLength::new(32).ticks() as usize);
+    /// ```
+    pub fn from_roman(key: &impl Scale, numerals: &str, length: NoteLength) -> Result<Self, ParseChordError> {
+        numerals
+            .split_whitespace()
+            .filter(|numeral| *numeral != "|")
+            .map(|numeral| Ok((Chord::from_roman(key, numeral)?, length)))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Progression)
+    }
+
+    /// Plays each chord of the progression with a striker, one after another, combining them
+    /// into a piece.
+    ///
+    /// # Parameters
+    /// - `striker`: The function which plays a chord for its length
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let progression = Progression::from_roman(&MinorScale(A4), "i iv V", NoteLength::new(16)).unwrap();
+    ///
+    /// // Each chord as a half-note block chord, then arpeggiated in eighth notes
+    /// let piece = progression.strike(|chord, _| {
+    ///     half(chord.clone()) + Piece::from(chord.arpeggiate(ArpPattern::UpDown, eighth))
+    /// });
+    /// assert_eq!(piece.length(), progression.length());
+    /// ```
+    pub fn strike(&self, striker: impl Fn(&Chord, NoteLength) -> Piece) -> Piece {
+        self.0
+            .iter()
+            .map(|(chord, length)| striker(chord, *length))
+            .reduce(Add::add)
+            .unwrap_or_default()
+    }
+
+    /// Plays the progression as block chords, each held for its length.
+    ///
+    /// Each voice is its own line, and notes have the default timbre, which can be changed
+    /// with functions like [`piano`](crate::prelude::piano).
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let progression = Progression::from_roman(&MajorScale(C4), "I IV V7 I", NoteLength::new(16)).unwrap();
+    /// let chords = piano(progression.to_piece());
+    ///
+    /// assert_eq!(chords.0.len(), 4); // The seventh chord has four voices
+    /// assert_eq!(chords.length(), progression.length());
+    /// ```
+    pub fn to_piece(&self) -> Piece {
+        self.strike(|chord, length| chord.clone().with_length(length))
+    }
+
+    /// Plays the root of each chord as a bass line, repeating it with `length_fn` for as long
+    /// as the chord lasts.
+    ///
+    /// The root is the chord's [`root`](Chord::root), so inverted and slash chords such as C/E
+    /// still play C. It's played an octave below the chord's lowest pitch. The last note for
+    /// each chord is cut short if it would run into the next chord.
+    ///
+    /// # Parameters
+    /// - `length_fn`: The function which gives each bass note its length, such as [`quarter`](crate::quarter)
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let progression = Progression::from_roman(&MajorScale(C4), "I V", NoteLength::new(8)).unwrap();
+    /// let bass_line = bass(progression.bassline_roots(quarter));
+    ///
+    /// // Two quarter notes on C3, then two on G3
+    /// let g = progression.0[1].0[0];
+    /// assert_eq!(bass_line.notes.len(), 4);
+    /// assert_eq!(bass_line.notes[2], bass(quarter(g.octave(-1))));
+    ///
+    /// // Slash chords play their root, not their bass note. C/E has E3 in the bass, so C2 is played
+    /// use symphoxy::scales::tet12::get_note_name_with_octave;
+    /// let slash = Progression::new([(Chord::from_symbol("C/E", 4).unwrap(), NoteLength::new(4))]);
+    /// let NoteKind::Pitched { pitch, .. } = slash.bassline_roots(quarter).notes[0].1 else { panic!() };
+    /// assert_eq!(get_note_name_with_octave(pitch, A4), "C2");
+    /// ```
+    pub fn bassline_roots(&self, length_fn: impl Fn(NotePitch) -> Note) -> Line {
+        Line::from(
+            self.0
+                .iter()
+                .flat_map(|(chord, length)| {
+                    let root = chord.bass_root();
+                    let notes = root.map(|root| length_fn(root.octave(-1))).into_iter().collect();
+                    fill(notes, *length)
+                })
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Arpeggiates every chord with the same pattern, repeating each arpeggio for as long as
+    /// its chord lasts.
+    ///
+    /// Each chord is arpeggiated with [`Chord::arpeggiate`]. The last note for each chord is
+    /// cut short if it would run into the next chord.
+    ///
+    /// # Parameters
+    /// - `pattern`: The order the pitches of each chord are played in
+    /// - `length_fn`: The function which gives each note its length, such as [`eighth`](crate::eighth)
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let progression = Progression::from_roman(&MajorScale(C4), "I vi IV V", NoteLength::new(16)).unwrap();
+    ///
+    /// // A whole accompaniment from one progression
+    /// let accompaniment = piano(progression.arpeggiate_all(ArpPattern::UpDown, eighth))
+    ///     * bass(progression.bassline_roots(half));
+    /// assert_eq!(accompaniment.length(), progression.length());
+    /// ```
+    pub fn arpeggiate_all(&self, pattern: ArpPattern, length_fn: impl Fn(NotePitch) -> Note) -> Line {
+        Line::from(
+            self.0
+                .iter()
+                .flat_map(|(chord, length)| fill(chord.arpeggiate(pattern.clone(), &length_fn).notes, *length))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Writes the progression as a plain-text chord chart, which can also be pasted into markdown.
+    ///
+    /// The chart is laid out in bars of the given time signature, four bars to a row.
+    /// Each bar lists the chords played in it, and a bar which only continues the previous
+    /// bar's chord is written as `%`. Chords are named with [`Chord::symbol`], and chords
+    /// without a symbol are written as `?`.
+    ///
+    /// # Parameters
+    /// - `time_signature`: The time signature used to divide the progression into bars
+    /// - `sections`: Section labels, as the bar number the section starts at (counting from 1) and its name.
+    ///   Each section starts on a new row.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let scale = MajorScale(C4);
+    /// let [one, four, five, six] = [[1, 3, 5], [4, 6, 8], [5, 7, 9], [6, 8, 10]]
+    ///     .map(|degrees| Chord::from_degrees(&scale, &degrees));
+    ///
+    /// let progression = Progression::new([
+    ///     (one.clone(), NoteLength::new(32)),
+    ///     (four, NoteLength::new(8)),
+    ///     (five, NoteLength::new(8)),
+    ///     (six, NoteLength::new(16)),
+    ///     (one, NoteLength::new(16)),
+    /// ]);
+    ///
+    /// let chart = progression.to_chart(TimeSignature::COMMON_TIME, &[(1, "Verse"), (5, "Chorus")]);
+    /// assert_eq!(chart, "\
+    /// Time: 4/4
+    ///
+    /// [Verse]
+    /// | C   | %   | F G | Am  |
+    ///
+    /// [Chorus]
+    /// | C   |
+    /// ");
+    /// ```
+    #[expect(clippy::arithmetic_side_effects, reason = "The bar length is at least 1, and bar indices are in range")]
+    pub fn to_chart(&self, time_signature: TimeSignature, sections: &[(usize, &str)]) -> String {
+        let bar_length = time_signature.bar_length().max(1);
+
+        // The chords in each bar, and whether each chord was carried over from an earlier bar
+        let mut bars: Vec<Vec<(String, bool)>> = vec![vec![]; self.length().div_ceil(bar_length)];
+        let mut time = 0;
+        for (chord, length) in &self.0 {
+            let symbol = if chord.is_empty() {
+                String::from("N.C.")
+            } else {
+                chord.symbol().unwrap_or_else(|| String::from("?"))
+            };
+            let end = time + length.0 as usize;
+            let first_bar = time / bar_length;
+            for (offset, bar) in bars[first_bar..end.div_ceil(bar_length)].iter_mut().enumerate() {
+                bar.push((symbol.clone(), offset > 0));
+            }
+            time = end;
+        }
+
+        let cells: Vec<String> = bars
+            .iter()
+            .map(|chords| match chords.as_slice() {
+                [(_, true)] => String::from("%"),
+                chords => chords.iter().map(|(symbol, _)| symbol.as_str()).join(" "),
+            })
+            .collect();
+        let width = cells.iter().map(String::len).max().unwrap_or_default();
+
+        let mut chart = format!("Time: {time_signature}\n");
+        let mut row_length = 0;
+        for (index, cell) in cells.iter().enumerate() {
+            if let Some((_, label)) = sections.iter().find(|(bar, _)| *bar == index + 1) {
+                if row_length > 0 {
+                    chart.push_str("|\n");
+                }
+                let _ = write!(chart, "\n[{label}]\n");
+                row_length = 0;
+            } else if row_length == BARS_PER_ROW {
+                chart.push_str("|\n");
+                row_length = 0;
+            } else if index == 0 {
+                chart.push('\n');
+            }
+
+            let _ = write!(chart, "| {cell:<width$} ");
+            row_length += 1;
+        }
+        if row_length > 0 {
+            chart.push_str("|\n");
+        }
+
+        chart
+    }
+}
+
+/// Repeats notes until they last `length`, cutting the last one short if it runs past the end.
+/// If there are no notes to repeat, `length` is filled with a rest.
+fn fill(notes: Vec<Note>, length: NoteLength) -> Vec<Note> {
+    let mut filled = vec![];
+    let mut time: u16 = 0;
+    // Notes with no length would never fill anything
+    for Note(note_length, kind) in notes.iter().filter(|note| note.0 .0 > 0).cycle() {
+        let remaining = length.0.saturating_sub(time);
+        if remaining == 0 {
+            break;
+        }
+        let note_length = note_length.0.min(remaining);
+        filled.push(Note(NoteLength(note_length), kind.clone()));
+        time = time.saturating_add(note_length);
+    }
+    if time < length.0 {
+        filled.push(Note(NoteLength(length.0.saturating_sub(time)), REST));
+    }
+    filled
+}
+
+impl From<Vec<(Chord, NoteLength)>> for Progression {
+    fn from(chords: Vec<(Chord, NoteLength)>) -> Self {
+        Progression(chords)
+    }
+}
+
+impl<const N: usize> From<[(Chord, NoteLength); N]> for Progression {
+    fn from(chords: [(Chord, NoteLength); N]) -> Self {
+        Progression(chords.to_vec())
+    }
+}
@@ -0,0 +1,182 @@
+use std::{
+    hash::{Hash, Hasher},
+    path::Path,
+    sync::Arc,
+};
+
+use crate::{note::float_key, NotePitch};
+
+/// A pitched instrument made from a single recorded sample.
+///
+/// Unlike [`Timbre::CustomSourcePitched`](crate::Timbre::CustomSourcePitched), which assumes
+/// the sample was recorded at C4, the sample can be recorded at any pitch. It's pitch-shifted
+/// from its root pitch to each note's pitch. Sustained sounds can also loop part of the sample,
+/// so notes can last longer than the recording.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// // A cello sample recorded at G2, whose sustain loops between frames 12000 and 30000
+/// let cello = SampleInstrument::new("path/to/cello_g2.wav", G2).with_loop(12000, 30000);
+///
+/// let melody = (half(G2) + whole(D3)).with_timbre(Timbre::Sample(cello));
+/// ```
+#[derive(Clone, Debug)]
+pub struct SampleInstrument {
+    /// The path of the sample's audio file
+    pub path: Arc<Path>,
+    /// The pitch the sample was recorded at
+    pub root_pitch: NotePitch,
+    /// The first and last frame of the part of the sample which loops while a note is held.
+    /// If this is `None`, the sample plays once.
+    pub loop_points: Option<(usize, usize)>,
+}
+
+impl SampleInstrument {
+    /// Creates a sample instrument which plays the sample once.
+    ///
+    /// # Parameters
+    /// - `path`: The path of the sample's audio file
+    /// - `root_pitch`: The pitch the sample was recorded at
+    pub fn new(path: impl AsRef<Path>, root_pitch: NotePitch) -> Self {
+        SampleInstrument {
+            path: Arc::from(path.as_ref()),
+            root_pitch,
+            loop_points: None,
+        }
+    }
+
+    /// Returns this instrument, looping the sample between two frames while a note is held.
+    ///
+    /// The sample plays from the start up to `end`, then repeats from `start` to `end`
+    /// until the note finishes.
+    ///
+    /// # Parameters
+    /// - `start`: The frame the loop starts at
+    /// - `end`: The frame the loop ends at (exclusive)
+    pub fn with_loop(self, start: usize, end: usize) -> Self {
+        SampleInstrument {
+            loop_points: Some((start, end)),
+            ..self
+        }
+    }
+}
+
+impl PartialEq for SampleInstrument {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path
+            && float_key(self.root_pitch.0) == float_key(other.root_pitch.0)
+            && self.loop_points == other.loop_points
+    }
+}
+
+impl Eq for SampleInstrument {}
+
+impl Hash for SampleInstrument {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.path.hash(state);
+        float_key(self.root_pitch.0).hash(state);
+        self.loop_points.hash(state);
+    }
+}
+
+/// A range of pitches played by one sample of a [`SampledInstrument`].
+#[derive(Clone, Debug)]
+pub struct KeyZone {
+    /// The lowest pitch played with this zone's sample
+    pub lowest: NotePitch,
+    /// The highest pitch played with this zone's sample
+    pub highest: NotePitch,
+    /// The sample, and the pitch it was recorded at
+    pub sample: SampleInstrument,
+}
+
+impl PartialEq for KeyZone {
+    fn eq(&self, other: &Self) -> bool {
+        float_key(self.lowest.0) == float_key(other.lowest.0)
+            && float_key(self.highest.0) == float_key(other.highest.0)
+            && self.sample == other.sample
+    }
+}
+
+impl Eq for KeyZone {}
+
+impl Hash for KeyZone {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        float_key(self.lowest.0).hash(state);
+        float_key(self.highest.0).hash(state);
+        self.sample.hash(state);
+    }
+}
+
+/// A pitched instrument made from several samples, each played over its own range of pitches.
+///
+/// A single sample sounds unnatural when it's pitch-shifted far from where it was recorded,
+/// so realistic instruments use a sample every few notes. Each note is played with the sample of
+/// the key zone containing its pitch. If no zone contains it, the zone whose sample was recorded
+/// closest to the pitch is used.
+///
+/// # Examples
+/// ```
+/// use std::path::Path;
+/// use symphoxy::prelude::*;
+///
+/// let piano = SampledInstrument::new()
+///     .with_zone(C2, B2, SampleInstrument::new("samples/piano_f2.wav", F2))
+///     .with_zone(C3, B3, SampleInstrument::new("samples/piano_f3.wav", F3))
+///     .with_zone(C4, B4, SampleInstrument::new("samples/piano_f4.wav", F4));
+///
+/// assert_eq!(&*piano.sample_for(G3).unwrap().path, Path::new("samples/piano_f3.wav"));
+/// assert_eq!(&*piano.sample_for(C6).unwrap().path, Path::new("samples/piano_f4.wav")); // The closest sample
+///
+/// let melody = (quarter(C3) + quarter(G3) + half(E4)).with_timbre(Timbre::Sampled(piano));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Hash)]
+pub struct SampledInstrument {
+    /// The key zones of the instrument
+    pub zones: Vec<KeyZone>,
+}
+
+impl SampledInstrument {
+    /// Creates an instrument with no key zones.
+    pub fn new() -> Self {
+        SampledInstrument::default()
+    }
+
+    /// Returns this instrument with another key zone.
+    ///
+    /// # Parameters
+    /// - `lowest`: The lowest pitch played with the sample
+    /// - `highest`: The highest pitch played with the sample
+    /// - `sample`: The sample, and the pitch it was recorded at
+    pub fn with_zone(mut self, lowest: NotePitch, highest: NotePitch, sample: SampleInstrument) -> Self {
+        self.zones.push(KeyZone {
+            lowest,
+            highest,
+            sample,
+        });
+        self
+    }
+
+    /// Returns the sample which plays the given pitch, or `None` if the instrument has no zones.
+    ///
+    /// Pitches within a quarter of a semitone of a zone count as inside it, so rounding
+    /// doesn't leave gaps between zones.
+    pub fn sample_for(&self, pitch: NotePitch) -> Option<&SampleInstrument> {
+        // A quarter of a semitone
+        let tolerance = 2.0_f32.powf(0.25 / 12.0);
+        let distance = |sample: &SampleInstrument| (pitch.0 / sample.root_pitch.0).log2().abs();
+
+        self.zones
+            .iter()
+            .find(|zone| zone.lowest.0 / tolerance <= pitch.0 && pitch.0 <= zone.highest.0 * tolerance)
+            .map(|zone| &zone.sample)
+            .or_else(|| {
+                self.zones
+                    .iter()
+                    .map(|zone| &zone.sample)
+                    .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+            })
+    }
+}
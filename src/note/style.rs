@@ -0,0 +1,120 @@
+use std::cell::RefCell;
+
+use crate::Timbre;
+
+/// The timbre and volume notes are given when they're created from a pitch.
+///
+/// Outside of [`with_defaults`], notes are created with a sine timbre at full volume. Inside it,
+/// they're created with the style it was given, so a score doesn't need to wrap every fragment
+/// in `piano(...)`. Notes can still be given a timbre or volume of their own afterwards.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let style = Style::new(Timbre::Piano).with_volume(0.8);
+/// assert_eq!(style.timbre, Timbre::Piano);
+/// assert_eq!(Style::current(), Style::default());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Style {
+    /// The timbre of new notes
+    pub timbre: Timbre,
+    /// The volume of new notes (0.0 = silent, 1.0 = full volume, can exceed 1.0)
+    pub volume: f32,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style {
+            timbre: Timbre::Sine,
+            volume: 1.0,
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT_STYLE: RefCell<Style> = RefCell::new(Style::default());
+}
+
+impl Style {
+    /// Creates a style with the given timbre, at full volume.
+    pub fn new(timbre: Timbre) -> Self {
+        Style { timbre, volume: 1.0 }
+    }
+
+    /// Sets the volume of the style.
+    pub fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    /// Returns the style new notes are created with on this thread: the one given to the
+    /// innermost [`with_defaults`] call running, or the default style outside of one.
+    pub fn current() -> Style {
+        CURRENT_STYLE.with(|style| style.borrow().clone())
+    }
+}
+
+/// Runs `compose` with notes created from pitches given the timbre and volume of `style`,
+/// and returns what it returns.
+///
+/// The style only applies to notes created on this thread while `compose` runs, and calls can
+/// be nested, with the innermost style applying. Notes which are given a timbre, such as with
+/// `bass(...)`, or a volume, keep it.
+///
+/// # Parameters
+/// - `style`: The timbre and volume new notes are given
+/// - `compose`: The code creating the notes
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let piece = with_defaults(Style::new(Timbre::Piano).with_volume(0.8), || {
+///     let melody = quarter(C4) + quarter(E4) + half(G4);
+///     let bass_line = bass(whole(C4.octave(-1)));
+///     melody * bass_line
+/// });
+///
+/// assert_eq!(piece.0[0].notes[0], piano(quarter(C4)).volume(0.8));
+/// assert_eq!(piece.0[1].notes[0], bass(whole(C4.octave(-1))).volume(0.8));
+///
+/// // Outside of the call, notes are sine waves at full volume again
+/// assert_eq!(quarter(C4), sine(quarter(C4)));
+/// ```
+pub fn with_defaults<R>(style: Style, compose: impl FnOnce() -> R) -> R {
+    /// Puts the previous style back when dropped, so it's restored even if `compose` panics.
+    struct Restore(Option<Style>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            if let Some(previous) = self.0.take() {
+                CURRENT_STYLE.with(|style| *style.borrow_mut() = previous);
+            }
+        }
+    }
+
+    let _restore = Restore(Some(CURRENT_STYLE.with(|current| current.replace(style))));
+    compose()
+}
+
+#[test]
+fn test_nested_defaults_restore_the_outer_style() {
+    use crate::prelude::*;
+
+    let organ_style = Style::new(Timbre::Organ);
+    let line = with_defaults(organ_style.clone(), || {
+        let inner = with_defaults(Style::new(Timbre::Flute).with_volume(0.5), || quarter(C4));
+        assert_eq!(Style::current(), organ_style);
+        inner + quarter(C4)
+    });
+
+    assert_eq!(line.notes[0], flute(quarter(C4)).volume(0.5));
+    assert_eq!(line.notes[1], organ(quarter(C4)));
+    assert_eq!(Style::current(), Style::default());
+
+    let panicked = std::panic::catch_unwind(|| with_defaults(Style::new(Timbre::Brass), || panic!("composing failed")));
+    assert!(panicked.is_err());
+    assert_eq!(Style::current(), Style::default());
+}
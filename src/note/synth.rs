@@ -0,0 +1,124 @@
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+
+use rodio::Source;
+
+use crate::{note::Lfo, Envelope};
+
+/// A custom instrument, defined in code.
+///
+/// Implement this trait and wrap your synth in `Timbre::Custom` to play notes with it.
+/// The source you return is resampled and mixed like any built-in timbre, and the
+/// note's volume and envelope are applied on top of it.
+///
+/// Closures taking the frequency and duration are synths too, so simple instruments
+/// don't need a new type.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use rodio::{source::SineWave, Source};
+/// use std::{sync::Arc, time::Duration};
+///
+/// /// A hollow-sounding synth made of odd harmonics
+/// struct Hollow;
+///
+/// impl Synth for Hollow {
+///     fn source(&self, frequency: f32, duration_ms: u64) -> Box<dyn Source<Item = f32> + Send> {
+///         Box::new(
+///             SineWave::new(frequency)
+///                 .mix(SineWave::new(frequency * 3.0).amplify(1.0 / 3.0))
+///                 .mix(SineWave::new(frequency * 5.0).amplify(1.0 / 5.0))
+///                 .take_duration(Duration::from_millis(duration_ms))
+///                 .amplify(0.3),
+///         )
+///     }
+///
+///     fn default_envelope(&self) -> Option<Envelope> {
+///         Some(Envelope::from_millis(20, 300, 0.7, 100))
+///     }
+/// }
+///
+/// let hollow = Timbre::Custom(Arc::new(Hollow));
+/// let melody = (quarter(C4) + quarter(A4)).with_timbre(hollow);
+///
+/// // Or, with a closure
+/// let square_ish = Timbre::Custom(Arc::new(|frequency: f32, duration_ms: u64| {
+///     Box::new(
+///         SineWave::new(frequency)
+///             .mix(SineWave::new(frequency * 3.0).amplify(0.3))
+///             .take_duration(Duration::from_millis(duration_ms)),
+///     ) as Box<dyn Source<Item = f32> + Send>
+/// }));
+/// ```
+pub trait Synth: Send + Sync {
+    /// Creates the sound of a single note.
+    ///
+    /// # Parameters
+    /// - `frequency`: The frequency of the note in Hz
+    /// - `duration_ms`: How long the note sounds for, including its envelope's release.
+    ///   Sources longer than this are cut short.
+    fn source(&self, frequency: f32, duration_ms: u64) -> Box<dyn Source<Item = f32> + Send>;
+
+    /// The envelope used for notes which don't specify their own.
+    ///
+    /// Defaults to `None`, which plays the source unchanged.
+    fn default_envelope(&self) -> Option<Envelope> {
+        None
+    }
+
+    /// The LFOs which modulate every note played with this synth.
+    ///
+    /// Defaults to none. Usually, LFOs are attached with [`Synth::with_lfo`] rather than by implementing this.
+    fn lfos(&self) -> Vec<Lfo> {
+        Vec::new()
+    }
+
+    /// Attaches an LFO to this synth, modulating the pitch, amplitude, or filter cutoff of every note.
+    ///
+    /// Call this more than once to attach several LFOs. See [`Lfo`] for an example.
+    fn with_lfo(self, lfo: Lfo) -> Modulated<Self>
+    where
+        Self: Sized,
+    {
+        Modulated {
+            synth: self,
+            lfos: vec![lfo],
+        }
+    }
+}
+
+/// A synth with LFOs attached, created with [`Synth::with_lfo`].
+#[derive(Clone, Debug)]
+pub struct Modulated<S> {
+    synth: S,
+    lfos: Vec<Lfo>,
+}
+
+impl<S: Synth> Synth for Modulated<S> {
+    fn source(&self, frequency: f32, duration_ms: u64) -> Box<dyn Source<Item = f32> + Send> {
+        self.synth.source(frequency, duration_ms)
+    }
+
+    fn default_envelope(&self) -> Option<Envelope> {
+        self.synth.default_envelope()
+    }
+
+    fn lfos(&self) -> Vec<Lfo> {
+        [self.synth.lfos(), self.lfos.clone()].concat()
+    }
+}
+
+impl<F> Synth for F
+where
+    F: Fn(f32, u64) -> Box<dyn Source<Item = f32> + Send> + Send + Sync,
+{
+    fn source(&self, frequency: f32, duration_ms: u64) -> Box<dyn Source<Item = f32> + Send> {
+        self(frequency, duration_ms)
+    }
+}
+
+impl Debug for dyn Synth {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str("Synth")
+    }
+}
@@ -1,4 +1,13 @@
-use crate::{Line, Note, NoteKind, Piece};
+use std::{
+    hash::{Hash, Hasher},
+    path::Path,
+    sync::Arc,
+    time::Duration,
+};
+
+#[cfg(any(feature = "wav-output", feature = "live-output"))]
+use crate::note::Synth;
+use crate::{note::float_key, Envelope, FmPatch, Line, Note, NoteKind, Piece, SampleInstrument, SampledInstrument};
 
 /// Defines the sound characteristics (timbre) of a musical note.
 ///
@@ -14,18 +23,25 @@ use crate::{Line, Note, NoteKind, Piece};
 /// let guitar_note = electric_guitar(half(A4));
 ///
 /// // Create notes with specific timbres
-/// let sine_note = Note(4.into(), NoteKind::Pitched {
-///     pitch: NotePitch::new(440.0),
-///     timbre: Timbre::Sine,
-///     volume: 1.0,
-/// });
+/// let sine_note = Note(4.into(), NoteKind::pitched(NotePitch::new(440.0), Timbre::Sine));
 /// ```
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[derive(Clone, Debug, Default)]
 pub enum Timbre {
     /// Pure sine wave - clean, simple tone with no harmonics
     #[default]
     Sine,
 
+    /// Square wave - hollow, reedy tone with only odd harmonics, as heard in chiptune music.
+    ///
+    /// Like the saw and triangle waves, it's band-limited, so high notes don't alias.
+    Square,
+
+    /// Sawtooth wave - bright, buzzy tone with every harmonic, the starting point of most synth sounds
+    Saw,
+
+    /// Triangle wave - soft, flute-like tone with quiet odd harmonics
+    Triangle,
+
     /// Bass guitar sound - deep, rich low-frequency tones
     Bass,
 
@@ -35,6 +51,30 @@ pub enum Timbre {
     /// Electric guitar sound - bright, sustained tones with distortion
     ElectricGuitar,
 
+    /// Organ sound - steady, even tones built from drawbar-like harmonics
+    Organ,
+
+    /// String section sound - warm, slowly swelling tones from a filtered sawtooth ensemble
+    Strings,
+
+    /// Synth lead sound - bright, buzzy tones which cut through a mix
+    SynthLead,
+
+    /// Synth pad sound - soft, wide tones which fade in and linger
+    SynthPad,
+
+    /// Flute sound - pure, soft tones with few harmonics
+    Flute,
+
+    /// Brass section sound - bold tones from a brightly filtered sawtooth
+    Brass,
+
+    /// A sound made with FM synthesis, which can be bell-like or change over time.
+    ///
+    /// The patch's envelope is used for notes which don't specify their own.
+    /// See [`FmPatch`] for the ready-made patches, and how to make new ones.
+    Fm(FmPatch),
+
     /// Built-in drum kit sounds.
     ///
     /// The drum kit uses specific pitches to trigger different drum sounds:
@@ -64,14 +104,10 @@ pub enum Timbre {
     /// ```
     /// use symphoxy::prelude::*;
     ///
-    /// let custom = Timbre::CustomSourceUnpitched("path/to/crash.mp3");
-    /// let crash_note = Note(4.into(), NoteKind::Pitched {
-    ///     pitch: A4, // Pitch ignored for unpitched sources
-    ///     timbre: custom,
-    ///     volume: 1.0,
-    /// });
+    /// let custom = Timbre::custom_unpitched("path/to/crash.mp3");
+    /// let crash_note = Note(4.into(), NoteKind::pitched(A4, custom)); // Pitch ignored for unpitched sources
     /// ```
-    CustomSourceUnpitched(&'static str),
+    CustomSourceUnpitched(Arc<Path>),
 
     /// Custom pitched audio source from a file.
     ///
@@ -83,14 +119,198 @@ pub enum Timbre {
     /// ```
     /// use symphoxy::prelude::*;
     ///
-    /// let custom = Timbre::CustomSourcePitched("path/to/violin_c4.wav");
-    /// let violin_a4 = Note(4.into(), NoteKind::Pitched {
-    ///     pitch: NotePitch::new(440.0), // Will pitch-shift from C4 to A4
-    ///     timbre: custom,
-    ///     volume: 1.0,
-    /// });
+    /// let custom = Timbre::custom_pitched("path/to/violin_c4.wav");
+    /// let violin_a4 = Note(4.into(), NoteKind::pitched(NotePitch::new(440.0), custom)); // Will pitch-shift from C4 to A4
+    /// ```
+    CustomSourcePitched(Arc<Path>),
+
+    /// A pitched instrument made from a sample recorded at any pitch, which can loop while held.
+    ///
+    /// See [`SampleInstrument`] for an example.
+    Sample(SampleInstrument),
+
+    /// A pitched instrument made from several samples, each covering its own range of pitches.
+    ///
+    /// See [`SampledInstrument`] for an example.
+    Sampled(SampledInstrument),
+
+    /// Several timbres played together as one, each with its own gain.
+    ///
+    /// Each layer is shaped by its own default envelope, unless the note has an envelope.
+    ///
+    /// # Example
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// // A piano with a quiet sine wave underneath
+    /// let layered = Timbre::Layered(vec![(Timbre::Piano, 1.0), (Timbre::Sine, 0.4)]);
+    /// let melody = (quarter(C4) + quarter(E4) + half(G4)).with_timbre(layered);
+    /// ```
+    Layered(Vec<(Timbre, f32)>),
+
+    /// Another timbre played backwards, note by note.
+    ///
+    /// Each note is rendered with its envelope, then reversed, so a piano note swells up to a
+    /// sudden stop rather than dying away. The reversed note lasts as long as the original
+    /// sounded, including its release. See [`Line::reverse_audio`] for reversing a whole line.
+    ///
+    /// # Example
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let swell = quarter(C4).with_timbre(Timbre::Piano.reversed());
+    /// assert_eq!(Timbre::Piano.reversed().reversed(), Timbre::Piano);
     /// ```
-    CustomSourcePitched(&'static str),
+    Reversed(Box<Timbre>),
+
+    /// Custom instrument synthesized in code.
+    ///
+    /// See [`Synth`] for how to define one. Two custom timbres are equal
+    /// only if they share the same synth.
+    #[cfg(any(feature = "wav-output", feature = "live-output"))]
+    Custom(Arc<dyn Synth>),
+}
+
+impl PartialEq for Timbre {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Timbre::CustomSourceUnpitched(a), Timbre::CustomSourceUnpitched(b))
+            | (Timbre::CustomSourcePitched(a), Timbre::CustomSourcePitched(b)) => a == b,
+            (Timbre::Sample(a), Timbre::Sample(b)) => a == b,
+            (Timbre::Sampled(a), Timbre::Sampled(b)) => a == b,
+            (Timbre::Fm(a), Timbre::Fm(b)) => a == b,
+            (Timbre::Layered(a), Timbre::Layered(b)) => {
+                a.len() == b.len()
+                    && a.iter()
+                        .zip(b)
+                        .all(|((a, a_gain), (b, b_gain))| a == b && float_key(*a_gain) == float_key(*b_gain))
+            }
+            (Timbre::Reversed(a), Timbre::Reversed(b)) => a == b,
+            #[cfg(any(feature = "wav-output", feature = "live-output"))]
+            (Timbre::Custom(a), Timbre::Custom(b)) => Arc::ptr_eq(a, b),
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+}
+
+impl Eq for Timbre {}
+
+impl Hash for Timbre {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Timbre::CustomSourceUnpitched(file) | Timbre::CustomSourcePitched(file) => file.hash(state),
+            Timbre::Fm(patch) => patch.hash(state),
+            Timbre::Sample(sample) => sample.hash(state),
+            Timbre::Sampled(instrument) => instrument.hash(state),
+            Timbre::Layered(layers) => {
+                layers.len().hash(state);
+                for (timbre, gain) in layers {
+                    timbre.hash(state);
+                    float_key(*gain).hash(state);
+                }
+            }
+            Timbre::Reversed(timbre) => timbre.hash(state),
+            #[cfg(any(feature = "wav-output", feature = "live-output"))]
+            Timbre::Custom(synth) => Arc::as_ptr(synth).cast::<()>().hash(state),
+            _ => {}
+        }
+    }
+}
+
+impl Timbre {
+    /// Creates a [`Timbre::CustomSourceUnpitched`] which plays the audio file at the given path.
+    ///
+    /// # Parameters
+    /// - `path`: The path of the audio file
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// // Paths can be built at runtime
+    /// let kit = std::path::PathBuf::from("samples/kit");
+    /// let clap = Timbre::custom_unpitched(kit.join("clap.wav"));
+    /// ```
+    pub fn custom_unpitched(path: impl AsRef<Path>) -> Timbre {
+        Timbre::CustomSourceUnpitched(Arc::from(path.as_ref()))
+    }
+
+    /// Creates a [`Timbre::CustomSourcePitched`] which plays the audio file at the given path,
+    /// recorded at C4.
+    ///
+    /// # Parameters
+    /// - `path`: The path of the audio file
+    pub fn custom_pitched(path: impl AsRef<Path>) -> Timbre {
+        Timbre::CustomSourcePitched(Arc::from(path.as_ref()))
+    }
+
+    /// Returns the volume envelope used for notes of this timbre which don't specify their own.
+    ///
+    /// Synthesized timbres have envelopes modelled on their instruments - for example, the piano
+    /// has a fast attack and decays away even while held. Drums and custom sources return `None`,
+    /// as their samples already have their own shape. Custom synths use [`Synth::default_envelope`].
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let piano_envelope = Timbre::Piano.default_envelope().unwrap();
+    /// assert_eq!(piano_envelope.sustain, 0.0); // Piano notes die away
+    ///
+    /// assert_eq!(Timbre::Drums.default_envelope(), None);
+    /// ```
+    pub fn default_envelope(&self) -> Option<Envelope> {
+        let ms = Duration::from_millis;
+        match self {
+            Timbre::Sine => Some(Envelope::new(ms(40), ms(0), 1.0, ms(40))),
+            Timbre::Square | Timbre::Saw | Timbre::Triangle => Some(Envelope::new(ms(5), ms(0), 1.0, ms(30))),
+            Timbre::Bass => Some(Envelope::new(ms(5), ms(1000), 0.2, ms(60))),
+            Timbre::Piano => Some(Envelope::new(ms(5), ms(1200), 0.0, ms(80))),
+            Timbre::ElectricGuitar => Some(Envelope::new(ms(5), ms(600), 0.5, ms(60))),
+            Timbre::Organ => Some(Envelope::new(ms(8), ms(0), 1.0, ms(40))),
+            Timbre::Strings => Some(Envelope::new(ms(150), ms(0), 1.0, ms(250))),
+            Timbre::SynthLead => Some(Envelope::new(ms(5), ms(150), 0.8, ms(60))),
+            Timbre::SynthPad => Some(Envelope::new(ms(400), ms(0), 1.0, ms(600))),
+            Timbre::Flute => Some(Envelope::new(ms(60), ms(100), 0.9, ms(80))),
+            Timbre::Brass => Some(Envelope::new(ms(50), ms(200), 0.8, ms(100))),
+            Timbre::Fm(patch) => Some(patch.envelope),
+            Timbre::Reversed(timbre) => timbre.default_envelope(),
+            Timbre::Drums
+            | Timbre::CustomSourceUnpitched(_)
+            | Timbre::CustomSourcePitched(_)
+            | Timbre::Sample(_)
+            | Timbre::Sampled(_)
+            | Timbre::Layered(_) => None,
+            #[cfg(any(feature = "wav-output", feature = "live-output"))]
+            Timbre::Custom(synth) => synth.default_envelope(),
+        }
+    }
+
+    /// Returns this timbre played backwards. Reversing a reversed timbre gives back the original.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let reversed = Timbre::Piano.reversed();
+    /// assert_eq!(reversed, Timbre::Reversed(Box::new(Timbre::Piano)));
+    /// assert_eq!(reversed.reversed(), Timbre::Piano);
+    /// ```
+    pub fn reversed(self) -> Timbre {
+        match self {
+            Timbre::Reversed(timbre) => *timbre,
+            timbre => Timbre::Reversed(Box::new(timbre)),
+        }
+    }
+
+    /// Returns the timbre with any reversal removed, for checks like whether it's a drum.
+    pub(crate) fn unreversed(&self) -> &Timbre {
+        match self {
+            Timbre::Reversed(timbre) => timbre.unreversed(),
+            timbre => timbre,
+        }
+    }
 }
 
 /// A trait for types that can have their timbre (sound characteristics) modified.
@@ -128,7 +348,23 @@ pub trait TimbreFluid {
 impl TimbreFluid for NoteKind {
     fn with_timbre(self, timbre: Timbre) -> Self {
         match self {
-            NoteKind::Pitched { pitch, volume, .. } => NoteKind::Pitched { pitch, timbre, volume },
+            NoteKind::Pitched {
+                pitch,
+                volume,
+                envelope,
+                pan,
+                probability,
+                ratchet,
+                ..
+            } => NoteKind::Pitched {
+                pitch,
+                timbre,
+                volume,
+                envelope,
+                pan,
+                probability,
+                ratchet,
+            },
             NoteKind::Rest => NoteKind::Rest,
         }
     }
@@ -142,17 +378,18 @@ impl TimbreFluid for Note {
 
 impl TimbreFluid for Line {
     fn with_timbre(self, timbre: Timbre) -> Self {
-        Line {
-            notes: self.notes.into_iter().map(|n| n.with_timbre(timbre)).collect(),
-            pickup: self.pickup.into_iter().map(|n| n.with_timbre(timbre)).collect(),
-            hold_pickup: self.hold_pickup,
-        }
+        self.map_notes(|note| note.clone().with_timbre(timbre.clone()))
     }
 }
 
 impl TimbreFluid for Piece {
     fn with_timbre(self, timbre: Timbre) -> Self {
-        Piece(self.0.into_iter().map(|line| line.with_timbre(timbre)).collect())
+        Piece(
+            self.0
+                .into_iter()
+                .map(|line| line.with_timbre(timbre.clone()))
+                .collect(),
+        )
     }
 }
 
@@ -169,8 +406,35 @@ macro_rules! builtin_timbre_fns {
 
 builtin_timbre_fns!(
     sine, Sine, "Applies a pure sine wave timbre - clean, simple tone with no harmonics.";
+    square, Square, "Applies a band-limited square wave timbre - hollow, reedy tone with only odd harmonics.";
+    saw, Saw, "Applies a band-limited sawtooth wave timbre - bright, buzzy tone with every harmonic.";
+    triangle, Triangle, "Applies a band-limited triangle wave timbre - soft, flute-like tone with quiet odd harmonics.";
     bass, Bass, "Applies a bass guitar timbre - deep, rich low-frequency tones.";
     piano, Piano, "Applies a piano timbre - complex harmonic structure with natural decay.";
     electric_guitar, ElectricGuitar, "Applies an electric guitar timbre - bright, sustained tones with distortion.";
+    organ, Organ, "Applies an organ timbre - steady, even tones built from drawbar-like harmonics.";
+    strings, Strings, "Applies a string section timbre - warm, slowly swelling tones.";
+    synth_lead, SynthLead, "Applies a synth lead timbre - bright, buzzy tones which cut through a mix.";
+    synth_pad, SynthPad, "Applies a synth pad timbre - soft, wide tones which fade in and linger.";
+    flute, Flute, "Applies a flute timbre - pure, soft tones with few harmonics.";
+    brass, Brass, "Applies a brass section timbre - bold tones from a brightly filtered sawtooth.";
     drums, Drums, "Applies a drum kit timbre - use specific pitches to trigger different drum sounds."
 );
+
+#[test]
+fn test_layered_timbres_hash_like_they_compare() {
+    use std::collections::hash_map::DefaultHasher;
+
+    let hash = |timbre: &Timbre| {
+        let mut hasher = DefaultHasher::new();
+        timbre.hash(&mut hasher);
+        hasher.finish()
+    };
+    let positive = Timbre::Layered(vec![(Timbre::Piano, 0.0)]);
+    let negative = Timbre::Layered(vec![(Timbre::Piano, -0.0)]);
+    assert_eq!(positive, negative);
+    assert_eq!(hash(&positive), hash(&negative));
+
+    let nan = Timbre::Layered(vec![(Timbre::Piano, f32::NAN)]);
+    assert_eq!(nan, nan.clone());
+}
@@ -0,0 +1,203 @@
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    ops::{Range, RangeInclusive},
+};
+
+use crate::TICKS_PER_SIXTEENTH;
+
+/// Represents a time signature, such as 4/4 or 6/8.
+///
+/// A time signature says how many beats are in a bar, and which note length counts as one beat.
+/// It's used when laying music out in bars, such as in chord charts.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let waltz = TimeSignature::new(3, 4);
+/// assert_eq!(waltz.bar_length(), dotted(half)(C4).0.ticks() as usize); // Three quarter notes
+/// assert_eq!(waltz.to_string(), "3/4");
+///
+/// assert_eq!(TimeSignature::default(), TimeSignature::COMMON_TIME);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TimeSignature {
+    /// The number of beats in a bar (the top number)
+    pub beats: u16,
+    /// The note length which counts as one beat, e.g. 4 for a quarter note (the bottom number)
+    pub beat_unit: u16,
+}
+
+impl TimeSignature {
+    /// Four quarter notes to a bar.
+    pub const COMMON_TIME: TimeSignature = TimeSignature { beats: 4, beat_unit: 4 };
+
+    /// Creates a new time signature with `beats` beats of length `1 / beat_unit` per bar.
+    ///
+    /// # Parameters
+    /// - `beats`: The number of beats in a bar
+    /// - `beat_unit`: The note length of one beat. This should be 1, 2, 4, 8, or 16.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let six_eight = TimeSignature::new(6, 8);
+    /// assert_eq!(six_eight.bar_length(), TimeSignature::new(3, 4).bar_length()); // Six eighth notes
+    /// ```
+    pub fn new(beats: u16, beat_unit: u16) -> Self {
+        TimeSignature { beats, beat_unit }
+    }
+
+    /// Returns the length of one beat in ticks.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// assert_eq!(TimeSignature::new(2, 2).beat_length(), half(C4).0.ticks() as usize);
+    /// ```
+    pub fn beat_length(&self) -> usize {
+        16_usize
+            .saturating_mul(TICKS_PER_SIXTEENTH as usize)
+            .checked_div(self.beat_unit as usize)
+            .unwrap_or_default()
+    }
+
+    /// Returns the length of one bar in ticks.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// assert_eq!(TimeSignature::COMMON_TIME.bar_length(), whole(C4).0.ticks() as usize);
+    /// ```
+    pub fn bar_length(&self) -> usize {
+        self.beat_length().saturating_mul(self.beats as usize)
+    }
+}
+
+impl Default for TimeSignature {
+    fn default() -> Self {
+        TimeSignature::COMMON_TIME
+    }
+}
+
+impl Display for TimeSignature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}/{}", self.beats, self.beat_unit)
+    }
+}
+
+/// How music is divided into numbered bars: a time signature, and an optional anacrusis.
+///
+/// An anacrusis (or pickup bar) is a partial bar before the first full bar. As in printed music,
+/// it's numbered bar 0, so bar 1 is always the first full bar. Without an anacrusis, the music
+/// starts at bar 1.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let quarter_ticks = quarter(C4).0.ticks() as usize;
+/// let bar_ticks = TimeSignature::COMMON_TIME.bar_length();
+///
+/// // A melody which starts with a single quarter note before the first bar
+/// let meter = Meter::new(TimeSignature::COMMON_TIME).with_anacrusis(quarter_ticks);
+/// assert_eq!(meter.bar_at(0), 0);
+/// assert_eq!(meter.bar_at(quarter_ticks), 1);
+/// assert_eq!(meter.bar_start(9), quarter_ticks + 8 * bar_ticks);
+///
+/// let plain = Meter::from(TimeSignature::COMMON_TIME);
+/// assert_eq!(plain.bar_at(0), 1);
+/// assert_eq!(plain.bar_start(9), 8 * bar_ticks);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Meter {
+    /// The time signature of every full bar
+    pub time_signature: TimeSignature,
+    /// The length of the partial bar before bar 1 in ticks, or 0 if there is none
+    pub anacrusis: usize,
+}
+
+impl Meter {
+    /// Creates a new meter with the given time signature and no anacrusis.
+    pub fn new(time_signature: TimeSignature) -> Self {
+        Meter {
+            time_signature,
+            anacrusis: 0,
+        }
+    }
+
+    /// Creates a new meter with a partial bar before bar 1.
+    ///
+    /// # Parameters
+    /// - `anacrusis`: The length of the partial bar in ticks. This should be shorter than a full bar.
+    pub fn with_anacrusis(self, anacrusis: usize) -> Self {
+        Meter { anacrusis, ..self }
+    }
+
+    /// Returns the number of the first bar: 0 if there is an anacrusis, otherwise 1.
+    pub fn first_bar(&self) -> usize {
+        if self.anacrusis > 0 {
+            0
+        } else {
+            1
+        }
+    }
+
+    /// Returns the number of the bar which contains the given instant (in ticks).
+    pub fn bar_at(&self, instant: usize) -> usize {
+        match instant.checked_sub(self.anacrusis) {
+            None => 0,
+            Some(since_bar_one) => since_bar_one
+                .checked_div(self.time_signature.bar_length())
+                .unwrap_or_default()
+                .saturating_add(1),
+        }
+    }
+
+    /// Returns the instant (in ticks) which the given bar starts at.
+    ///
+    /// Bar 0 starts at the beginning of the music, even if there is no anacrusis.
+    pub fn bar_start(&self, bar: usize) -> usize {
+        match bar.checked_sub(1) {
+            None => 0,
+            Some(full_bars) => self
+                .anacrusis
+                .saturating_add(full_bars.saturating_mul(self.time_signature.bar_length())),
+        }
+    }
+
+    /// Returns the span of time (in ticks) covered by the given bars, including the last bar.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let quarter_ticks = quarter(C4).0.ticks() as usize;
+    /// let meter = Meter::new(TimeSignature::new(3, 4)).with_anacrusis(quarter_ticks);
+    ///
+    /// assert_eq!(meter.bars(0..=0), 0..quarter_ticks);
+    /// assert_eq!(meter.bars(1..=2), quarter_ticks..quarter_ticks * 7);
+    /// ```
+    pub fn bars(&self, bars: RangeInclusive<usize>) -> Range<usize> {
+        self.bar_start(*bars.start())..self.bar_start(bars.end().saturating_add(1))
+    }
+
+    /// Returns whether a bar starts at the given instant (in ticks).
+    pub fn is_bar_start(&self, instant: usize) -> bool {
+        match instant.checked_sub(self.anacrusis) {
+            None => instant == 0,
+            Some(since_bar_one) => since_bar_one
+                .checked_rem(self.time_signature.bar_length())
+                .is_none_or(|offset| offset == 0),
+        }
+    }
+}
+
+impl From<TimeSignature> for Meter {
+    fn from(time_signature: TimeSignature) -> Self {
+        Meter::new(time_signature)
+    }
+}
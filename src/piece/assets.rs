@@ -0,0 +1,217 @@
+use std::path::Path;
+#[cfg(any(feature = "wav-output", feature = "live-output"))]
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    fs::File,
+    io::BufReader,
+    path::PathBuf,
+};
+
+use crate::{note::NoteKind, Piece, Timbre};
+
+impl Piece {
+    /// Returns the path of every audio file the piece plays, each once, in the order they're first played.
+    ///
+    /// This includes the files of [`Timbre::CustomSourceUnpitched`], [`Timbre::CustomSourcePitched`],
+    /// [`Timbre::Sample`], and every zone of [`Timbre::Sampled`], including those inside layered
+    /// and reversed timbres.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::path::Path;
+    /// use symphoxy::prelude::*;
+    ///
+    /// let crash = Timbre::custom_unpitched("samples/crash.wav");
+    /// let cello = Timbre::Sample(SampleInstrument::new("samples/cello_g2.wav", G2));
+    /// let melody = piano(quarter(C4) + quarter(E4) + half(G4));
+    /// let samples = (half(G2) + quarter(C4)).with_timbre(cello) + quarter(C4).with_timbre(crash.clone());
+    /// let piece = melody * samples * Line::from(quarter(C4).with_timbre(crash));
+    ///
+    /// assert_eq!(piece.collect_assets(), [Path::new("samples/cello_g2.wav"), Path::new("samples/crash.wav")]);
+    /// ```
+    pub fn collect_assets(&self) -> Vec<&Path> {
+        let mut assets = vec![];
+        for line in &self.0 {
+            for note in line.pickup.iter().chain(&line.notes) {
+                if let NoteKind::Pitched { timbre, .. } = &note.1 {
+                    collect_timbre_assets(timbre, &mut assets);
+                }
+            }
+        }
+        assets
+    }
+
+    /// Checks that every audio file the piece plays exists and can be decoded, before it's rendered.
+    ///
+    /// Files which can't be loaded are played as silence, so a missing sample would otherwise
+    /// only be noticed as a gap after a long render. Every file is checked, so all the problems
+    /// are reported at once. See [`Piece::collect_assets`] for which files are checked.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let piece = Piece::from(quarter(C4).with_timbre(Timbre::custom_pitched("missing.wav")));
+    /// let problems = piece.verify_assets().unwrap_err();
+    ///
+    /// assert_eq!(problems.len(), 1);
+    /// assert_eq!(problems[0].path(), std::path::Path::new("missing.wav"));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns a problem for each file which can't be opened, can't be decoded, or has no sound in it.
+    #[cfg(any(feature = "wav-output", feature = "live-output"))]
+    pub fn verify_assets(&self) -> Result<(), Vec<AssetError>> {
+        let problems: Vec<AssetError> = self
+            .collect_assets()
+            .into_iter()
+            .filter_map(|path| verify_asset(path).err())
+            .collect();
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+}
+
+/// Adds the files a timbre plays to the list, if they aren't already in it.
+fn collect_timbre_assets<'a>(timbre: &'a Timbre, assets: &mut Vec<&'a Path>) {
+    let mut add = |path: &'a Path| {
+        if !assets.contains(&path) {
+            assets.push(path);
+        }
+    };
+    match timbre {
+        Timbre::CustomSourceUnpitched(path) | Timbre::CustomSourcePitched(path) => add(path),
+        Timbre::Sample(sample) => add(&sample.path),
+        Timbre::Sampled(instrument) => {
+            for zone in &instrument.zones {
+                add(&zone.sample.path);
+            }
+        }
+        Timbre::Layered(layers) => {
+            for (layer, _) in layers {
+                collect_timbre_assets(layer, assets);
+            }
+        }
+        Timbre::Reversed(timbre) => collect_timbre_assets(timbre, assets),
+        _ => {}
+    }
+}
+
+/// Opens and decodes a file, to check that it can be played.
+#[cfg(any(feature = "wav-output", feature = "live-output"))]
+fn verify_asset(path: &Path) -> Result<(), AssetError> {
+    let file = File::open(path).map_err(|error| AssetError::Open {
+        path: path.to_path_buf(),
+        reason: error.to_string(),
+    })?;
+    let mut decoder = rodio::Decoder::new(BufReader::new(file)).map_err(|error| AssetError::Decode {
+        path: path.to_path_buf(),
+        reason: error.to_string(),
+    })?;
+    match decoder.next() {
+        Some(_) => Ok(()),
+        None => Err(AssetError::Empty {
+            path: path.to_path_buf(),
+        }),
+    }
+}
+
+/// A problem with an audio file played by a piece, found by [`Piece::verify_assets`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg(any(feature = "wav-output", feature = "live-output"))]
+pub enum AssetError {
+    /// The file couldn't be opened, for this reason, usually because it doesn't exist
+    Open {
+        /// The path of the file
+        path: PathBuf,
+        /// Why it couldn't be opened
+        reason: String,
+    },
+    /// The file couldn't be decoded as audio, for this reason
+    Decode {
+        /// The path of the file
+        path: PathBuf,
+        /// Why it couldn't be decoded
+        reason: String,
+    },
+    /// The file has no sound in it
+    Empty {
+        /// The path of the file
+        path: PathBuf,
+    },
+}
+
+#[cfg(any(feature = "wav-output", feature = "live-output"))]
+impl AssetError {
+    /// Returns the path of the file with the problem.
+    pub fn path(&self) -> &Path {
+        match self {
+            AssetError::Open { path, .. } | AssetError::Decode { path, .. } | AssetError::Empty { path } => path,
+        }
+    }
+}
+
+#[cfg(any(feature = "wav-output", feature = "live-output"))]
+impl Display for AssetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            AssetError::Open { path, reason } => write!(f, "couldn't open {}: {reason}", path.display()),
+            AssetError::Decode { path, reason } => write!(f, "couldn't decode {}: {reason}", path.display()),
+            AssetError::Empty { path } => write!(f, "{} has no sound in it", path.display()),
+        }
+    }
+}
+
+#[cfg(any(feature = "wav-output", feature = "live-output"))]
+impl std::error::Error for AssetError {}
+
+#[cfg(feature = "wav-output")]
+#[test]
+fn test_verify_assets_reports_every_problem() {
+    use crate::prelude::*;
+
+    let dir = std::env::temp_dir().join(format!("symphoxy_assets_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let (good, empty, garbage, missing) = (
+        dir.join("good.wav"),
+        dir.join("empty.wav"),
+        dir.join("garbage.wav"),
+        dir.join("missing.wav"),
+    );
+
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: 1000,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(&good, spec).unwrap();
+    writer.write_sample(i16::MAX / 2).unwrap();
+    writer.finalize().unwrap();
+    hound::WavWriter::create(&empty, spec).unwrap().finalize().unwrap();
+    std::fs::write(&garbage, b"not audio").unwrap();
+
+    let sampled = SampledInstrument::new()
+        .with_zone(C3, B3, SampleInstrument::new(&good, C3))
+        .with_zone(C4, B4, SampleInstrument::new(&garbage, C4));
+    let layered = Timbre::Layered(vec![(Timbre::custom_unpitched(&empty), 1.0), (Timbre::Sine, 1.0)]);
+    let piece = quarter(C3).with_timbre(Timbre::Sampled(sampled))
+        * quarter(C4).with_timbre(layered.reversed())
+        * quarter(C4).with_timbre(Timbre::custom_pitched(&missing));
+
+    assert_eq!(piece.collect_assets(), [&good, &garbage, &empty, &missing]);
+    let problems = piece.verify_assets().unwrap_err();
+    assert!(matches!(&problems[0], AssetError::Decode { path, .. } if *path == garbage));
+    assert_eq!(problems[1], AssetError::Empty { path: empty });
+    assert!(matches!(&problems[2], AssetError::Open { path, .. } if *path == missing));
+    assert_eq!(problems.len(), 3);
+
+    assert_eq!(
+        Piece::from(quarter(C3).with_timbre(Timbre::custom_pitched(&good))).verify_assets(),
+        Ok(())
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,70 @@
+use crate::{note::NoteKind, Line, Note, Piece};
+
+impl Piece {
+    /// Appends another piece, overlapping the end of this piece with the start of the other
+    /// and crossfading between them.
+    ///
+    /// Over the overlap, this piece's notes fade out while the other piece's notes fade in.
+    /// Each note's volume is scaled by how far through the overlap the middle of the note is,
+    /// so the fade is heard the same way in live playback and in rendered files.
+    ///
+    /// # Parameters
+    /// - `other`: The piece to play after this one
+    /// - `overlap_units`: How long the pieces overlap for, in ticks. This is limited to the
+    ///   length of the shorter piece.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let verse = Piece::from(piano(quarter(C4) * 4));
+    /// let chorus = Piece::from(piano(quarter(G4) * 4));
+    ///
+    /// let quarter_ticks = quarter(C4).0.ticks() as usize;
+    /// let joined = verse.clone().append_crossfaded(chorus.clone(), quarter_ticks * 2);
+    ///
+    /// // The second half of the verse plays under the first half of the chorus
+    /// assert_eq!(joined.length(), verse.length() + chorus.length() - quarter_ticks * 2);
+    /// ```
+    #[expect(clippy::arithmetic_side_effects, reason = "Concatenating lines")]
+    pub fn append_crossfaded(self, other: Piece, overlap_units: usize) -> Piece {
+        let self_length = self.length();
+        let overlap = overlap_units.min(self_length).min(other.length());
+        let fade_start = self_length.saturating_sub(overlap);
+
+        let fading_out = self
+            .0
+            .into_iter()
+            .map(|line| fade_line(line, |middle| fade_gain(self_length.saturating_sub(middle), overlap)));
+
+        let fading_in = other.0.into_iter().map(|line| {
+            let faded = fade_line(line, |middle| fade_gain(middle, overlap));
+            Line::new().extend(fade_start) + faded
+        });
+
+        Piece(fading_out.chain(fading_in).collect())
+    }
+}
+
+/// The gain of a note which is `distance` ticks from the silent end of a fade.
+#[expect(clippy::cast_precision_loss, reason = "Only used for a volume ratio")]
+fn fade_gain(distance: usize, overlap: usize) -> f32 {
+    if distance >= overlap {
+        1.0
+    } else {
+        distance as f32 / overlap as f32
+    }
+}
+
+/// Scales the volume of each note by the gain at the middle of the note.
+fn fade_line(mut line: Line, gain_at: impl Fn(usize) -> f32) -> Line {
+    let mut time = 0_usize;
+    for Note(length, kind) in &mut line.notes {
+        let ticks = usize::from(length.ticks());
+        if let NoteKind::Pitched { volume, .. } = kind {
+            *volume *= gain_at(time.saturating_add(ticks / 2));
+        }
+        time = time.saturating_add(ticks);
+    }
+    line
+}
@@ -0,0 +1,133 @@
+use crate::{
+    note::{NoteKind, NoteLength},
+    Line, Note, Piece,
+};
+
+/// The notes which differ between two versions of a piece, found with [`Piece::diff`].
+///
+/// Each note is paired with the instant (in ticks) it starts at.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PieceDiff {
+    /// Notes which are in the old piece, but not the new one
+    pub removed: Vec<(usize, Note)>,
+    /// Notes which are in the new piece, but not the old one
+    pub added: Vec<(usize, Note)>,
+}
+
+impl Piece {
+    /// Finds the notes which differ between this piece and a new version of it.
+    ///
+    /// Notes are compared by their start time, length, pitch, and sound, regardless of
+    /// which line they're in. A note which was changed appears as removed and added.
+    ///
+    /// # Parameters
+    /// - `new`: The new version of the piece
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let old = Piece::from(piano(quarter(C4) + quarter(E4) + half(G4)));
+    /// let new = Piece::from(piano(quarter(C4) + quarter(F4) + half(G4)));
+    ///
+    /// let diff = old.diff(&new);
+    /// let quarter_ticks = quarter(C4).0.ticks() as usize;
+    /// assert_eq!(diff.removed, vec![(quarter_ticks, piano(quarter(E4)))]);
+    /// assert_eq!(diff.added, vec![(quarter_ticks, piano(quarter(F4)))]);
+    ///
+    /// assert!(old.diff(&old).is_empty());
+    /// ```
+    pub fn diff(&self, new: &Piece) -> PieceDiff {
+        let mut removed = timed_notes(self);
+        let mut added = vec![];
+
+        for (time, note) in timed_notes(new) {
+            match removed
+                .iter()
+                .position(|(old_time, old_note)| *old_time == time && *old_note == note)
+            {
+                Some(index) => {
+                    removed.remove(index);
+                }
+                None => added.push((time, note)),
+            }
+        }
+
+        PieceDiff { removed, added }
+    }
+}
+
+impl PieceDiff {
+    /// Returns whether the two pieces had the same notes.
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.added.is_empty()
+    }
+
+    /// Creates a piece of the differing notes, with removed notes panned hard left
+    /// and added notes panned hard right, so both versions can be heard at once.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let old = Piece::from(piano(quarter(C4) + quarter(E4)));
+    /// let new = Piece::from(piano(quarter(C4) + quarter(F4)));
+    ///
+    /// let changes = old.diff(&new).to_piece();
+    /// assert_eq!(changes.0.len(), 2); // E4 on the left, and F4 on the right
+    /// assert_eq!(changes.length(), old.length());
+    /// ```
+    pub fn to_piece(&self) -> Piece {
+        let removed = self.removed.iter().map(|(time, note)| (*time, note.pan(-1.0)));
+        let added = self.added.iter().map(|(time, note)| (*time, note.pan(1.0)));
+        Piece(lines_from_timed_notes(removed.chain(added).collect()))
+    }
+}
+
+/// Gets every sounding note in the piece, with the instant it starts at, in order.
+fn timed_notes(piece: &Piece) -> Vec<(usize, Note)> {
+    let mut notes = vec![];
+    for line in &piece.0 {
+        let mut time = 0_usize;
+        for note in &line.notes {
+            if matches!(note.1, NoteKind::Pitched { .. }) {
+                notes.push((time, note.clone()));
+            }
+            time = time.saturating_add(usize::from(note.0.ticks()));
+        }
+    }
+
+    notes.sort_by_key(|(time, _)| *time);
+    notes
+}
+
+/// Arranges notes into as few lines as possible, so that each note starts at its instant.
+fn lines_from_timed_notes(mut notes: Vec<(usize, Note)>) -> Vec<Line> {
+    notes.sort_by_key(|(time, _)| *time);
+
+    // Each line, with the instant it currently ends at
+    let mut lines: Vec<(Line, usize)> = vec![];
+    for (time, note) in notes {
+        let index = match lines.iter().position(|(_, end)| *end <= time) {
+            Some(index) => index,
+            None => {
+                lines.push((Line::new(), 0));
+                lines.len().saturating_sub(1)
+            }
+        };
+        let (line, end) = &mut lines[index];
+
+        // Rests are split up if the gap is too long for one note
+        let mut gap = time.saturating_sub(*end);
+        while gap > 0 {
+            let rest = u16::try_from(gap).unwrap_or(u16::MAX);
+            line.notes.push(Note(NoteLength::from_ticks(rest), NoteKind::Rest));
+            gap = gap.saturating_sub(usize::from(rest));
+        }
+
+        *end = time.saturating_add(usize::from(note.0.ticks()));
+        line.notes.push(note);
+    }
+
+    lines.into_iter().map(|(line, _)| line).collect()
+}
@@ -0,0 +1,156 @@
+use crate::{Line, Note, NoteKind, NoteLength, Piece, Tet12, REST, TICKS_PER_SIXTEENTH};
+
+/// The length of a section in ticks (one bar of 4/4). Each section has a single intensity.
+const SECTION_LENGTH: usize = 16 * TICKS_PER_SIXTEENTH as usize;
+
+/// Below this intensity, notes off the beat are dropped.
+const LOW_INTENSITY: f32 = 0.25;
+
+/// Above this intensity, long notes are re-struck and the top line is doubled an octave up.
+const HIGH_INTENSITY: f32 = 0.75;
+
+/// The length of a beat (a quarter note) in ticks. Long notes are re-struck on every beat at high intensity.
+const BEAT_LENGTH: u16 = 4 * TICKS_PER_SIXTEENTH;
+
+impl Piece {
+    /// Shapes the energy of the piece according to an intensity curve.
+    ///
+    /// The piece is split into one-bar sections, and the curve is sampled at the middle of each one.
+    /// The curve is given the position within the piece, from `0.0` (the start) to `1.0` (the end),
+    /// and should return an intensity from `0.0` (calmest) to `1.0` (most energetic).
+    ///
+    /// Each section is then shaped according to its intensity:
+    /// - **Velocity**: Volumes are scaled from half (intensity `0.0`) to one and a half times (intensity `1.0`).
+    ///   An intensity of `0.5` leaves volumes unchanged.
+    /// - **Rhythmic density**: Below an intensity of `0.25`, notes which don't start on a beat are dropped.
+    ///   Above `0.75`, notes of a half note or longer are re-struck every quarter note.
+    /// - **Register doubling**: Above an intensity of `0.75`, the first line is doubled an octave higher.
+    ///   The doubling is added as a new line at the end of the piece.
+    ///
+    /// # Parameters
+    /// - `curve`: A function from position in the piece to intensity
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let verse = piano(whole(C4) + whole(A4) + whole(C4) + whole(A4));
+    ///
+    /// // Build up over the whole piece
+    /// let build = Piece::from(verse.clone()).shape_intensity(|position| position);
+    /// assert_eq!(build.length(), verse.length());
+    ///
+    /// // Build up to the middle, then release
+    /// let arc = Piece::from(verse).shape_intensity(|position| 1.0 - (2.0 * position - 1.0).abs());
+    /// ```
+    ///
+    /// A constant low intensity thins out busy rhythms:
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let busy = Piece::from(piano(eighth(C4) * 8));
+    /// let calm = busy.shape_intensity(|_| 0.0);
+    ///
+    /// // Only the notes on the beat are left
+    /// let pitched_notes = calm.0[0].notes.iter().filter(|note| note.1 != REST).count();
+    /// assert_eq!(pitched_notes, 4);
+    /// ```
+    #[expect(clippy::arithmetic_side_effects, reason = "Manual bounds checking")]
+    #[expect(clippy::cast_precision_loss, reason = "Only used to sample the curve")]
+    pub fn shape_intensity(&self, curve: impl Fn(f32) -> f32) -> Piece {
+        let length = self.length();
+        if length == 0 {
+            return self.clone();
+        }
+
+        let section_intensities: Vec<f32> = (0..length.div_ceil(SECTION_LENGTH))
+            .map(|section| {
+                let midpoint = section * SECTION_LENGTH + SECTION_LENGTH / 2;
+                curve((midpoint as f32 / length as f32).min(1.0)).clamp(0.0, 1.0)
+            })
+            .collect();
+        let intensity_at =
+            |time: usize| section_intensities[(time / SECTION_LENGTH).min(section_intensities.len() - 1)];
+
+        let mut lines = vec![];
+        let mut doubling = None;
+
+        for (line_index, line) in self.0.iter().enumerate() {
+            let mut notes = vec![];
+            let mut doubled_notes = vec![];
+            let mut time = 0;
+
+            for note in &line.notes {
+                let intensity = intensity_at(time);
+                let shaped = shape_note(note.clone(), time, intensity);
+
+                if intensity > HIGH_INTENSITY {
+                    doubled_notes.extend(shaped.iter().map(|note| octave_up(note.clone())));
+                } else {
+                    doubled_notes.push(Note(note.0, REST));
+                }
+
+                notes.extend(shaped);
+                time += note.0 .0 as usize;
+            }
+
+            if line_index == 0 && doubled_notes.iter().any(|note| note.1 != REST) {
+                doubling = Some(Line::from(doubled_notes));
+            }
+
+            lines.push(Line { notes, ..line.clone() });
+        }
+
+        lines.extend(doubling);
+        Piece(lines)
+    }
+}
+
+/// Applies the velocity and rhythmic density changes for a note starting at `time`.
+fn shape_note(note: Note, time: usize, intensity: f32) -> Vec<Note> {
+    let NoteKind::Pitched { volume, .. } = note.1 else {
+        return vec![note];
+    };
+
+    if intensity < LOW_INTENSITY && !time.is_multiple_of(BEAT_LENGTH as usize) {
+        return vec![Note(note.0, REST)];
+    }
+
+    let note = note.volume(volume * (0.5 + intensity));
+
+    if intensity > HIGH_INTENSITY && note.0 .0 >= 2 * BEAT_LENGTH {
+        let mut strikes = vec![Note(NoteLength(BEAT_LENGTH), note.1.clone()); (note.0 .0 / BEAT_LENGTH) as usize];
+        if !note.0 .0.is_multiple_of(BEAT_LENGTH) {
+            strikes.push(Note(NoteLength(note.0 .0 % BEAT_LENGTH), note.1));
+        }
+        return strikes;
+    }
+
+    vec![note]
+}
+
+fn octave_up(note: Note) -> Note {
+    match note.1 {
+        NoteKind::Pitched {
+            pitch,
+            timbre,
+            volume,
+            envelope,
+            pan,
+            probability,
+            ratchet,
+        } => Note(
+            note.0,
+            NoteKind::Pitched {
+                pitch: pitch.octave(1),
+                timbre,
+                volume,
+                envelope,
+                pan,
+                probability,
+                ratchet,
+            },
+        ),
+        NoteKind::Rest => note,
+    }
+}
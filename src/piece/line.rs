@@ -1,8 +1,11 @@
-use std::ops::{Add, Mul, Neg, Not};
+use std::{
+    ops::{Add, Mul, Neg, Not},
+    sync::Arc,
+};
 
 use crate::{
     note::{NoteKind, NoteLength},
-    Note,
+    Effect, Note,
 };
 
 use super::Piece;
@@ -54,6 +57,10 @@ pub struct Line {
     pub pickup: Vec<Note>,
     /// Whether the pickup should be held into the first note of the main sequence
     pub hold_pickup: bool,
+    /// Effects applied, in order, to the sound of the whole line
+    pub effects: Vec<Arc<dyn Effect>>,
+    /// The name of the part the line plays, such as "bass", if it has one
+    pub name: Option<String>,
 }
 
 impl Line {
@@ -69,26 +76,31 @@ impl Line {
     pub fn new() -> Line {
         Line::default()
     }
-    /// Extends the line by adding a rest of the specified duration.
+    /// Extends the line by adding a rest of the specified duration, in ticks.
     ///
     /// This is mostly used internally for convenience, but can also be used
-    /// to add rests to a melody or rhythm line.
+    /// to add rests to a melody or rhythm line. Rests longer than a single note
+    /// can last are added as several rests in a row.
     ///
     /// # Examples
     /// ```
     /// use symphoxy::prelude::*;
     ///
     /// let melody = piano(quarter(C4)) + piano(quarter(A4));
-    /// let extended = melody.extend(4); // Add a quarter rest (4 time units)
+    /// let extended = melody.extend(usize::from(NoteLength::new(4).ticks())); // Add a quarter rest
+    /// assert_eq!(extended.length(), NoteLength::new(12).ticks() as usize);
     /// ```
-    pub fn extend(&self, extend_by: u16) -> Self {
-        if extend_by == 0 {
-            return self.clone();
+    pub fn extend(&self, extend_by: usize) -> Self {
+        let mut line = self.clone();
+        let mut remaining = extend_by;
+        while remaining > 0 {
+            let rest = u16::try_from(remaining).unwrap_or(u16::MAX);
+            line.notes.push(Note(NoteLength(rest), NoteKind::Rest));
+            remaining = remaining.saturating_sub(usize::from(rest));
         }
-        #[expect(clippy::arithmetic_side_effects, reason = "User is expected to handle this error")]
-        return self.clone() + Note(NoteLength(extend_by), NoteKind::Rest);
+        line
     }
-    /// Returns the total duration of the line in time units.
+    /// Returns the total duration of the line in ticks.
     ///
     /// This sums up the durations of all notes in the main sequence.
     /// Pickup notes are not included in this calculation.
@@ -97,8 +109,8 @@ impl Line {
     /// ```
     /// use symphoxy::prelude::*;
     ///
-    /// let line = piano(quarter(C4)) + piano(half(A4)); // 4 + 8 = 12 time units
-    /// assert_eq!(line.length(), 12);
+    /// let line = piano(quarter(C4)) + piano(half(A4)); // 4 + 8 = 12 sixteenths
+    /// assert_eq!(line.length(), 12 * TICKS_PER_SIXTEENTH as usize);
     /// ```
     pub fn length(&self) -> usize {
         self.notes.iter().map(|note| note.0 .0 as usize).sum()
@@ -118,45 +130,190 @@ impl Line {
     /// let very_loud = loud_line.volume(2.0);  // Double volume
     /// ```
     pub fn volume(&self, volume: f32) -> Line {
+        self.map_notes(|note| note.volume(volume))
+    }
+
+    /// Creates a new line with a function applied to every note, including the pickup, keeping
+    /// everything else about the line, such as its effects and name.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let melody = piano(quarter(C4) + quarter(E4)).with_name("melody");
+    /// let higher = melody.map_notes(|note| note.octave(1));
+    ///
+    /// assert_eq!(higher, piano(quarter(C4.octave(1)) + quarter(E4.octave(1))).with_name("melody"));
+    /// ```
+    pub fn map_notes(&self, f: impl Fn(&Note) -> Note) -> Line {
+        // Destructured in full, so a new field can't be left out here by accident
+        let Line {
+            notes,
+            pickup,
+            hold_pickup,
+            effects,
+            name,
+        } = self;
         Line {
-            notes: self.notes.iter().map(|note| note.volume(volume)).collect(),
-            pickup: self.pickup.iter().map(|note| note.volume(volume)).collect(),
-            hold_pickup: self.hold_pickup,
+            notes: notes.iter().map(&f).collect(),
+            pickup: pickup.iter().map(&f).collect(),
+            hold_pickup: *hold_pickup,
+            effects: effects.clone(),
+            name: name.clone(),
         }
     }
 
+    /// Creates a new line with every pitched note's volume multiplied by the given amount,
+    /// keeping the dynamics between notes.
+    pub(crate) fn scale_volume(&self, scale: f32) -> Line {
+        self.map_notes(|note| match note.1 {
+            NoteKind::Pitched { volume, .. } => note.volume(volume * scale),
+            NoteKind::Rest => note.clone(),
+        })
+    }
+
+    /// Creates a new line with the given name, such as the name of the part it plays.
+    ///
+    /// Named lines can be found in a piece with [`Piece::part`]. When lines are joined with `+`,
+    /// the joined line keeps the first line's name, or the second's if the first has none.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let bass_line = bass(half(C4.octave(-2)) + half(G4.octave(-3))).with_name("bass");
+    /// let piece = Line::from(piano(whole(E4))) * bass_line;
+    ///
+    /// assert_eq!(piece.part("bass").unwrap().notes.len(), 2);
+    /// ```
+    pub fn with_name(self, name: impl Into<String>) -> Line {
+        Line {
+            name: Some(name.into()),
+            ..self
+        }
+    }
+
+    /// Creates a new line with all notes set to the specified stereo position.
+    ///
+    /// See [`Note::pan`] for details. Rest notes are unaffected.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let melody = piano(quarter(C4) + quarter(A4)).pan(-0.3); // Slightly left
+    /// let bass_line = bass(half(C4.octave(-1))).pan(0.3);     // Slightly right
+    /// let piece = melody * bass_line;
+    /// ```
+    pub fn pan(&self, pan: f32) -> Line {
+        self.map_notes(|note| note.pan(pan))
+    }
+
+    /// Creates a new line with effects, such as reverb or delay, applied to its sound.
+    ///
+    /// The effects are applied in order, after any effects the line already has, to the mix of
+    /// the line's notes, before it's mixed with other lines. When lines are joined with `+`,
+    /// the joined line keeps the first line's effects, or the second's if the first has none.
+    ///
+    /// # Parameters
+    /// - `effects`: The effects to apply, in order. See [`Effect`] for the built-in effects.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use std::sync::Arc;
+    ///
+    /// let effects: Vec<Arc<dyn Effect>> = vec![
+    ///     Arc::new(Distortion::default()),
+    ///     Arc::new(Delay::default()),
+    ///     Arc::new(Reverb::default()),
+    /// ];
+    /// let lead = synth_lead(quarter(C5) + quarter(G4) + half(E4)).with_effects(effects);
+    /// assert_eq!(lead.effects.len(), 3);
+    /// ```
+    pub fn with_effects(self, effects: Vec<Arc<dyn Effect>>) -> Line {
+        Line {
+            effects: [self.effects, effects].concat(),
+            ..self
+        }
+    }
+
+    /// Creates a new line with an effect applied to its sound, after any effects it already has.
+    ///
+    /// This is a shortcut for [`Line::with_effects`] with a single effect.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let melody = piano(quarter(C4) + quarter(E4) + half(G4))
+    ///     .with_effect(Delay::default())
+    ///     .with_effect(Reverb::default());
+    /// ```
+    pub fn with_effect(self, effect: impl Effect + 'static) -> Line {
+        self.with_effects(vec![Arc::new(effect)])
+    }
+
     /// Gets the note that starts playing at a specific time instant.
     ///
     /// Returns an iterator containing the note that begins at the specified
-    /// time point, or an empty iterator if no note starts at that instant.
+    /// time point in ticks, or an empty iterator if no note starts at that instant.
     /// This is useful for timing-based analysis or custom playback systems.
     ///
     /// # Examples
     /// ```
     /// use symphoxy::prelude::*;
     ///
-    /// let line = piano(quarter(C4) + half(A4)); // C4 at 0, A4 at 4
+    /// let line = piano(quarter(C4) + half(A4)); // C4 at 0, A4 after a quarter note
+    /// let quarter_ticks = NoteLength::new(4).ticks() as usize;
     ///
     /// let notes_at_0: Vec<_> = line.get_notes_at_instant(0).collect();
     /// assert_eq!(notes_at_0.len(), 1); // C4 starts at time 0
     ///
-    /// let notes_at_4: Vec<_> = line.get_notes_at_instant(4).collect();  
-    /// assert_eq!(notes_at_4.len(), 1); // D4 starts at time 4
+    /// let notes_at_quarter: Vec<_> = line.get_notes_at_instant(quarter_ticks).collect();
+    /// assert_eq!(notes_at_quarter.len(), 1); // A4 starts after a quarter note
     ///
-    /// let notes_at_2: Vec<_> = line.get_notes_at_instant(2).collect();
-    /// assert_eq!(notes_at_2.len(), 0); // No note starts at time 2
+    /// let notes_at_eighth: Vec<_> = line.get_notes_at_instant(quarter_ticks / 2).collect();
+    /// assert_eq!(notes_at_eighth.len(), 0); // No note starts after an eighth note
     /// ```
-    #[expect(clippy::arithmetic_side_effects, reason = "Manual bounds checking, almost always safe")]
     pub fn get_notes_at_instant(&self, instant: usize) -> impl Iterator<Item = Note> {
-        let mut time_acc = 0;
-        for note in self.notes.clone() {
-            if time_acc == instant {
-                return Some(note).into_iter();
-            }
-            time_acc += note.0 .0 as usize
-        }
+        self.note_starting_at(instant).cloned().into_iter()
+    }
 
-        None.into_iter()
+    /// Returns the note which starts at the given instant, in ticks, if any.
+    pub(crate) fn note_starting_at(&self, instant: usize) -> Option<&Note> {
+        self.iter_timed()
+            .take_while(|(start, _)| *start <= instant)
+            .find(|(start, _)| *start == instant)
+            .map(|(_, note)| note)
+    }
+
+    /// Returns the note which is playing during the given instant, in ticks, if any.
+    pub(crate) fn note_during(&self, instant: usize) -> Option<&Note> {
+        self.iter_timed()
+            .take_while(|(start, _)| *start <= instant)
+            .find(|(start, note)| instant < start.saturating_add(usize::from(note.0 .0)))
+            .map(|(_, note)| note)
+    }
+
+    /// Returns every note of the line with when it starts, in ticks from the start of the line.
+    ///
+    /// Pickups aren't included, as they start before the line does.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let line = piano(quarter(C4) + eighth(REST) + eighth(G4));
+    /// let starts: Vec<usize> = line.iter_timed().map(|(start, _)| start).collect();
+    /// assert_eq!(starts, [0, 4 * TICKS_PER_SIXTEENTH as usize, 6 * TICKS_PER_SIXTEENTH as usize]);
+    /// ```
+    pub fn iter_timed(&self) -> impl Iterator<Item = (usize, &Note)> {
+        self.notes.iter().scan(0usize, |start, note| {
+            let note_start = *start;
+            *start = start.saturating_add(usize::from(note.0 .0));
+            Some((note_start, note))
+        })
     }
 }
 
@@ -167,7 +324,7 @@ impl Neg for Line {
         Self {
             notes: vec![],
             pickup: self.notes,
-            hold_pickup: self.hold_pickup,
+            ..self
         }
     }
 }
@@ -191,11 +348,7 @@ impl From<Note> for Line {
 
 impl From<Vec<Note>> for Line {
     fn from(notes: Vec<Note>) -> Line {
-        Line {
-            notes,
-            pickup: vec![],
-            hold_pickup: false,
-        }
+        Line { notes, ..Line::new() }
     }
 }
 
@@ -230,7 +383,6 @@ impl Add<Piece> for Line {
 
     /// This implementation puts this line as the first line of the piece
     #[expect(clippy::arithmetic_side_effects, reason = "Arithmetic implementation")]
-    #[expect(clippy::cast_possible_truncation, reason = "I don't want to deal with this right now")]
     fn add(self, rhs: Piece) -> Self::Output {
         if !rhs.0.is_empty() {
             let mut piece = rhs.clone();
@@ -238,7 +390,7 @@ impl Add<Piece> for Line {
 
             piece.0[0] = self + piece.0[0].clone();
             for line_no in 1..piece.0.len() {
-                piece.0[line_no] = Line::new().extend(self_len as u16) + piece.0[line_no].clone()
+                piece.0[line_no] = Line::new().extend(self_len) + piece.0[line_no].clone()
             }
 
             piece
@@ -284,7 +436,7 @@ impl Add<Line> for Line {
                 notes_to_remove += 1;
                 note_to_add = Some(Note(
                     NoteLength(note.0 .0 - (pickup_length - time_removed) as u16),
-                    note.1,
+                    note.1.clone(),
                 ));
                 break;
             }
@@ -306,7 +458,7 @@ impl Add<Line> for Line {
             if let Some(last_note) = notes.iter().last() {
                 let last_index = notes.len() - 1;
 
-                notes[last_index] = Note(NoteLength(last_note.0 .0 + rhs_notes[0].0 .0), last_note.1);
+                notes[last_index] = Note(NoteLength(last_note.0 .0 + rhs_notes[0].0 .0), last_note.1.clone());
 
                 rhs_notes.remove(0);
             }
@@ -314,8 +466,16 @@ impl Add<Line> for Line {
 
         Line {
             notes: [notes, rhs_notes].concat(),
-            pickup: self.pickup,
-            hold_pickup: self.hold_pickup,
+            // The first line's effects are kept, unless it has none
+            effects: if self.effects.is_empty() {
+                rhs.effects
+            } else {
+                self.effects
+            },
+            // Likewise the first line's name, unless it has none
+            name: self.name.or(rhs.name),
+            // Anything else, such as the pickup, is the first line's
+            ..self
         }
     }
 }
@@ -0,0 +1,172 @@
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
+};
+
+use crate::{LengthFluid, Line, Note, NoteKind, NoteLength, NotePitch, TICKS_PER_SIXTEENTH};
+
+/// The number of ticks in a whole note, which note lengths in melody text are divisions of.
+const TICKS_PER_WHOLE: u16 = TICKS_PER_SIXTEENTH * 16;
+
+/// Creates a [`Line`] from melody text, such as `notes!("c4 d4 e4/8 r/4 g4.")`.
+///
+/// This is shorthand for [`Line::parse_melody`], which describes the text, and panics if the
+/// text can't be read. Use [`Line::parse_melody`] directly to handle the error instead.
+///
+/// # Panics
+/// Panics if the text isn't valid melody text.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let melody = piano(notes!("e4/8 d4 c4 d4 | e4 e4 e4/4 | d4/8 d4 d4/4"));
+/// assert_eq!(melody.length(), 3 * 8 * TICKS_PER_SIXTEENTH as usize);
+/// ```
+#[macro_export]
+macro_rules! notes {
+    ($text:expr) => {
+        $crate::Line::parse_melody($text).unwrap_or_else(|error| panic!("{}", error))
+    };
+}
+
+impl Line {
+    /// Reads a line from melody text: a compact notation for writing melodies as note names.
+    ///
+    /// The text is a list of notes, separated by spaces:
+    /// - Each note is a pitch name, such as `c4`, `F#3`, or `bb5`, or `r` for a rest. Pitches
+    ///   are read with [`NotePitch::from_name`].
+    /// - A note's length is written after a `/`, as a fraction of a whole note, so `c4/8` is an
+    ///   eighth note. Lengths carry over to the notes after, and the first note is a quarter note
+    ///   if it has no length. Divisions of 3, such as `/12`, are triplets.
+    /// - Dots after a note, such as `g4/4.`, make it dotted, adding half its length for each dot.
+    /// - `|` can be written between bars to make the text easier to read, and is ignored.
+    ///
+    /// Notes are pitched with the default timbre, so the line is usually given an instrument,
+    /// such as with [`piano`](crate::piano). See [`notes!`](crate::notes) for a shorter way to
+    /// write melodies in code.
+    ///
+    /// # Parameters
+    /// - `text`: The melody text
+    ///
+    /// # Errors
+    /// Returns an error if a note's pitch or length can't be read, or if its length isn't a
+    /// whole number of ticks.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let melody = Line::parse_melody("c4 d4 e4/8 r/4 g4.").unwrap();
+    ///
+    /// // A quarter, a quarter, an eighth, a quarter rest, and a dotted quarter
+    /// assert_eq!(melody.notes.len(), 5);
+    /// assert_eq!(melody.length(), (4 + 4 + 2 + 4 + 6) * TICKS_PER_SIXTEENTH as usize);
+    /// ```
+    pub fn parse_melody(text: &str) -> Result<Line, ParseMelodyError> {
+        let mut length = NoteLength(TICKS_PER_WHOLE / 4);
+        let mut notes = vec![];
+        for word in text.split_whitespace().filter(|word| *word != "|") {
+            let error = |reason: &str| ParseMelodyError {
+                note: word.to_string(),
+                reason: reason.to_string(),
+            };
+
+            let undotted = word.trim_end_matches('.');
+            let dots = word.len().saturating_sub(undotted.len());
+            let (name, division) = match undotted.split_once('/') {
+                Some((name, division)) => (name, Some(division)),
+                None => (undotted, None),
+            };
+            if let Some(division) = division {
+                let division: u16 = division.parse().map_err(|_| error("the length isn't a number"))?;
+                length = TICKS_PER_WHOLE
+                    .checked_rem(division)
+                    .filter(|remainder| *remainder == 0)
+                    .and_then(|_| TICKS_PER_WHOLE.checked_div(division))
+                    .map(NoteLength)
+                    .ok_or_else(|| error("the length isn't a whole number of ticks"))?;
+            }
+
+            let note_length =
+                dot(length, dots).ok_or_else(|| error("the dotted length isn't a whole number of ticks"))?;
+            let note = if name.eq_ignore_ascii_case("r") {
+                Note(note_length, NoteKind::Rest)
+            } else {
+                let pitch = NotePitch::from_name(name).ok_or_else(|| error("the pitch isn't a note name"))?;
+                pitch.with_length(note_length)
+            };
+            notes.push(note);
+        }
+        Ok(Line { notes, ..Line::new() })
+    }
+}
+
+impl FromStr for Line {
+    type Err = ParseMelodyError;
+
+    /// Reads a line from melody text. See [`Line::parse_melody`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Line::parse_melody(s)
+    }
+}
+
+/// Returns the length with the given number of dots, each adding half as much as the one before,
+/// or `None` if that isn't a whole number of ticks.
+fn dot(length: NoteLength, dots: usize) -> Option<NoteLength> {
+    let (mut total, mut added) = (length.0, length.0);
+    for _ in 0..dots {
+        if added.checked_rem(2)? != 0 {
+            return None;
+        }
+        added = added.checked_div(2)?;
+        total = total.checked_add(added)?;
+    }
+    Some(NoteLength(total))
+}
+
+/// The error returned when a [`Line`] can't be read from melody text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseMelodyError {
+    /// The note which couldn't be read, as it was written
+    pub note: String,
+    /// Why it couldn't be read
+    pub reason: String,
+}
+
+impl Display for ParseMelodyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "invalid note \"{}\": {}", self.note, self.reason)
+    }
+}
+
+impl std::error::Error for ParseMelodyError {}
+
+#[test]
+fn test_melody_text_lengths_carry_over() {
+    use crate::prelude::*;
+
+    let pitch = |name| NotePitch::from_name(name).unwrap();
+    let melody: Line = "C4/8 r d#4 | eb4/12 f4 g4 | a4/2.. b4/1".parse().unwrap();
+    assert_eq!(
+        melody,
+        Line::from(eighth(pitch("C4")))
+            + eighth(REST)
+            + eighth(pitch("D#4"))
+            + triplet(eighth)(pitch("D#4"))
+            + triplet(eighth)(pitch("F4"))
+            + triplet(eighth)(pitch("G4"))
+            + Note(NoteLength::new(14), A4.into())
+            + whole(pitch("B4"))
+    );
+
+    assert_eq!(
+        Line::parse_melody("c4 x4"),
+        Err(ParseMelodyError {
+            note: "x4".to_string(),
+            reason: "the pitch isn't a note name".to_string()
+        })
+    );
+    assert!(Line::parse_melody("c4/64...").is_err());
+    assert!(Line::parse_melody("c4/5").is_err());
+}
@@ -0,0 +1,129 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use crate::Key;
+
+/// Information about a piece of music, such as its title and who wrote it.
+///
+/// Pieces are built up from lines by joining and layering them, so they don't carry metadata
+/// themselves. Instead, it's given to whatever presents the piece: [`NotationOptions`](crate::NotationOptions)
+/// writes it into the header of LilyPond scores, with the `wav-output` feature
+/// `MusicPlayer::with_metadata` writes it into the WAV files the player renders, and with the
+/// `interactive-tui` feature `InteractiveTui::start_with_metadata` shows it.
+///
+/// Every field is optional, and fields which aren't set are left out.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::{Metadata, NotationOptions};
+///
+/// let metadata = Metadata::new("Ode to Joy")
+///     .with_artist("Ludwig van Beethoven")
+///     .with_tempo(480)
+///     .with_key("D major");
+///
+/// assert_eq!(metadata.to_string(), "Ode to Joy by Ludwig van Beethoven (D major, 480 BPM)");
+///
+/// let theme = Piece::from(piano(quarter(FS4) * 2 + quarter(G4) + quarter(A4)));
+/// let score = theme.to_lilypond_with(&NotationOptions { metadata, ..NotationOptions::default() });
+/// assert!(score.contains("title = \"Ode to Joy\""));
+/// assert!(score.contains("composer = \"Ludwig van Beethoven\""));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Metadata {
+    /// The title of the piece
+    pub title: Option<String>,
+    /// Who wrote or performs the piece
+    pub artist: Option<String>,
+    /// The copyright notice, such as "© 2025 Jane Doe"
+    pub copyright: Option<String>,
+    /// The tempo the piece is meant to be played at, in sixteenth notes per minute.
+    /// This is only a hint: players keep their own tempo.
+    pub tempo_bpm: Option<u32>,
+    /// The key of the piece, such as "A minor"
+    pub key: Option<String>,
+}
+
+impl Metadata {
+    /// Creates metadata for a piece with the given title.
+    pub fn new(title: impl Into<String>) -> Self {
+        Metadata {
+            title: Some(title.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Sets who wrote or performs the piece.
+    pub fn with_artist(mut self, artist: impl Into<String>) -> Self {
+        self.artist = Some(artist.into());
+        self
+    }
+
+    /// Sets the copyright notice.
+    pub fn with_copyright(mut self, copyright: impl Into<String>) -> Self {
+        self.copyright = Some(copyright.into());
+        self
+    }
+
+    /// Sets the tempo the piece is meant to be played at, in sixteenth notes per minute.
+    pub fn with_tempo(mut self, tempo_bpm: u32) -> Self {
+        self.tempo_bpm = Some(tempo_bpm);
+        self
+    }
+
+    /// Sets the key of the piece, such as "A minor". A [`Key`] can be given with its `to_string`,
+    /// so that it can be read back with [`Metadata::parsed_key`].
+    pub fn with_key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Reads the key of the piece as a [`Key`], or returns `None` if it isn't set or isn't
+    /// written as one, such as "Bb major".
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::Metadata;
+    ///
+    /// let metadata = Metadata::new("Etude").with_key(Key::new(AS3, Mode::Major).to_string());
+    /// assert_eq!(metadata.key.as_deref(), Some("Bb major"));
+    ///
+    /// let key = metadata.parsed_key().unwrap();
+    /// assert_eq!(DS4.name(&key.note_names()), "Eb4");
+    /// ```
+    pub fn parsed_key(&self) -> Option<Key> {
+        self.key.as_deref()?.parse().ok()
+    }
+
+    /// Returns whether no field is set.
+    pub fn is_empty(&self) -> bool {
+        *self == Metadata::default()
+    }
+
+    /// Returns the key and tempo, such as "D major, 480 BPM", or `None` if neither is set.
+    pub(crate) fn details(&self) -> Option<String> {
+        let details: Vec<String> = self
+            .key
+            .iter()
+            .cloned()
+            .chain(self.tempo_bpm.map(|tempo_bpm| format!("{tempo_bpm} BPM")))
+            .collect();
+        (!details.is_empty()).then(|| details.join(", "))
+    }
+}
+
+impl Display for Metadata {
+    /// Formats the metadata as one line, such as "Ode to Joy by Ludwig van Beethoven (D major, 480 BPM)".
+    /// The copyright isn't included.
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.title.as_deref().unwrap_or("Untitled"))?;
+        if let Some(artist) = &self.artist {
+            write!(f, " by {artist}")?;
+        }
+        if let Some(details) = self.details() {
+            write!(f, " ({details})")?;
+        }
+        Ok(())
+    }
+}
@@ -7,15 +7,47 @@ use itertools::{EitherOrBoth, Itertools};
 use line::Line;
 
 use crate::{
-    note::{NoteKind, NotePitch, Timbre},
-    scales::tet12::{self, A4, C4},
-    Note, Tet12,
+    note::{NoteKind, NotePitch, Timbre, TICKS_PER_SIXTEENTH},
+    scales::tet12::{self, Key, NoteNameOptions, A4, C4},
+    Chord, Meter, Note, Scale, Tet12,
 };
 
+mod assets;
+mod crossfade;
+mod diff;
+mod intensity;
 /// Line sequence types and functionality.
 ///
 /// Contains the `Line` type for representing sequential note sequences.
 pub mod line;
+mod melody;
+mod metadata;
+mod notation;
+mod part;
+mod percussion;
+mod piece_file;
+mod project;
+mod schedule;
+mod slice;
+mod song;
+mod swing;
+mod transform;
+mod variation;
+
+#[cfg(any(feature = "wav-output", feature = "live-output"))]
+pub use assets::AssetError;
+pub use diff::PieceDiff;
+pub use melody::ParseMelodyError;
+pub use metadata::Metadata;
+pub use notation::NotationOptions;
+pub use part::Part;
+pub use percussion::{Notehead, PercussionMap, PercussionNotation};
+pub use piece_file::{PieceFileError, PIECE_FILE_VERSION};
+pub use project::{Movement, Project};
+pub use schedule::{NoteEvent, ScheduledPiece};
+pub use song::{Section, Song, SongError};
+pub use transform::{zip_rhythm, StretchError};
+pub use variation::VariationRules;
 
 /// Represents a complete musical composition with multiple simultaneous parts.
 ///
@@ -76,6 +108,22 @@ impl Piece {
     pub fn volume(&self, volume: f32) -> Self {
         Piece(self.0.iter().map(|line| line.volume(volume)).collect())
     }
+
+    /// Creates a new piece with all notes set to the specified stereo position.
+    ///
+    /// This is mostly useful for moving a whole piece before combining it with another.
+    /// To spread the lines of a piece across the stereo field, pan each line instead.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let accompaniment = piano(quarter(C4)) * bass(quarter(C4));
+    /// let left_accompaniment = accompaniment.pan(-0.5);
+    /// ```
+    pub fn pan(&self, pan: f32) -> Self {
+        Piece(self.0.iter().map(|line| line.pan(pan)).collect())
+    }
 }
 
 impl From<Line> for Piece {
@@ -107,7 +155,7 @@ impl Piece {
     /// Gets all notes that start playing at a specific time instant.
     ///
     /// Returns an iterator over all notes across all lines that begin
-    /// at the specified time point. Useful for analysis or custom playback.
+    /// at the specified time point in ticks. Useful for analysis or custom playback.
     ///
     /// # Examples
     /// ```
@@ -117,32 +165,35 @@ impl Piece {
     /// let notes_at_start: Vec<_> = piece.get_notes_at_instant(0).collect();
     /// assert_eq!(notes_at_start.len(), 2); // Piano C4 and bass A4
     /// ```
+    ///
+    /// Each call walks the lines up to the instant. To look up many instants, such as every
+    /// instant of the piece, [`compile`](Piece::compile) it once and use [`ScheduledPiece::notes_at_instant`].
     pub fn get_notes_at_instant(&self, instant: usize) -> impl Iterator<Item = Note> {
-        self.0
-            .clone()
-            .into_iter()
-            .flat_map(move |l| l.get_notes_at_instant(instant).collect::<Vec<_>>())
+        let notes: Vec<Note> = self
+            .0
+            .iter()
+            .filter_map(|line| line.note_starting_at(instant))
+            .cloned()
+            .collect();
+        notes.into_iter()
     }
 
     /// As opposed to `get_notes_at_instant`, this gets any note which would
     /// be playing during a given instant, rather than the notes which start at a given instant.
-    #[expect(clippy::arithmetic_side_effects, reason = "Manual bounds checking, almost always safe")]
+    ///
+    /// To look up many instants, [`compile`](Piece::compile) the piece once and use
+    /// [`ScheduledPiece::notes_during_instant`].
     pub fn get_notes_during_instant(&self, instant: usize) -> impl Iterator<Item = Note> {
-        self.0.clone().into_iter().filter_map(move |l| {
-            // get note at time
-            let mut time_acc = 0;
-            for note in l.notes.clone() {
-                if time_acc <= instant && instant < time_acc + note.0 .0 as usize {
-                    return Some(note);
-                }
-                time_acc += note.0 .0 as usize;
-            }
-
-            None
-        })
+        let notes: Vec<Note> = self
+            .0
+            .iter()
+            .filter_map(|line| line.note_during(instant))
+            .cloned()
+            .collect();
+        notes.into_iter()
     }
 
-    /// Returns the total duration of the piece in time units.
+    /// Returns the total duration of the piece in ticks.
     ///
     /// This is the length of the longest line in the piece, since all lines
     /// play simultaneously and the piece ends when the longest line finishes.
@@ -151,11 +202,11 @@ impl Piece {
     /// ```
     /// use symphoxy::prelude::*;
     ///
-    /// let short_line = piano(quarter(C4));           // 4 time units
-    /// let long_line = piano(whole(C4));              // 16 time units  
+    /// let short_line = piano(quarter(C4));           // 4 sixteenths
+    /// let long_line = piano(whole(C4));              // 16 sixteenths
     /// let piece = short_line * long_line;
     ///
-    /// assert_eq!(piece.length(), 16); // Length of the longest line
+    /// assert_eq!(piece.length(), whole(C4).0.ticks() as usize); // Length of the longest line
     /// ```
     pub fn length(&self) -> usize {
         self.0.iter().map(|line| line.length()).max().unwrap_or_default()
@@ -191,10 +242,9 @@ impl Add<Piece> for Piece {
     type Output = Piece;
 
     #[expect(clippy::arithmetic_side_effects, reason = "Arithmetic implementation")]
-    #[expect(clippy::cast_possible_truncation, reason = "I don't want to deal with this right now")]
     fn add(self, rhs: Piece) -> Self::Output {
-        let self_length = self.length() as u16;
-        let rhs_length = rhs.length() as u16;
+        let self_length = self.length();
+        let rhs_length = rhs.length();
         Piece(
             self.0
                 .into_iter()
@@ -222,7 +272,6 @@ impl Add<Note> for Piece {
 impl Mul<Line> for Piece {
     type Output = Piece;
 
-    #[expect(clippy::cast_possible_truncation, reason = "I don't want to deal with this right now")]
     fn mul(self, rhs: Line) -> Self::Output {
         let self_len = self.length();
         let rhs_len = rhs.length();
@@ -233,12 +282,12 @@ impl Mul<Line> for Piece {
             .0
             .into_iter()
             .map(|line| {
-                let padding = new_len.saturating_sub(self_len) as u16;
+                let padding = new_len.saturating_sub(self_len);
                 line.extend(padding)
             })
             .collect();
 
-        let padding = new_len.saturating_sub(rhs_len) as u16;
+        let padding = new_len.saturating_sub(rhs_len);
         let extended_rhs = vec![rhs.extend(padding)];
 
         Piece([extended_self, extended_rhs].concat())
@@ -253,38 +302,342 @@ impl Mul<Note> for Piece {
     }
 }
 
+/// Displays a piece as a grid, with its bars laid out in a given meter.
+///
+/// Created with [`Piece::display_with_meter`].
+pub struct ScoreDisplay<'a> {
+    piece: &'a Piece,
+    meter: Meter,
+    /// The pitches of the seven degrees of the key, if chords should be labelled
+    key: Option<[NotePitch; 7]>,
+    /// How the rows are labelled with note names
+    note_names: NoteNameOptions,
+}
+
+impl ScoreDisplay<'_> {
+    /// Labels each bar with the chord it holds, as a chord symbol and a Roman numeral in the key.
+    ///
+    /// Chords are found with [`Piece::bar_chords`]. Bars without a recognized chord aren't
+    /// labelled, and chords whose root isn't in the key are labelled with just their symbol.
+    ///
+    /// # Parameters
+    /// - `key`: The scale the chords are analysed in
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let key = MajorScale(C4);
+    /// let chords = Chord::from_degrees(&key, &[1, 3, 5]).strike(|pitch, _| piano(whole(pitch)).into())
+    ///     + Chord::from_degrees(&key, &[7, 9, 11, 12]).strike(|pitch, _| piano(whole(pitch)).into());
+    ///
+    /// let score = chords.display_with_meter(Meter::default()).with_key(&key).to_string();
+    /// assert!(score.lines().nth(1).unwrap().starts_with("       C (I)            G7/B (V65)"));
+    /// ```
+    pub fn with_key(self, key: &impl Scale) -> Self {
+        ScoreDisplay {
+            key: Some(key.get_degrees([1, 2, 3, 4, 5, 6, 7])),
+            ..self
+        }
+    }
+
+    /// Labels each row with its note name written as the options say, rather than in scientific
+    /// pitch notation with sharps. Chord labels are spelled with the same sharps or flats.
+    ///
+    /// # Parameters
+    /// - `note_names`: How the note names are written
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{NoteNameOptions, NoteNameStyle, Spelling};
+    ///
+    /// let german = NoteNameOptions { style: NoteNameStyle::German, spelling: Spelling::Flats };
+    /// let piece = Piece::from(piano(quarter(C4.semitone(-1)) + quarter(C4.semitone(-2))));
+    ///
+    /// let score = piece.display_with_meter(Meter::default()).with_note_names(german).to_string();
+    /// assert!(score.contains("H3  ║█║■"));
+    /// assert!(score.contains("B3  ║ ║    ■"));
+    /// ```
+    pub fn with_note_names(self, note_names: NoteNameOptions) -> Self {
+        ScoreDisplay { note_names, ..self }
+    }
+
+    /// Displays the score in a key: chords are labelled in the key, as with
+    /// [`ScoreDisplay::with_key`], and rows are labelled with note names spelled with the key's
+    /// sharps or flats.
+    ///
+    /// # Parameters
+    /// - `key`: The key of the piece
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let piece = Piece::from(piano(quarter(AS4) + quarter(DS4)));
+    /// let score = piece.display_with_meter(Meter::default()).in_key(&"Bb major".parse().unwrap()).to_string();
+    /// assert!(score.contains("Eb4║"));
+    /// assert!(score.lines().nth(1).unwrap().trim_start().starts_with("Eb"));
+    /// ```
+    pub fn in_key(self, key: &Key) -> Self {
+        ScoreDisplay {
+            note_names: NoteNameOptions {
+                spelling: key.note_names().spelling,
+                ..self.note_names
+            },
+            ..self.with_key(key)
+        }
+    }
+}
+
+impl Piece {
+    /// Displays the piece as a grid, like its `Display` implementation, but with barlines
+    /// and bar numbers placed according to the given meter.
+    ///
+    /// If the meter has an anacrusis, the partial bar before the first barline is numbered 0.
+    ///
+    /// # Parameters
+    /// - `meter`: How the piece is divided into bars
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let quarter_ticks = quarter(C4).0.ticks() as usize;
+    /// let meter = Meter::new(TimeSignature::COMMON_TIME).with_anacrusis(quarter_ticks);
+    ///
+    /// let piece = Piece::from(piano(quarter(G4) + whole(C4) + whole(E4)));
+    /// let score = piece.display_with_meter(meter).to_string();
+    /// assert!(score.starts_with("    0      1"));
+    /// ```
+    pub fn display_with_meter(&self, meter: Meter) -> ScoreDisplay<'_> {
+        ScoreDisplay {
+            piece: self,
+            meter,
+            key: None,
+            note_names: NoteNameOptions::default(),
+        }
+    }
+
+    /// Finds the chord played in each bar, ignoring drums.
+    ///
+    /// The chord holds every pitch heard during the bar, with octave doublings removed. If those
+    /// pitches don't make a chord recognized by [`Chord::symbol`], such as when a melody passes
+    /// through other notes, the chord holds just the pitches sounding at the start of the bar.
+    ///
+    /// The first chord is of the first bar of the meter, which is bar 0 if it has an anacrusis.
+    ///
+    /// # Parameters
+    /// - `meter`: How the piece is divided into bars
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let chords = Chord::new([C4, E4, G4]).strike(|pitch, _| piano(whole(pitch)).into());
+    /// let melody = piano(quarter(C5) + quarter(D5) + half(F5));
+    ///
+    /// let bar_chords = (chords * melody).bar_chords(Meter::default());
+    /// assert_eq!(bar_chords[0].symbol().as_deref(), Some("C"));
+    /// ```
+    pub fn bar_chords(&self, meter: Meter) -> Vec<Chord> {
+        bar_chords(&self.compile(), meter)
+    }
+}
+
+/// Finds the chord of each bar of a compiled piece. See [`Piece::bar_chords`].
+fn bar_chords(compiled: &ScheduledPiece, meter: Meter) -> Vec<Chord> {
+    let ticks = TICKS_PER_SIXTEENTH as usize;
+    let length = compiled.length();
+    if length == 0 {
+        return vec![];
+    }
+
+    (meter.first_bar()..=meter.bar_at(length.saturating_sub(1)))
+        .map(|bar| {
+            let start = meter.bar_start(bar);
+            let end = meter.bar_start(bar.saturating_add(1)).min(length);
+            let chord_at = |instants: &mut dyn Iterator<Item = usize>| {
+                let mut semitones: Vec<i16> = instants
+                    .flat_map(|instant| compiled.notes_during_instant(instant))
+                    .filter_map(|event| match &event.note.1 {
+                        NoteKind::Pitched { pitch, timbre, .. } if !matches!(timbre.unreversed(), Timbre::Drums) =>
+                        {
+                            #[expect(clippy::cast_possible_truncation, reason = "Intentional precision loss")]
+                            Some((12.0 * f32::log2(pitch.0 / C4.0)).round() as i16)
+                        }
+                        _ => None,
+                    })
+                    .collect();
+                semitones.sort_unstable();
+                // Keep only the lowest of each pitch class
+                let pitch_classes: Vec<i16> = semitones.iter().map(|s| s.rem_euclid(12)).collect();
+                Chord::new(
+                    semitones
+                        .iter()
+                        .enumerate()
+                        .filter(|&(index, semitone)| !pitch_classes[..index].contains(&semitone.rem_euclid(12)))
+                        .map(|(_, &semitone)| C4.semitone(semitone)),
+                )
+            };
+
+            let whole_bar = chord_at(&mut (start..end).step_by(ticks));
+            if whole_bar.symbol().is_some() {
+                whole_bar
+            } else {
+                chord_at(&mut std::iter::once(start))
+            }
+        })
+        .collect()
+}
+
+impl std::fmt::Display for ScoreDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.piece.write_score(f, self.meter, self.key, &self.note_names)
+    }
+}
+
 impl std::fmt::Display for Piece {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_score(f, Meter::default(), None, &NoteNameOptions::default())
+    }
+}
+
+impl Piece {
+    fn write_score(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        meter: Meter,
+        key: Option<[NotePitch; 7]>,
+        note_names: &NoteNameOptions,
+    ) -> std::fmt::Result {
         let black_keys = [
             false, true, false, true, false, false, true, false, true, false, true, false,
         ];
+        let compiled = self.compile();
+        let bar_chords = if key.is_some() {
+            bar_chords(&compiled, meter)
+        } else {
+            vec![]
+        };
+
+        // Each column of the grid is a sixteenth note
+        let ticks = TICKS_PER_SIXTEENTH as usize;
+
+        for bar_group in 0..compiled.length().div_ceil(ticks.saturating_mul(64)) {
+            // The notes which start during each column, and which are sounding at the start of each column
+            let group_start = bar_group.saturating_mul(64).saturating_mul(ticks);
+            let group_end = group_start.saturating_add(64_usize.saturating_mul(ticks));
+            let mut starting: Vec<Vec<&Note>> = vec![vec![]; 64];
+            let mut sounding: Vec<Vec<&Note>> = vec![vec![]; 64];
+            let (mut highest_semitone, mut lowest_semitone) = (i16::MIN, i16::MAX);
+            let events = compiled
+                .notes_during_instant(group_start)
+                .filter(|event| event.start < group_start)
+                .chain(compiled.events_starting_in(group_start, group_end));
+            for event in events {
+                let offset = event.start.saturating_sub(group_start);
+                if event.start >= group_start {
+                    if let Some(column) = starting.get_mut(offset.checked_div(ticks).unwrap_or_default()) {
+                        column.push(&event.note);
+                    }
+                }
+                let end = event.end().saturating_sub(group_start);
+                for column in sounding
+                    .iter_mut()
+                    .take(end.div_ceil(ticks))
+                    .skip(offset.div_ceil(ticks))
+                {
+                    column.push(&event.note);
+                }
 
-        for bar_group in 0..self.length().div_ceil(64) {
-            let (highest_semitone, lowest_semitone) = {
-                let (mut highest, mut lowest) = (i16::MIN, i16::MAX);
-                #[expect(clippy::arithmetic_side_effects, reason = "Guaranteed to be safe, manual bounds checking")]
-                for time in (bar_group * 64)..(bar_group * 64 + 64) {
-                    for note in self.get_notes_during_instant(time) {
-                        if let NoteKind::Pitched {
-                            pitch: NotePitch(frequency),
-                            ..
-                        } = note.1
-                        {
-                            let semitone_diff_from_c4 = 12.0 * f32::log2(frequency / C4.0);
-
-                            #[expect(clippy::cast_possible_truncation, reason = "Intentional precision loss")]
-                            if highest < semitone_diff_from_c4 as i16 {
-                                highest = semitone_diff_from_c4 as i16;
-                            } else if lowest > semitone_diff_from_c4 as i16 {
-                                lowest = semitone_diff_from_c4 as i16;
-                            }
-                        }
+                if let NoteKind::Pitched {
+                    pitch: NotePitch(frequency),
+                    ..
+                } = event.note.1
+                {
+                    // Notes which never sound don't make the grid any taller
+                    if end > offset {
+                        #[expect(clippy::cast_possible_truncation, reason = "Intentional precision loss")]
+                        let semitone = (12.0 * f32::log2(frequency / C4.0)) as i16;
+                        highest_semitone = highest_semitone.max(semitone);
+                        lowest_semitone = lowest_semitone.min(semitone);
                     }
                 }
-                (highest, lowest)
+            }
+
+            // The bar which starts during each column, if any. The first column is always marked.
+            let bar_starts: Vec<Option<usize>> = (0..64_usize)
+                .map(|bar_group_time| {
+                    #[expect(clippy::arithmetic_side_effects, reason = "Guaranteed to be safe, manual bounds checking")]
+                    let time = (64 * bar_group + bar_group_time) * ticks;
+                    (time..time.saturating_add(ticks))
+                        .find(|&tick| meter.is_bar_start(tick))
+                        .map(|tick| meter.bar_at(tick))
+                })
+                .collect();
+            // Rows are labelled with their note names, and long names push the grid to the right
+            let label = |semitone: i16| tet12::format_note_name_with_octave(C4.semitone(semitone), A4, note_names);
+            #[expect(clippy::arithmetic_side_effects, reason = "User's fault")]
+            let label_width = (lowest_semitone - 2..=highest_semitone + 2)
+                .map(|semitone| label(semitone).chars().count())
+                .fold(3, usize::max);
+            let indent = label_width.saturating_sub(3);
+
+            let barline_count = bar_starts.iter().skip(1).flatten().count();
+            let width = 71_usize.saturating_add(barline_count).saturating_add(indent);
+
+            // Number each bar above its barline, and label its chord just after the barline
+            let mut numbers = vec![' '; width];
+            let mut chord_labels = vec![' '; width];
+            let mut cursor = 7_usize.saturating_add(indent);
+            let (mut numbers_free_from, mut chords_free_from) = (0, 0);
+            let write_label = |row: &mut Vec<char>, free_from: &mut usize, position: usize, label: &str| {
+                let label_end = position.saturating_add(label.chars().count());
+                if position >= *free_from && label_end <= width {
+                    row.splice(position..label_end, label.chars());
+                    *free_from = label_end.saturating_add(1);
+                }
             };
+            for (bar_group_time, bar) in bar_starts.iter().enumerate() {
+                // The first bar is numbered above the left edge, and the rest above their barlines
+                let position = if bar_group_time == 0 {
+                    4_usize.saturating_add(indent)
+                } else {
+                    cursor
+                };
+                let chord_position = if bar_group_time == 0 {
+                    7_usize.saturating_add(indent)
+                } else {
+                    cursor.saturating_add(1)
+                };
+                if bar_group_time > 0 && bar.is_some() {
+                    cursor = cursor.saturating_add(1);
+                }
+                cursor = cursor.saturating_add(1);
+
+                let Some(bar) = bar else { continue };
+                write_label(&mut numbers, &mut numbers_free_from, position, &bar.to_string());
+
+                let chord = bar
+                    .checked_sub(meter.first_bar())
+                    .and_then(|index| bar_chords.get(index));
+                if let (Some(chord), Some(degrees)) = (chord, key) {
+                    if let Some(symbol) = chord.spelled_symbol(note_names.spelling) {
+                        let label = match chord.roman_numeral_in(&degrees) {
+                            Some(numeral) => format!("{symbol} ({numeral})"),
+                            None => symbol,
+                        };
+                        write_label(&mut chord_labels, &mut chords_free_from, chord_position, &label);
+                    }
+                }
+            }
+            writeln!(f, "{}", numbers.into_iter().collect::<String>().trim_end())?;
+            if key.is_some() {
+                writeln!(f, "{}", chord_labels.into_iter().collect::<String>().trim_end())?;
+            }
 
-            f.write_str(&"═".repeat(74))?;
+            f.write_str(&"═".repeat(width))?;
             f.write_str("╗\n")?;
 
             #[expect(clippy::arithmetic_side_effects, reason = "User's fault")]
@@ -298,42 +651,39 @@ impl std::fmt::Display for Piece {
                     f.write_char(' ')?;
                 }
 
-                for bar_group_time in 0..64 {
-                    let time = 64 * bar_group + bar_group_time;
+                for (bar_group_time, bar_start) in bar_starts.iter().enumerate() {
                     let black_key = black_keys[(semitone.rem_euclid(12)) as usize];
 
                     // Add barline
-                    if bar_group_time % 16 == 0 {
-                        if bar_group_time == 0 {
-                            line_str.push_str(&format!("{: <3}", tet12::get_note_name_with_octave(pitch, A4)));
-                            if black_key {
-                                line_str.push_str("║ ║");
-                            } else {
-                                line_str.push_str("║█║");
-                            }
+                    if bar_group_time == 0 {
+                        line_str.push_str(&format!("{: <label_width$}", label(semitone)));
+                        if black_key {
+                            line_str.push_str("║ ║");
                         } else {
-                            line_str.push('|');
+                            line_str.push_str("║█║");
                         }
+                    } else if bar_start.is_some() {
+                        line_str.push('|');
                     }
 
                     let blank_space = if black_key { ' ' } else { '░' };
 
-                    let note_matches_line = |note: &Note| match note.1 {
+                    let note_matches_line = |note: &Note| match &note.1 {
                         NoteKind::Rest => false,
                         NoteKind::Pitched {
                             pitch: note_pitch,
                             timbre,
                             ..
                         } => {
-                            !matches!(timbre, Timbre::Drums)
+                            !matches!(timbre.unreversed(), Timbre::Drums)
                                 && (note_pitch.0 / pitch.0 - 1.0).abs() < (2.0f32.powf(1.0 / 24.0) - 1.0)
                         }
                     };
 
                     // Find notes at this time on this line
-                    if let Some(_note) = self.get_notes_at_instant(time).find(note_matches_line) {
+                    if starting[bar_group_time].iter().any(|note| note_matches_line(note)) {
                         line_str.push('■');
-                    } else if let Some(_note) = self.get_notes_during_instant(time).find(note_matches_line) {
+                    } else if sounding[bar_group_time].iter().any(|note| note_matches_line(note)) {
                         line_str.push('≡');
                     } else {
                         line_str.push(blank_space);
@@ -344,29 +694,25 @@ impl std::fmt::Display for Piece {
                 f.write_str(&line_str)?;
             }
 
-            f.write_str(&("═".repeat(74) + "╣" + "\n"))?;
+            f.write_str(&("═".repeat(width) + "╣" + "\n"))?;
 
+            let kind_width = 6_usize.saturating_add(indent);
             for kind in ["crash", "hi-hat", "snare", "kick"] {
                 let mut line_str = String::new();
 
-                for bar_group_time in 0..64 {
-                    #[expect(clippy::arithmetic_side_effects, reason = "User's fault")]
-                    let time = 64 * bar_group + bar_group_time;
-
+                for (bar_group_time, bar_start) in bar_starts.iter().enumerate() {
                     // Add barline
-                    if bar_group_time % 16 == 0 {
-                        if bar_group_time == 0 {
-                            line_str.push_str(&format!("{kind: <6}"));
-                            line_str.push('║');
-                        } else {
-                            line_str.push('|');
-                        }
+                    if bar_group_time == 0 {
+                        line_str.push_str(&format!("{kind: <kind_width$}"));
+                        line_str.push('║');
+                    } else if bar_start.is_some() {
+                        line_str.push('|');
                     }
 
-                    let note_matches_line = |note: &Note| match note.1 {
+                    let note_matches_line = |note: &Note| match &note.1 {
                         NoteKind::Rest => false,
                         NoteKind::Pitched { pitch, timbre, .. } => {
-                            matches!(timbre, crate::note::Timbre::Drums)
+                            matches!(timbre.unreversed(), crate::note::Timbre::Drums)
                                 && match kind {
                                     "crash" => pitch.0 > C4.octave(1).semitone(6).0,
                                     "hi-hat" => C4.octave(1).semitone(6).0 > pitch.0 && pitch.0 > C4.semitone(6).0,
@@ -378,9 +724,9 @@ impl std::fmt::Display for Piece {
                     };
 
                     // Find notes at this time on this line
-                    if let Some(_note) = self.get_notes_at_instant(time).find(note_matches_line) {
+                    if starting[bar_group_time].iter().any(|note| note_matches_line(note)) {
                         line_str.push('■');
-                    } else if let Some(_note) = self.get_notes_during_instant(time).find(note_matches_line) {
+                    } else if sounding[bar_group_time].iter().any(|note| note_matches_line(note)) {
                         line_str.push('≡');
                     } else {
                         line_str.push(' ');
@@ -391,10 +737,31 @@ impl std::fmt::Display for Piece {
                 f.write_str(&line_str)?;
             }
 
-            f.write_str(&"═".repeat(74))?;
+            f.write_str(&"═".repeat(width))?;
             f.write_str("╝\n\n\n")?;
         }
 
         Ok(())
     }
 }
+
+#[test]
+fn test_long_pieces_stay_aligned() {
+    use crate::prelude::*;
+
+    let bars = 400;
+    let long_ticks = bars * whole(C4).0.ticks() as usize;
+    let quarter_ticks = quarter(C4).0.ticks() as usize;
+    assert!(long_ticks > usize::from(u16::MAX));
+
+    let appended = Piece::from(piano(whole(C4) * bars)) + (piano(quarter(C4)) * bass(quarter(C3)));
+    assert_eq!(appended.0[0].length(), long_ticks + quarter_ticks);
+    assert_eq!(appended.0[1].length(), long_ticks + quarter_ticks);
+
+    let layered = Piece::from(piano(quarter(C4))) * bass(whole(C3) * bars);
+    assert_eq!(layered.0[0].length(), long_ticks);
+    assert_eq!(layered.0[1].length(), long_ticks);
+
+    let prepended = piano(whole(C4) * bars) + (piano(quarter(C4)) * bass(quarter(C3)));
+    assert_eq!(prepended.0[1].length(), long_ticks + quarter_ticks);
+}
@@ -0,0 +1,452 @@
+use std::fmt::Write;
+
+use crate::{
+    piece::percussion::PercussionMap, Line, Metadata, Meter, NoteKind, NotePitch, Piece, Timbre, C4,
+    TICKS_PER_SIXTEENTH,
+};
+
+/// The length of a sixteenth note in ticks.
+const T: u16 = TICKS_PER_SIXTEENTH;
+
+/// The length of a sixty-fourth note in ticks, the shortest note which isn't a tuplet.
+/// Notes which start or end off this grid are part of a tuplet, so they're only split at barlines.
+const GRID: usize = T as usize / 4;
+
+/// LilyPond durations, longest first, as (length in ticks, duration).
+const DURATIONS: [(u16, &str); 14] = [
+    (32 * T, "\\breve"),
+    (24 * T, "1."),
+    (16 * T, "1"),
+    (12 * T, "2."),
+    (8 * T, "2"),
+    (6 * T, "4."),
+    (4 * T, "4"),
+    (3 * T, "8."),
+    (2 * T, "8"),
+    (3 * T / 2, "16."),
+    (T, "16"),
+    (3 * T / 4, "32."),
+    (T / 2, "32"),
+    (T / 4, "64"),
+];
+
+/// LilyPond durations of triplet notes, longest first, as (length in ticks, duration).
+/// These are written inside `\tuplet 3/2`.
+const TRIPLET_DURATIONS: [(u16, &str); 4] = [(16 * T / 3, "2"), (8 * T / 3, "4"), (4 * T / 3, "8"), (2 * T / 3, "16")];
+
+/// How a piece is written by [`Piece::to_lilypond_with`].
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::NotationOptions;
+///
+/// // A waltz which starts with a single quarter note before the first bar
+/// let options = NotationOptions {
+///     meter: Meter::new(TimeSignature::new(3, 4)).with_anacrusis(quarter(C4).0.ticks() as usize),
+///     ..NotationOptions::default()
+/// };
+///
+/// let waltz = Piece::from(piano(quarter(G4) + half(C4) + quarter(E4)));
+/// assert!(waltz.to_lilypond_with(&options).contains("\\time 3/4 \\partial 4 g'4 | c'2 e'4 |"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NotationOptions {
+    /// The time signature the piece is written in, and the length of any pickup bar (default: 4/4, with no pickup bar)
+    pub meter: Meter,
+    /// Which drum each drum note is written as, and where it goes on the staff (default: [`PercussionMap::default`])
+    pub percussion: PercussionMap,
+    /// The title, composer, and copyright written in the score's header (default: none)
+    pub metadata: Metadata,
+}
+
+impl Piece {
+    /// Exports the piece as a LilyPond score.
+    ///
+    /// Each line becomes its own staff, written in 4/4 (see [`Piece::to_lilypond_with`] for other
+    /// time signatures). Notes which cross a barline,
+    /// or whose length can't be written as a single note, are split into tied notes.
+    /// Notes are also split where they would hide a beat, or the middle of the bar, so the
+    /// beats are easy to read, and LilyPond can beam them by the time signature.
+    /// Triplets are written with `\tuplet`, but other tuplets can't be exported exactly.
+    /// Lines sitting mostly below middle C are written in the bass clef.
+    /// Lines of drums are written on a drum staff, placed by the default [`PercussionMap`].
+    /// Pickups are not exported.
+    ///
+    /// The result can be engraved with `lilypond score.ly`, or pasted into any LilyPond editor.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let melody = piano(quarter(C4) + quarter(C4.semitone(2)) + half(C4.semitone(4)));
+    /// let bass_line = bass(whole(C4.octave(-1)));
+    ///
+    /// let score = (melody * bass_line).to_lilypond();
+    /// assert!(score.contains("c'4 d'4 e'2 |"));
+    /// assert!(score.contains("\\clef bass \\time 4/4 c1 |"));
+    /// ```
+    pub fn to_lilypond(&self) -> String {
+        self.to_lilypond_with_percussion(&PercussionMap::default())
+    }
+
+    /// Exports the piece as a LilyPond score, writing drums with the given percussion map.
+    ///
+    /// See [`Piece::to_lilypond`] for how the rest of the piece is written, and
+    /// [`PercussionMap`] for an example.
+    ///
+    /// # Parameters
+    /// - `percussion`: Which drum each drum note is written as, and where it goes on the staff
+    pub fn to_lilypond_with_percussion(&self, percussion: &PercussionMap) -> String {
+        self.to_lilypond_with(&NotationOptions {
+            percussion: percussion.clone(),
+            ..NotationOptions::default()
+        })
+    }
+
+    /// Exports the piece as a LilyPond score, in the given time signature.
+    ///
+    /// Notes are split at the barlines and beats of the time signature. In compound time, such as
+    /// 6/8, each beat is a dotted quarter note. A pickup bar is written with `\partial`.
+    /// See [`Piece::to_lilypond`] for how the rest of the piece is written.
+    ///
+    /// # Parameters
+    /// - `options`: The meter to write the piece in, and how to write its drums
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::NotationOptions;
+    ///
+    /// let options = NotationOptions {
+    ///     meter: Meter::new(TimeSignature::new(6, 8)),
+    ///     ..NotationOptions::default()
+    /// };
+    ///
+    /// // The quarter note crosses the second beat of the bar, so it's split and tied
+    /// let jig = Piece::from(piano(eighth(C4) * 2 + quarter(D4) + eighth(E4) * 2));
+    /// assert!(jig.to_lilypond_with(&options).contains("\\time 6/8 c'8 c'8 d'8 ~ d'8 e'8 e'8 |"));
+    /// ```
+    pub fn to_lilypond_with(&self, options: &NotationOptions) -> String {
+        lilypond_score(
+            &options.metadata,
+            self.0.iter().map(|line| line_to_staff(line, None, options)),
+        )
+    }
+}
+
+impl Line {
+    /// Exports the line as a single-staff LilyPond score.
+    ///
+    /// See [`Piece::to_lilypond`] for details.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// // The half note crosses the barline, so it is split into two tied quarter notes
+    /// let line = piano(dotted(half)(C4) + half(A4) + quarter(REST));
+    /// assert!(line.to_lilypond().contains("c'2. a'4 ~ | a'4 r4"));
+    ///
+    /// let run = piano(thirty_second(C4) * 2 + dotted(eighth)(C4) + half(REST) + quarter(REST));
+    /// // Rests are split at the middle of the bar
+    /// assert!(run.to_lilypond().contains("c'32 c'32 c'8. r4 r2"));
+    ///
+    /// let triplets = piano(triplet(quarter)(C4) * 3 + half(REST));
+    /// assert!(triplets.to_lilypond().contains("\\tuplet 3/2 { c'4 } \\tuplet 3/2 { c'4 }"));
+    /// ```
+    pub fn to_lilypond(&self) -> String {
+        Piece::from(self.clone()).to_lilypond()
+    }
+
+    /// Exports the line as a single-staff LilyPond score, with an instrument name beside the staff.
+    pub(crate) fn to_lilypond_named(&self, instrument: &str) -> String {
+        lilypond_score(
+            &Metadata::default(),
+            std::iter::once(line_to_staff(self, Some(instrument), &NotationOptions::default())),
+        )
+    }
+}
+
+/// Writes a LilyPond score with the given staves played together.
+fn lilypond_score(metadata: &Metadata, staves: impl Iterator<Item = String>) -> String {
+    let mut score = String::from("\\version \"2.24.0\"\n");
+    let header = [
+        ("title", &metadata.title),
+        ("composer", &metadata.artist),
+        ("copyright", &metadata.copyright),
+    ];
+    if header.iter().any(|(_, value)| value.is_some()) {
+        score.push_str("\\header {\n");
+        for (field, value) in header {
+            if let Some(value) = value {
+                let _ = writeln!(
+                    score,
+                    "  {field} = \"{}\"",
+                    value.replace('\\', "\\\\").replace('"', "\\\"")
+                );
+            }
+        }
+        score.push_str("}\n");
+    }
+    score.push_str("\\score {\n  <<\n");
+    for staff in staves {
+        let _ = writeln!(score, "    {staff}");
+    }
+    score.push_str("  >>\n  \\layout { }\n}\n");
+    score
+}
+
+fn line_to_staff(line: &Line, instrument: Option<&str>, options: &NotationOptions) -> String {
+    let NotationOptions { meter, percussion, .. } = options;
+    let grid = BarGrid::new(meter);
+
+    let pitches: Vec<f32> = line
+        .notes
+        .iter()
+        .filter_map(|note| match &note.1 {
+            NoteKind::Pitched { pitch, .. } => Some(pitch.0),
+            NoteKind::Rest => None,
+        })
+        .collect();
+    let below_middle_c = pitches.iter().filter(|&&frequency| frequency < C4.0).count();
+    let clef = if below_middle_c.saturating_mul(2) > pitches.len() {
+        "bass"
+    } else {
+        "treble"
+    };
+
+    // Lines of drums are written on a drum staff, with each drum placed by the percussion map
+    let is_drums = !pitches.is_empty()
+        && line.notes.iter().all(|note| match &note.1 {
+            NoteKind::Pitched { timbre, .. } => *timbre == Timbre::Drums,
+            NoteKind::Rest => true,
+        });
+
+    let mut with: Vec<String> = vec![];
+    if let Some(name) = instrument {
+        with.push(format!("instrumentName = \"{name}\""));
+    }
+    if is_drums {
+        with.push(format!("drumStyleTable = {}", percussion.lilypond_style_table()));
+    }
+    let with = if with.is_empty() {
+        String::new()
+    } else {
+        format!(" \\with {{ {} }}", with.join(" "))
+    };
+    let time_signature = meter.time_signature;
+    let mut staff = if is_drums {
+        format!("\\new DrumStaff{with} \\drummode {{ \\time {time_signature}")
+    } else {
+        format!("\\new Staff{with} {{ \\clef {clef} \\time {time_signature}")
+    };
+    if meter.anacrusis > 0 {
+        let _ = write!(staff, " \\partial {}", partial_duration(meter.anacrusis));
+    }
+    let mut time = 0;
+
+    // Rests in a row are written together, so they're split by the beats rather than by how they were written
+    let mut notes: Vec<(usize, &NoteKind)> = vec![];
+    for note in &line.notes {
+        match (notes.last_mut(), &note.1) {
+            (Some((length, NoteKind::Rest)), NoteKind::Rest) => {
+                *length = length.saturating_add(note.0.ticks() as usize)
+            }
+            _ => notes.push((note.0.ticks() as usize, &note.1)),
+        }
+    }
+
+    for (length, kind) in notes {
+        let pitch = match kind {
+            NoteKind::Pitched { pitch, .. } if is_drums => match percussion.notation(*pitch) {
+                Some(notation) => notation.drum.to_string(),
+                None => String::from("r"),
+            },
+            NoteKind::Pitched { pitch, .. } => lilypond_pitch(*pitch),
+            NoteKind::Rest => String::from("r"),
+        };
+        let tie = if matches!(kind, NoteKind::Pitched { .. }) {
+            " ~"
+        } else {
+            ""
+        };
+
+        // Split the note at barlines and beats, then into durations which can be written as a single note
+        let mut pieces = vec![];
+        let mut remaining = length;
+        while remaining > 0 {
+            let offset = grid.offset(time);
+            let segment = remaining.min(grid.bar_length.saturating_sub(offset));
+            let durations: Vec<_> = grid
+                .split(offset, segment)
+                .into_iter()
+                .flat_map(split_duration)
+                .collect();
+            let last_index = durations.len().saturating_sub(1);
+
+            remaining = remaining.saturating_sub(segment);
+            time = time.saturating_add(segment);
+            let ends_bar = grid.offset(time) == 0;
+
+            pieces.extend(
+                durations
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, duration)| (duration, ends_bar && index == last_index)),
+            );
+        }
+
+        let last_index = pieces.len().saturating_sub(1);
+        for (index, ((duration, is_triplet), ends_bar)) in pieces.into_iter().enumerate() {
+            if is_triplet {
+                let _ = write!(staff, " \\tuplet 3/2 {{ {pitch}{duration} }}");
+            } else {
+                let _ = write!(staff, " {pitch}{duration}");
+            }
+            if index != last_index {
+                staff.push_str(tie);
+            }
+            if ends_bar {
+                staff.push_str(" |");
+            }
+        }
+    }
+
+    staff.push_str(" }");
+    staff
+}
+
+/// Where the beats of a bar fall, for splitting notes so they don't hide the beats.
+struct BarGrid {
+    /// The length of a bar in ticks
+    bar_length: usize,
+    /// The length of a beat in ticks. In compound time, this is three of the time signature's beats.
+    beat_length: usize,
+    /// Where the middle of the bar is, in bars with an even number of beats, and at least four
+    half_bar: Option<usize>,
+    /// How far into a bar the music starts, which is only past the start when there's a pickup bar
+    start_offset: usize,
+}
+
+impl BarGrid {
+    #[expect(clippy::arithmetic_side_effects, reason = "Bar and beat lengths are at least one tick")]
+    fn new(meter: &Meter) -> Self {
+        let time_signature = meter.time_signature;
+        let bar_length = time_signature.bar_length().max(1);
+        // Compound time groups its beats in threes, such as the two dotted quarter notes of 6/8
+        let compound =
+            time_signature.beat_unit >= 8 && time_signature.beats > 3 && time_signature.beats.is_multiple_of(3);
+        let (beat_length, beats) = if compound {
+            (time_signature.beat_length().saturating_mul(3), time_signature.beats / 3)
+        } else {
+            (time_signature.beat_length(), time_signature.beats)
+        };
+        BarGrid {
+            bar_length,
+            beat_length: beat_length.max(1),
+            half_bar: (beats >= 4 && beats % 2 == 0).then_some(bar_length / 2),
+            start_offset: bar_length.saturating_sub(meter.anacrusis % bar_length) % bar_length,
+        }
+    }
+
+    /// Returns how far into its bar an instant (in ticks) is.
+    #[expect(clippy::arithmetic_side_effects, reason = "Bar and beat lengths are at least one tick")]
+    fn offset(&self, instant: usize) -> usize {
+        instant.saturating_add(self.start_offset) % self.bar_length
+    }
+
+    /// Splits a note which starts `offset` ticks into a bar, and ends in the same bar, into the
+    /// lengths it's written as, so it doesn't hide a beat.
+    ///
+    /// A note which starts on a beat is written whole if it's a single note value, unless it
+    /// crosses the middle of the bar without starting the bar. Otherwise, it's split at the beats.
+    /// Notes in tuplets are left whole, as their beats are grouped by the tuplet.
+    #[expect(clippy::arithmetic_side_effects, reason = "Bar and beat lengths are at least one tick")]
+    fn split(&self, offset: usize, length: usize) -> Vec<usize> {
+        if !offset.is_multiple_of(GRID) || !length.is_multiple_of(GRID) {
+            return vec![length];
+        }
+        let crosses_half_bar = |start: usize, length: usize| {
+            self.half_bar
+                .is_some_and(|half| start != 0 && start < half && start.saturating_add(length) > half)
+        };
+        let is_single_note = |length: usize| DURATIONS.iter().any(|&(duration, _)| usize::from(duration) == length);
+
+        let mut lengths = vec![];
+        let mut start = offset;
+        let end = offset.saturating_add(length);
+        while start < end {
+            let next_beat = (start / self.beat_length)
+                .saturating_add(1)
+                .saturating_mul(self.beat_length);
+            let length = if !start.is_multiple_of(self.beat_length) {
+                // Off the beat, the note only goes up to the next beat
+                end.min(next_beat).saturating_sub(start)
+            } else if is_single_note(end.saturating_sub(start)) && !crosses_half_bar(start, end.saturating_sub(start)) {
+                end.saturating_sub(start)
+            } else {
+                // The longest run of whole beats which can be written as a single note
+                let whole_beats = end.saturating_sub(start) / self.beat_length;
+                (1..=whole_beats)
+                    .rev()
+                    .map(|beats| beats.saturating_mul(self.beat_length))
+                    .find(|&length| is_single_note(length) && !crosses_half_bar(start, length))
+                    .unwrap_or_else(|| self.beat_length.min(end.saturating_sub(start)))
+            };
+            lengths.push(length);
+            start = start.saturating_add(length);
+        }
+        lengths
+    }
+}
+
+/// Returns the LilyPond duration of a pickup bar, such as `4` or `8*3`.
+fn partial_duration(length: usize) -> String {
+    match DURATIONS.iter().find(|&&(duration, _)| usize::from(duration) == length) {
+        Some((_, duration)) => duration.to_string(),
+        None => format!("64*{}", length / GRID),
+    }
+}
+
+/// Splits a length in ticks into LilyPond durations, longest first, along with whether each is a triplet.
+///
+/// Lengths which aren't a whole number of sixteenths are written with triplets first.
+fn split_duration(length: usize) -> Vec<(&'static str, bool)> {
+    let plain = DURATIONS.map(|(duration_length, duration)| (duration_length, duration, false));
+    let triplets = TRIPLET_DURATIONS.map(|(duration_length, duration)| (duration_length, duration, true));
+    let candidates = if length.checked_rem(usize::from(T)) == Some(0) {
+        [plain.as_slice(), triplets.as_slice()].concat()
+    } else {
+        [triplets.as_slice(), plain.as_slice()].concat()
+    };
+
+    let mut remaining = length;
+    let mut durations = vec![];
+    for (duration_length, duration, is_triplet) in candidates {
+        while remaining >= duration_length as usize {
+            durations.push((duration, is_triplet));
+            remaining = remaining.saturating_sub(duration_length as usize);
+        }
+    }
+    durations
+}
+
+/// Returns the pitch in LilyPond's absolute note names, e.g. `cis'` for C#4.
+fn lilypond_pitch(pitch: NotePitch) -> String {
+    const NAMES: [&str; 12] = ["c", "cis", "d", "dis", "e", "f", "fis", "g", "gis", "a", "ais", "b"];
+
+    #[expect(clippy::cast_possible_truncation, reason = "Audible pitches are within a few hundred semitones")]
+    let semitones_from_c4 = (12.0 * f32::log2(pitch.0 / C4.0)).round() as i32;
+    let name = NAMES[usize::try_from(semitones_from_c4.rem_euclid(12)).unwrap_or_default()];
+
+    // In LilyPond, `c` is C3, and each `'` or `,` moves up or down an octave
+    let octave_marks = semitones_from_c4.div_euclid(12).saturating_add(1);
+    let marks = if octave_marks >= 0 {
+        "'".repeat(octave_marks.unsigned_abs() as usize)
+    } else {
+        ",".repeat(octave_marks.unsigned_abs() as usize)
+    };
+
+    format!("{name}{marks}")
+}
@@ -0,0 +1,141 @@
+use crate::{Line, Piece, Timbre, TimbreFluid};
+
+/// One named part of an arrangement, such as the bass or the lead guitar, with the instrument
+/// it's played on and how loud it is.
+///
+/// Build a piece from parts with [`Piece::from_parts`], then find each part's line by name with
+/// [`Piece::part`] and [`Piece::part_mut`], rather than by its position in the piece.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::Part;
+///
+/// let mut piece = Piece::from_parts([
+///     Part::new("melody", quarter(E4) + quarter(D4) + half(C4)).with_timbre(Timbre::Piano),
+///     Part::new("bass", whole(C4.octave(-2))).with_timbre(Timbre::Bass).with_volume(0.5),
+/// ]);
+///
+/// // Double the bass line's length without knowing where it is in the piece
+/// let bass_line = piece.part_mut("bass").unwrap();
+/// *bass_line = bass_line.clone() * 2;
+///
+/// assert_eq!(piece.part("bass").unwrap().notes.len(), 2);
+/// assert_eq!(piece.part_names().collect::<Vec<_>>(), ["melody", "bass"]);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Part {
+    /// The name the part is found by, such as "bass"
+    pub name: String,
+    /// The instrument every note of the part is played on. If `None`, each note keeps its own timbre.
+    pub timbre: Option<Timbre>,
+    /// How loud the part is, as a multiple of each note's volume (default: 1.0)
+    pub volume: f32,
+    /// The music of the part
+    pub line: Line,
+}
+
+impl Part {
+    /// Creates a part with the given name, which plays its notes as they are.
+    pub fn new(name: impl Into<String>, line: impl Into<Line>) -> Self {
+        Part {
+            name: name.into(),
+            timbre: None,
+            volume: 1.0,
+            line: line.into(),
+        }
+    }
+
+    /// Sets the instrument every note of the part is played on.
+    pub fn with_timbre(mut self, timbre: Timbre) -> Self {
+        self.timbre = Some(timbre);
+        self
+    }
+
+    /// Sets how loud the part is, as a multiple of each note's volume.
+    pub fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+}
+
+impl From<Part> for Line {
+    /// Returns the part's line, named after the part, with its timbre and volume applied.
+    fn from(part: Part) -> Line {
+        let line = match part.timbre {
+            Some(timbre) => part.line.with_timbre(timbre),
+            None => part.line,
+        };
+        line.scale_volume(part.volume).with_name(part.name)
+    }
+}
+
+impl FromIterator<Part> for Piece {
+    fn from_iter<I: IntoIterator<Item = Part>>(parts: I) -> Self {
+        Piece(parts.into_iter().map(Line::from).collect())
+    }
+}
+
+impl Piece {
+    /// Creates a piece with one line for each part, in order.
+    ///
+    /// Each line is named after its part, and has the part's timbre and volume applied.
+    ///
+    /// # Parameters
+    /// - `parts`: The parts of the piece
+    ///
+    /// # Examples
+    /// See [`Part`].
+    pub fn from_parts(parts: impl IntoIterator<Item = Part>) -> Piece {
+        parts.into_iter().collect()
+    }
+
+    /// Returns the first line with the given name, if there is one.
+    ///
+    /// # Parameters
+    /// - `name`: The name of the part, as given to [`Part::new`] or [`Line::with_name`]
+    pub fn part(&self, name: &str) -> Option<&Line> {
+        self.0.iter().find(|line| line.name.as_deref() == Some(name))
+    }
+
+    /// Returns the first line with the given name, to change it, if there is one.
+    ///
+    /// # Parameters
+    /// - `name`: The name of the part, as given to [`Part::new`] or [`Line::with_name`]
+    pub fn part_mut(&mut self, name: &str) -> Option<&mut Line> {
+        self.0.iter_mut().find(|line| line.name.as_deref() == Some(name))
+    }
+
+    /// Returns the names of the piece's named lines, in order.
+    pub fn part_names(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().filter_map(|line| line.name.as_deref())
+    }
+}
+
+#[test]
+fn test_parts_apply_their_timbre_and_volume() {
+    use crate::prelude::*;
+
+    let piece = Piece::from_parts([
+        Part::new("lead", piano(quarter(C4)).volume(0.8) + quarter(REST)).with_volume(0.5),
+        Part::new("drums", quarter(C4) * 2).with_timbre(Timbre::Drums),
+    ]);
+
+    let lead = piece.part("lead").unwrap();
+    assert!(matches!(lead.notes[0].1, NoteKind::Pitched { volume, .. } if (volume - 0.4).abs() < 1e-6));
+    assert_eq!(lead.notes[1], quarter(REST));
+
+    let drums = piece.part("drums").unwrap();
+    assert!(drums.notes.iter().all(|note| matches!(
+        &note.1,
+        NoteKind::Pitched {
+            timbre: Timbre::Drums,
+            ..
+        }
+    )));
+    assert_eq!(piece.part("guitar"), None);
+
+    // Joined lines keep the first one's name
+    let twice = piece.clone() + piece;
+    assert_eq!(twice.part_names().collect::<Vec<_>>(), ["lead", "drums"]);
+}
@@ -0,0 +1,140 @@
+use crate::{
+    scales::tet12::pitches::{C3, C4, C5, C6},
+    NotePitch,
+};
+
+/// The shape of a note's head in drum notation, as used by [`PercussionMap`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Notehead {
+    /// An ordinary oval notehead, used for drums
+    #[default]
+    Normal,
+    /// A cross, used for hi-hats and cymbals
+    Cross,
+    /// A cross inside a circle, used for crash cymbals
+    CircledCross,
+    /// A triangle, used for cowbells and triangles
+    Triangle,
+    /// A diamond, used for ride bells
+    Diamond,
+}
+
+impl Notehead {
+    /// Returns LilyPond's name for the notehead style.
+    fn lilypond_style(self) -> &'static str {
+        match self {
+            Notehead::Normal => "default",
+            Notehead::Cross => "cross",
+            Notehead::CircledCross => "xcircle",
+            Notehead::Triangle => "triangle",
+            Notehead::Diamond => "diamond",
+        }
+    }
+}
+
+/// How one drum is written in notation, as part of a [`PercussionMap`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PercussionNotation {
+    /// LilyPond's name for the drum, such as `"bassdrum"` or `"hihat"`. See the list of
+    /// percussion notes in LilyPond's documentation.
+    pub drum: &'static str,
+    /// The shape of the drum's noteheads
+    pub notehead: Notehead,
+    /// Where the drum is written on the staff, in steps from the middle line. 4 is the top
+    /// line, and -4 is the bottom line.
+    pub staff_position: i8,
+}
+
+/// Assigns drum hits to where and how they're written on a drum staff, for notation export.
+///
+/// Drum notes pick their sound with their pitch (see [`Timbre::Drums`](crate::Timbre::Drums)).
+/// Each drum in the map has a pitch, and a drum note is written as the drum whose pitch is closest to its own.
+/// The default map matches the built-in drum kit: a kick, snare, hi-hat, and crash.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::{Notehead, PercussionMap, PercussionNotation};
+///
+/// // Write the hi-hat on the top line, with a circled notehead
+/// let map = PercussionMap::default().with_drum(
+///     C5,
+///     PercussionNotation { drum: "hihat", notehead: Notehead::CircledCross, staff_position: 4 },
+/// );
+///
+/// let groove = drums(quarter(C3) + quarter(C5) + quarter(C4) + quarter(C5));
+/// let score = Piece::from(groove).to_lilypond_with_percussion(&map);
+/// assert!(score.contains("bassdrum4 hihat4 snare4 hihat4 |"));
+/// assert!(score.contains("(hihat xcircle #f 4)"));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct PercussionMap {
+    drums: Vec<(NotePitch, PercussionNotation)>,
+}
+
+impl Default for PercussionMap {
+    fn default() -> Self {
+        let notation = |drum, notehead, staff_position| PercussionNotation {
+            drum,
+            notehead,
+            staff_position,
+        };
+        PercussionMap {
+            drums: vec![
+                (C3, notation("bassdrum", Notehead::Normal, -3)),
+                (C4, notation("snare", Notehead::Normal, 1)),
+                (C5, notation("hihat", Notehead::Cross, 5)),
+                (C6, notation("crashcymbal", Notehead::CircledCross, 6)),
+            ],
+        }
+    }
+}
+
+impl PercussionMap {
+    /// Creates a map with no drums. Drum notes can't be written until drums are added.
+    pub fn new() -> Self {
+        PercussionMap { drums: vec![] }
+    }
+
+    /// Adds a drum to the map, replacing any drum already at the same pitch.
+    ///
+    /// # Parameters
+    /// - `pitch`: The pitch of drum notes which play the drum
+    /// - `notation`: How the drum is written
+    pub fn with_drum(mut self, pitch: NotePitch, notation: PercussionNotation) -> Self {
+        self.drums.retain(|(existing, _)| *existing != pitch);
+        self.drums.push((pitch, notation));
+        self
+    }
+
+    /// Returns how a drum note with the given pitch is written, or `None` if the map is empty.
+    ///
+    /// # Parameters
+    /// - `pitch`: The pitch of the drum note
+    pub fn notation(&self, pitch: NotePitch) -> Option<&PercussionNotation> {
+        self.drums
+            .iter()
+            .min_by(|(a, _), (b, _)| {
+                let distance = |drum: &NotePitch| (drum.0 / pitch.0).log2().abs();
+                distance(a).total_cmp(&distance(b))
+            })
+            .map(|(_, notation)| notation)
+    }
+
+    /// Returns the LilyPond style table which places each drum on the staff.
+    pub(crate) fn lilypond_style_table(&self) -> String {
+        let entries: Vec<String> = self
+            .drums
+            .iter()
+            .map(|(_, notation)| {
+                format!(
+                    "({} {} #f {})",
+                    notation.drum,
+                    notation.notehead.lilypond_style(),
+                    notation.staff_position
+                )
+            })
+            .collect();
+        format!("#(alist->hash-table '({}))", entries.join(" "))
+    }
+}
@@ -0,0 +1,758 @@
+//! The piece file format, a plain text format for saving pieces and sharing them with people
+//! who don't write Rust.
+//!
+//! A file starts with `symphoxy-piece` and the format's version, then the piece's metadata, then
+//! each line of the piece. Each entry is on its own line, as a keyword followed by its values,
+//! separated by spaces. Text is written in double quotes. Blank lines, and lines starting with
+//! `#`, are ignored.
+//!
+//! ```text
+//! symphoxy-piece 1
+//! title "Ode to Joy"
+//! tempo 480
+//!
+//! line "melody"
+//! note 48 369.994 piano
+//! note 48 391.995 piano volume 0.8
+//! note 96 rest
+//! ```
+//!
+//! Note lengths are in ticks of 12 to a sixteenth note. Files saved with a different
+//! [`TICKS_PER_SIXTEENTH`](crate::TICKS_PER_SIXTEENTH) have a `ticks_per_sixteenth` entry before
+//! their first line, and their lengths are converted when they're loaded.
+//!
+//! A `line` is followed by its notes, and `pickup` notes, in order. `hold_pickup` holds its
+//! pickup into its first note. Each note is its length in ticks, then either `rest`, or its
+//! pitch in Hz, its timbre, and any of `volume`, `pan`, `probability`, `ratchet`, and
+//! `envelope` (attack, decay, sustain, and release) which aren't their defaults.
+//! Times are in milliseconds.
+//!
+//! Timbres are written by name, such as `piano`, except for:
+//! - `fm`, then the carrier ratio, the envelope, the number of modulators, and each one's ratio,
+//!   index, and decay
+//! - `unpitched` and `pitched`, then the path of the custom source
+//! - `sample`, then the path, the root pitch, and the loop's start and end frames, or `-` for no loop
+//! - `sampled`, then the number of zones, and each one's lowest and highest pitch and `sample`
+//! - `layered`, then the number of layers, and each one's gain and timbre
+//! - `reversed`, then the timbre which is reversed
+
+use std::{
+    cell::Cell,
+    fmt::{Display, Formatter, Result as FmtResult, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+
+use crate::{
+    note::KeyZone, Envelope, FmModulator, FmPatch, Line, Metadata, Note, NoteKind, NoteLength, NotePitch, Piece,
+    SampleInstrument, SampledInstrument, Timbre, TICKS_PER_SIXTEENTH,
+};
+
+/// The version of the piece file format written by this version of the crate. Files of any
+/// version up to this one can be loaded.
+pub const PIECE_FILE_VERSION: u32 = 1;
+
+/// The ticks per sixteenth note of files without a `ticks_per_sixteenth` entry.
+const DEFAULT_TICKS_PER_SIXTEENTH: u16 = 12;
+
+/// The first word of every piece file.
+const MAGIC: &str = "symphoxy-piece";
+
+/// The timbres written by name, with their names.
+const NAMED_TIMBRES: [(&str, Timbre); 14] = [
+    ("sine", Timbre::Sine),
+    ("square", Timbre::Square),
+    ("saw", Timbre::Saw),
+    ("triangle", Timbre::Triangle),
+    ("bass", Timbre::Bass),
+    ("piano", Timbre::Piano),
+    ("electric_guitar", Timbre::ElectricGuitar),
+    ("organ", Timbre::Organ),
+    ("strings", Timbre::Strings),
+    ("synth_lead", Timbre::SynthLead),
+    ("synth_pad", Timbre::SynthPad),
+    ("flute", Timbre::Flute),
+    ("brass", Timbre::Brass),
+    ("drums", Timbre::Drums),
+];
+
+impl Piece {
+    /// Saves the piece to a piece file, which can be loaded with [`Piece::load`].
+    ///
+    /// Piece files are plain text, so they can be shared and looked over without Rust.
+    /// See [`Piece::save_with_metadata`] to save the piece's title and tempo with it.
+    ///
+    /// # Parameters
+    /// - `path`: The path of the file to write. It's overwritten if it exists.
+    ///
+    /// # Errors
+    /// Returns an error if the piece has something which can't be saved, such as line
+    /// effects or a custom synth, or if the file can't be written.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    ///
+    /// let piece = piano(quarter(C4) + quarter(E4) + half(G4)) * bass(whole(C4.octave(-2)));
+    /// piece.save("song.symphoxy").unwrap();
+    ///
+    /// assert_eq!(Piece::load("song.symphoxy").unwrap(), piece);
+    /// ```
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), PieceFileError> {
+        self.save_with_metadata(path, &Metadata::default())
+    }
+
+    /// Saves the piece to a piece file, along with its metadata, such as its title and tempo.
+    ///
+    /// # Parameters
+    /// - `path`: The path of the file to write. It's overwritten if it exists.
+    /// - `metadata`: The piece's metadata
+    ///
+    /// # Errors
+    /// Returns an error if the piece has something which can't be saved, such as line
+    /// effects or a custom synth, or if the file can't be written.
+    pub fn save_with_metadata(&self, path: impl AsRef<Path>, metadata: &Metadata) -> Result<(), PieceFileError> {
+        let path = path.as_ref();
+        let text = self.to_piece_file(metadata)?;
+        std::fs::write(path, text).map_err(|error| PieceFileError::Io {
+            path: path.to_path_buf(),
+            reason: error.to_string(),
+        })
+    }
+
+    /// Loads a piece from a piece file, ignoring its metadata.
+    ///
+    /// # Parameters
+    /// - `path`: The path of the file to read
+    ///
+    /// # Errors
+    /// Returns an error if the file can't be read, isn't a piece file, or was written by a
+    /// newer version of the format.
+    pub fn load(path: impl AsRef<Path>) -> Result<Piece, PieceFileError> {
+        Piece::load_with_metadata(path).map(|(piece, _)| piece)
+    }
+
+    /// Loads a piece from a piece file, along with its metadata.
+    ///
+    /// # Parameters
+    /// - `path`: The path of the file to read
+    ///
+    /// # Errors
+    /// Returns an error if the file can't be read, isn't a piece file, or was written by a
+    /// newer version of the format.
+    pub fn load_with_metadata(path: impl AsRef<Path>) -> Result<(Piece, Metadata), PieceFileError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|error| PieceFileError::Io {
+            path: path.to_path_buf(),
+            reason: error.to_string(),
+        })?;
+        Piece::from_piece_file(&text)
+    }
+
+    /// Writes the piece and its metadata in the piece file format.
+    ///
+    /// # Errors
+    /// Returns an error if the piece has something which can't be saved.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::Metadata;
+    ///
+    /// let piece = Piece::from(piano(quarter(C4)) + quarter(REST));
+    /// let text = piece.to_piece_file(&Metadata::new("Blip")).unwrap();
+    ///
+    /// # if TICKS_PER_SIXTEENTH == 12 {
+    /// assert_eq!(text, "symphoxy-piece 1\ntitle \"Blip\"\n\nline\nnote 48 261.626 piano\nnote 48 rest\n");
+    /// # }
+    /// assert_eq!(Piece::from_piece_file(&text).unwrap(), (piece, Metadata::new("Blip")));
+    /// ```
+    pub fn to_piece_file(&self, metadata: &Metadata) -> Result<String, PieceFileError> {
+        let mut text = format!("{MAGIC} {PIECE_FILE_VERSION}\n");
+        let texts = [
+            ("title", &metadata.title),
+            ("artist", &metadata.artist),
+            ("copyright", &metadata.copyright),
+            ("key", &metadata.key),
+        ];
+        for (keyword, value) in texts {
+            if let Some(value) = value {
+                let _ = writeln!(text, "{keyword} {}", quote(value));
+            }
+        }
+        if let Some(tempo_bpm) = metadata.tempo_bpm {
+            let _ = writeln!(text, "tempo {tempo_bpm}");
+        }
+        if TICKS_PER_SIXTEENTH != DEFAULT_TICKS_PER_SIXTEENTH {
+            let _ = writeln!(text, "ticks_per_sixteenth {TICKS_PER_SIXTEENTH}");
+        }
+
+        for line in &self.0 {
+            if !line.effects.is_empty() {
+                return Err(PieceFileError::Unsupported("line effects".to_string()));
+            }
+            text.push('\n');
+            match &line.name {
+                Some(name) => {
+                    let _ = writeln!(text, "line {}", quote(name));
+                }
+                None => text.push_str("line\n"),
+            }
+            if line.hold_pickup {
+                text.push_str("hold_pickup\n");
+            }
+            for (keyword, notes) in [("pickup", &line.pickup), ("note", &line.notes)] {
+                for note in notes {
+                    let _ = writeln!(text, "{keyword} {}", write_note(note)?);
+                }
+            }
+        }
+        Ok(text)
+    }
+
+    /// Reads a piece and its metadata from text in the piece file format.
+    ///
+    /// See [`Piece::to_piece_file`] for an example.
+    ///
+    /// # Errors
+    /// Returns an error if the text isn't a piece file, or was written by a newer version of the format.
+    pub fn from_piece_file(text: &str) -> Result<(Piece, Metadata), PieceFileError> {
+        let mut entries = text
+            .lines()
+            .enumerate()
+            .map(|(index, text)| (index.saturating_add(1), text.trim()))
+            .filter(|(_, text)| !text.is_empty() && !text.starts_with('#'));
+
+        let (line_number, header) = entries.next().ok_or(PieceFileError::NotAPieceFile)?;
+        let header = Tokens::new(header, line_number)?;
+        if header.next_word().ok() != Some(MAGIC) {
+            return Err(PieceFileError::NotAPieceFile);
+        }
+        let version = header.parse::<u32>("version")?;
+        if version > PIECE_FILE_VERSION {
+            return Err(PieceFileError::Version(version));
+        }
+
+        let mut metadata = Metadata::default();
+        let mut lines: Vec<Line> = vec![];
+        let mut file_ticks = DEFAULT_TICKS_PER_SIXTEENTH;
+        for (line_number, entry) in entries {
+            let tokens = Tokens::new(entry, line_number)?;
+            let keyword = tokens.next_word()?;
+            match (keyword, lines.last_mut()) {
+                ("title", _) => metadata.title = Some(tokens.next_text()?),
+                ("artist", _) => metadata.artist = Some(tokens.next_text()?),
+                ("copyright", _) => metadata.copyright = Some(tokens.next_text()?),
+                ("key", _) => metadata.key = Some(tokens.next_text()?),
+                ("tempo", _) => metadata.tempo_bpm = Some(tokens.parse("tempo")?),
+                ("ticks_per_sixteenth", None) => {
+                    file_ticks = tokens.parse("number of ticks")?;
+                    if file_ticks == 0 {
+                        return Err(tokens.error("there must be at least one tick per sixteenth"));
+                    }
+                }
+                ("ticks_per_sixteenth", Some(_)) => {
+                    return Err(tokens.error("ticks_per_sixteenth must come before the first line"))
+                }
+                ("line", _) => {
+                    let name = if tokens.is_empty() {
+                        None
+                    } else {
+                        Some(tokens.next_text()?)
+                    };
+                    lines.push(Line { name, ..Line::new() });
+                }
+                ("hold_pickup", Some(line)) => line.hold_pickup = true,
+                ("pickup", Some(line)) => line.pickup.push(read_note(&tokens, file_ticks)?),
+                ("note", Some(line)) => line.notes.push(read_note(&tokens, file_ticks)?),
+                ("hold_pickup" | "pickup" | "note", None) => return Err(tokens.error("notes must come after a line")),
+                (keyword, _) => return Err(tokens.error(&format!("unknown entry {keyword:?}"))),
+            }
+            tokens.finish()?;
+        }
+        Ok((Piece(lines), metadata))
+    }
+}
+
+/// Writes a note's length and kind.
+fn write_note(note: &Note) -> Result<String, PieceFileError> {
+    let mut text = note.0 .0.to_string();
+    let NoteKind::Pitched {
+        pitch,
+        timbre,
+        volume,
+        envelope,
+        pan,
+        probability,
+        ratchet,
+    } = &note.1
+    else {
+        text.push_str(" rest");
+        return Ok(text);
+    };
+    let _ = write!(text, " {} {}", pitch.0, write_timbre(timbre)?);
+    if *volume != 1.0 {
+        let _ = write!(text, " volume {volume}");
+    }
+    if *pan != 0.0 {
+        let _ = write!(text, " pan {pan}");
+    }
+    if *probability != 1.0 {
+        let _ = write!(text, " probability {probability}");
+    }
+    if *ratchet != 1 {
+        let _ = write!(text, " ratchet {ratchet}");
+    }
+    if let Some(envelope) = envelope {
+        let _ = write!(text, " envelope {}", write_envelope(envelope));
+    }
+    Ok(text)
+}
+
+/// Reads a note's length and kind, converting its length from `file_ticks` ticks per sixteenth.
+fn read_note(tokens: &Tokens, file_ticks: u16) -> Result<Note, PieceFileError> {
+    let length = read_length(tokens, file_ticks)?;
+    if tokens.peek() == Some("rest") {
+        tokens.next_word()?;
+        return Ok(Note(length, NoteKind::Rest));
+    }
+
+    let pitch = NotePitch(tokens.parse("pitch")?);
+    let timbre = read_timbre(tokens)?;
+    let (mut volume, mut pan, mut probability, mut ratchet, mut envelope) = (1.0, 0.0, 1.0, 1, None);
+    while !tokens.is_empty() {
+        match tokens.next_word()? {
+            "volume" => volume = tokens.parse("volume")?,
+            "pan" => pan = tokens.parse("pan")?,
+            "probability" => probability = tokens.parse("probability")?,
+            "ratchet" => ratchet = tokens.parse("ratchet")?,
+            "envelope" => envelope = Some(read_envelope(tokens)?),
+            other => return Err(tokens.error(&format!("unknown note setting {other:?}"))),
+        }
+    }
+    Ok(Note(
+        length,
+        NoteKind::Pitched {
+            pitch,
+            timbre,
+            volume,
+            envelope,
+            pan,
+            probability,
+            ratchet,
+        },
+    ))
+}
+
+/// Reads a note length written with `file_ticks` ticks per sixteenth, and converts it to this
+/// crate's [`TICKS_PER_SIXTEENTH`].
+fn read_length(tokens: &Tokens, file_ticks: u16) -> Result<NoteLength, PieceFileError> {
+    let ticks: u16 = tokens.parse("note length")?;
+    let scaled = u32::from(ticks).saturating_mul(u32::from(TICKS_PER_SIXTEENTH));
+    let file_ticks = u32::from(file_ticks);
+    if scaled.checked_rem(file_ticks) != Some(0) {
+        return Err(tokens.error(&format!(
+            "{ticks} ticks can't be converted to {TICKS_PER_SIXTEENTH} ticks per sixteenth"
+        )));
+    }
+    scaled
+        .checked_div(file_ticks)
+        .and_then(|ticks| u16::try_from(ticks).ok())
+        .map(NoteLength)
+        .ok_or_else(|| tokens.error(&format!("{ticks} ticks is too long for a note")))
+}
+
+/// Writes a timbre, by name if it has one, otherwise with its settings.
+fn write_timbre(timbre: &Timbre) -> Result<String, PieceFileError> {
+    if let Some((name, _)) = NAMED_TIMBRES.iter().find(|(_, named)| named == timbre) {
+        return Ok((*name).to_string());
+    }
+    Ok(match timbre {
+        Timbre::Fm(patch) => {
+            let mut text = format!(
+                "fm {} {} {}",
+                patch.carrier_ratio,
+                write_envelope(&patch.envelope),
+                patch.modulators.len()
+            );
+            for modulator in &patch.modulators {
+                let _ = write!(
+                    text,
+                    " {} {} {}",
+                    modulator.ratio,
+                    modulator.index,
+                    write_duration(modulator.decay)
+                );
+            }
+            text
+        }
+        Timbre::CustomSourceUnpitched(path) => format!("unpitched {}", write_path(path)?),
+        Timbre::CustomSourcePitched(path) => format!("pitched {}", write_path(path)?),
+        Timbre::Sample(sample) => write_sample(sample)?,
+        Timbre::Sampled(instrument) => {
+            let mut text = format!("sampled {}", instrument.zones.len());
+            for zone in &instrument.zones {
+                let _ = write!(
+                    text,
+                    " {} {} {}",
+                    zone.lowest.0,
+                    zone.highest.0,
+                    write_sample(&zone.sample)?
+                );
+            }
+            text
+        }
+        Timbre::Layered(layers) => {
+            let mut text = format!("layered {}", layers.len());
+            for (timbre, gain) in layers {
+                let _ = write!(text, " {gain} {}", write_timbre(timbre)?);
+            }
+            text
+        }
+        Timbre::Reversed(timbre) => format!("reversed {}", write_timbre(timbre)?),
+        _ => return Err(PieceFileError::Unsupported("custom synths".to_string())),
+    })
+}
+
+/// Reads a timbre written by [`write_timbre`].
+fn read_timbre(tokens: &Tokens) -> Result<Timbre, PieceFileError> {
+    let name = tokens.next_word()?;
+    if let Some((_, timbre)) = NAMED_TIMBRES.iter().find(|(named, _)| *named == name) {
+        return Ok(timbre.clone());
+    }
+    Ok(match name {
+        "fm" => {
+            let mut patch = FmPatch::new(tokens.parse("carrier ratio")?, read_envelope(tokens)?);
+            for _ in 0..tokens.parse::<usize>("modulator count")? {
+                patch.modulators.push(FmModulator {
+                    ratio: tokens.parse("modulator ratio")?,
+                    index: tokens.parse("modulator index")?,
+                    decay: read_duration(tokens)?,
+                });
+            }
+            Timbre::Fm(patch)
+        }
+        "unpitched" => Timbre::CustomSourceUnpitched(Arc::from(Path::new(&tokens.next_text()?))),
+        "pitched" => Timbre::CustomSourcePitched(Arc::from(Path::new(&tokens.next_text()?))),
+        "sample" => Timbre::Sample(read_sample(tokens)?),
+        "sampled" => {
+            let mut instrument = SampledInstrument::new();
+            for _ in 0..tokens.parse::<usize>("zone count")? {
+                let lowest = NotePitch(tokens.parse("lowest pitch")?);
+                let highest = NotePitch(tokens.parse("highest pitch")?);
+                if tokens.next_word()? != "sample" {
+                    return Err(tokens.error("expected a sample"));
+                }
+                instrument.zones.push(KeyZone {
+                    lowest,
+                    highest,
+                    sample: read_sample(tokens)?,
+                });
+            }
+            Timbre::Sampled(instrument)
+        }
+        "layered" => {
+            let mut layers = vec![];
+            for _ in 0..tokens.parse::<usize>("layer count")? {
+                let gain = tokens.parse("layer gain")?;
+                layers.push((read_timbre(tokens)?, gain));
+            }
+            Timbre::Layered(layers)
+        }
+        "reversed" => Timbre::Reversed(Box::new(read_timbre(tokens)?)),
+        other => return Err(tokens.error(&format!("unknown timbre {other:?}"))),
+    })
+}
+
+/// Writes a sample instrument, starting with `sample`.
+fn write_sample(sample: &SampleInstrument) -> Result<String, PieceFileError> {
+    let loop_points = match sample.loop_points {
+        Some((start, end)) => format!("{start} {end}"),
+        None => "-".to_string(),
+    };
+    Ok(format!(
+        "sample {} {} {loop_points}",
+        write_path(&sample.path)?,
+        sample.root_pitch.0
+    ))
+}
+
+/// Reads a sample instrument, after its `sample` keyword.
+fn read_sample(tokens: &Tokens) -> Result<SampleInstrument, PieceFileError> {
+    let sample = SampleInstrument::new(tokens.next_text()?, NotePitch(tokens.parse("root pitch")?));
+    if tokens.peek() == Some("-") {
+        tokens.next_word()?;
+        return Ok(sample);
+    }
+    Ok(sample.with_loop(tokens.parse("loop start")?, tokens.parse("loop end")?))
+}
+
+/// Writes an envelope's attack, decay, sustain, and release.
+fn write_envelope(envelope: &Envelope) -> String {
+    format!(
+        "{} {} {} {}",
+        write_duration(envelope.attack),
+        write_duration(envelope.decay),
+        envelope.sustain,
+        write_duration(envelope.release)
+    )
+}
+
+/// Reads an envelope's attack, decay, sustain, and release.
+fn read_envelope(tokens: &Tokens) -> Result<Envelope, PieceFileError> {
+    Ok(Envelope {
+        attack: read_duration(tokens)?,
+        decay: read_duration(tokens)?,
+        sustain: tokens.parse("sustain")?,
+        release: read_duration(tokens)?,
+    })
+}
+
+/// Writes a duration in milliseconds, with as many decimal places as it needs, down to nanoseconds.
+fn write_duration(duration: Duration) -> String {
+    let nanos = duration.subsec_nanos().checked_rem(1_000_000).unwrap_or_default();
+    let millis = duration.as_millis();
+    if nanos == 0 {
+        millis.to_string()
+    } else {
+        format!("{millis}.{}", format!("{nanos:06}").trim_end_matches('0'))
+    }
+}
+
+/// Reads a duration written by [`write_duration`].
+fn read_duration(tokens: &Tokens) -> Result<Duration, PieceFileError> {
+    let text = tokens.next_word()?;
+    let (millis, fraction) = text.split_once('.').unwrap_or((text, ""));
+    let parsed = (|| {
+        let millis: u64 = millis.parse().ok()?;
+        if fraction.len() > 6 || !fraction.bytes().all(|byte| byte.is_ascii_digit()) {
+            return None;
+        }
+        let nanos: u64 = format!("{fraction:0<6}").parse().ok()?;
+        Duration::from_millis(millis).checked_add(Duration::from_nanos(nanos))
+    })();
+    parsed.ok_or_else(|| tokens.error(&format!("{text:?} isn't a time in milliseconds")))
+}
+
+/// Writes a path as quoted text.
+fn write_path(path: &Path) -> Result<String, PieceFileError> {
+    path.to_str()
+        .map(quote)
+        .ok_or_else(|| PieceFileError::Unsupported(format!("the path {}, which isn't valid UTF-8", path.display())))
+}
+
+/// Writes text in double quotes, escaping any quotes and backslashes in it.
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// The words and quoted text of one line of a piece file, read in order.
+struct Tokens {
+    tokens: Vec<Token>,
+    /// The index of the next token to read
+    next: Cell<usize>,
+    line_number: usize,
+}
+
+/// A word, or quoted text, of a piece file.
+enum Token {
+    Word(String),
+    Text(String),
+}
+
+impl Tokens {
+    /// Splits a line of a piece file into words and quoted text.
+    fn new(line: &str, line_number: usize) -> Result<Self, PieceFileError> {
+        let mut tokens = vec![];
+        let mut chars = line.chars().peekable();
+        while let Some(&next) = chars.peek() {
+            if next.is_whitespace() {
+                chars.next();
+            } else if next == '"' {
+                chars.next();
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => text.extend(chars.next()),
+                        Some(char) => text.push(char),
+                        None => {
+                            return Err(PieceFileError::Parse {
+                                line: line_number,
+                                reason: "text is missing its closing quote".to_string(),
+                            })
+                        }
+                    }
+                }
+                tokens.push(Token::Text(text));
+            } else {
+                let mut word = String::new();
+                while let Some(char) = chars.next_if(|char| !char.is_whitespace()) {
+                    word.push(char);
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+        Ok(Tokens {
+            tokens,
+            next: Cell::new(0),
+            line_number,
+        })
+    }
+
+    /// Returns an error about this line.
+    fn error(&self, reason: &str) -> PieceFileError {
+        PieceFileError::Parse {
+            line: self.line_number,
+            reason: reason.to_string(),
+        }
+    }
+
+    /// Returns whether every token has been read.
+    fn is_empty(&self) -> bool {
+        self.next.get() >= self.tokens.len()
+    }
+
+    /// Returns the next word without reading it, or `None` if the next token isn't a word.
+    fn peek(&self) -> Option<&str> {
+        match self.tokens.get(self.next.get()) {
+            Some(Token::Word(word)) => Some(word),
+            _ => None,
+        }
+    }
+
+    /// Reads the next token.
+    fn next_token(&self) -> Result<&Token, PieceFileError> {
+        let index = self.next.get();
+        self.next.set(index.saturating_add(1));
+        match self.tokens.get(index) {
+            Some(token) => Ok(token),
+            None => Err(self.error("the line ends too soon")),
+        }
+    }
+
+    /// Reads the next word.
+    fn next_word(&self) -> Result<&str, PieceFileError> {
+        match self.next_token()? {
+            Token::Word(word) => Ok(word),
+            Token::Text(_) => Err(self.error("expected a word, not quoted text")),
+        }
+    }
+
+    /// Reads the next quoted text.
+    fn next_text(&self) -> Result<String, PieceFileError> {
+        match self.next_token()? {
+            Token::Text(text) => Ok(text.clone()),
+            Token::Word(word) => Err(self.error(&format!("expected quoted text, not {word:?}"))),
+        }
+    }
+
+    /// Reads the next word as a number, or other value, described by `what` if it can't be read.
+    fn parse<T: FromStr>(&self, what: &str) -> Result<T, PieceFileError> {
+        let word = self.next_word()?;
+        word.parse()
+            .map_err(|_| self.error(&format!("{word:?} isn't a valid {what}")))
+    }
+
+    /// Returns an error if any tokens haven't been read.
+    fn finish(&self) -> Result<(), PieceFileError> {
+        match self.tokens.get(self.next.get()) {
+            None => Ok(()),
+            Some(Token::Word(word)) => Err(self.error(&format!("unexpected {word:?} at the end of the line"))),
+            Some(Token::Text(text)) => Err(self.error(&format!("unexpected \"{text}\" at the end of the line"))),
+        }
+    }
+}
+
+/// The error returned when a piece can't be saved to, or loaded from, a piece file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PieceFileError {
+    /// The file couldn't be read or written
+    Io {
+        /// The path of the file
+        path: PathBuf,
+        /// Why it couldn't be read or written
+        reason: String,
+    },
+    /// The piece has something which the piece file format can't hold, such as line effects
+    Unsupported(String),
+    /// The file doesn't start with a piece file header
+    NotAPieceFile,
+    /// The file was written with a newer version of the format than this version of the crate reads
+    Version(u32),
+    /// A line of the file couldn't be read
+    Parse {
+        /// The line number, starting at 1
+        line: usize,
+        /// What's wrong with the line
+        reason: String,
+    },
+}
+
+impl Display for PieceFileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            PieceFileError::Io { path, reason } => write!(f, "couldn't access {}: {reason}", path.display()),
+            PieceFileError::Unsupported(what) => write!(f, "piece files can't hold {what}"),
+            PieceFileError::NotAPieceFile => write!(f, "the file isn't a piece file"),
+            PieceFileError::Version(version) => write!(
+                f,
+                "the file is version {version} of the piece file format, but only versions up to {PIECE_FILE_VERSION} can be read"
+            ),
+            PieceFileError::Parse { line, reason } => write!(f, "line {line}: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for PieceFileError {}
+
+#[test]
+fn test_piece_files_round_trip() {
+    use crate::prelude::*;
+
+    let fm = Timbre::Fm(
+        FmPatch::new(
+            1.0,
+            Envelope::new(
+                Duration::from_micros(2500),
+                Duration::from_millis(80),
+                0.5,
+                Duration::from_millis(300),
+            ),
+        )
+        .with_modulator(FmModulator::new(2.0, 3.5)),
+    );
+    let melody = Line {
+        pickup: vec![eighth(B3)],
+        hold_pickup: true,
+        ..piano(quarter(C4).volume(0.5) + quarter(E4).pan(-0.25)) + Line::from(half(G4)).with_timbre(fm)
+    }
+    .with_name("melody \"lead\"");
+    let bass = Line::from(whole(C4.octave(-2))).with_timbre(Timbre::Layered(vec![
+        (Timbre::Bass, 1.0),
+        (Timbre::Reversed(Box::new(Timbre::Saw)), 0.25),
+    ])) + quarter(REST);
+    let piece = Piece(vec![melody, bass]);
+    let metadata = Metadata::new("Test").with_artist("Someone").with_tempo(480);
+
+    let text = piece.to_piece_file(&metadata).unwrap();
+    assert_eq!(Piece::from_piece_file(&text).unwrap(), (piece, metadata));
+
+    assert_eq!(
+        Piece::from_piece_file("symphoxy-piece 2\n"),
+        Err(PieceFileError::Version(2))
+    );
+    assert_eq!(
+        Piece::from_piece_file("symphoxy-piece 1\nline\nnote 48 440 kazoo\n"),
+        Err(PieceFileError::Parse {
+            line: 3,
+            reason: "unknown timbre \"kazoo\"".to_string()
+        })
+    );
+    let (finer, _) =
+        Piece::from_piece_file("symphoxy-piece 1\nticks_per_sixteenth 96\nline\nnote 384 440 piano\n").unwrap();
+    assert_eq!(finer, Piece::from(piano(quarter(A4))));
+    assert!(Piece::from_piece_file("symphoxy-piece 1\nticks_per_sixteenth 96\nline\nnote 1 rest\n").is_err());
+}
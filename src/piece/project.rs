@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+
+use crate::{Piece, Timbre};
+
+/// A group of pieces released together, such as the movements of a suite or the tracks of an EP,
+/// with the metadata, tempo, and timbres they share.
+///
+/// With the `wav-output` feature, every movement can be rendered at once with
+/// `MusicPlayer::render_project`, along with a combined render of the whole project.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::{Movement, Project};
+///
+/// let project = Project::new("Seasons")
+///     .with_artist("Symphoxy")
+///     .with_tempo(300)
+///     .with_timbre("lead", Timbre::Sine);
+///
+/// // Movements use the project's timbres, so they all sound like the same band
+/// let lead = project.timbre("lead").unwrap().clone();
+/// let spring = Piece::from((quarter(C4) + quarter(E4) + half(G4)).with_timbre(lead.clone()));
+/// let winter = Piece::from((half(A3) + half(C4)).with_timbre(lead));
+///
+/// let project = project
+///     .with_movement(Movement::new("Spring", spring))
+///     .with_movement(Movement::new("Winter", winter).with_tempo(200));
+///
+/// assert_eq!(project.movements.len(), 2);
+/// assert_eq!(project.tempo_of(&project.movements[0]), Some(300));
+/// assert_eq!(project.tempo_of(&project.movements[1]), Some(200));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Project {
+    /// The title of the project
+    pub title: String,
+    /// Who made the project, if set
+    pub artist: Option<String>,
+    /// The tempo of movements which don't set their own, in sixteenth notes per minute.
+    /// If neither sets one, the player's tempo is used.
+    pub tempo_bpm: Option<u32>,
+    /// Timbres shared by the movements, by name
+    pub timbres: HashMap<String, Timbre>,
+    /// The movements, in the order they're played
+    pub movements: Vec<Movement>,
+    /// The silence between movements when the whole project is played in one go, in milliseconds
+    pub gap_ms: u64,
+}
+
+/// One piece of a [`Project`], with its title and, optionally, its own tempo.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Movement {
+    /// The title of the movement
+    pub title: String,
+    /// The music of the movement
+    pub piece: Piece,
+    /// The tempo of the movement, in sixteenth notes per minute. If `None`, the project's tempo is used.
+    pub tempo_bpm: Option<u32>,
+}
+
+impl Project {
+    /// Creates an empty project with the given title, and two seconds between movements.
+    pub fn new(title: impl Into<String>) -> Self {
+        Project {
+            title: title.into(),
+            artist: None,
+            tempo_bpm: None,
+            timbres: HashMap::new(),
+            movements: vec![],
+            gap_ms: 2000,
+        }
+    }
+
+    /// Sets who made the project.
+    pub fn with_artist(mut self, artist: impl Into<String>) -> Self {
+        self.artist = Some(artist.into());
+        self
+    }
+
+    /// Sets the tempo of movements which don't set their own, in sixteenth notes per minute.
+    pub fn with_tempo(mut self, tempo_bpm: u32) -> Self {
+        self.tempo_bpm = Some(tempo_bpm);
+        self
+    }
+
+    /// Adds a timbre the movements can share, replacing any timbre with the same name.
+    pub fn with_timbre(mut self, name: impl Into<String>, timbre: Timbre) -> Self {
+        self.timbres.insert(name.into(), timbre);
+        self
+    }
+
+    /// Adds a movement after the others.
+    pub fn with_movement(mut self, movement: Movement) -> Self {
+        self.movements.push(movement);
+        self
+    }
+
+    /// Sets the silence between movements when the whole project is played in one go, in milliseconds.
+    pub fn with_gap_ms(mut self, gap_ms: u64) -> Self {
+        self.gap_ms = gap_ms;
+        self
+    }
+
+    /// Returns the shared timbre with the given name, if there is one.
+    pub fn timbre(&self, name: &str) -> Option<&Timbre> {
+        self.timbres.get(name)
+    }
+
+    /// Returns the tempo a movement is played at: its own if it has one, otherwise the project's.
+    /// Returns `None` if neither is set, in which case the player's tempo is used.
+    pub fn tempo_of(&self, movement: &Movement) -> Option<u32> {
+        movement.tempo_bpm.or(self.tempo_bpm)
+    }
+}
+
+impl Movement {
+    /// Creates a movement which plays at the project's tempo.
+    pub fn new(title: impl Into<String>, piece: impl Into<Piece>) -> Self {
+        Movement {
+            title: title.into(),
+            piece: piece.into(),
+            tempo_bpm: None,
+        }
+    }
+
+    /// Sets the tempo of the movement, in sixteenth notes per minute, instead of the project's.
+    pub fn with_tempo(mut self, tempo_bpm: u32) -> Self {
+        self.tempo_bpm = Some(tempo_bpm);
+        self
+    }
+}
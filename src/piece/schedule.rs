@@ -0,0 +1,211 @@
+use itertools::Itertools;
+
+use crate::{Note, Piece};
+
+/// A note of a compiled piece, with when it starts and which line it's in.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NoteEvent {
+    /// When the note starts, in ticks from the start of the piece
+    pub start: usize,
+    /// The index of the line the note is in
+    pub line_index: usize,
+    /// The note itself
+    pub note: Note,
+}
+
+impl NoteEvent {
+    /// Returns when the note ends, in ticks from the start of the piece.
+    pub fn end(&self) -> usize {
+        self.start.saturating_add(usize::from(self.note.0 .0))
+    }
+}
+
+/// A piece compiled into a list of its notes, sorted by when they start, for fast lookup by time.
+///
+/// Created by [`Piece::compile`]. Looking notes up in a [`Piece`] walks every line from the
+/// start, so finding the notes at every instant takes time proportional to the square of the
+/// piece's length. A compiled piece finds them with a binary search instead.
+///
+/// A compiled piece is a snapshot: changes to the piece afterwards aren't reflected in it.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let piece = piano(quarter(C4) + quarter(E4)) * bass(half(C4.octave(-1)));
+/// let compiled = piece.compile();
+///
+/// let quarter_ticks = quarter(C4).0.ticks() as usize;
+/// let starting: Vec<_> = compiled.notes_at_instant(quarter_ticks).map(|event| event.note.clone()).collect();
+/// assert_eq!(starting, [piano(quarter(E4))]);
+///
+/// let sounding: Vec<_> = compiled.notes_during_instant(quarter_ticks).map(|event| event.line_index).collect();
+/// assert_eq!(sounding, [0, 1]); // The bass is still holding
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScheduledPiece {
+    events: Vec<NoteEvent>,
+    length: usize,
+    line_count: usize,
+    /// The length of the longest note, which bounds how far back a sounding note can have started
+    longest_note: usize,
+}
+
+impl Piece {
+    /// Compiles the piece into a [`ScheduledPiece`], which can look up the notes at any instant quickly.
+    ///
+    /// Rests are included, so the notes at each instant are the same as those found by
+    /// [`Piece::get_notes_at_instant`], in the same order. As there, pickups aren't included.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let piece = piano(quarter(C4) + quarter(REST) + half(G4)) * bass(whole(C4.octave(-1)));
+    /// let compiled = piece.compile();
+    ///
+    /// assert_eq!(compiled.events().len(), 4);
+    /// assert_eq!(compiled.length(), piece.length());
+    /// for instant in 0..piece.length() {
+    ///     let notes: Vec<_> = compiled.notes_at_instant(instant).map(|event| event.note.clone()).collect();
+    ///     assert_eq!(notes, piece.get_notes_at_instant(instant).collect::<Vec<_>>());
+    /// }
+    /// ```
+    pub fn compile(&self) -> ScheduledPiece {
+        let events: Vec<NoteEvent> = self.iter_events().collect();
+        ScheduledPiece {
+            longest_note: events
+                .iter()
+                .map(|event| usize::from(event.note.0 .0))
+                .max()
+                .unwrap_or_default(),
+            events,
+            length: self.length(),
+            line_count: self.0.len(),
+        }
+    }
+
+    /// Returns every note of the piece in time order, with when it starts and which line it's in.
+    ///
+    /// Notes which start at the same time are in the order of their lines. Rests are included,
+    /// and pickups aren't. Visualizers and exporters can walk the piece with this once, rather
+    /// than asking for the notes at every tick.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let piece = piano(quarter(C4) + quarter(E4)) * bass(half(C4.octave(-1)));
+    /// let quarter_ticks = quarter(C4).0.ticks() as usize;
+    ///
+    /// let events: Vec<_> = piece.iter_events().map(|event| (event.start, event.line_index)).collect();
+    /// assert_eq!(events, [(0, 0), (0, 1), (quarter_ticks, 0)]);
+    /// ```
+    pub fn iter_events(&self) -> impl Iterator<Item = NoteEvent> + '_ {
+        self.0
+            .iter()
+            .enumerate()
+            .map(|(line_index, line)| {
+                line.iter_timed().map(move |(start, note)| NoteEvent {
+                    start,
+                    line_index,
+                    note: note.clone(),
+                })
+            })
+            .kmerge_by(|a, b| (a.start, a.line_index) < (b.start, b.line_index))
+    }
+}
+
+impl ScheduledPiece {
+    /// Returns every note of the piece, sorted by when they start.
+    /// Notes which start at the same time are in the order of their lines.
+    pub fn events(&self) -> &[NoteEvent] {
+        &self.events
+    }
+
+    /// Returns the length of the piece, in ticks. See [`Piece::length`].
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// Returns the number of lines in the piece.
+    pub fn line_count(&self) -> usize {
+        self.line_count
+    }
+
+    /// Returns the notes which start at the given instant, in ticks.
+    pub fn notes_at_instant(&self, instant: usize) -> impl Iterator<Item = &NoteEvent> {
+        self.events_starting_in(instant, instant.saturating_add(1)).iter()
+    }
+
+    /// Returns the notes which are playing during the given instant, in ticks, rather than
+    /// only those which start at it, in the order of their lines. See [`Piece::get_notes_during_instant`].
+    pub fn notes_during_instant(&self, instant: usize) -> impl Iterator<Item = &NoteEvent> {
+        // A note playing now can't have started longer ago than the longest note lasts
+        let earliest = instant.saturating_add(1).saturating_sub(self.longest_note);
+        let mut playing: Vec<&NoteEvent> = self
+            .events_starting_in(earliest, instant.saturating_add(1))
+            .iter()
+            .filter(|event| instant < event.end())
+            .collect();
+        playing.sort_by_key(|event| event.line_index);
+        playing.into_iter()
+    }
+
+    /// Returns each instant at which any notes start, with the notes which start at it.
+    pub fn instants(&self) -> impl Iterator<Item = (usize, &[NoteEvent])> {
+        let mut rest = self.events.as_slice();
+        std::iter::from_fn(move || {
+            let start = rest.first()?.start;
+            let (starting, after) = rest.split_at(rest.partition_point(|event| event.start == start));
+            rest = after;
+            Some((start, starting))
+        })
+    }
+
+    /// Returns the notes which start from `from`, up to but not including `to`.
+    pub(crate) fn events_starting_in(&self, from: usize, to: usize) -> &[NoteEvent] {
+        let first = self.events.partition_point(|event| event.start < from);
+        let last = self.events.partition_point(|event| event.start < to);
+        self.events.get(first..last).unwrap_or_default()
+    }
+}
+
+#[test]
+fn test_compiled_piece_matches_piece_lookups() {
+    use crate::prelude::*;
+
+    let piece = piano(quarter(C4) + triplet(eighth)(D4) + triplet(eighth)(E4) + eighth(E4) + whole(F4))
+        * bass(half(C4) + quarter(REST) + dotted(quarter)(G4))
+        * Line::new();
+    let compiled = piece.compile();
+
+    assert_eq!(compiled.line_count(), 3);
+    for instant in 0..piece.length().saturating_add(10) {
+        let at: Vec<Note> = compiled
+            .notes_at_instant(instant)
+            .map(|event| event.note.clone())
+            .collect();
+        assert_eq!(
+            at,
+            piece.get_notes_at_instant(instant).collect::<Vec<_>>(),
+            "at {instant}"
+        );
+        let during: Vec<Note> = compiled
+            .notes_during_instant(instant)
+            .map(|event| event.note.clone())
+            .collect();
+        assert_eq!(
+            during,
+            piece.get_notes_during_instant(instant).collect::<Vec<_>>(),
+            "during {instant}"
+        );
+    }
+
+    let starts: Vec<usize> = compiled.instants().map(|(start, _)| start).collect();
+    assert!(starts.windows(2).all(|pair| pair[0] < pair[1]));
+    assert_eq!(
+        compiled.instants().map(|(_, events)| events.len()).sum::<usize>(),
+        compiled.events().len()
+    );
+}
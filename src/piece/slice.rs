@@ -0,0 +1,85 @@
+use std::ops::{Range, RangeInclusive};
+
+use crate::{note::NoteLength, Line, Meter, Note, Piece};
+
+impl Line {
+    /// Cuts out the part of the line within the given span of time (in ticks).
+    ///
+    /// Notes which cross either end of the span are shortened to fit inside it.
+    /// The pickup is kept only if the span starts at the beginning of the line.
+    ///
+    /// # Parameters
+    /// - `range`: The span of time to keep, in ticks
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let quarter_ticks = quarter(C4).0.ticks() as usize;
+    /// let line = piano(half(C4) + half(E4));
+    ///
+    /// // The middle two quarter notes' worth: the end of the C4 and the start of the E4
+    /// let middle = line.slice(quarter_ticks..quarter_ticks * 3);
+    /// assert_eq!(middle, piano(quarter(C4) + quarter(E4)));
+    /// ```
+    pub fn slice(&self, range: Range<usize>) -> Line {
+        let mut notes = vec![];
+        let mut time = 0_usize;
+        for Note(length, kind) in &self.notes {
+            let end = time.saturating_add(usize::from(length.ticks()));
+            let kept = end.min(range.end).saturating_sub(time.max(range.start));
+            if kept > 0 {
+                // The kept part can't be longer than the note
+                let ticks = u16::try_from(kept).unwrap_or(u16::MAX);
+                notes.push(Note(NoteLength::from_ticks(ticks), kind.clone()));
+            }
+            time = end;
+        }
+
+        let keeps_start = range.start == 0;
+        Line {
+            notes,
+            pickup: if keeps_start { self.pickup.clone() } else { vec![] },
+            hold_pickup: keeps_start && self.hold_pickup,
+            ..self.clone()
+        }
+    }
+}
+
+impl Piece {
+    /// Cuts out the part of the piece within the given span of time (in ticks).
+    ///
+    /// See [`Line::slice`] for details.
+    ///
+    /// # Parameters
+    /// - `range`: The span of time to keep, in ticks
+    pub fn slice(&self, range: Range<usize>) -> Piece {
+        Piece(self.0.iter().map(|line| line.slice(range.clone())).collect())
+    }
+
+    /// Cuts out the given bars of the piece, including the last bar.
+    ///
+    /// Bars are numbered as a musician would number them: if the meter has an anacrusis,
+    /// it's bar 0, and bar 1 is the first full bar.
+    ///
+    /// # Parameters
+    /// - `meter`: How the piece is divided into bars
+    /// - `bars`: The numbers of the bars to keep
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let quarter_ticks = quarter(C4).0.ticks() as usize;
+    /// let meter = Meter::new(TimeSignature::COMMON_TIME).with_anacrusis(quarter_ticks);
+    ///
+    /// // A pickup, followed by two bars
+    /// let piece = Piece::from(piano(quarter(G4) + whole(C4) + whole(E4)));
+    ///
+    /// assert_eq!(piece.bars(meter, 0..=0), Piece::from(piano(quarter(G4))));
+    /// assert_eq!(piece.bars(meter, 2..=2), Piece::from(piano(whole(E4))));
+    /// ```
+    pub fn bars(&self, meter: Meter, bars: RangeInclusive<usize>) -> Piece {
+        self.slice(meter.bars(bars))
+    }
+}
@@ -0,0 +1,214 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use crate::{Piece, Ratio, StretchError};
+
+/// A song built from named sections, such as an intro, verses, and a chorus, and the order
+/// they're played in.
+///
+/// Each section is written once, then the arrangement lists which sections play, in order, and
+/// how many times each repeats. Sections can have their own tempo and volume. The song is
+/// flattened into a single [`Piece`] with [`Song::to_piece`], to be played at the song's tempo.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::{Section, Song};
+///
+/// let verse = piano(quarter(C4) + quarter(E4) + half(G4)) * bass(whole(C4.octave(-2)));
+/// let chorus = piano(half(A4) + half(F4)) * bass(whole(F4.octave(-2)));
+///
+/// let song = Song::new(300)
+///     .with_section(Section::new("verse", verse.clone()))
+///     .with_section(Section::new("chorus", chorus).with_volume(1.5))
+///     .with_section(Section::new("outro", verse).with_tempo(150))
+///     .then("verse")
+///     .then_repeat("chorus", 2)
+///     .then("verse")
+///     .then("outro");
+///
+/// let piece = song.to_piece().unwrap();
+///
+/// // The outro is at half the tempo, so it lasts twice as long
+/// assert_eq!(piece.length(), 6 * 16 * TICKS_PER_SIXTEENTH as usize);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Song {
+    /// The tempo the song is played at, in sixteenth notes per minute
+    pub tempo_bpm: u32,
+    /// The sections the song can use, in no particular order
+    pub sections: Vec<Section>,
+    /// The name of each section in the order they're played, with how many times each plays in a row
+    pub arrangement: Vec<(String, usize)>,
+}
+
+/// A named part of a [`Song`], such as a verse or a chorus, with its own tempo and volume if set.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Section {
+    /// The name the arrangement refers to the section by
+    pub name: String,
+    /// The music of the section
+    pub piece: Piece,
+    /// The tempo of the section, in sixteenth notes per minute. If `None`, the song's tempo is used.
+    pub tempo_bpm: Option<u32>,
+    /// How much louder or quieter the section is than it was written, as a multiple of each note's volume
+    pub volume: f32,
+}
+
+impl Song {
+    /// Creates a song with no sections, played at the given tempo in sixteenth notes per minute.
+    pub fn new(tempo_bpm: u32) -> Self {
+        Song {
+            tempo_bpm,
+            sections: vec![],
+            arrangement: vec![],
+        }
+    }
+
+    /// Adds a section the arrangement can use, replacing any section with the same name.
+    pub fn with_section(mut self, section: Section) -> Self {
+        self.sections.retain(|existing| existing.name != section.name);
+        self.sections.push(section);
+        self
+    }
+
+    /// Plays the named section once, after everything arranged so far.
+    pub fn then(self, name: impl Into<String>) -> Self {
+        self.then_repeat(name, 1)
+    }
+
+    /// Plays the named section the given number of times in a row, after everything arranged so far.
+    pub fn then_repeat(mut self, name: impl Into<String>, repeats: usize) -> Self {
+        self.arrangement.push((name.into(), repeats));
+        self
+    }
+
+    /// Returns the section with the given name, if there is one.
+    pub fn section(&self, name: &str) -> Option<&Section> {
+        self.sections.iter().find(|section| section.name == name)
+    }
+
+    /// Flattens the song into a single piece, with its sections in the order they're arranged.
+    ///
+    /// Sections with their own tempo are stretched so they play at that tempo when the piece is
+    /// played at the song's tempo, and sections with their own volume have every note's volume
+    /// scaled. Lines are joined by position, so the first line of each section follows on from
+    /// the first line of the section before.
+    ///
+    /// # Errors
+    /// Returns an error if the arrangement names a section the song doesn't have, or if a
+    /// section's notes can't be stretched exactly to its tempo.
+    #[expect(clippy::arithmetic_side_effects, reason = "Sections are joined with +")]
+    pub fn to_piece(&self) -> Result<Piece, SongError> {
+        let mut piece = Piece::new();
+        for (name, repeats) in &self.arrangement {
+            let section = self
+                .section(name)
+                .ok_or_else(|| SongError::UnknownSection(name.clone()))?;
+            let music = section.render(self.tempo_bpm).map_err(|error| SongError::Stretch {
+                section: name.clone(),
+                error,
+            })?;
+            for _ in 0..*repeats {
+                piece = piece + music.clone();
+            }
+        }
+        Ok(piece)
+    }
+}
+
+impl Section {
+    /// Creates a section which plays at the song's tempo, at the volume it was written.
+    pub fn new(name: impl Into<String>, piece: impl Into<Piece>) -> Self {
+        Section {
+            name: name.into(),
+            piece: piece.into(),
+            tempo_bpm: None,
+            volume: 1.0,
+        }
+    }
+
+    /// Sets the tempo of the section, in sixteenth notes per minute, instead of the song's.
+    pub fn with_tempo(mut self, tempo_bpm: u32) -> Self {
+        self.tempo_bpm = Some(tempo_bpm);
+        self
+    }
+
+    /// Scales the volume of every note in the section, so `0.5` is half as loud as written.
+    pub fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    /// Returns the section's music as it's played in a song at the given tempo.
+    fn render(&self, song_tempo_bpm: u32) -> Result<Piece, StretchError> {
+        let piece = match self.tempo_bpm {
+            Some(tempo_bpm) if tempo_bpm != song_tempo_bpm => {
+                // A slower section lasts longer, so its notes are stretched by how much slower it is
+                let divisor = gcd(song_tempo_bpm, tempo_bpm).max(1);
+                self.piece.stretch(Ratio::new(
+                    song_tempo_bpm.checked_div(divisor).unwrap_or_default(),
+                    tempo_bpm.checked_div(divisor).unwrap_or_default(),
+                ))?
+            }
+            _ => self.piece.clone(),
+        };
+        Ok(Piece(
+            piece.0.iter().map(|line| line.scale_volume(self.volume)).collect(),
+        ))
+    }
+}
+
+/// Returns the greatest common divisor of two numbers.
+fn gcd(a: u32, b: u32) -> u32 {
+    match a.checked_rem(b) {
+        Some(remainder) => gcd(b, remainder),
+        None => a,
+    }
+}
+
+/// The error returned when a [`Song`] can't be flattened into a piece.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SongError {
+    /// The arrangement names a section the song doesn't have
+    UnknownSection(String),
+    /// A section's notes can't be stretched to its tempo
+    Stretch {
+        /// The name of the section
+        section: String,
+        /// Why the notes couldn't be stretched
+        error: StretchError,
+    },
+}
+
+impl Display for SongError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            SongError::UnknownSection(name) => write!(f, "the song has no section called \"{name}\""),
+            SongError::Stretch { section, error } => write!(f, "section \"{section}\" can't change tempo: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for SongError {}
+
+#[test]
+fn test_song_flattens_sections_in_order() {
+    use crate::prelude::*;
+
+    let song = Song::new(300)
+        .with_section(Section::new("a", piano(quarter(C4))))
+        .with_section(Section::new("b", piano(quarter(E4))).with_volume(0.5).with_tempo(600))
+        .then_repeat("a", 2)
+        .then("b");
+
+    let piece = song.to_piece().unwrap();
+    assert_eq!(
+        piece.0,
+        [piano(quarter(C4)) + piano(quarter(C4)) + piano(eighth(E4)).volume(0.5)]
+    );
+
+    assert_eq!(
+        song.then("bridge").to_piece(),
+        Err(SongError::UnknownSection("bridge".to_string()))
+    );
+}
@@ -0,0 +1,152 @@
+use crate::{Line, Note, NoteKind, NoteLength, Piece};
+
+impl Line {
+    /// Swings the eighth notes of the line, delaying every off-beat eighth.
+    ///
+    /// Each pair of eighths is played long-short: the on-beat half of every beat is lengthened,
+    /// and the off-beat half is shortened by the same amount, so the line keeps its length and
+    /// every beat still starts on time. Notes which span the off-beat are stretched to match.
+    ///
+    /// # Parameters
+    /// - `amount`: How far the off-beat is delayed, as a fraction of an eighth note.
+    ///   `0.0` is straight, `1.0 / 3.0` is triplet swing (2:1), and `0.5` is dotted swing (3:1).
+    ///   It is clamped between `0.0` and `0.9`.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let straight = piano(eighth(C4) * 4);
+    /// let swung = straight.swing(1.0 / 3.0);
+    ///
+    /// // Each pair of eighths becomes a triplet quarter and a triplet eighth
+    /// assert_eq!(swung.notes[0].0, triplet(quarter)(C4).0);
+    /// assert_eq!(swung.notes[1].0, triplet(eighth)(C4).0);
+    /// assert_eq!(swung.length(), straight.length());
+    /// ```
+    pub fn swing(&self, amount: f32) -> Line {
+        self.swing_subdivision(NoteLength::new(2), amount)
+    }
+
+    /// Swings the sixteenth notes of the line, delaying every off-beat sixteenth.
+    ///
+    /// See [`Line::swing`] for details. Here, `amount` is a fraction of a sixteenth note.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let hats = drums(sixteenth(C4.octave(1)) * 16);
+    /// let shuffled = hats.swing_sixteenths(0.5);
+    /// assert_eq!(shuffled.notes[0].0, dotted(sixteenth)(C4).0);
+    /// ```
+    pub fn swing_sixteenths(&self, amount: f32) -> Line {
+        self.swing_subdivision(NoteLength::new(1), amount)
+    }
+
+    fn swing_subdivision(&self, subdivision: NoteLength, amount: f32) -> Line {
+        let subdivision = i64::from(subdivision.ticks());
+        let amount = f64::from(amount.clamp(0.0, 0.9));
+        let pickup_length = i64::try_from(Line::from(self.pickup.clone()).length()).unwrap_or(i64::MAX);
+
+        Line {
+            notes: swing_notes(&self.notes, 0, subdivision, amount),
+            pickup: swing_notes(&self.pickup, pickup_length.saturating_neg(), subdivision, amount),
+            ..self.clone()
+        }
+    }
+}
+
+impl Piece {
+    /// Swings the eighth notes of every line of the piece.
+    ///
+    /// See [`Line::swing`] for details.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let groove = piano(eighth(C4) * 8) * bass(quarter(C4.octave(-1)) * 4);
+    /// let swung = groove.swing(1.0 / 3.0);
+    /// assert_eq!(swung.length(), groove.length());
+    /// ```
+    pub fn swing(&self, amount: f32) -> Piece {
+        Piece(self.0.iter().map(|line| line.swing(amount)).collect())
+    }
+
+    /// Swings the sixteenth notes of every line of the piece.
+    ///
+    /// See [`Line::swing`] for details. Here, `amount` is a fraction of a sixteenth note.
+    pub fn swing_sixteenths(&self, amount: f32) -> Piece {
+        Piece(self.0.iter().map(|line| line.swing_sixteenths(amount)).collect())
+    }
+}
+
+/// Swings a sequence of notes starting at `start` ticks, keeping the start of every pair of subdivisions in place.
+fn swing_notes(notes: &[Note], start: i64, subdivision: i64, amount: f64) -> Vec<Note> {
+    let mut swung = vec![];
+    let mut time = start;
+    let mut swung_time = swing_time(start, subdivision, amount);
+
+    for note in notes {
+        let end = time.saturating_add(i64::from(note.0.ticks()));
+        let swung_end = swing_time(end, subdivision, amount);
+        let length = u64::try_from(swung_end.saturating_sub(swung_time)).unwrap_or_default();
+
+        // Notes shortened to nothing can't be heard, so are dropped. A note stretched past the
+        // longest length a note can have is held for as long as it can be, then followed by
+        // rests, so the notes after it stay in time.
+        if length > 0 {
+            let held = u16::try_from(length).unwrap_or(u16::MAX);
+            swung.push(Note(NoteLength::from_ticks(held), note.1.clone()));
+            let mut remaining = length.saturating_sub(u64::from(held));
+            while remaining > 0 {
+                let rest = u16::try_from(remaining).unwrap_or(u16::MAX);
+                swung.push(Note(NoteLength::from_ticks(rest), NoteKind::Rest));
+                remaining = remaining.saturating_sub(u64::from(rest));
+            }
+        }
+
+        time = end;
+        swung_time = swung_end;
+    }
+
+    swung
+}
+
+/// Moves a point in time (in ticks) so that off-beat subdivisions are delayed by `amount` of a subdivision.
+#[expect(clippy::arithmetic_side_effects, reason = "The subdivision is never zero")]
+#[expect(clippy::cast_precision_loss, reason = "Only loses precision for pieces lasting centuries")]
+#[expect(clippy::cast_possible_truncation, reason = "The result is close to the input, which fit in an i64")]
+fn swing_time(time: i64, subdivision: i64, amount: f64) -> i64 {
+    let period = subdivision.max(1) * 2;
+    let period_start = time.div_euclid(period) * period;
+    let offset = time.rem_euclid(period) as f64;
+    let subdivision = subdivision as f64;
+
+    let swung_offset = if offset <= subdivision {
+        offset * (1.0 + amount)
+    } else {
+        subdivision * (1.0 + amount) + (offset - subdivision) * (1.0 - amount)
+    };
+
+    period_start + swung_offset.round() as i64
+}
+
+#[test]
+fn test_swing_keeps_notes_stretched_past_the_longest_length() {
+    use crate::prelude::*;
+
+    // The long note ends just before an off-beat, so swinging it makes it longer
+    let beat = quarter(C4).0.ticks();
+    let line = Line::from(vec![
+        Note(NoteLength::from_ticks(u16::MAX), C4.into()),
+        Note(NoteLength::from_ticks(beat - u16::MAX % beat), NoteKind::Rest),
+        quarter(E4),
+    ]);
+    let swung = line.swing(0.5);
+
+    assert_eq!(swung.length(), line.length());
+    assert_eq!(swung.notes[0], Note(NoteLength::from_ticks(u16::MAX), C4.into()));
+    assert_eq!(swung.notes.last(), Some(&quarter(E4)));
+}
@@ -0,0 +1,449 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use crate::{
+    note::Ratio, scales::tet12::transpose_kind, Line, Note, NoteKind, NoteLength, NotePitch, Piece, Scale, Tet12,
+    TimbreFluid,
+};
+
+/// How far (in semitones) a pitch can be from a scale tone and still count as that scale tone.
+const SCALE_TONE_TOLERANCE: f32 = 0.25;
+
+/// How many scale tones to search, in each direction from the root, for the tones around a pitch.
+/// This covers the range of hearing in any scale with fewer than 100 tones an octave.
+const MAX_SCALE_SEARCH: isize = 1000;
+
+impl Line {
+    /// Transposes the line by scale degrees, rather than semitones, so the melody stays in key.
+    ///
+    /// Each note moves up or down the scale by the given number of degrees, so the size of each
+    /// interval changes to fit the scale. Notes outside the scale move with the scale tone below
+    /// them, keeping their sharp or flat. Rests and unpitched notes, such as drums, are left as they are.
+    /// So are pitches which the scale never reaches, such as in a scale whose degrees don't rise.
+    ///
+    /// # Parameters
+    /// - `scale`: The scale to move along
+    /// - `degrees`: How many scale degrees to move. Positive values move up, negative values move down.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let scale = MajorScale(C4);
+    /// let [c4, e4, g4] = scale.get_degrees([1, 3, 5]);
+    /// let [d4, f4, a4] = scale.get_degrees([2, 4, 6]);
+    ///
+    /// // The major triad becomes a minor triad, since it stays in C major
+    /// let melody = piano(quarter(c4) + quarter(e4) + half(g4));
+    /// assert_eq!(melody.transpose_in_scale(&scale, 1), piano(quarter(d4) + quarter(f4) + half(a4)));
+    ///
+    /// // Notes outside the scale keep their accidentals
+    /// let chromatic = Line::from(piano(quarter(CS4))).transpose_in_scale(&scale, 1);
+    /// if let NoteKind::Pitched { pitch, .. } = chromatic.notes[0].1 {
+    ///     assert_eq!(format!("{pitch:?}"), "D#4");
+    /// }
+    /// ```
+    pub fn transpose_in_scale(&self, scale: &impl Scale, degrees: isize) -> Line {
+        let transpose = |note: &Note| {
+            Note(
+                note.0,
+                transpose_kind(&note.1, |pitch| transpose_pitch_in_scale(pitch, scale, degrees)),
+            )
+        };
+
+        self.map_notes(transpose)
+    }
+
+    /// Returns the line played backwards.
+    ///
+    /// The notes (including rests) are reversed, but each keeps its length and sound.
+    /// The pickup isn't part of the melody, so is kept as it is.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let melody = piano(quarter(C4) + eighth(E4) + eighth(G4));
+    /// assert_eq!(melody.retrograde(), piano(eighth(G4) + eighth(E4) + quarter(C4)));
+    /// ```
+    pub fn retrograde(&self) -> Line {
+        Line {
+            notes: self.notes.iter().rev().cloned().collect(),
+            ..self.clone()
+        }
+    }
+
+    /// Returns the line turned upside down, mirroring every pitch around the given pitch.
+    ///
+    /// A note a major third above `axis` becomes a note a major third below it, and so on.
+    /// Rests and unpitched notes, such as drums, are left as they are.
+    ///
+    /// # Parameters
+    /// - `axis`: The pitch to mirror around. Notes at this pitch are unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let melody = piano(quarter(C4) + quarter(E4) + quarter(G4));
+    /// let inverted = melody.invert_around(C4);
+    ///
+    /// // Pitches are compared by name, as the frequencies are rounded differently
+    /// let expected = piano(quarter(C4) + quarter(AF3) + quarter(F3));
+    /// assert_eq!(format!("{inverted:?}"), format!("{expected:?}"));
+    /// ```
+    pub fn invert_around(&self, axis: NotePitch) -> Line {
+        let invert = |note: &Note| {
+            Note(
+                note.0,
+                transpose_kind(&note.1, |pitch| NotePitch(axis.0 * axis.0 / pitch.0)),
+            )
+        };
+
+        self.map_notes(invert)
+    }
+
+    /// Returns the line turned upside down and played backwards.
+    ///
+    /// The line is inverted around its first pitched note, as with [`Line::invert_around`],
+    /// and then reversed, as with [`Line::retrograde`].
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let melody = piano(quarter(C4) + quarter(E4) + half(G4));
+    /// assert_eq!(melody.retrograde_inversion(), melody.invert_around(C4).retrograde());
+    /// ```
+    pub fn retrograde_inversion(&self) -> Line {
+        let axis = self.notes.iter().find_map(|note| match note.1 {
+            NoteKind::Pitched { pitch, .. } => Some(pitch),
+            NoteKind::Rest => None,
+        });
+
+        match axis {
+            Some(axis) => self.invert_around(axis).retrograde(),
+            None => self.retrograde(),
+        }
+    }
+
+    /// Returns the line with the sound of every note played backwards.
+    ///
+    /// Unlike [`Line::retrograde`], which changes the order of the notes, this reverses the audio
+    /// of each note, so notes swell up to a sudden stop - a common effect leading into a transition.
+    /// Each note's timbre is wrapped in [`Timbre::Reversed`](crate::Timbre::Reversed), and calling
+    /// this again gives back the original line. Combine it with [`Line::retrograde`] to play the
+    /// whole line backwards.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let swell = piano(quarter(C4) + half(G4)).reverse_audio();
+    /// assert_eq!(swell, (quarter(C4) + half(G4)).with_timbre(Timbre::Piano.reversed()));
+    /// assert_eq!(swell.reverse_audio(), piano(quarter(C4) + half(G4)));
+    /// ```
+    pub fn reverse_audio(&self) -> Line {
+        let reverse = |note: &Note| match &note.1 {
+            NoteKind::Pitched { timbre, .. } => note.clone().with_timbre(timbre.clone().reversed()),
+            NoteKind::Rest => note.clone(),
+        };
+
+        self.map_notes(reverse)
+    }
+
+    /// Scales the length of every note in the line, including the pickup.
+    ///
+    /// A factor of 2 plays the line at half speed (augmentation), and a factor of 1/2 plays
+    /// it at double speed (diminution).
+    ///
+    /// # Parameters
+    /// - `factor`: The ratio to scale every length by
+    ///
+    /// # Errors
+    /// Returns an error if the factor is zero, or if any stretched note wouldn't be a whole
+    /// number of ticks, or would be too long to be a note length.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let theme = piano(quarter(C4) + eighth(E4) + eighth(G4));
+    ///
+    /// let augmented = theme.stretch(2).unwrap();
+    /// assert_eq!(augmented, piano(half(C4) + quarter(E4) + quarter(G4)));
+    ///
+    /// let diminished = theme.stretch(Ratio::new(1, 2)).unwrap();
+    /// assert_eq!(diminished, piano(eighth(C4) + sixteenth(E4) + sixteenth(G4)));
+    ///
+    /// let too_short = Line::from(piano(sixty_fourth(C4))).stretch(Ratio::new(1, 5));
+    /// assert_eq!(too_short, Err(StretchError::Inexact(sixty_fourth(C4).0)));
+    /// ```
+    pub fn stretch(&self, factor: impl Into<Ratio>) -> Result<Line, StretchError> {
+        let factor = factor.into();
+        if factor.numerator == 0 || factor.denominator == 0 {
+            return Err(StretchError::InvalidRatio(factor));
+        }
+
+        let stretch_notes = |notes: &[Note]| {
+            notes
+                .iter()
+                .map(|note| {
+                    let length = factor.apply(note.0).ok_or_else(|| {
+                        // The length was either not a whole number of ticks, or too long
+                        let exact = u64::from(note.0 .0).saturating_mul(u64::from(factor.numerator));
+                        if exact.checked_rem(u64::from(factor.denominator)) == Some(0) {
+                            StretchError::TooLong(note.0)
+                        } else {
+                            StretchError::Inexact(note.0)
+                        }
+                    })?;
+                    Ok(Note(length, note.1.clone()))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        Ok(Line {
+            notes: stretch_notes(&self.notes)?,
+            pickup: stretch_notes(&self.pickup)?,
+            ..self.clone()
+        })
+    }
+
+    /// Doubles the line in another octave, returning a piece of the original line and the doubling.
+    ///
+    /// Drums and rests are left as they are, so doubling a line with drums doubles the drum hits.
+    ///
+    /// # Parameters
+    /// - `offset`: How many octaves away the doubling is. Negative values double below.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let melody = piano(quarter(C4) + quarter(E4) + half(G4));
+    /// let doubled = melody.double_octave(-1);
+    ///
+    /// assert_eq!(doubled.0.len(), 2);
+    /// assert_eq!(doubled.0[1], melody.octave(-1));
+    /// ```
+    pub fn double_octave(&self, offset: i32) -> Piece {
+        Piece(vec![self.clone(), self.octave(offset)])
+    }
+
+    /// Harmonizes the line with parallel lines which stay in key, returning a piece of the
+    /// original line and the harmonies.
+    ///
+    /// Each harmony line is the original, moved along the scale by some number of degrees, as
+    /// with [`Line::transpose_in_scale`]. Since scale degrees are counted from zero here, a third
+    /// above is `2`, a sixth below is `-5`, and so on.
+    ///
+    /// # Parameters
+    /// - `scale`: The scale to harmonize in
+    /// - `interval_degrees`: How many scale degrees away each harmony line is
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let scale = MajorScale(C4);
+    /// let melody = piano(quarter(C4) + quarter(D4) + half(E4));
+    ///
+    /// // Thirds above, and sixths below
+    /// let harmonized = melody.harmonize(&scale, &[2, -5]);
+    ///
+    /// assert_eq!(harmonized.0.len(), 3);
+    /// assert_eq!(harmonized.0[1], melody.transpose_in_scale(&scale, 2));
+    /// ```
+    pub fn harmonize(&self, scale: &impl Scale, interval_degrees: &[isize]) -> Piece {
+        Piece(
+            std::iter::once(self.clone())
+                .chain(
+                    interval_degrees
+                        .iter()
+                        .map(|&degrees| self.transpose_in_scale(scale, degrees)),
+                )
+                .collect(),
+        )
+    }
+}
+
+impl Piece {
+    /// Transposes every line of the piece by scale degrees.
+    ///
+    /// See [`Line::transpose_in_scale`] for details.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let scale = MinorScale(A3);
+    /// let piece = piano(quarter(A3) + quarter(C4)) * bass(half(A2));
+    /// let down_a_third = piece.transpose_in_scale(&scale, -2);
+    /// assert_eq!(down_a_third.length(), piece.length());
+    /// ```
+    pub fn transpose_in_scale(&self, scale: &impl Scale, degrees: isize) -> Piece {
+        Piece(
+            self.0
+                .iter()
+                .map(|line| line.transpose_in_scale(scale, degrees))
+                .collect(),
+        )
+    }
+
+    /// Returns the piece with the sound of every note played backwards.
+    ///
+    /// See [`Line::reverse_audio`] for details.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let piece = piano(quarter(C4) + quarter(E4)) * bass(half(C2));
+    /// assert_eq!(piece.reverse_audio().reverse_audio(), piece);
+    /// ```
+    pub fn reverse_audio(&self) -> Piece {
+        Piece(self.0.iter().map(Line::reverse_audio).collect())
+    }
+
+    /// Scales the length of every note in the piece.
+    ///
+    /// See [`Line::stretch`] for details.
+    ///
+    /// # Errors
+    /// Returns an error if any line can't be stretched.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let piece = piano(quarter(C4) + quarter(E4)) * bass(half(C2));
+    /// assert_eq!(piece.stretch(3).unwrap().length(), 3 * piece.length());
+    /// ```
+    pub fn stretch(&self, factor: impl Into<Ratio>) -> Result<Piece, StretchError> {
+        let factor = factor.into();
+        Ok(Piece(
+            self.0
+                .iter()
+                .map(|line| line.stretch(factor))
+                .collect::<Result<_, _>>()?,
+        ))
+    }
+}
+
+/// Combines a sequence of pitches with a rhythm, making a line.
+///
+/// The nth note has the nth pitch and the nth length. If one sequence is shorter, it repeats
+/// until the longer one is finished, so pitch and rhythm patterns of different lengths drift
+/// against each other. If either is empty, the line is empty.
+///
+/// # Parameters
+/// - `pitches`: The pitches (or rests) of the notes
+/// - `rhythm`: The lengths of the notes
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// // Three pitches against a rhythm of four notes
+/// let pitches = [C4, E4, G4];
+/// let rhythm = [quarter, eighth, eighth, half].map(|length| length(C4).0);
+/// let line = piano(zip_rhythm(pitches, rhythm));
+///
+/// assert_eq!(
+///     line,
+///     piano(quarter(C4) + eighth(E4) + eighth(G4) + half(C4))
+/// );
+/// ```
+pub fn zip_rhythm(
+    pitches: impl IntoIterator<Item = impl Into<NoteKind>>,
+    rhythm: impl IntoIterator<Item = NoteLength>,
+) -> Line {
+    let pitches: Vec<NoteKind> = pitches.into_iter().map(Into::into).collect();
+    let rhythm: Vec<NoteLength> = rhythm.into_iter().collect();
+    let count = pitches.len().max(rhythm.len());
+
+    Line::from(
+        pitches
+            .iter()
+            .cycle()
+            .zip(rhythm.iter().cycle())
+            .take(count)
+            .map(|(kind, &length)| Note(length, kind.clone()))
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Moves a pitch along a scale by some number of degrees.
+#[expect(clippy::arithmetic_side_effects, reason = "Indices stay near the pitch's place in the scale")]
+fn transpose_pitch_in_scale(pitch: NotePitch, scale: &impl Scale, degrees: isize) -> NotePitch {
+    if !pitch.0.is_finite() || pitch.0 <= 0.0 {
+        return pitch;
+    }
+
+    // Indices count scale tones from the root, which is index 0.
+    // Degrees skip zero, so the tone below the root is degree -1.
+    let tone = |index: isize| scale.get_degree(if index >= 0 { index + 1 } else { index });
+    let semitones_above = |tone: NotePitch| 12.0 * (pitch.0 / tone.0).log2();
+
+    // Find the scale tone at, or just below, the pitch. A scale which doesn't rise may never
+    // pass the pitch, so the search gives up after a while.
+    let mut index = 0;
+    while semitones_above(tone(index)) < -SCALE_TONE_TOLERANCE {
+        if index <= -MAX_SCALE_SEARCH {
+            return pitch;
+        }
+        index -= 1;
+    }
+    while semitones_above(tone(index + 1)) >= -SCALE_TONE_TOLERANCE {
+        if index >= MAX_SCALE_SEARCH {
+            return pitch;
+        }
+        index += 1;
+    }
+
+    // Keep any sharp or flat, and any difference in tuning
+    let alteration = pitch.0 / tone(index).0;
+    NotePitch(tone(index + degrees).0 * alteration)
+}
+
+/// The error returned when a line or piece can't be stretched.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StretchError {
+    /// The ratio was zero, or had a zero denominator
+    InvalidRatio(Ratio),
+    /// Stretching this length doesn't give a whole number of ticks
+    Inexact(NoteLength),
+    /// Stretching this length gives a length too long for one note
+    TooLong(NoteLength),
+}
+
+impl Display for StretchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            StretchError::InvalidRatio(ratio) => {
+                write!(f, "can't stretch by {}/{}", ratio.numerator, ratio.denominator)
+            }
+            StretchError::Inexact(length) => write!(f, "a note of {length} can't be stretched exactly"),
+            StretchError::TooLong(length) => write!(f, "a note of {length} is too long once stretched"),
+        }
+    }
+}
+
+impl std::error::Error for StretchError {}
+
+#[test]
+fn test_transpose_in_scale_leaves_pitches_a_flat_scale_never_reaches() {
+    use crate::prelude::*;
+
+    struct FlatScale;
+
+    impl Scale for FlatScale {
+        fn get_degree(&self, _degree: isize) -> NotePitch {
+            C4
+        }
+    }
+
+    let line = Line::from(vec![quarter(E4), quarter(C3)]);
+    assert_eq!(line.transpose_in_scale(&FlatScale, 1), line);
+    assert_eq!(line.transpose_in_scale(&FlatScale, -3), line);
+}
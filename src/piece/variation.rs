@@ -0,0 +1,198 @@
+use crate::{
+    rng::Rng, scales::tet12::transpose_kind, Line, Note, NoteKind, NoteLength, NotePitch, Tet12, Timbre,
+    TICKS_PER_SIXTEENTH,
+};
+
+/// How a line may change each time it's varied by [`Line::vary`].
+///
+/// Each kind of change has a chance of being made to each variation, from 0.0 (never) to
+/// 1.0 (always). At most one change of each kind is made, so variations stay recognisable.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// // Only ever move notes by an octave
+/// let rules = VariationRules {
+///     octave_displacement: 0.5,
+///     rhythmic_displacement: 0.0,
+///     passing_tones: 0.0,
+/// };
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VariationRules {
+    /// The chance of moving one note up or down an octave (default: 0.3)
+    pub octave_displacement: f32,
+    /// The chance of moving one note a sixteenth earlier or later, taking the time from the note
+    /// next to it, so the line keeps its length (default: 0.25)
+    pub rhythmic_displacement: f32,
+    /// The chance of filling a leap of a third with a passing tone, taken from the end of the
+    /// note before it (default: 0.3)
+    pub passing_tones: f32,
+}
+
+impl Default for VariationRules {
+    fn default() -> Self {
+        VariationRules {
+            octave_displacement: 0.3,
+            rhythmic_displacement: 0.25,
+            passing_tones: 0.3,
+        }
+    }
+}
+
+impl Line {
+    /// Returns a slight variation of the line, made with the given rules.
+    ///
+    /// The variation always has the same length as the line, and the same seed always gives the
+    /// same variation. Only the main sequence of notes is changed, not the pickup. Rests and
+    /// drums are never changed. See [`Line::repeat_varied`] to vary each repetition of a line.
+    ///
+    /// # Parameters
+    /// - `seed`: Chooses which changes are made
+    /// - `rules`: Which changes can be made, and how often
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let riff = piano(eighth(C4) + eighth(E4) + eighth(G4) + eighth(E4));
+    /// let variation = riff.vary(7, &VariationRules::default());
+    ///
+    /// assert_eq!(variation.length(), riff.length());
+    /// assert_eq!(variation, riff.vary(7, &VariationRules::default()));
+    /// ```
+    pub fn vary(&self, seed: u64, rules: &VariationRules) -> Line {
+        let mut rng = Rng::new(seed);
+        let mut notes = self.notes.clone();
+
+        if chance(&mut rng, rules.octave_displacement) {
+            displace_octave(&mut notes, &mut rng);
+        }
+        if chance(&mut rng, rules.rhythmic_displacement) {
+            displace_rhythm(&mut notes, &mut rng);
+        }
+        if chance(&mut rng, rules.passing_tones) {
+            add_passing_tone(&mut notes, &mut rng);
+        }
+
+        Line { notes, ..self.clone() }
+    }
+
+    /// Repeats the line, varying each repetition after the first, so repeats don't sound like
+    /// literal copies.
+    ///
+    /// Repetitions are varied with [`Line::vary`], each with its own seed, so the same seed
+    /// always gives the same result.
+    ///
+    /// # Parameters
+    /// - `times`: How many times the line is played
+    /// - `seed`: Chooses which changes are made
+    /// - `rules`: Which changes can be made, and how often
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let riff = piano(eighth(C4) + eighth(E4) + eighth(G4) + eighth(E4));
+    /// let verse = riff.repeat_varied(16, 42, &VariationRules::default());
+    ///
+    /// assert_eq!(verse.length(), (riff.clone() * 16).length());
+    /// assert_ne!(verse, riff * 16);
+    /// ```
+    #[expect(clippy::arithmetic_side_effects, reason = "Adding lines joins them")]
+    pub fn repeat_varied(&self, times: usize, seed: u64, rules: &VariationRules) -> Line {
+        let mut seeds = Rng::new(seed);
+        (1..times).fold(self.clone(), |line, _| line + self.vary(seeds.next_u64(), rules))
+    }
+}
+
+/// Returns true with the given probability.
+#[expect(clippy::cast_precision_loss, reason = "Only used for probabilities")]
+fn chance(rng: &mut Rng, probability: f32) -> bool {
+    (rng.next_u64() as f32 / u64::MAX as f32) < probability
+}
+
+/// Returns the indices of the notes which can have their pitch changed.
+fn pitched_indices(notes: &[Note]) -> Vec<usize> {
+    notes
+        .iter()
+        .enumerate()
+        .filter(|(_, note)| match &note.1 {
+            NoteKind::Pitched { timbre, .. } => {
+                !matches!(timbre.unreversed(), Timbre::Drums | Timbre::CustomSourceUnpitched(_))
+            }
+            NoteKind::Rest => false,
+        })
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Moves one pitched note up or down an octave.
+fn displace_octave(notes: &mut [Note], rng: &mut Rng) {
+    let Some(&index) = rng.choose(&pitched_indices(notes)) else {
+        return;
+    };
+    let change = if rng.below(2) == 0 { -1 } else { 1 };
+    if let Some(note) = notes.get_mut(index) {
+        note.1 = transpose_kind(&note.1, |pitch| pitch.octave(change));
+    }
+}
+
+/// Moves the boundary between two notes by a sixteenth, so one note starts earlier or later.
+#[expect(clippy::arithmetic_side_effects, reason = "Lengths are checked before they're changed")]
+fn displace_rhythm(notes: &mut [Note], rng: &mut Rng) {
+    // Either note can give up a sixteenth, as long as it keeps some length
+    let mut boundaries = vec![];
+    for (index, pair) in notes.windows(2).enumerate() {
+        if pair[0].0.ticks() > TICKS_PER_SIXTEENTH {
+            boundaries.push((index, true));
+        }
+        if pair[1].0.ticks() > TICKS_PER_SIXTEENTH {
+            boundaries.push((index, false));
+        }
+    }
+
+    let Some(&(index, earlier)) = rng.choose(&boundaries) else {
+        return;
+    };
+    let (shorter, longer) = if earlier {
+        (index, index + 1)
+    } else {
+        (index + 1, index)
+    };
+    notes[shorter].0 = NoteLength::from_ticks(notes[shorter].0.ticks() - TICKS_PER_SIXTEENTH);
+    notes[longer].0 = NoteLength::from_ticks(notes[longer].0.ticks().saturating_add(TICKS_PER_SIXTEENTH));
+}
+
+/// Splits a note which leaps a third to the next note, filling the leap with a passing tone.
+#[expect(clippy::arithmetic_side_effects, reason = "Indices and lengths are checked")]
+fn add_passing_tone(notes: &mut Vec<Note>, rng: &mut Rng) {
+    let pitched = pitched_indices(notes);
+    let pitch_of = |note: &Note| match note.1 {
+        NoteKind::Pitched { pitch, .. } => pitch,
+        NoteKind::Rest => NotePitch(0.0),
+    };
+
+    // Leaps between neighbouring notes, where the first note is long enough to split
+    let leaps: Vec<(usize, i16)> = pitched
+        .windows(2)
+        .filter(|pair| pair[1] == pair[0] + 1)
+        .filter_map(|pair| {
+            let (from, to) = (&notes[pair[0]], &notes[pair[1]]);
+            #[expect(clippy::cast_possible_truncation, reason = "Intervals between notes are small")]
+            let semitones = (12.0 * (pitch_of(to).0 / pitch_of(from).0).log2()).round() as i16;
+            let ticks = from.0.ticks();
+            (matches!(semitones.abs(), 3 | 4) && ticks >= 2 * TICKS_PER_SIXTEENTH && ticks % 2 == 0)
+                .then_some((pair[0], semitones.signum() * 2))
+        })
+        .collect();
+
+    let Some(&(index, step)) = rng.choose(&leaps) else {
+        return;
+    };
+    let half = NoteLength::from_ticks(notes[index].0.ticks() / 2);
+    let passing = Note(half, transpose_kind(&notes[index].1, |pitch| pitch.semitone(step)));
+    notes[index].0 = half;
+    notes.insert(index + 1, passing);
+}
@@ -0,0 +1,291 @@
+use std::{f64::consts::PI, ops::Range};
+
+use crate::{
+    play::{mix::Mixer, ticks_to_ms, FileOutputConfig},
+    MusicPlayer, Piece,
+};
+
+/// The levels of some rendered audio, as measured by [`MusicPlayer::analyze_render`].
+///
+/// Silent audio has levels of negative infinity.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Loudness {
+    /// The highest level the waveform reaches, including between samples, in dBTP
+    pub true_peak_db: f32,
+    /// The average power of every channel, in dBFS
+    pub rms_db: f32,
+    /// The integrated loudness, as used by streaming services and broadcasters, in LUFS
+    pub lufs: f32,
+}
+
+/// A report on the levels of a rendered piece, found with [`MusicPlayer::analyze_render`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenderReport {
+    /// The levels of the whole mix, as it would be written to a WAV file
+    pub master: Loudness,
+    /// The levels of each line played on its own, on the same scale as the master
+    pub lines: Vec<Loudness>,
+    /// Spans of time (in milliseconds) where the mix went past full scale before it was normalized.
+    /// Clips less than 50 ms apart are merged into one span.
+    pub clipped_ms: Vec<Range<u64>>,
+}
+
+/// Clips closer together than this (in milliseconds) are reported as one span.
+const CLIP_MERGE_MS: u64 = 50;
+
+impl MusicPlayer<FileOutputConfig> {
+    /// Renders a piece without saving it, and measures the levels of the mix and of each line.
+    ///
+    /// Levels are measured as the piece would be written by [`MusicPlayer::render_to_wav`],
+    /// after it's normalized and the master effects and output gain are applied. Each line is rendered on its own,
+    /// and scaled the same way as the master, so lines can be compared with each other and
+    /// with the master. The report also lists where the mix clipped before it was normalized,
+    /// which is where notes pile up loudly enough to squash the rest of the piece.
+    ///
+    /// # Arguments
+    /// * `piece` - The piece to analyze. Notes and lines are treated as pieces with one line.
+    ///
+    /// # Example
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let piece = piano(quarter(C4) + quarter(E4) + half(G4)) * bass(whole(C4.octave(-1)));
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 8000);
+    /// let report = player.analyze_render(piece);
+    ///
+    /// assert_eq!(report.lines.len(), 2);
+    /// assert!(report.master.lufs < 0.0);
+    /// ```
+    pub fn analyze_render(&self, piece: impl Into<Piece>) -> RenderReport {
+        let piece: Piece = piece.into();
+        let FileOutputConfig {
+            output_gain,
+            sample_rate,
+            ..
+        } = self.output_config;
+
+        // Every render uses the same seed, so each line has the same notes as in the master
+        let seed = self.pass_seed();
+        let player = |mixer: Mixer| MusicPlayer {
+            tempo_bpm: self.tempo_bpm,
+            output_config: self.output_config.clone(),
+            seed: Some(seed),
+            mixer,
+        };
+        let mut master = player(self.mixer.clone()).render_range_mix(piece.clone(), 0..piece.length());
+        let clipped_ms = clipped_spans(&master, sample_rate);
+
+        // Lines are scaled by the same amount as the master, so their levels can be compared
+        let gains = self.output_config.normalization.apply(&mut master, sample_rate);
+        self.apply_master_effects(&mut master);
+        self.apply_output_stage(&mut master);
+
+        // Each line is rendered as part of the piece, with the others silenced, so the mixer treats it the same
+        let lines = (0..piece.0.len())
+            .map(|line| {
+                let mut samples = player(self.mixer.isolate(line)).render_range_mix(piece.clone(), 0..piece.length());
+                // Only the line's own length is measured
+                let length_ms = ticks_to_ms(piece.0[line].length(), self.beat_duration_ms());
+                let length =
+                    usize::try_from(u64::from(sample_rate).saturating_mul(length_ms) / 1000).unwrap_or(usize::MAX);
+                samples.iter_mut().for_each(|channel| channel.truncate(length));
+                for (channel, gain) in samples.iter_mut().zip(&gains) {
+                    for sample in channel {
+                        *sample *= gain * output_gain;
+                    }
+                }
+                measure(&samples, sample_rate)
+            })
+            .collect();
+
+        RenderReport {
+            master: measure(&master, sample_rate),
+            lines,
+            clipped_ms,
+        }
+    }
+}
+
+/// Finds the spans of time (in milliseconds) where any channel is past full scale.
+fn clipped_spans(channels: &[Vec<f32>], sample_rate: u32) -> Vec<Range<u64>> {
+    let length = channels.first().map_or(0, Vec::len);
+    let to_ms = |sample: usize| {
+        u64::try_from(sample)
+            .unwrap_or(u64::MAX)
+            .saturating_mul(1000)
+            .checked_div(u64::from(sample_rate))
+            .unwrap_or_default()
+    };
+
+    let mut spans: Vec<Range<u64>> = vec![];
+    for index in 0..length {
+        if !channels.iter().any(|channel| channel[index].abs() > 1.0) {
+            continue;
+        }
+        let start = to_ms(index);
+        let end = to_ms(index.saturating_add(1)).max(start.saturating_add(1));
+        match spans.last_mut() {
+            Some(span) if start <= span.end.saturating_add(CLIP_MERGE_MS) => span.end = end,
+            _ => spans.push(start..end),
+        }
+    }
+    spans
+}
+
+/// Measures the levels of some audio, with one buffer of samples per channel.
+#[expect(clippy::cast_possible_truncation, reason = "Levels are well within f32's range")]
+#[expect(clippy::cast_precision_loss, reason = "Sample counts are far below f64's precision limit")]
+fn measure(channels: &[Vec<f32>], sample_rate: u32) -> Loudness {
+    let true_peak = channels
+        .iter()
+        .map(|channel| true_peak(channel))
+        .fold(0.0_f32, f32::max);
+
+    let sample_count: usize = channels.iter().map(Vec::len).sum();
+    let sum_of_squares: f64 = channels.iter().flatten().map(|&s| f64::from(s) * f64::from(s)).sum();
+    let mean_square = sum_of_squares / sample_count.max(1) as f64;
+
+    Loudness {
+        true_peak_db: 20.0 * true_peak.log10(),
+        rms_db: (10.0 * mean_square.log10()) as f32,
+        lufs: integrated_loudness(channels, sample_rate) as f32,
+    }
+}
+
+/// Finds the highest level of a waveform, including between its samples, by upsampling it
+/// four times with cubic interpolation.
+#[expect(clippy::arithmetic_side_effects, reason = "Indices are checked")]
+fn true_peak(samples: &[f32]) -> f32 {
+    let at = |index: usize| samples.get(index).copied().unwrap_or_default();
+    let mut peak = samples.iter().fold(0.0_f32, |peak, s| peak.max(s.abs()));
+
+    for index in 0..samples.len() {
+        let [y0, y1, y2, y3] = [at(index.wrapping_sub(1)), at(index), at(index + 1), at(index + 2)];
+        for step in [0.25_f32, 0.5, 0.75] {
+            // Catmull-Rom spline between y1 and y2
+            let value = y1
+                + 0.5
+                    * step
+                    * (y2 - y0 + step * (2.0 * y0 - 5.0 * y1 + 4.0 * y2 - y3 + step * (3.0 * (y1 - y2) + y3 - y0)));
+            peak = peak.max(value.abs());
+        }
+    }
+    peak
+}
+
+/// A second-order IIR filter, as (b0, b1, b2, a1, a2), normalized so a0 is 1.
+type Biquad = [f64; 5];
+
+/// The two filters of ITU-R BS.1770's K-weighting, which model how loud frequencies sound:
+/// a high shelf which boosts the highs, and a high-pass filter which cuts the lowest bass.
+fn k_weighting(sample_rate: u32) -> [Biquad; 2] {
+    let sample_rate = f64::from(sample_rate);
+
+    let (frequency, gain_db, q) = (1_681.974_450_955_533, 3.999_843_853_973_347, 0.707_175_236_955_419_6);
+    let k = (PI * frequency / sample_rate).tan();
+    let high_gain = 10.0_f64.powf(gain_db / 20.0);
+    let band_gain = high_gain.powf(0.499_666_774_154_541_6);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = [
+        (high_gain + band_gain * k / q + k * k) / a0,
+        2.0 * (k * k - high_gain) / a0,
+        (high_gain - band_gain * k / q + k * k) / a0,
+        2.0 * (k * k - 1.0) / a0,
+        (1.0 - k / q + k * k) / a0,
+    ];
+
+    let (frequency, q) = (38.135_470_876_024_44, 0.500_327_037_323_877_3);
+    let k = (PI * frequency / sample_rate).tan();
+    let a0 = 1.0 + k / q + k * k;
+    let high_pass = [1.0, -2.0, 1.0, 2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0];
+
+    [shelf, high_pass]
+}
+
+/// Runs samples through a filter.
+fn filter(samples: &[f64], [b0, b1, b2, a1, a2]: Biquad) -> Vec<f64> {
+    let (mut x1, mut x2, mut y1, mut y2) = (0.0, 0.0, 0.0, 0.0);
+    samples
+        .iter()
+        .map(|&x| {
+            let y = b0 * x + b1 * x1 + b2 * x2 - a1 * y1 - a2 * y2;
+            (x2, x1, y2, y1) = (x1, x, y1, y);
+            y
+        })
+        .collect()
+}
+
+/// Measures integrated loudness (in LUFS) as described by ITU-R BS.1770.
+///
+/// The audio is K-weighted, then split into overlapping 400 ms blocks. Blocks which are nearly
+/// silent, or much quieter than the rest, are ignored, so pauses don't lower the loudness.
+#[expect(clippy::cast_precision_loss, reason = "Sample counts are far below f64's precision limit")]
+#[expect(clippy::arithmetic_side_effects, reason = "Block indices are checked")]
+pub(super) fn integrated_loudness(channels: &[Vec<f32>], sample_rate: u32) -> f64 {
+    let [shelf, high_pass] = k_weighting(sample_rate);
+    let weighted: Vec<Vec<f64>> = channels
+        .iter()
+        .map(|channel| {
+            let samples: Vec<f64> = channel.iter().map(|&s| f64::from(s)).collect();
+            filter(&filter(&samples, shelf), high_pass)
+        })
+        .collect();
+
+    let length = weighted.first().map_or(0, Vec::len);
+    let rate = usize::try_from(sample_rate).unwrap_or(usize::MAX);
+    // Audio shorter than one block is measured as a single block
+    let block = (rate * 4 / 10).min(length).max(1);
+    let step = (block / 4).max(1);
+
+    // The power of each block, summed over the channels
+    let powers: Vec<f64> = (0..=length.saturating_sub(block))
+        .step_by(step)
+        .map(|start| {
+            weighted
+                .iter()
+                .map(|channel| channel[start..start + block].iter().map(|s| s * s).sum::<f64>() / block as f64)
+                .sum()
+        })
+        .collect();
+
+    let loudness = |power: f64| -0.691 + 10.0 * power.log10();
+    let mean_loudness = |powers: &[f64]| loudness(powers.iter().sum::<f64>() / powers.len() as f64);
+
+    let audible: Vec<f64> = powers.into_iter().filter(|&power| loudness(power) > -70.0).collect();
+    if audible.is_empty() {
+        return f64::NEG_INFINITY;
+    }
+    let relative_gate = mean_loudness(&audible) - 10.0;
+    let gated: Vec<f64> = audible
+        .into_iter()
+        .filter(|&power| loudness(power) > relative_gate)
+        .collect();
+    mean_loudness(&gated)
+}
+
+#[test]
+#[expect(clippy::cast_precision_loss, reason = "Test signal")]
+fn test_full_scale_sine_levels() {
+    // A full scale 997 Hz sine wave is defined to be -3.01 LUFS
+    let sine: Vec<f32> = (0..96_000_u32)
+        .map(|index| (2.0 * std::f32::consts::PI * 997.0 * index as f32 / 48_000.0).sin())
+        .collect();
+
+    let levels = measure(&[sine], 48_000);
+    assert!((levels.lufs + 3.01).abs() < 0.05, "{}", levels.lufs);
+    assert!((levels.rms_db + 3.01).abs() < 0.05, "{}", levels.rms_db);
+    assert!(levels.true_peak_db.abs() < 0.05, "{}", levels.true_peak_db);
+}
+
+#[test]
+fn test_clipped_spans_are_merged() {
+    let mut samples = vec![0.5_f32; 1000];
+    samples[100] = 1.5;
+    samples[110] = -1.5;
+    samples[900] = 2.0;
+
+    // At 1000 samples per second, each sample is a millisecond
+    assert_eq!(clipped_spans(&[samples], 1000), vec![100..111, 900..901]);
+}
@@ -0,0 +1,105 @@
+use crate::{
+    play::{FileOutputConfig, Playable},
+    MusicPlayer,
+};
+
+/// Rendered audio held in memory, made by [`MusicPlayer::render_to_buffer`].
+///
+/// Samples are interleaved, as in a WAV file: the first sample of every channel, then the second
+/// sample of every channel, and so on.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AudioBuffer {
+    /// The number of samples per second, per channel
+    pub sample_rate: u32,
+    /// The number of channels
+    pub channels: u16,
+    /// The interleaved samples, from -1.0 to 1.0
+    pub samples: Vec<f32>,
+}
+
+impl AudioBuffer {
+    /// Returns the number of frames, which is the number of samples in each channel.
+    pub fn frames(&self) -> usize {
+        self.samples
+            .len()
+            .checked_div(usize::from(self.channels))
+            .unwrap_or_default()
+    }
+
+    /// Returns how long the audio lasts, in milliseconds.
+    pub fn duration_ms(&self) -> u64 {
+        u64::try_from(self.frames())
+            .unwrap_or(u64::MAX)
+            .saturating_mul(1000)
+            .checked_div(u64::from(self.sample_rate))
+            .unwrap_or_default()
+    }
+
+    /// Returns the samples of one channel.
+    ///
+    /// # Arguments
+    /// * `channel` - The index of the channel. Channels past the last one have no samples.
+    pub fn channel(&self, channel: u16) -> impl Iterator<Item = f32> + '_ {
+        let skip = if channel < self.channels {
+            usize::from(channel)
+        } else {
+            self.samples.len()
+        };
+        self.samples
+            .iter()
+            .copied()
+            .skip(skip)
+            .step_by(usize::from(self.channels.max(1)))
+    }
+}
+
+impl MusicPlayer<FileOutputConfig> {
+    /// Renders a piece into memory, without touching the filesystem.
+    ///
+    /// The samples are the same as [`MusicPlayer::render_to_wav`] would write, before they're
+    /// converted to 16-bit integers, so they can be post-processed, analyzed, or streamed elsewhere.
+    ///
+    /// # Arguments
+    /// * `piece` - Any playable musical content (Note, Chord, Line, Piece, etc.)
+    ///
+    /// # Example
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 8000);
+    /// let buffer = player.render_to_buffer(piano(quarter(C4) + quarter(E4)));
+    ///
+    /// assert_eq!(buffer.sample_rate, 8000);
+    /// assert_eq!(buffer.samples.len(), buffer.frames() * buffer.channels as usize);
+    /// let peak = buffer.samples.iter().fold(0.0_f32, |peak, s| peak.max(s.abs()));
+    /// assert!(peak > 0.5 && peak <= 1.0);
+    /// ```
+    pub fn render_to_buffer<T: Playable>(&self, piece: T) -> AudioBuffer {
+        let channels = self.render_samples(piece);
+        let frames = channels.first().map_or(0, Vec::len);
+        let samples = (0..frames)
+            .flat_map(|frame| channels.iter().map(move |channel| channel[frame]))
+            .collect();
+
+        AudioBuffer {
+            sample_rate: self.output_config.sample_rate,
+            channels: u16::try_from(channels.len()).unwrap_or(u16::MAX),
+            samples,
+        }
+    }
+}
+
+#[test]
+fn test_audio_buffer_channels_are_deinterleaved() {
+    let buffer = AudioBuffer {
+        sample_rate: 4,
+        channels: 2,
+        samples: vec![0.1, -0.1, 0.2, -0.2, 0.3, -0.3, 0.4, -0.4],
+    };
+
+    assert_eq!(buffer.frames(), 4);
+    assert_eq!(buffer.duration_ms(), 1000);
+    assert_eq!(buffer.channel(1).collect::<Vec<_>>(), vec![-0.1, -0.2, -0.3, -0.4]);
+    assert_eq!(buffer.channel(2).count(), 0);
+}
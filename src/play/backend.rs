@@ -0,0 +1,201 @@
+use crate::{
+    play::{mix::Mixer, note_plays, ratchet_hits, ticks_to_ms, MusicOutput, Playable},
+    MusicPlayer, Note, NoteKind, Piece,
+};
+
+/// A note sent to an [`OutputBackend`], after the player has decided how it's played.
+///
+/// The player has already applied the mixer's gain and pan to the note, skipped notes which
+/// don't play because of their probability or a muted line, and split ratchets into their hits,
+/// so a backend only has to make the sound.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutputNote {
+    /// How long the note is held, in milliseconds, not counting the release of its envelope.
+    /// For a ratchet, this is the length of one hit.
+    pub duration_ms: u64,
+    /// The index of the line the note is in, such as to route lines to their own channels
+    pub line_index: usize,
+    /// The note, which is always pitched
+    pub note: Note,
+}
+
+/// An output which a [`MusicPlayer`] sends notes to, such as a JACK client, a network
+/// stream, or a MIDI port.
+///
+/// Implement it to play music through an output the crate doesn't support, then create a player
+/// for it with [`MusicPlayer::new_backend`] and send it pieces with [`MusicPlayer::send`].
+/// The player works out when each note starts, and the backend decides how to play it.
+///
+/// # Example
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::{MusicPlayer, OutputBackend, OutputNote};
+///
+/// /// Prints each note as it's scheduled
+/// #[derive(Clone, Default)]
+/// struct PrintBackend {
+///     lines: Vec<String>,
+/// }
+///
+/// impl OutputBackend for PrintBackend {
+///     fn schedule_note(&mut self, start_ms: u64, note: &OutputNote) {
+///         self.lines.push(format!("{start_ms} ms: {:?} for {} ms", note.note.1, note.duration_ms));
+///     }
+///
+///     fn finish(&mut self) {
+///         self.lines.push("done".to_string());
+///     }
+/// }
+///
+/// let mut player = MusicPlayer::new_backend(300, PrintBackend::default());
+/// player.send(piano(quarter(C4) + quarter(REST) + quarter(E4)));
+///
+/// let lines = &player.backend().lines;
+/// assert_eq!(lines.len(), 3); // The rest isn't sent
+/// assert!(lines[1].starts_with("1600 ms"));
+/// assert_eq!(lines[2], "done");
+/// ```
+pub trait OutputBackend {
+    /// Schedules a note to start the given number of milliseconds after the start of the piece.
+    ///
+    /// Notes are scheduled in the order they start, before any of them need to play, so a
+    /// backend which plays in real time should queue them against its own clock.
+    fn schedule_note(&mut self, start_ms: u64, note: &OutputNote);
+
+    /// Called once every note of a piece has been scheduled.
+    fn finish(&mut self);
+}
+
+impl<B: OutputBackend> MusicOutput for B {}
+
+impl<B: OutputBackend + Clone> MusicPlayer<B> {
+    /// Creates a new music player which sends notes to a custom output backend.
+    ///
+    /// # Arguments
+    /// * `tempo_bpm` - The tempo in beats per minute. The number of sixteenth notes per minute.
+    /// * `backend` - The output notes are sent to
+    ///
+    /// # Example
+    /// See [`OutputBackend`].
+    pub fn new_backend(tempo_bpm: u32, backend: B) -> Self {
+        Self {
+            tempo_bpm,
+            output_config: backend,
+            seed: None,
+            mixer: Mixer::new(),
+        }
+    }
+
+    /// Schedules every note of a piece on the backend, in the order they start, then finishes it.
+    ///
+    /// # Arguments
+    /// * `piece` - Any playable musical content (Note, Chord, Line, Piece, etc.)
+    ///
+    /// # Example
+    /// See [`OutputBackend`].
+    pub fn send<T: Playable>(&mut self, piece: T) {
+        let notes = output_notes(
+            &piece.to_piece(),
+            self.beat_duration_ms(),
+            self.pass_seed(),
+            &self.mixer,
+        );
+        for (start_ms, note) in &notes {
+            self.output_config.schedule_note(*start_ms, note);
+        }
+        self.output_config.finish();
+    }
+
+    /// Returns the backend notes are sent to.
+    pub fn backend(&self) -> &B {
+        &self.output_config
+    }
+
+    /// Returns the backend notes are sent to, to change its settings.
+    pub fn backend_mut(&mut self) -> &mut B {
+        &mut self.output_config
+    }
+}
+
+/// Returns every note of a piece which is played in a pass with the given seed, with when it
+/// starts in milliseconds, sorted by start.
+///
+/// The mixer's settings are applied to each note, and ratchets are split into their hits.
+pub(crate) fn output_notes(piece: &Piece, beat_duration_ms: u64, seed: u64, mixer: &Mixer) -> Vec<(u64, OutputNote)> {
+    let mut notes = vec![];
+    for (instant, events) in piece.compile().instants() {
+        let start_ms = ticks_to_ms(instant, beat_duration_ms);
+        for (index, event) in events.iter().enumerate() {
+            let Some(kind) = mixer.apply(event.line_index, &event.note.1) else {
+                continue;
+            };
+            let NoteKind::Pitched {
+                probability, ratchet, ..
+            } = kind
+            else {
+                continue;
+            };
+            if !note_plays(seed, instant, index, probability) {
+                continue;
+            }
+            let note = Note(event.note.0, kind);
+            let note_duration_ms = ticks_to_ms(note.0.ticks() as usize, beat_duration_ms);
+            for (offset_ms, duration_ms) in ratchet_hits(note_duration_ms, ratchet) {
+                notes.push((
+                    start_ms.saturating_add(offset_ms),
+                    OutputNote {
+                        duration_ms,
+                        line_index: event.line_index,
+                        note: note.clone(),
+                    },
+                ));
+            }
+        }
+    }
+
+    // Ratchets are scheduled after the notes which start while they play
+    notes.sort_by_key(|(start_ms, _)| *start_ms);
+    notes
+}
+
+#[test]
+fn test_backend_receives_mixed_ratcheted_notes_in_order() {
+    use crate::prelude::*;
+
+    #[derive(Clone, Default)]
+    struct Recorder {
+        notes: Vec<(u64, OutputNote)>,
+        finished: bool,
+    }
+
+    impl OutputBackend for Recorder {
+        fn schedule_note(&mut self, start_ms: u64, note: &OutputNote) {
+            assert!(!self.finished);
+            self.notes.push((start_ms, note.clone()));
+        }
+
+        fn finish(&mut self) {
+            self.finished = true;
+        }
+    }
+
+    // At 300 sixteenths a minute, a sixteenth is 200 ms
+    let mut player = MusicPlayer::new_backend(300, Recorder::default()).with_mixer(Mixer::new().gain_db(1, -20.0));
+    player.send(
+        piano(quarter(C4) + half(E4).ratchet(2))
+            * bass(half(REST) + quarter(C4.octave(-1)))
+            * drums(whole(C4).with_probability(0.0))
+            * sine(whole(G4)),
+    );
+
+    let recorder = player.backend();
+    assert!(recorder.finished);
+    let starts: Vec<(u64, usize)> = recorder
+        .notes
+        .iter()
+        .map(|(start, note)| (*start, note.line_index))
+        .collect();
+    assert_eq!(starts, [(0, 0), (0, 3), (800, 0), (1600, 0), (1600, 1)]);
+    assert_eq!(recorder.notes[2].1.duration_ms, 800);
+    assert!(matches!(recorder.notes[4].1.note.1, NoteKind::Pitched { volume, .. } if (volume - 0.1).abs() < 1e-6));
+}
@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+
+use crate::{Line, Mixer, MusicPlayer, Note, NoteKind, Piece, Timbre};
+
+/// How loud each kind of line should be compared to the others, for [`Piece::auto_balance`].
+///
+/// Each timbre has a target level, in decibels. Lines are balanced so their loudness (RMS)
+/// differs by as much as their targets do, so a line with a target of -6.0 ends up 6 dB quieter
+/// than one with a target of 0.0. A line is balanced by the timbre it plays for longest.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::BalanceProfile;
+///
+/// // Every line as loud as the others, except the piano, which leads
+/// let profile = BalanceProfile::even().with_level(Timbre::Piano, 3.0);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct BalanceProfile {
+    /// The target level of timbres which aren't given their own, in decibels
+    pub default_db: f32,
+    levels: HashMap<Timbre, f32>,
+}
+
+impl Default for BalanceProfile {
+    /// A starting point for a typical band: drums and bass at the front, leads just behind them,
+    /// and pads sitting underneath.
+    fn default() -> Self {
+        let levels = [
+            (Timbre::Drums, 0.0),
+            (Timbre::Bass, -2.0),
+            (Timbre::Piano, -3.0),
+            (Timbre::ElectricGuitar, -3.0),
+            (Timbre::SynthLead, -3.0),
+            (Timbre::Flute, -3.0),
+            (Timbre::Brass, -3.0),
+            (Timbre::Organ, -8.0),
+            (Timbre::Strings, -8.0),
+            (Timbre::SynthPad, -8.0),
+        ];
+        BalanceProfile {
+            default_db: -4.0,
+            levels: levels.into_iter().collect(),
+        }
+    }
+}
+
+impl BalanceProfile {
+    /// A profile where every line is as loud as every other.
+    pub fn even() -> Self {
+        BalanceProfile {
+            default_db: 0.0,
+            levels: HashMap::new(),
+        }
+    }
+
+    /// Sets the target level of a timbre.
+    ///
+    /// # Parameters
+    /// - `timbre`: The timbre
+    /// - `level_db`: How loud lines of the timbre should be, in decibels, compared to the other targets
+    pub fn with_level(mut self, timbre: Timbre, level_db: f32) -> Self {
+        self.levels.insert(timbre, level_db);
+        self
+    }
+
+    /// Returns the target level of a timbre, in decibels.
+    ///
+    /// # Parameters
+    /// - `timbre`: The timbre
+    pub fn level(&self, timbre: &Timbre) -> f32 {
+        self.levels.get(timbre).copied().unwrap_or(self.default_db)
+    }
+}
+
+/// The tempo pieces are rendered at to measure their balance. Balance barely depends on tempo.
+const BALANCE_TEMPO_BPM: u32 = 120;
+
+/// The sample rate pieces are rendered at to measure their balance, which is low to keep it quick.
+const BALANCE_SAMPLE_RATE: u32 = 11025;
+
+/// The seed pieces are rendered with to measure their balance, so the suggestion is always the same.
+const BALANCE_SEED: u64 = 0;
+
+impl Piece {
+    /// Suggests a gain for each line, so the lines are balanced the way the profile describes.
+    ///
+    /// The piece is rendered quickly, and the loudness (RMS) of each line is measured over its
+    /// own length. Gains are only ever cuts, so the loudest line compared to its target is left
+    /// at 0 dB, and the mix doesn't get louder. Silent lines are left as they are.
+    ///
+    /// This is meant as a starting point for a mix. Pass the suggestion to
+    /// [`MusicPlayer::with_mixer`], and adjust it by ear from there, or use
+    /// [`Piece::auto_balanced`] to apply it to the notes.
+    ///
+    /// # Parameters
+    /// - `profile`: How loud each kind of line should be compared to the others
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{BalanceProfile, MusicPlayer};
+    ///
+    /// let piece = piano(quarter(C4) + quarter(E4) + half(G4)) * bass(whole(C2)) * drums(quarter(C3) * 4);
+    /// let mixer = piece.auto_balance(&BalanceProfile::default());
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 44100).with_mixer(mixer);
+    /// player.render_to_wav(piece, "balanced.wav");
+    /// ```
+    pub fn auto_balance(&self, profile: &BalanceProfile) -> Mixer {
+        // How much each line would need to be turned up to meet its target
+        let offsets: Vec<Option<f32>> = self
+            .0
+            .iter()
+            .enumerate()
+            .map(|(index, line)| {
+                let timbre = main_timbre(line)?;
+                let rms_db = line_rms_db(self, index);
+                rms_db.is_finite().then(|| profile.level(&timbre) - rms_db)
+            })
+            .collect();
+        let loudest = offsets.iter().flatten().copied().fold(f32::NEG_INFINITY, f32::max);
+
+        offsets
+            .into_iter()
+            .enumerate()
+            .fold(Mixer::new(), |mixer, (line, offset)| match offset {
+                Some(offset) => mixer.gain_db(line, offset - loudest),
+                None => mixer,
+            })
+    }
+
+    /// Returns the piece with each line's notes turned down as suggested by [`Piece::auto_balance`].
+    ///
+    /// Each note's volume is scaled, so the dynamics within a line are kept.
+    ///
+    /// # Parameters
+    /// - `profile`: How loud each kind of line should be compared to the others
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::BalanceProfile;
+    ///
+    /// let loud = piano(quarter(C4) + quarter(E4));
+    /// let quiet = piano(half(G3)).volume(0.1);
+    ///
+    /// // With an even profile, the loud line is turned down to match the quiet one
+    /// let balanced = (loud * quiet).auto_balanced(&BalanceProfile::even());
+    /// assert!(matches!(balanced.0[0].notes[0].1, NoteKind::Pitched { volume, .. } if volume < 0.2));
+    /// assert!(matches!(balanced.0[1].notes[0].1, NoteKind::Pitched { volume, .. } if volume == 0.1));
+    /// ```
+    pub fn auto_balanced(&self, profile: &BalanceProfile) -> Piece {
+        let mixer = self.auto_balance(profile);
+        Piece(
+            self.0
+                .iter()
+                .enumerate()
+                .map(|(index, line)| {
+                    let gain = 10.0_f32.powf(mixer.strip(index).gain_db / 20.0);
+                    let scale = |notes: &[Note]| -> Vec<Note> {
+                        notes
+                            .iter()
+                            .map(|note| match &note.1 {
+                                NoteKind::Pitched { volume, .. } => note.volume(volume * gain),
+                                NoteKind::Rest => note.clone(),
+                            })
+                            .collect()
+                    };
+                    Line {
+                        notes: scale(&line.notes),
+                        pickup: scale(&line.pickup),
+                        ..line.clone()
+                    }
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Renders one line of a piece, with the others silenced, and returns its RMS level in dBFS over its own length.
+fn line_rms_db(piece: &Piece, line: usize) -> f32 {
+    let player = MusicPlayer::new_file(BALANCE_TEMPO_BPM, 1.0, BALANCE_SAMPLE_RATE)
+        .with_seed(BALANCE_SEED)
+        .with_mixer(Mixer::new().solo(line));
+    let samples = player.render_range_mix(piece.clone(), 0..piece.0[line].length());
+
+    let (sum, count) = samples
+        .iter()
+        .flatten()
+        .fold((0.0_f64, 0_u32), |(sum, count), &sample| {
+            (sum + f64::from(sample) * f64::from(sample), count.saturating_add(1))
+        });
+    #[expect(clippy::cast_possible_truncation, reason = "Levels are far inside f32's range")]
+    let rms_db = (10.0 * (sum / f64::from(count.max(1))).log10()) as f32;
+    rms_db
+}
+
+/// Returns the timbre a line plays for longest, or `None` if it's all rests.
+fn main_timbre(line: &Line) -> Option<Timbre> {
+    let mut ticks: Vec<(Timbre, usize)> = vec![];
+    for note in line.pickup.iter().chain(&line.notes) {
+        if let NoteKind::Pitched { timbre, .. } = &note.1 {
+            let length = note.0.ticks() as usize;
+            match ticks.iter_mut().find(|(played, _)| played == timbre) {
+                Some((_, total)) => *total = total.saturating_add(length),
+                None => ticks.push((timbre.clone(), length)),
+            }
+        }
+    }
+    // The first timbre played wins ties
+    ticks
+        .into_iter()
+        .rev()
+        .max_by_key(|(_, total)| *total)
+        .map(|(timbre, _)| timbre)
+}
+
+#[test]
+fn test_auto_balanced_lines_meet_their_targets() {
+    use crate::prelude::*;
+
+    let piece = piano(eighth(C4) + eighth(E4)) * bass(quarter(C2)).volume(0.2) * strings(quarter(E3));
+    let profile = BalanceProfile::even().with_level(Timbre::Strings, -6.0);
+    let balanced = piece.auto_balanced(&profile);
+
+    let levels: Vec<f32> = (0..3).map(|line| line_rms_db(&balanced, line)).collect();
+    assert!((levels[0] - levels[1]).abs() < 0.1);
+    assert!((levels[0] - levels[2] - 6.0).abs() < 0.1);
+
+    // The gains are only cuts
+    let mixer = piece.auto_balance(&profile);
+    let gains: Vec<f32> = (0..3).map(|line| mixer.strip(line).gain_db).collect();
+    assert!(gains.iter().all(|&gain| gain <= 0.0) && gains.contains(&0.0));
+}
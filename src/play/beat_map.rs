@@ -0,0 +1,149 @@
+use std::fmt::Write;
+
+use crate::{
+    play::{ticks_to_ms, MusicOutput, Playable},
+    Meter, MusicPlayer,
+};
+
+/// One beat of a [`BeatMap`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Beat {
+    /// When the beat falls, in milliseconds from the start of the piece
+    pub time_ms: u64,
+    /// The bar the beat is in. If there's an anacrusis, it's bar 0.
+    pub bar: usize,
+    /// Which beat of the bar it is, counting from 1. Beats of an anacrusis are numbered
+    /// as the end of a full bar, so a one-beat pickup in 4/4 is beat 4.
+    pub beat: u16,
+}
+
+impl Beat {
+    /// Returns whether this is the first beat of a bar.
+    pub fn is_downbeat(&self) -> bool {
+        self.beat == 1
+    }
+}
+
+/// The time of every beat in a piece, for lining other things up with the music,
+/// such as cuts in a video or notes in a rhythm game.
+///
+/// Created with [`MusicPlayer::beat_map`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BeatMap(pub Vec<Beat>);
+
+impl BeatMap {
+    /// Writes the beat map as CSV, with a header row of `time_ms,bar,beat`.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[cfg(feature = "wav-output")] {
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// // At 300 BPM, each sixteenth note lasts 200 ms
+    /// let player = MusicPlayer::new_file(300, 1.0, 44100);
+    /// let beats = player.beat_map(&piano(half(C4)), TimeSignature::COMMON_TIME.into());
+    ///
+    /// assert_eq!(beats.to_csv(), "time_ms,bar,beat\n0,1,1\n800,1,2\n");
+    /// # }
+    /// ```
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("time_ms,bar,beat\n");
+        for beat in &self.0 {
+            let _ = writeln!(csv, "{},{},{}", beat.time_ms, beat.bar, beat.beat);
+        }
+        csv
+    }
+
+    /// Writes the beat map as a JSON array of objects, each with a `time_ms`, `bar`, and `beat`.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[cfg(feature = "wav-output")] {
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 44100);
+    /// let beats = player.beat_map(&piano(quarter(C4)), TimeSignature::COMMON_TIME.into());
+    ///
+    /// assert_eq!(beats.to_json(), r#"[{"time_ms":0,"bar":1,"beat":1}]"#);
+    /// # }
+    /// ```
+    pub fn to_json(&self) -> String {
+        let beats: Vec<String> = self
+            .0
+            .iter()
+            .map(|beat| {
+                format!(
+                    r#"{{"time_ms":{},"bar":{},"beat":{}}}"#,
+                    beat.time_ms, beat.bar, beat.beat
+                )
+            })
+            .collect();
+        format!("[{}]", beats.join(","))
+    }
+}
+
+impl<O: MusicOutput + Clone> MusicPlayer<O> {
+    /// Finds when every beat of a piece falls at this player's tempo.
+    ///
+    /// The times match the audio rendered or played by this player exactly, so the beat map
+    /// can be saved alongside a WAV file with [`BeatMap::to_csv`] or [`BeatMap::to_json`].
+    ///
+    /// # Arguments
+    /// * `piece` - Any playable musical content (Note, Chord, Line, Piece, etc.)
+    /// * `meter` - How the piece is divided into bars and beats
+    ///
+    /// # Example
+    /// ```no_run
+    /// # #[cfg(feature = "wav-output")] {
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let quarter_ticks = quarter(C4).0.ticks() as usize;
+    /// let meter = Meter::new(TimeSignature::COMMON_TIME).with_anacrusis(quarter_ticks);
+    /// let song = piano(quarter(G4) + whole(C4) * 4);
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 44100);
+    /// let beats = player.beat_map(&song, meter);
+    /// assert_eq!((beats.0[0].bar, beats.0[0].beat), (0, 4)); // The pickup is the last beat of bar 0
+    ///
+    /// player.render_to_wav(song, "song.wav");
+    /// std::fs::write("song_beats.csv", beats.to_csv()).unwrap();
+    /// # }
+    /// ```
+    pub fn beat_map<T: Playable>(&self, piece: &T, meter: Meter) -> BeatMap {
+        let beat_length = meter.time_signature.beat_length();
+        let beats_per_bar = meter.time_signature.beats;
+        if beat_length == 0 || beats_per_bar == 0 {
+            return BeatMap::default();
+        }
+        let beat_duration_ms = self.beat_duration_ms();
+
+        // Beats line up with the start of bar 1, so the anacrusis holds the end of a bar
+        let first_beat = meter.anacrusis.checked_rem(beat_length).unwrap_or_default();
+        let beats = (first_beat..piece.to_piece().length())
+            .step_by(beat_length)
+            .map(|instant| {
+                let bar = meter.bar_at(instant);
+                let beat = match instant.checked_sub(meter.anacrusis) {
+                    Some(since_bar_one) => since_bar_one
+                        .checked_div(beat_length)
+                        .and_then(|beats| beats.checked_rem(usize::from(beats_per_bar)))
+                        .unwrap_or_default(),
+                    None => {
+                        let beats_before_bar_one = meter.anacrusis.saturating_sub(instant).div_ceil(beat_length);
+                        usize::from(beats_per_bar).saturating_sub(beats_before_bar_one)
+                    }
+                };
+                Beat {
+                    time_ms: ticks_to_ms(instant, beat_duration_ms),
+                    bar,
+                    beat: u16::try_from(beat.saturating_add(1)).unwrap_or(u16::MAX),
+                }
+            })
+            .collect();
+
+        BeatMap(beats)
+    }
+}
@@ -0,0 +1,236 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    play::{
+        mixer::{PlaybackRate, MIXER_SAMPLE_RATE},
+        playback_handle::seek_sink,
+        LiveOutputConfig, Playable, PlaybackHandle,
+    },
+    MusicPlayer,
+};
+
+/// A beat clock kept by something outside the player, such as a DAW, a drum machine, or an
+/// Ableton Link session, which live playback can follow.
+///
+/// The crate implements it for MIDI clock with `MidiClock`, under the `midi-live` feature.
+/// Implement it for other clocks, such as a Link session, to play in time with them using
+/// [`MusicPlayer::play_synced`].
+///
+/// # Example
+/// ```
+/// use std::time::Instant;
+/// use symphoxy::ExternalClock;
+///
+/// /// A clock which started when it was created, at a fixed tempo
+/// struct Metronome {
+///     started: Instant,
+///     tempo_bpm: f64,
+/// }
+///
+/// impl ExternalClock for Metronome {
+///     fn tempo_bpm(&self) -> Option<f64> {
+///         Some(self.tempo_bpm)
+///     }
+///
+///     fn beat_position(&self) -> Option<f64> {
+///         Some(self.started.elapsed().as_secs_f64() * self.tempo_bpm / 60.0)
+///     }
+/// }
+/// ```
+pub trait ExternalClock: Send + Sync {
+    /// Returns the clock's tempo, in quarter notes per minute, or `None` if it isn't known yet.
+    fn tempo_bpm(&self) -> Option<f64>;
+
+    /// Returns how many quarter notes have passed since the clock's transport started, including
+    /// how far through the current one it is, or `None` if the transport is stopped.
+    fn beat_position(&self) -> Option<f64>;
+}
+
+/// How often the clock is checked while waiting for it to start, and while playing along with it.
+const SYNC_INTERVAL: Duration = Duration::from_millis(5);
+
+/// How far playback can be from the clock before it jumps to where the clock is, in milliseconds,
+/// such as when the clock's transport is moved. Smaller differences are caught up with smoothly.
+const MAX_DRIFT_MS: f64 = 250.0;
+
+/// How much of the difference between playback and the clock is caught up with each second.
+const CATCH_UP_PER_SECOND: f64 = 0.5;
+
+/// The most playback speeds up or slows down from the clock's tempo to catch up with it, as a
+/// fraction of the tempo. This is small enough that the rhythm doesn't sound like it's wavering.
+const MAX_CATCH_UP: f64 = 0.02;
+
+impl MusicPlayer<LiveOutputConfig> {
+    /// Plays a musical piece in time with an external clock, such as a band's DAW session.
+    ///
+    /// Waits for the clock to be running and to know its tempo, then plays the piece at the
+    /// clock's tempo, from the point the clock has reached, so the piece's first beat lines up
+    /// with the clock's first beat. The player's own tempo isn't used. While the piece plays,
+    /// its notes follow the clock's position and tempo, speeding up or slowing down slightly to
+    /// stay in time, without changing their pitch. If the clock's transport is moved, playback
+    /// jumps to the new position. It stops when the clock's transport stops, or if it can't jump.
+    ///
+    /// # Arguments
+    /// * `piece` - Any playable musical content (Note, Chord, Line, Piece, etc.)
+    /// * `clock` - The clock to follow
+    ///
+    /// # Returns
+    /// A [`PlaybackHandle`] for the playback.
+    ///
+    /// # Panics
+    /// Panics if an audio sink can't be created on the output stream.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::{sync::Arc, time::Instant};
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{ExternalClock, MusicPlayer};
+    ///
+    /// struct Metronome(Instant);
+    ///
+    /// impl ExternalClock for Metronome {
+    ///     fn tempo_bpm(&self) -> Option<f64> {
+    ///         Some(120.0)
+    ///     }
+    ///
+    ///     fn beat_position(&self) -> Option<f64> {
+    ///         Some(self.0.elapsed().as_secs_f64() * 2.0)
+    ///     }
+    /// }
+    ///
+    /// let (_stream, handle) = rodio::OutputStream::try_default().unwrap();
+    /// let player = MusicPlayer::new_live(300, Arc::new(handle));
+    ///
+    /// let clock = Arc::new(Metronome(Instant::now()));
+    /// player.play_synced(piano(quarter(C4) + quarter(E4) + half(G4)), clock).join().unwrap();
+    /// ```
+    pub fn play_synced<T: Playable>(&self, piece: T, clock: Arc<dyn ExternalClock>) -> PlaybackHandle {
+        let tempo_bpm = loop {
+            if let (Some(tempo_bpm), Some(_)) = (clock.tempo_bpm(), clock.beat_position()) {
+                if tempo_bpm > 0.0 {
+                    break tempo_bpm;
+                }
+            }
+            thread::sleep(SYNC_INTERVAL);
+        };
+
+        // The player counts sixteenth notes, and the clock counts quarter notes. The piece is
+        // scheduled at about the clock's tempo, then the rate makes up the difference.
+        #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss, reason = "Tempos are small and positive")]
+        let player = MusicPlayer {
+            tempo_bpm: (tempo_bpm * 4.0).round().max(1.0) as u32,
+            output_config: self.output_config.clone(),
+            seed: self.seed,
+            mixer: self.mixer.clone(),
+        };
+        let beat_duration_ms = player.beat_duration_ms();
+        let rate: PlaybackRate = Arc::new(AtomicU64::new(tempo_rate(tempo_bpm, beat_duration_ms).to_bits()));
+        let handle = player.start_playback(piece, 0, None, Some(rate.clone()));
+        handle.pause();
+
+        let sink = handle.sink.clone();
+        let position = handle.position.clone();
+        let seek_to = move |ms: u64| {
+            // If playback can't be moved to where the clock is, it can't follow the clock, so it stops
+            if seek_sink(&sink, ms).is_err() {
+                sink.stop();
+            }
+        };
+        if let Some(beats) = clock.beat_position() {
+            #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss, reason = "Clamped to be positive")]
+            seek_to(clock_ms(beats, beat_duration_ms).round() as u64);
+        }
+        handle.resume();
+
+        let sink = handle.sink.clone();
+        thread::spawn(move || {
+            let mut tempo_bpm = tempo_bpm;
+            while !sink.empty() {
+                let Some(beats) = clock.beat_position() else {
+                    sink.stop();
+                    return;
+                };
+                tempo_bpm = clock.tempo_bpm().filter(|tempo| *tempo > 0.0).unwrap_or(tempo_bpm);
+                #[expect(clippy::cast_precision_loss, reason = "Only loses precision after centuries of playback")]
+                let playing_ms = position.load(Ordering::Relaxed) as f64 * 1000.0 / f64::from(MIXER_SAMPLE_RATE);
+                match follow_clock(
+                    clock_ms(beats, beat_duration_ms),
+                    playing_ms,
+                    tempo_rate(tempo_bpm, beat_duration_ms),
+                ) {
+                    Following::Rate(new_rate) => rate.store(new_rate.to_bits(), Ordering::Relaxed),
+                    Following::Jump(ms) => seek_to(ms),
+                }
+                thread::sleep(SYNC_INTERVAL);
+            }
+        });
+
+        handle
+    }
+}
+
+/// Returns how far into the piece the clock is, in milliseconds, given how many quarter notes
+/// have passed and the length of a sixteenth note the piece is scheduled with.
+#[expect(clippy::cast_precision_loss, reason = "Beat lengths are small")]
+fn clock_ms(beats: f64, beat_duration_ms: u64) -> f64 {
+    beats.max(0.0) * 4.0 * beat_duration_ms as f64
+}
+
+/// Returns how fast a piece scheduled with sixteenth notes of `beat_duration_ms` has to play to
+/// keep up with a clock at `tempo_bpm` quarter notes a minute.
+#[expect(clippy::cast_precision_loss, reason = "Beat lengths are small")]
+fn tempo_rate(tempo_bpm: f64, beat_duration_ms: u64) -> f64 {
+    tempo_bpm * 4.0 * beat_duration_ms as f64 / 60_000.0
+}
+
+/// How playback follows the clock.
+#[derive(Debug, PartialEq)]
+enum Following {
+    /// Play at this rate, which is the clock's tempo, sped up or slowed down to catch up with it
+    Rate(f64),
+    /// Jump to this many milliseconds into the piece, as the clock is too far away to catch up with
+    Jump(u64),
+}
+
+/// Decides how playback follows the clock, given where each of them are in the piece.
+/// `tempo_rate` is the rate which keeps up with the clock's tempo.
+#[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss, reason = "Clock positions are positive")]
+fn follow_clock(clock_ms: f64, playing_ms: f64, tempo_rate: f64) -> Following {
+    let behind_ms = clock_ms - playing_ms;
+    if behind_ms.abs() > MAX_DRIFT_MS {
+        return Following::Jump(clock_ms.round() as u64);
+    }
+    let catch_up = (behind_ms / 1000.0 * CATCH_UP_PER_SECOND).clamp(-MAX_CATCH_UP, MAX_CATCH_UP);
+    Following::Rate(tempo_rate * (1.0 + catch_up))
+}
+
+#[test]
+fn test_playback_follows_the_clock() {
+    // Two quarter notes in, with a sixteenth of 125 ms (120 quarter notes a minute)
+    assert_eq!(clock_ms(2.0, 125), 1000.0);
+    assert_eq!(clock_ms(-0.5, 125), 0.0);
+
+    // 123.4 quarter notes a minute is scheduled with sixteenths of 121 ms, which are a little short,
+    // so the piece plays a little slower to keep up with the clock without drifting
+    assert!((tempo_rate(123.4, 121) - 0.995_43).abs() < 1e-5);
+    assert_eq!(follow_clock(1000.0, 1000.0, 0.99), Following::Rate(0.99));
+
+    // Playback which is behind or ahead catches up smoothly, but never by much
+    let rate = |playing_ms| match follow_clock(1000.0, playing_ms, 1.0) {
+        Following::Rate(rate) => rate,
+        Following::Jump(_) => panic!("Playback jumped"),
+    };
+    assert!(rate(990.0) > 1.0 && rate(1010.0) < 1.0);
+    assert!(rate(800.0) <= 1.0 + MAX_CATCH_UP);
+
+    // Playback jumps when the clock is moved
+    assert_eq!(follow_clock(5000.0, 1000.0, 1.0), Following::Jump(5000));
+    assert_eq!(follow_clock(1000.0, 5000.0, 1.0), Following::Jump(1000));
+}
@@ -0,0 +1,221 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::SystemTime,
+};
+
+use lazy_static::lazy_static;
+use rodio::{Decoder, Source};
+
+/// An audio file, decoded once and shared by every note which plays it.
+#[derive(Debug)]
+pub(super) struct DecodedAudio {
+    pub channels: u16,
+    pub sample_rate: u32,
+    /// The samples, with the channels interleaved
+    pub samples: Arc<[f32]>,
+}
+
+/// Why an audio file couldn't be decoded.
+pub(super) enum DecodeError {
+    /// The file couldn't be opened
+    Open,
+    /// The file couldn't be decoded as audio
+    Decode,
+}
+
+/// The most samples kept in the cache, about 512 MB. Once it's full, the files used longest ago
+/// are dropped to make room.
+const MAX_CACHED_SAMPLES: usize = 1 << 27;
+
+/// A decoded file, with when it was last modified when it was decoded.
+struct Entry {
+    modified: Option<SystemTime>,
+    audio: Arc<DecodedAudio>,
+    /// When the file was last used, counted in uses of the cache
+    last_used: u64,
+}
+
+/// Every file decoded so far, so files are only decoded once however many notes play them.
+#[derive(Default)]
+struct Cache {
+    entries: HashMap<PathBuf, Entry>,
+    /// How many samples the entries hold together
+    samples: usize,
+    /// How many times the cache has been used, for finding the files used longest ago
+    uses: u64,
+}
+
+impl Cache {
+    /// Returns a cached file, marking it as just used.
+    fn get(&mut self, path: &Path) -> Option<Arc<DecodedAudio>> {
+        self.uses = self.uses.saturating_add(1);
+        let entry = self.entries.get_mut(path)?;
+        entry.last_used = self.uses;
+        Some(Arc::clone(&entry.audio))
+    }
+
+    /// Adds a decoded file, dropping the files used longest ago if the cache is full.
+    fn insert(&mut self, path: &Path, modified: Option<SystemTime>, audio: Arc<DecodedAudio>) {
+        self.remove(path);
+        self.uses = self.uses.saturating_add(1);
+        self.samples = self.samples.saturating_add(audio.samples.len());
+        while self.samples > MAX_CACHED_SAMPLES {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(path, _)| path.clone())
+            else {
+                break;
+            };
+            self.remove(&oldest);
+        }
+        self.entries.insert(
+            path.to_path_buf(),
+            Entry {
+                modified,
+                audio,
+                last_used: self.uses,
+            },
+        );
+    }
+
+    fn remove(&mut self, path: &Path) {
+        if let Some(entry) = self.entries.remove(path) {
+            self.samples = self.samples.saturating_sub(entry.audio.samples.len());
+        }
+    }
+}
+
+lazy_static! {
+    static ref CACHE: Mutex<Cache> = Mutex::new(Cache::default());
+}
+
+/// Gets every file a session will play ready, before it starts.
+///
+/// This is the only time files are checked for changes, so playing a note never touches the
+/// filesystem. Files which are new, or have been modified since they were cached, are decoded.
+pub(super) fn prepare<'a>(paths: impl IntoIterator<Item = &'a Path>) {
+    for path in paths {
+        let modified = modified(path);
+        let fresh = lock().entries.get(path).is_some_and(|entry| entry.modified == modified);
+        if !fresh {
+            // Files which can't be decoded are reported when their notes are played
+            let _ = decode_into_cache(path, modified);
+        }
+    }
+}
+
+/// Returns the decoded samples of an audio file, decoding it only the first time it's played.
+///
+/// Cached files aren't checked for changes here, as that's done once per session by [`prepare`].
+/// Files which can't be decoded aren't cached, so they're tried again the next time they're played.
+pub(super) fn decode(path: &Path) -> Result<Arc<DecodedAudio>, DecodeError> {
+    if let Some(audio) = lock().get(path) {
+        return Ok(audio);
+    }
+    decode_into_cache(path, modified(path))
+}
+
+/// Empties the cache of decoded audio files, freeing their memory.
+///
+/// Files played by drums, custom sources, and samples are decoded once and kept in memory, so
+/// later notes and sessions don't decode them again. Old files are dropped once the cache holds
+/// about 512 MB, but a long-running program which has finished with its files can free them sooner.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let piece = Piece::from(quarter(C4).with_timbre(Timbre::custom_pitched("samples/violin_c4.wav")));
+/// // ... play or render the piece ...
+///
+/// // The next piece doesn't use the violin, so its samples can go
+/// symphoxy::clear_decode_cache();
+/// ```
+pub fn clear_decode_cache() {
+    *lock() = Cache::default();
+}
+
+/// When a file was last modified, if the filesystem knows.
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+fn decode_into_cache(path: &Path, modified: Option<SystemTime>) -> Result<Arc<DecodedAudio>, DecodeError> {
+    // Files are decoded without holding the lock, so other threads can use the cache meanwhile
+    let file = File::open(path).map_err(|_| DecodeError::Open)?;
+    let decoder = Decoder::new(BufReader::new(file)).map_err(|_| DecodeError::Decode)?;
+    let audio = Arc::new(DecodedAudio {
+        channels: decoder.channels().max(1),
+        sample_rate: decoder.sample_rate(),
+        samples: decoder.convert_samples().collect(),
+    });
+    lock().insert(path, modified, Arc::clone(&audio));
+    Ok(audio)
+}
+
+/// Locks the cache. The cache is always left in a valid state, so a thread which panicked while
+/// holding the lock doesn't stop others from using it.
+fn lock() -> MutexGuard<'static, Cache> {
+    CACHE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(feature = "wav-output")]
+#[test]
+fn test_decoded_files_are_cached_until_modified_before_a_session() {
+    let path = std::env::temp_dir().join(format!("symphoxy_decode_cache_{}.wav", std::process::id()));
+    let write = |value: i16| {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 1000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        writer.write_sample(value).unwrap();
+        writer.finalize().unwrap();
+    };
+
+    write(i16::MAX / 2);
+    let first = decode(&path).ok().unwrap();
+    assert!(Arc::ptr_eq(&first, &decode(&path).ok().unwrap()));
+
+    // A file modified since it was cached is only decoded again once the next session starts
+    write(i16::MAX / 4);
+    lock().insert(&path, Some(SystemTime::UNIX_EPOCH), Arc::clone(&first));
+    assert!(Arc::ptr_eq(&first, &decode(&path).ok().unwrap()));
+    prepare([path.as_path()]);
+    let second = decode(&path).ok().unwrap();
+    assert!(!Arc::ptr_eq(&first, &second));
+    assert!((second.samples[0] - 0.25).abs() < 0.01);
+
+    std::fs::remove_file(&path).unwrap();
+    lock().remove(&path);
+    assert!(matches!(decode(&path), Err(DecodeError::Open)));
+}
+
+#[test]
+fn test_cache_drops_the_files_used_longest_ago_when_full() {
+    let audio = |samples: usize| {
+        Arc::new(DecodedAudio {
+            channels: 1,
+            sample_rate: 1000,
+            samples: vec![0.0; samples].into(),
+        })
+    };
+    let mut cache = Cache::default();
+    cache.insert(Path::new("a"), None, audio(MAX_CACHED_SAMPLES / 2));
+    cache.insert(Path::new("b"), None, audio(MAX_CACHED_SAMPLES / 2));
+    assert!(cache.get(Path::new("a")).is_some());
+
+    // "b" was used longest ago, so it makes room for "c"
+    cache.insert(Path::new("c"), None, audio(1));
+    assert!(cache.get(Path::new("b")).is_none());
+    assert!(cache.get(Path::new("a")).is_some() && cache.get(Path::new("c")).is_some());
+    assert_eq!(cache.samples, MAX_CACHED_SAMPLES / 2 + 1);
+}
@@ -0,0 +1,55 @@
+use std::f32::consts::PI;
+
+/// An in-place radix-2 fast Fourier transform. The length must be a power of two.
+#[expect(clippy::cast_precision_loss, reason = "Transform sizes are small")]
+#[expect(clippy::arithmetic_side_effects, reason = "Signal processing, with checked bounds")]
+pub(crate) fn fft(real: &mut [f32], imaginary: &mut [f32]) {
+    let size = real.len();
+
+    // Put the samples in bit-reversed order
+    let mut reversed = 0;
+    for index in 1..size {
+        let mut bit = size >> 1;
+        while reversed & bit != 0 {
+            reversed ^= bit;
+            bit >>= 1;
+        }
+        reversed |= bit;
+        if index < reversed {
+            real.swap(index, reversed);
+            imaginary.swap(index, reversed);
+        }
+    }
+
+    let mut span = 2;
+    while span <= size {
+        let angle = -2.0 * PI / span as f32;
+        for start in (0..size).step_by(span) {
+            for offset in 0..span / 2 {
+                let (sin, cos) = (angle * offset as f32).sin_cos();
+                let even = start + offset;
+                let odd = even + span / 2;
+
+                let odd_real = real[odd] * cos - imaginary[odd] * sin;
+                let odd_imaginary = real[odd] * sin + imaginary[odd] * cos;
+
+                real[odd] = real[even] - odd_real;
+                imaginary[odd] = imaginary[even] - odd_imaginary;
+                real[even] += odd_real;
+                imaginary[even] += odd_imaginary;
+            }
+        }
+        span <<= 1;
+    }
+}
+
+/// An in-place inverse of [`fft`], scaled so transforming and inverting gives back the input.
+#[expect(clippy::cast_precision_loss, reason = "Transform sizes are small")]
+pub(crate) fn inverse_fft(real: &mut [f32], imaginary: &mut [f32]) {
+    // Conjugating before and after a forward transform inverts it
+    imaginary.iter_mut().for_each(|value| *value = -*value);
+    fft(real, imaginary);
+    let scale = (real.len() as f32).recip();
+    real.iter_mut().for_each(|value| *value *= scale);
+    imaginary.iter_mut().for_each(|value| *value *= -scale);
+}
@@ -0,0 +1,99 @@
+use std::{f32::consts::TAU, time::Duration};
+
+use rodio::Source;
+
+use crate::{FmModulator, FmPatch};
+
+/// One note of an [`FmPatch`], as a mono source.
+///
+/// This uses phase modulation, as most FM synthesizers do: each modulator is added to the
+/// carrier's phase, rather than its frequency, so the pitch doesn't drift.
+pub struct FmVoice {
+    /// The modulators, and how far through its cycle each one is, from 0 to 1
+    modulators: Vec<(FmModulator, f32, f32)>,
+    /// How far through its cycle the carrier is, from 0 to 1
+    carrier_phase: f32,
+    /// How far the carrier's phase moves each sample
+    carrier_increment: f32,
+    samples_elapsed: u32,
+}
+
+impl FmVoice {
+    /// The sample rate of every FM voice, which matches `SineWave`.
+    pub const SAMPLE_RATE: u32 = 48000;
+
+    #[expect(clippy::cast_precision_loss, reason = "The sample rate is exactly representable")]
+    pub fn new(patch: &FmPatch, frequency: f32) -> Self {
+        let sample_rate = Self::SAMPLE_RATE as f32;
+        // Modulators above the Nyquist frequency would only add aliasing
+        let modulators = patch
+            .modulators
+            .iter()
+            .filter(|modulator| frequency * modulator.ratio < sample_rate / 2.0)
+            .map(|&modulator| (modulator, 0.0, frequency * modulator.ratio / sample_rate))
+            .collect();
+
+        FmVoice {
+            modulators,
+            carrier_phase: 0.0,
+            carrier_increment: (frequency * patch.carrier_ratio / sample_rate).clamp(0.0, 0.5),
+            samples_elapsed: 0,
+        }
+    }
+}
+
+impl Iterator for FmVoice {
+    type Item = f32;
+
+    #[expect(clippy::cast_precision_loss, reason = "Only loses precision for notes lasting hours")]
+    fn next(&mut self) -> Option<f32> {
+        let secs = self.samples_elapsed as f32 / Self::SAMPLE_RATE as f32;
+        self.samples_elapsed = self.samples_elapsed.saturating_add(1);
+
+        let mut modulation = 0.0;
+        for (modulator, phase, increment) in &mut self.modulators {
+            modulation += modulator.index_at_secs(secs) * (TAU * *phase).sin();
+            *phase = (*phase + *increment).fract();
+        }
+
+        let sample = (TAU * self.carrier_phase + modulation).sin();
+        self.carrier_phase = (self.carrier_phase + self.carrier_increment).fract();
+        Some(sample)
+    }
+}
+
+impl Source for FmVoice {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        Self::SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+#[test]
+fn test_fm_brightness_decays() {
+    // Counts how often the wave crosses zero, which rises with its brightness
+    let crossings = |samples: &[f32]| {
+        samples
+            .windows(2)
+            .filter(|pair| pair[0].signum() != pair[1].signum())
+            .count()
+    };
+
+    let samples: Vec<f32> = FmVoice::new(&FmPatch::electric_piano(), 220.0).take(48000).collect();
+    let start = crossings(&samples[..4800]);
+    let end = crossings(&samples[43200..]);
+
+    assert!(samples.iter().all(|s| s.abs() <= 1.0));
+    assert!(start > end, "{start} crossings at the start, {end} at the end");
+}
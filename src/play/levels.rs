@@ -0,0 +1,142 @@
+use std::sync::{
+    mpsc::{sync_channel, Receiver, SyncSender, TrySendError},
+    Arc, Mutex,
+};
+
+use crate::play::mixer::{frames_to_ms, ms_to_frames, MIXER_CHANNELS, MIXER_SAMPLE_RATE};
+
+/// How loud each line of a piece is at a moment of playback.
+///
+/// Received from [`PlaybackHandle::subscribe_levels`](crate::PlaybackHandle::subscribe_levels).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Levels {
+    /// How far through the piece the levels were measured, in milliseconds
+    pub time_ms: u64,
+    /// The level of each line, in the order the lines are in the piece. Levels are linear
+    /// amplitudes, where 1.0 is full scale, and aren't affected by the live output's gain.
+    pub lines: Vec<f32>,
+}
+
+/// How often levels are sent to subscribers, in milliseconds.
+const LEVELS_INTERVAL_MS: u64 = 10;
+
+/// How long a line's level takes to fall to about a third once it goes quiet, in seconds.
+const RELEASE_SECS: f32 = 0.15;
+
+/// How many levels a subscriber can fall behind by before new levels are dropped, about 160 milliseconds' worth.
+const SUBSCRIBER_CAPACITY: usize = 16;
+
+/// Shared list of the senders of everyone subscribed to a mixer's levels.
+pub(super) type LevelSubscribers = Arc<Mutex<Vec<SyncSender<Levels>>>>;
+
+/// Creates the channel a subscriber receives levels on. It holds a few levels at most, so a
+/// subscriber which doesn't keep up misses levels rather than piling them up in memory.
+pub(super) fn level_channel() -> (SyncSender<Levels>, Receiver<Levels>) {
+    sync_channel(SUBSCRIBER_CAPACITY)
+}
+
+/// Follows the amplitude envelope of each line mixed by a `MixerSource`.
+///
+/// Each line's level jumps up to its peaks immediately, then falls away smoothly,
+/// which suits driving visuals such as meters or pulsing shapes.
+pub(super) struct LevelMeter {
+    levels: Vec<f32>,
+    release_coefficient: f32,
+    interval_frames: u64,
+    /// The frame at or after which levels are next sent. The mixer can skip or repeat frames when
+    /// it follows a rate, so this is kept rather than sending on every multiple of the interval.
+    next_send_frame: u64,
+    subscribers: LevelSubscribers,
+    /// Levels which no subscriber had room for, kept to send next time rather than allocated again
+    spare: Option<Levels>,
+}
+
+impl LevelMeter {
+    #[expect(clippy::cast_precision_loss, reason = "The sample rate is exactly representable")]
+    pub(super) fn new(line_count: usize) -> Self {
+        LevelMeter {
+            levels: vec![0.0; line_count],
+            release_coefficient: (-1.0 / (RELEASE_SECS * MIXER_SAMPLE_RATE as f32)).exp(),
+            interval_frames: ms_to_frames(LEVELS_INTERVAL_MS).max(1),
+            next_send_frame: 0,
+            subscribers: Arc::default(),
+            spare: None,
+        }
+    }
+
+    /// Returns the shared list of subscribers, which new subscribers can be added to.
+    pub(super) fn subscribers(&self) -> LevelSubscribers {
+        self.subscribers.clone()
+    }
+
+    /// Updates each line's level with the samples of each line in the frame just mixed, and sends
+    /// the levels to subscribers every few milliseconds. The mixer's `gain` is divided out of the levels.
+    pub(super) fn finish_frame(&mut self, frame: u64, gain: f32, line_frames: &[[f32; MIXER_CHANNELS as usize]]) {
+        let gain = if gain > 0.0 { gain } else { 1.0 };
+        for (level, line_frame) in self.levels.iter_mut().zip(line_frames) {
+            let peak = line_frame.iter().fold(0.0_f32, |peak, sample| peak.max(sample.abs())) / gain;
+            *level = peak.max(*level * self.release_coefficient);
+        }
+
+        if frame < self.next_send_frame {
+            return;
+        }
+        let interval_start = frame.saturating_sub(frame.checked_rem(self.interval_frames).unwrap_or_default());
+        self.next_send_frame = interval_start.saturating_add(self.interval_frames);
+        // Never wait for the lock or for subscribers, so subscribing can't stall the audio
+        let Ok(mut subscribers) = self.subscribers.try_lock() else {
+            return;
+        };
+        let time_ms = frames_to_ms(frame);
+        subscribers.retain(|subscriber| {
+            let mut levels = self.spare.take().unwrap_or_else(|| Levels {
+                time_ms,
+                lines: Vec::with_capacity(self.levels.len()),
+            });
+            levels.time_ms = time_ms;
+            levels.lines.clone_from(&self.levels);
+            match subscriber.try_send(levels) {
+                Ok(()) => true,
+                // A subscriber which is behind misses these levels
+                Err(TrySendError::Full(levels)) => {
+                    self.spare = Some(levels);
+                    true
+                }
+                Err(TrySendError::Disconnected(levels)) => {
+                    self.spare = Some(levels);
+                    false
+                }
+            }
+        });
+    }
+
+    /// Sets every level back to silence, such as after seeking.
+    pub(super) fn reset(&mut self) {
+        self.levels.fill(0.0);
+        self.next_send_frame = 0;
+    }
+
+    /// Disconnects every subscriber, so their streams end.
+    pub(super) fn finish(&mut self) {
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.clear();
+        }
+    }
+}
+
+#[test]
+fn test_slow_subscribers_miss_levels_instead_of_queueing_them() {
+    let mut meter = LevelMeter::new(1);
+    let (sender, receiver) = level_channel();
+    meter.subscribers().lock().unwrap().push(sender);
+
+    // A second's worth of levels, which the subscriber doesn't read until the end
+    for frame in 0..u64::from(MIXER_SAMPLE_RATE) {
+        meter.finish_frame(frame, 1.0, &[[0.5; MIXER_CHANNELS as usize]]);
+    }
+    meter.finish();
+
+    let times: Vec<u64> = receiver.iter().map(|levels| levels.time_ms).collect();
+    assert_eq!(times.len(), SUBSCRIBER_CAPACITY);
+    assert_eq!(times[..3], [0, LEVELS_INTERVAL_MS, 2 * LEVELS_INTERVAL_MS]);
+}
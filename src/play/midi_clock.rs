@@ -0,0 +1,184 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use midir::{ConnectError, MidiInput, MidiInputConnection, MidiInputPort};
+
+use crate::play::ExternalClock;
+
+/// How many clock pulses MIDI clock sends per quarter note.
+const PULSES_PER_QUARTER: u32 = 24;
+
+/// The status bytes of the MIDI messages a clock follows.
+const CLOCK: u8 = 0xF8;
+const START: u8 = 0xFA;
+const CONTINUE: u8 = 0xFB;
+const STOP: u8 = 0xFC;
+const SONG_POSITION: u8 = 0xF2;
+
+/// The tempo of a MIDI clock is averaged over this many pulses, a quarter note's worth,
+/// so jitter in when pulses arrive doesn't make it wobble.
+const TEMPO_PULSES: usize = PULSES_PER_QUARTER as usize;
+
+/// A clock which follows the MIDI clock sent by a DAW, drum machine, or other MIDI device.
+///
+/// It listens on a MIDI input port for clock pulses, which set its tempo, and for start,
+/// stop, continue, and song position messages, which set where its transport is. Pass it to
+/// [`MusicPlayer::play_synced`](crate::MusicPlayer::play_synced) to play along with the device.
+///
+/// # Example
+/// ```no_run
+/// use std::sync::Arc;
+/// use symphoxy::prelude::*;
+/// use symphoxy::{MidiClock, MusicPlayer};
+///
+/// let input = midir::MidiInput::new("symphoxy").unwrap();
+/// let port = input.ports().into_iter().next().expect("No MIDI input ports");
+/// let clock = MidiClock::connect(input, &port).unwrap();
+///
+/// let (_stream, handle) = rodio::OutputStream::try_default().unwrap();
+/// let player = MusicPlayer::new_live(300, Arc::new(handle));
+///
+/// // Press play on the DAW, and the piece comes in on its first beat
+/// player.play_synced(drums(quarter(C4.octave(1)) * 16), Arc::new(clock)).join().unwrap();
+/// ```
+pub struct MidiClock {
+    state: Arc<Mutex<ClockState>>,
+    /// Kept so the clock keeps listening. It's only locked to share the clock between threads.
+    _connection: Mutex<MidiInputConnection<()>>,
+}
+
+impl MidiClock {
+    /// Starts listening for MIDI clock on the given input port.
+    ///
+    /// # Arguments
+    /// * `input` - The MIDI input the port belongs to, made with `midir`
+    /// * `port` - The port the clock is sent to
+    ///
+    /// # Errors
+    /// Returns an error, with the input back, if the port can't be connected to.
+    pub fn connect(input: MidiInput, port: &MidiInputPort) -> Result<Self, ConnectError<MidiInput>> {
+        let state = Arc::new(Mutex::new(ClockState::default()));
+        let callback_state = state.clone();
+        let connection = input.connect(
+            port,
+            "symphoxy clock",
+            move |timestamp_us, message, ()| {
+                if let Ok(mut state) = callback_state.lock() {
+                    state.receive(timestamp_us, message);
+                }
+            },
+            (),
+        )?;
+        Ok(MidiClock {
+            state,
+            _connection: Mutex::new(connection),
+        })
+    }
+}
+
+impl ExternalClock for MidiClock {
+    fn tempo_bpm(&self) -> Option<f64> {
+        self.state.lock().ok()?.tempo_bpm()
+    }
+
+    fn beat_position(&self) -> Option<f64> {
+        self.state.lock().ok()?.beat_position()
+    }
+}
+
+/// What a MIDI clock has heard so far.
+#[derive(Debug, Default)]
+struct ClockState {
+    running: bool,
+    /// Pulses since the start of the song, including any song position
+    pulses: u32,
+    /// When the most recent pulses arrived, in microseconds, oldest first
+    pulse_times_us: VecDeque<u64>,
+}
+
+impl ClockState {
+    /// Updates the clock with a message received at the given time, in microseconds.
+    fn receive(&mut self, timestamp_us: u64, message: &[u8]) {
+        match message {
+            [CLOCK, ..] => {
+                if self.pulse_times_us.len() > TEMPO_PULSES {
+                    self.pulse_times_us.pop_front();
+                }
+                self.pulse_times_us.push_back(timestamp_us);
+                if self.running {
+                    self.pulses = self.pulses.saturating_add(1);
+                }
+            }
+            [START, ..] => {
+                self.running = true;
+                self.pulses = 0;
+            }
+            [CONTINUE, ..] => self.running = true,
+            [STOP, ..] => self.running = false,
+            // The position is in sixteenth notes, split into two seven-bit bytes
+            [SONG_POSITION, low, high, ..] => {
+                let sixteenths = u32::from(*low & 0x7F) | (u32::from(*high & 0x7F) << 7);
+                self.pulses = sixteenths.saturating_mul(PULSES_PER_QUARTER / 4);
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the tempo, in quarter notes per minute, from how far apart the recent pulses are.
+    fn tempo_bpm(&self) -> Option<f64> {
+        let pulse_us = self.pulse_us()?;
+        Some(60_000_000.0 / (pulse_us * f64::from(PULSES_PER_QUARTER)))
+    }
+
+    /// Returns the average time between the recent pulses, in microseconds.
+    #[expect(clippy::cast_precision_loss, reason = "Pulse gaps are small")]
+    fn pulse_us(&self) -> Option<f64> {
+        let (first, last) = (self.pulse_times_us.front()?, self.pulse_times_us.back()?);
+        let gaps = self.pulse_times_us.len().checked_sub(1).filter(|gaps| *gaps > 0)?;
+        let pulse_us = last.saturating_sub(*first) as f64 / gaps as f64;
+        (pulse_us > 0.0).then_some(pulse_us)
+    }
+
+    /// Returns how many quarter notes have passed since the start of the song, or `None` if stopped.
+    ///
+    /// Pulses only say when each 24th of a quarter note starts, so the position is counted in
+    /// whole pulses.
+    fn beat_position(&self) -> Option<f64> {
+        self.running
+            .then(|| f64::from(self.pulses) / f64::from(PULSES_PER_QUARTER))
+    }
+}
+
+#[test]
+fn test_midi_clock_follows_tempo_and_transport() {
+    let mut state = ClockState::default();
+    assert_eq!(state.tempo_bpm(), None);
+    assert_eq!(state.beat_position(), None);
+
+    // Pulses 20.833 ms apart are 120 quarter notes a minute
+    let pulse = |state: &mut ClockState, count: u64| {
+        let first = state.pulse_times_us.back().map_or(0, |last| last + 1_000_000 / 48);
+        for index in 0..count {
+            state.receive(first + index * 1_000_000 / 48, &[CLOCK]);
+        }
+    };
+
+    pulse(&mut state, 30);
+    assert!((state.tempo_bpm().unwrap() - 120.0).abs() < 0.1);
+    assert_eq!(state.beat_position(), None); // Not started yet
+
+    state.receive(0, &[START]);
+    pulse(&mut state, 36);
+    assert_eq!(state.beat_position(), Some(1.5));
+
+    state.receive(0, &[STOP]);
+    pulse(&mut state, 12);
+    assert_eq!(state.beat_position(), None);
+
+    // Jump to the fifth bar of 4/4 (64 sixteenths in) and carry on
+    state.receive(0, &[SONG_POSITION, 64, 0]);
+    state.receive(0, &[CONTINUE]);
+    assert_eq!(state.beat_position(), Some(16.0));
+}
@@ -0,0 +1,342 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use midir::MidiOutputConnection;
+
+use crate::{
+    play::{backend::output_notes, midi_number, mix::Mixer, sources::drum_kind, Playable},
+    MusicPlayer, NoteKind, Timbre,
+};
+
+/// The MIDI channel drums are sent on, which is channel 10 when counting from one.
+const DRUM_CHANNEL: u8 = 9;
+
+/// Where the notes of a timbre are sent when playing over MIDI.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MidiChannel {
+    /// The channel, from 0 to 15. Channel 9 is the General MIDI drum channel.
+    pub channel: u8,
+    /// The General MIDI program selected on the channel before playing, from 0 to 127,
+    /// or `None` to leave the device's program as it is
+    pub program: Option<u8>,
+}
+
+/// The output of a player which sends notes to a MIDI device or program.
+#[derive(Clone)]
+pub struct MidiOutputConfig {
+    /// The connection messages are sent over
+    pub connection: Arc<Mutex<MidiOutputConnection>>,
+    /// The channel each timbre is sent on (default: none, so each timbre gets its own channel,
+    /// with the closest General MIDI program)
+    pub channels: HashMap<Timbre, MidiChannel>,
+}
+
+impl MusicPlayer<MidiOutputConfig> {
+    /// Creates a new music player which plays pieces by sending MIDI messages, to drive hardware
+    /// synths, DAWs, or any other MIDI device, rather than making sound itself.
+    ///
+    /// Each timbre is sent on its own channel, with the closest General MIDI program, and drums
+    /// are sent on the drum channel. Use [`MusicPlayer::with_midi_channel`] to choose them yourself.
+    ///
+    /// # Arguments
+    /// * `tempo_bpm` - The tempo in beats per minute for playback. The number of sixteenth notes per minute.
+    /// * `connection` - A connection to a MIDI output port, made with `midir`
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let output = midir::MidiOutput::new("symphoxy").unwrap();
+    /// let port = output.ports().into_iter().next().expect("No MIDI output ports");
+    /// let connection = output.connect(&port, "symphoxy").unwrap();
+    ///
+    /// let player = MusicPlayer::new_midi(300, connection);
+    /// player.play(piano(quarter(C4) + quarter(E4) + half(G4))).join().unwrap();
+    /// ```
+    pub fn new_midi(tempo_bpm: u32, connection: MidiOutputConnection) -> Self {
+        Self {
+            tempo_bpm,
+            output_config: MidiOutputConfig {
+                connection: Arc::new(Mutex::new(connection)),
+                channels: HashMap::new(),
+            },
+            seed: None,
+            mixer: Mixer::new(),
+        }
+    }
+
+    /// Sends the notes of a timbre on the given channel, selecting the given program first.
+    ///
+    /// # Arguments
+    /// * `timbre` - The timbre whose notes are sent on the channel
+    /// * `channel` - The channel and program. See [`MidiChannel`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{MidiChannel, MusicPlayer};
+    ///
+    /// let output = midir::MidiOutput::new("symphoxy").unwrap();
+    /// let port = output.ports().into_iter().next().expect("No MIDI output ports");
+    /// let connection = output.connect(&port, "symphoxy").unwrap();
+    ///
+    /// // The synth on channel 3 already has the right patch loaded, so keep it
+    /// let player = MusicPlayer::new_midi(300, connection)
+    ///     .with_midi_channel(Timbre::SynthLead, MidiChannel { channel: 2, program: None });
+    /// player.play(synth_lead(quarter(C4) + quarter(G4))).join().unwrap();
+    /// ```
+    pub fn with_midi_channel(mut self, timbre: Timbre, channel: MidiChannel) -> Self {
+        self.output_config.channels.insert(timbre, channel);
+        self
+    }
+
+    /// Plays a musical piece by sending MIDI messages to the connected device in real time.
+    ///
+    /// Notes are sent with their pitch rounded to the nearest semitone, their volume as their
+    /// velocity, and their pan as the channel's pan. Envelopes and effects are left to the device.
+    /// Playback happens in the background. Messages which fail to send, such as if the device is
+    /// unplugged, are skipped.
+    ///
+    /// # Arguments
+    /// * `piece` - Any playable musical content (Note, Chord, Line, Piece, etc.)
+    ///
+    /// # Returns
+    /// A [`MidiPlayback`] which can stop the playback, or be joined to wait for it to finish.
+    ///
+    /// # Example
+    /// See [`MusicPlayer::new_midi`].
+    pub fn play<T: Playable>(&self, piece: T) -> MidiPlayback {
+        let notes = output_notes(
+            &piece.to_piece(),
+            self.beat_duration_ms(),
+            self.pass_seed(),
+            &self.mixer,
+        );
+        let messages = midi_messages(&notes, &self.output_config.channels);
+        let used_channels = {
+            let mut channels: Vec<u8> = messages.iter().map(|(_, message)| message[0] & 0x0F).collect();
+            channels.sort_unstable();
+            channels.dedup();
+            channels
+        };
+
+        let stopped = Arc::new(AtomicBool::new(false));
+        let thread_stopped = stopped.clone();
+        let connection = self.output_config.connection.clone();
+        let thread = thread::spawn(move || {
+            let send = |message: &[u8]| {
+                if let Ok(mut connection) = connection.lock() {
+                    // A device which has gone away can't be told anything, so there's nothing to do
+                    let _ = connection.send(message);
+                }
+            };
+
+            let start = Instant::now();
+            for (time_ms, message) in &messages {
+                let due = start.checked_add(Duration::from_millis(*time_ms)).unwrap_or(start);
+                while !thread_stopped.load(Ordering::Relaxed) && Instant::now() < due {
+                    thread::sleep(due.saturating_duration_since(Instant::now()).min(STOP_CHECK_INTERVAL));
+                }
+                if thread_stopped.load(Ordering::Relaxed) {
+                    // Silence anything left sounding
+                    for channel in &used_channels {
+                        send(&[0xB0 | channel, ALL_NOTES_OFF, 0]);
+                    }
+                    return;
+                }
+                send(message);
+            }
+        });
+
+        MidiPlayback { stopped, thread }
+    }
+}
+
+/// How often a sleeping playback thread checks whether it's been stopped.
+const STOP_CHECK_INTERVAL: Duration = Duration::from_millis(10);
+
+/// The controller which stops every note on a channel.
+const ALL_NOTES_OFF: u8 = 123;
+
+/// The controller which sets a channel's pan.
+const PAN: u8 = 10;
+
+/// A handle to a piece which is being played over MIDI.
+///
+/// Returned by `MusicPlayer::play` on a MIDI player. Dropping the handle does not stop playback.
+pub struct MidiPlayback {
+    stopped: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+impl MidiPlayback {
+    /// Stops playback, and stops every note still sounding on the channels the piece uses.
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns whether every message has been sent, or playback was stopped.
+    pub fn is_finished(&self) -> bool {
+        self.thread.is_finished()
+    }
+
+    /// Waits for playback to finish.
+    ///
+    /// # Errors
+    /// Returns an error if the playback thread panicked.
+    pub fn join(self) -> std::thread::Result<()> {
+        self.thread.join()
+    }
+}
+
+/// Returns the messages which play the given notes, with when each is sent in milliseconds, in
+/// the order they're sent. Program changes come first, and at any moment, notes are stopped
+/// before new ones start, so repeated notes are heard.
+fn midi_messages(
+    notes: &[(u64, crate::play::OutputNote)],
+    channels: &HashMap<Timbre, MidiChannel>,
+) -> Vec<(u64, Vec<u8>)> {
+    let mut channel_of: HashMap<Timbre, MidiChannel> = channels.clone();
+    let mut next_channel = 0_u8;
+    let mut programs = vec![];
+    let mut starts = vec![];
+    let mut stops = vec![];
+    let mut pans: HashMap<u8, u8> = HashMap::new();
+
+    for (start_ms, output_note) in notes {
+        let NoteKind::Pitched {
+            pitch,
+            timbre,
+            volume,
+            pan,
+            ..
+        } = &output_note.note.1
+        else {
+            continue;
+        };
+
+        let channel = *channel_of.entry(timbre.clone()).or_insert_with(|| {
+            let channel = if *timbre == Timbre::Drums {
+                MidiChannel {
+                    channel: DRUM_CHANNEL,
+                    program: None,
+                }
+            } else {
+                let taken: Vec<u8> = channels.values().map(|channel| channel.channel).collect();
+                // Once every channel is in use, timbres share the last one
+                while next_channel < 15 && (next_channel == DRUM_CHANNEL || taken.contains(&next_channel)) {
+                    next_channel = next_channel.saturating_add(1);
+                }
+                let channel = next_channel;
+                next_channel = next_channel.saturating_add(1).min(15);
+                MidiChannel {
+                    channel,
+                    program: Some(general_midi_program(timbre)),
+                }
+            };
+            if let Some(program) = channel.program {
+                programs.push((0, vec![0xC0 | channel.channel, program & 0x7F]));
+            }
+            channel
+        });
+
+        let status = channel.channel & 0x0F;
+        let key = if *timbre == Timbre::Drums {
+            drum_key(pitch.0)
+        } else {
+            u8::try_from(midi_number(*pitch).clamp(0, 127)).unwrap_or_default()
+        };
+        #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss, reason = "Clamped to 1..=127")]
+        let velocity = (volume * 100.0).round().clamp(1.0, 127.0) as u8;
+        #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss, reason = "Clamped to 0..=127")]
+        let pan = ((pan.clamp(-1.0, 1.0) + 1.0) * 63.5).round().min(127.0) as u8;
+
+        if pans.insert(status, pan) != Some(pan) {
+            starts.push((*start_ms, vec![0xB0 | status, PAN, pan]));
+        }
+        starts.push((*start_ms, vec![0x90 | status, key, velocity]));
+        stops.push((
+            start_ms.saturating_add(output_note.duration_ms),
+            vec![0x80 | status, key, 0],
+        ));
+    }
+
+    // Sorting is stable, so stops stay before starts at the same moment, and pans before their notes
+    let mut messages: Vec<(u64, Vec<u8>)> = programs.into_iter().chain(stops).chain(starts).collect();
+    messages.sort_by_key(|(time_ms, _)| *time_ms);
+    messages
+}
+
+/// Returns the General MIDI drum key of the kit's drum a pitch plays.
+fn drum_key(frequency: f32) -> u8 {
+    match drum_kind(frequency) {
+        "kick" => 36,
+        "hi-hat" => 42,
+        "crash" => 49,
+        _ => 38,
+    }
+}
+
+/// Returns the General MIDI program, counting from zero, closest to a timbre.
+fn general_midi_program(timbre: &Timbre) -> u8 {
+    match timbre {
+        Timbre::Sine | Timbre::Triangle => 79, // Ocarina
+        Timbre::Square => 80,                  // Square lead
+        Timbre::Saw | Timbre::SynthLead => 81, // Sawtooth lead
+        Timbre::Bass => 33,                    // Fingered electric bass
+        Timbre::ElectricGuitar => 29,          // Overdriven guitar
+        Timbre::Organ => 16,                   // Drawbar organ
+        Timbre::Strings => 48,                 // String ensemble
+        Timbre::SynthPad => 89,                // Warm pad
+        Timbre::Flute => 73,
+        Timbre::Brass => 61, // Brass section
+        Timbre::Fm(_) => 4,  // Electric piano, the classic FM sound
+        Timbre::Layered(layers) => layers.first().map_or(0, |(timbre, _)| general_midi_program(timbre)),
+        Timbre::Reversed(timbre) => general_midi_program(timbre),
+        _ => 0, // Acoustic grand piano
+    }
+}
+
+#[test]
+fn test_midi_messages_map_timbres_to_channels() {
+    use crate::prelude::*;
+
+    // At 300 sixteenths a minute, a sixteenth is 200 ms
+    let piece = piano(quarter(C4) + quarter(C4)) * drums(half(C4.octave(-1))) * flute(half(A4).volume(0.5).pan(1.0));
+    let notes = output_notes(&piece, 200, 0, &Mixer::new());
+    let custom = HashMap::from([(
+        Timbre::Flute,
+        MidiChannel {
+            channel: 4,
+            program: None,
+        },
+    )]);
+    let messages = midi_messages(&notes, &custom);
+
+    assert_eq!(
+        messages,
+        [
+            (0, vec![0xC0, 0]),
+            (0, vec![0xB0, PAN, 64]),
+            (0, vec![0x90, 60, 100]),
+            (0, vec![0xB9, PAN, 64]),
+            (0, vec![0x99, 36, 100]),
+            (0, vec![0xB4, PAN, 127]),
+            (0, vec![0x94, 69, 50]),
+            // The repeated C4 is stopped before it starts again
+            (800, vec![0x80, 60, 0]),
+            (800, vec![0x90, 60, 100]),
+            (1600, vec![0x89, 36, 0]),
+            (1600, vec![0x84, 69, 0]),
+            (1600, vec![0x80, 60, 0]),
+        ]
+    );
+}
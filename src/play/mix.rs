@@ -0,0 +1,165 @@
+use crate::NoteKind;
+
+/// The gain, pan, mute, and solo of every line of a piece, like the channel strips of a mixing desk.
+///
+/// Give a mixer to a player with [`MusicPlayer::with_mixer`](crate::MusicPlayer::with_mixer), and
+/// it's used both when playing live and when rendering, so a piece can be balanced without
+/// changing the volume of its notes. Lines are numbered from zero, in the order they're in the piece.
+/// Lines the mixer hasn't been told about play as they are.
+///
+/// # Example
+/// ```no_run
+/// # #[cfg(feature = "wav-output")] {
+/// use symphoxy::prelude::*;
+/// use symphoxy::{Mixer, MusicPlayer};
+///
+/// let piece = piano(quarter(C4) + quarter(E4)) * bass(half(C2)) * drums(quarter(C4) + quarter(C3));
+///
+/// // Turn the bass down, move the piano left, and leave the drums out
+/// let mixer = Mixer::new().gain_db(1, -6.0).pan(0, -0.5).mute(2);
+/// let player = MusicPlayer::new_file(300, 1.0, 44100).with_mixer(mixer);
+///
+/// player.render_to_wav(piece, "balanced.wav");
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Mixer {
+    strips: Vec<LineStrip>,
+}
+
+/// The settings of one line in a [`Mixer`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LineStrip {
+    /// How much the line is turned up, in decibels (default: 0.0)
+    pub gain_db: f32,
+    /// How far the line is moved, from -1.0 (left) to 1.0 (right). This is added to each note's
+    /// own pan (default: 0.0)
+    pub pan: f32,
+    /// Whether the line is silenced (default: false)
+    pub mute: bool,
+    /// Whether the line is soloed. If any line is soloed, only soloed lines are heard (default: false)
+    pub solo: bool,
+}
+
+impl Default for LineStrip {
+    fn default() -> Self {
+        LineStrip {
+            gain_db: 0.0,
+            pan: 0.0,
+            mute: false,
+            solo: false,
+        }
+    }
+}
+
+impl Mixer {
+    /// Creates a mixer which plays every line as it is.
+    pub fn new() -> Self {
+        Mixer::default()
+    }
+
+    /// Returns the settings of a line.
+    ///
+    /// # Arguments
+    /// * `line` - The index of the line in the piece
+    pub fn strip(&self, line: usize) -> LineStrip {
+        self.strips.get(line).copied().unwrap_or_default()
+    }
+
+    /// Returns the settings of a line, to be changed.
+    ///
+    /// # Arguments
+    /// * `line` - The index of the line in the piece
+    pub fn strip_mut(&mut self, line: usize) -> &mut LineStrip {
+        if self.strips.len() <= line {
+            self.strips.resize(line.saturating_add(1), LineStrip::default());
+        }
+        &mut self.strips[line]
+    }
+
+    /// Sets how much a line is turned up, in decibels. Negative values turn it down.
+    ///
+    /// # Arguments
+    /// * `line` - The index of the line in the piece
+    /// * `gain_db` - The gain, in decibels. -6.0 about halves the line's volume.
+    pub fn gain_db(mut self, line: usize, gain_db: f32) -> Self {
+        self.strip_mut(line).gain_db = gain_db;
+        self
+    }
+
+    /// Sets how far a line is moved left or right.
+    ///
+    /// # Arguments
+    /// * `line` - The index of the line in the piece
+    /// * `pan` - From -1.0 (left) to 1.0 (right). This is added to each note's own pan.
+    pub fn pan(mut self, line: usize, pan: f32) -> Self {
+        self.strip_mut(line).pan = pan;
+        self
+    }
+
+    /// Silences a line.
+    ///
+    /// # Arguments
+    /// * `line` - The index of the line in the piece
+    pub fn mute(mut self, line: usize) -> Self {
+        self.strip_mut(line).mute = true;
+        self
+    }
+
+    /// Solos a line, so only it and any other soloed lines are heard.
+    ///
+    /// # Arguments
+    /// * `line` - The index of the line in the piece
+    pub fn solo(mut self, line: usize) -> Self {
+        self.strip_mut(line).solo = true;
+        self
+    }
+
+    /// Returns whether a line is heard, given which lines are muted and soloed.
+    ///
+    /// # Arguments
+    /// * `line` - The index of the line in the piece
+    ///
+    /// # Example
+    /// ```
+    /// use symphoxy::Mixer;
+    ///
+    /// let mixer = Mixer::new().solo(0).solo(1).mute(1);
+    /// assert!(mixer.is_audible(0));
+    /// assert!(!mixer.is_audible(1)); // Muted lines stay muted, even when soloed
+    /// assert!(!mixer.is_audible(2)); // Only soloed lines are heard
+    /// ```
+    pub fn is_audible(&self, line: usize) -> bool {
+        let strip = self.strip(line);
+        let any_solo = self.strips.iter().any(|strip| strip.solo);
+        !strip.mute && (strip.solo || !any_solo)
+    }
+
+    /// Returns a copy of the mixer where only one line can be heard, with its own settings.
+    #[cfg(feature = "wav-output")]
+    pub(crate) fn isolate(&self, line: usize) -> Mixer {
+        let mut isolated = Mixer {
+            strips: self
+                .strips
+                .iter()
+                .map(|strip| LineStrip { solo: false, ..*strip })
+                .collect(),
+        };
+        isolated.strip_mut(line).solo = true;
+        isolated
+    }
+
+    /// Applies a line's settings to one of its notes, returning `None` if the line isn't heard.
+    pub(crate) fn apply(&self, line: usize, kind: &NoteKind) -> Option<NoteKind> {
+        if !self.is_audible(line) {
+            return None;
+        }
+        let strip = self.strip(line);
+        let mut kind = kind.clone();
+        if let NoteKind::Pitched { volume, pan, .. } = &mut kind {
+            *volume *= 10.0_f32.powf(strip.gain_db / 20.0);
+            *pan = (*pan + strip.pan).clamp(-1.0, 1.0);
+        }
+        Some(kind)
+    }
+}
@@ -0,0 +1,675 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use rodio::{
+    source::{SeekError, UniformSourceIterator},
+    Source,
+};
+
+use crate::{
+    effects::{processors, Effect, EffectProcessor},
+    note::NoteKind,
+    play::{
+        backend::{output_notes, OutputNote},
+        levels::{LevelMeter, LevelSubscribers},
+        mix::Mixer,
+        sources::{get_source, pan_gains, prepare_files, sounding_ms, SymphoxySource},
+        ticks_to_ms,
+        voices::VoiceLimit,
+        Playable, EFFECT_TAIL_MS,
+    },
+    rng::Rng,
+    Note, Piece,
+};
+
+/// Sample rate of the mixed output. This matches rodio's built-in oscillators,
+/// so synthesized notes don't need to be resampled.
+pub const MIXER_SAMPLE_RATE: u32 = 48_000;
+
+/// Number of interleaved channels in the mixed output.
+pub const MIXER_CHANNELS: u16 = 2;
+
+/// The gain of live output. For some reason, playing live is way louder than file output.
+/// 64 is arbitrary, but seems about right.
+pub const LIVE_GAIN: f32 = 1.0 / 64.0;
+
+/// Converts a duration in milliseconds to a number of frames at the mixer's sample rate.
+pub fn ms_to_frames(ms: u64) -> u64 {
+    ms.saturating_mul(MIXER_SAMPLE_RATE as u64) / 1000
+}
+
+/// Converts a number of frames at the mixer's sample rate to a duration in milliseconds.
+pub fn frames_to_ms(frames: u64) -> u64 {
+    frames
+        .saturating_mul(1000)
+        .checked_div(MIXER_SAMPLE_RATE as u64)
+        .unwrap_or_default()
+}
+
+/// Converts a number of frames at the mixer's sample rate to a duration.
+pub fn frames_to_duration(frames: u64) -> Duration {
+    Duration::from_micros(
+        frames
+            .saturating_mul(1_000_000)
+            .checked_div(MIXER_SAMPLE_RATE as u64)
+            .unwrap_or_default(),
+    )
+}
+
+/// A note scheduled to be started by the mixer.
+///
+/// Sources are only created once their note starts, but the files they play are decoded when the
+/// schedule is made, so starting a note on the audio thread never waits on the filesystem.
+struct ScheduledNote {
+    start_frame: u64,
+    /// The frame at which the note stops sounding, including its release
+    end_frame: u64,
+    duration_ms: u64,
+    /// The index of the line the note is in
+    line: usize,
+    note: Note,
+}
+
+/// How fast a mixer moves through its piece, as the bits of an `f64`, which can be changed while
+/// it plays. At 1.0, it plays at the tempo it was scheduled at.
+///
+/// Only the timing of notes follows the rate. Notes themselves sound as they would at 1.0,
+/// so small changes, such as to follow an external clock, don't change their pitch.
+pub type PlaybackRate = Arc<AtomicU64>;
+
+/// A note which is sounding.
+struct Voice {
+    source: UniformSourceIterator<SymphoxySource, f32>,
+    /// The gain of each output channel
+    gains: [f32; MIXER_CHANNELS as usize],
+    /// The index of the line the note is in
+    line: usize,
+    start_frame: u64,
+    /// How loud the note is, following its peaks, for deciding which voice to steal
+    level: f32,
+}
+
+/// How much a voice's level falls each frame, so it follows the note as it decays.
+const VOICE_LEVEL_RELEASE: f32 = 0.9995;
+
+/// A single rodio source which plays every note of a piece.
+///
+/// Notes are started at exact sample offsets rather than by sleeping a thread,
+/// so long pieces don't drift, and only one sink is needed for the whole piece.
+/// The source supports seeking, and publishes its position so playback progress can be queried.
+pub struct MixerSource {
+    /// Every pitched note of the piece, sorted by start frame
+    schedule: Vec<ScheduledNote>,
+    /// The index in `schedule` of the next note to start
+    next_note: usize,
+    /// The notes which are sounding
+    active: Vec<Voice>,
+    /// The most notes which can sound at once, if there's a limit
+    voice_limit: Option<VoiceLimit>,
+    /// How many notes have been cut off to make room for others
+    stolen_voices: Arc<AtomicU64>,
+    gain: f32,
+    beat_duration_ms: u64,
+    end_frame: u64,
+    /// The frame at which the mixer stops, once the last note's release and any effects have
+    /// had time to finish
+    finish_frame: u64,
+    /// How far through the piece the mixer is, in frames
+    frame: u64,
+    /// How far through the next frame of the piece the mixer is, when it's following a rate
+    frame_fraction: f64,
+    /// How many frames of the piece to move through for each frame played, if it isn't always 1
+    rate: Option<PlaybackRate>,
+    frame_buffer: [f32; MIXER_CHANNELS as usize],
+    channel: usize,
+    position: Arc<AtomicU64>,
+    /// The samples of each line in the frame being mixed
+    line_frames: Vec<[f32; MIXER_CHANNELS as usize]>,
+    /// The effects of each line
+    line_effects: Vec<Vec<Arc<dyn Effect>>>,
+    /// The processors applying each line's effects
+    line_processors: Vec<Vec<Box<dyn EffectProcessor>>>,
+    levels: LevelMeter,
+}
+
+impl MixerSource {
+    /// Schedules every note of `piece`. Each note's volume is multiplied by `gain`.
+    /// Ratchets are scheduled as separate notes.
+    /// `seed` decides which notes with a probability are played, and `mixer` sets each line's gain, pan, mute, and solo.
+    pub fn new<T: Playable>(piece: &T, beat_duration_ms: u64, gain: f32, seed: u64, mixer: &Mixer) -> Self {
+        let piece = piece.to_piece();
+        prepare_files(&piece);
+        let length = piece.length();
+        let schedule: Vec<ScheduledNote> = output_notes(&piece, beat_duration_ms, seed, mixer)
+            .into_iter()
+            .map(
+                |(
+                    start_ms,
+                    OutputNote {
+                        duration_ms,
+                        line_index,
+                        note,
+                    },
+                )| {
+                    let start_frame = ms_to_frames(start_ms);
+                    let sounding_ms = match &note.1 {
+                        NoteKind::Pitched { timbre, envelope, .. } => sounding_ms(duration_ms, timbre, *envelope),
+                        NoteKind::Rest => duration_ms,
+                    };
+                    ScheduledNote {
+                        start_frame,
+                        end_frame: start_frame.saturating_add(ms_to_frames(sounding_ms)),
+                        duration_ms,
+                        line: line_index,
+                        note,
+                    }
+                },
+            )
+            .collect();
+
+        let line_effects: Vec<_> = (0..piece.0.len())
+            .map(|line| piece.line_effects(line).to_vec())
+            .collect();
+
+        let end_frame = ms_to_frames(ticks_to_ms(length, beat_duration_ms));
+        let release_end = schedule.iter().map(|note| note.end_frame).fold(end_frame, u64::max);
+        let effect_tail = if line_effects.iter().any(|effects| !effects.is_empty()) {
+            ms_to_frames(EFFECT_TAIL_MS)
+        } else {
+            0
+        };
+
+        MixerSource {
+            schedule,
+            next_note: 0,
+            active: Vec::new(),
+            voice_limit: None,
+            stolen_voices: Arc::new(AtomicU64::new(0)),
+            gain,
+            beat_duration_ms,
+            end_frame,
+            finish_frame: release_end.saturating_add(effect_tail),
+            frame: 0,
+            frame_fraction: 0.0,
+            rate: None,
+            frame_buffer: [0.0; MIXER_CHANNELS as usize],
+            channel: 0,
+            position: Arc::new(AtomicU64::new(0)),
+            line_frames: vec![[0.0; MIXER_CHANNELS as usize]; line_effects.len()],
+            line_processors: new_line_processors(&line_effects),
+            line_effects,
+            levels: LevelMeter::new(piece.0.len()),
+        }
+    }
+
+    /// Limits how many notes sound at once, cutting notes off to make room for new ones.
+    pub fn with_voice_limit(mut self, voice_limit: Option<VoiceLimit>) -> Self {
+        self.voice_limit = voice_limit;
+        self
+    }
+
+    /// Moves through the piece at a rate which can be changed while it plays, rather than
+    /// always one frame of the piece for each frame played.
+    pub fn with_rate(mut self, rate: PlaybackRate) -> Self {
+        self.rate = Some(rate);
+        self
+    }
+
+    /// Moves on to the next frame of the piece, or several or none of them when following a rate.
+    #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss, reason = "Whole and positive")]
+    fn advance(&mut self) {
+        let frames = match &self.rate {
+            None => 1,
+            Some(rate) => {
+                let rate = f64::from_bits(rate.load(Ordering::Relaxed));
+                self.frame_fraction += if rate.is_finite() { rate.max(0.0) } else { 1.0 };
+                let whole = self.frame_fraction.floor();
+                self.frame_fraction -= whole;
+                whole as u64
+            }
+        };
+        self.frame = self.frame.saturating_add(frames);
+        self.position.store(self.frame, Ordering::Relaxed);
+    }
+
+    /// Returns a shared counter holding the number of notes cut off to make room for others.
+    pub fn stolen_voices(&self) -> Arc<AtomicU64> {
+        self.stolen_voices.clone()
+    }
+
+    /// Returns a shared counter holding the number of frames played so far.
+    pub fn position(&self) -> Arc<AtomicU64> {
+        self.position.clone()
+    }
+
+    /// Returns the shared list of subscribers to the level of each line.
+    pub fn level_subscribers(&self) -> LevelSubscribers {
+        self.levels.subscribers()
+    }
+
+    /// Returns the total number of frames in the piece.
+    pub fn end_frame(&self) -> u64 {
+        self.end_frame
+    }
+
+    fn is_finished(&self) -> bool {
+        self.frame >= self.finish_frame && self.next_note >= self.schedule.len() && self.active.is_empty()
+    }
+
+    /// Creates the source for a scheduled note, skipping its first `skip_frames` frames.
+    /// If every voice is in use, another note is cut off to make room.
+    fn start_note(&mut self, index: usize, skip_frames: u64) {
+        let ScheduledNote {
+            start_frame,
+            duration_ms,
+            line,
+            note,
+            ..
+        } = &self.schedule[index];
+        if let NoteKind::Pitched {
+            pitch,
+            timbre,
+            volume,
+            envelope,
+            pan,
+            ..
+        } = &note.1
+        {
+            let mut source = get_source(
+                *duration_ms,
+                pitch.0,
+                timbre,
+                volume * self.gain,
+                *envelope,
+                self.beat_duration_ms,
+            );
+            if skip_frames > 0 {
+                source = Box::new(source.skip_duration(frames_to_duration(skip_frames)));
+            }
+            if let Some(limit) = self.voice_limit.filter(|limit| !limit.has_room(self.active.len())) {
+                let voices = self.active.iter().map(|voice| (voice.start_frame, voice.level));
+                if let Some(victim) = limit.victim(voices) {
+                    self.active.remove(victim);
+                    self.stolen_voices.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            self.active.push(Voice {
+                source: UniformSourceIterator::new(source, MIXER_CHANNELS, MIXER_SAMPLE_RATE),
+                gains: pan_gains(*pan),
+                line: *line,
+                start_frame: *start_frame,
+                // Until it's heard, a note is as loud as it's played
+                level: volume * self.gain,
+            });
+        }
+    }
+
+    /// Starts any notes due at the current frame, then sums one frame from every active source.
+    #[expect(clippy::arithmetic_side_effects, reason = "next_note is always below schedule.len()")]
+    fn mix_frame(&mut self) {
+        while self
+            .schedule
+            .get(self.next_note)
+            .is_some_and(|next| next.start_frame <= self.frame)
+        {
+            self.start_note(self.next_note, 0);
+            self.next_note += 1;
+        }
+
+        let line_frames = &mut self.line_frames;
+        line_frames.fill([0.0; MIXER_CHANNELS as usize]);
+        self.active.retain_mut(|voice| {
+            let mut source_frame = [0.0; MIXER_CHANNELS as usize];
+            for (sample, gain) in source_frame.iter_mut().zip(voice.gains.iter()) {
+                match voice.source.next() {
+                    Some(value) => *sample = value * gain,
+                    None => return false,
+                }
+            }
+            let peak = source_frame.iter().fold(0.0_f32, |peak, sample| peak.max(sample.abs()));
+            voice.level = peak.max(voice.level * VOICE_LEVEL_RELEASE);
+            if let Some(line_frame) = line_frames.get_mut(voice.line) {
+                for (mixed, sample) in line_frame.iter_mut().zip(source_frame) {
+                    *mixed += sample;
+                }
+            }
+            true
+        });
+
+        self.frame_buffer = [0.0; MIXER_CHANNELS as usize];
+        for (line_frame, processors) in self.line_frames.iter_mut().zip(&mut self.line_processors) {
+            // Effects see the line at its own volume, so the gain doesn't change how hard it's driven
+            if !processors.is_empty() && self.gain > 0.0 {
+                line_frame.iter_mut().for_each(|sample| *sample /= self.gain);
+                for processor in processors.iter_mut() {
+                    processor.process(line_frame);
+                }
+                line_frame.iter_mut().for_each(|sample| *sample *= self.gain);
+            }
+            for (mixed, sample) in self.frame_buffer.iter_mut().zip(line_frame.iter()) {
+                *mixed += sample;
+            }
+        }
+        self.levels.finish_frame(self.frame, self.gain, &self.line_frames);
+    }
+}
+
+/// Creates the processors for each line's effects.
+fn new_line_processors(line_effects: &[Vec<Arc<dyn Effect>>]) -> Vec<Vec<Box<dyn EffectProcessor>>> {
+    line_effects
+        .iter()
+        .map(|effects| processors(effects, MIXER_SAMPLE_RATE, MIXER_CHANNELS as usize))
+        .collect()
+}
+
+impl Iterator for MixerSource {
+    type Item = f32;
+
+    #[expect(clippy::arithmetic_side_effects, reason = "The channel index is always below MIXER_CHANNELS")]
+    fn next(&mut self) -> Option<f32> {
+        if self.channel == 0 {
+            if self.is_finished() {
+                self.levels.finish();
+                return None;
+            }
+            self.mix_frame();
+        }
+
+        let sample = self.frame_buffer[self.channel];
+        self.channel = (self.channel + 1) % MIXER_CHANNELS as usize;
+        if self.channel == 0 {
+            self.advance();
+        }
+
+        Some(sample)
+    }
+}
+
+impl Source for MixerSource {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        MIXER_CHANNELS
+    }
+
+    fn sample_rate(&self) -> u32 {
+        MIXER_SAMPLE_RATE
+    }
+
+    #[expect(clippy::cast_precision_loss, reason = "Only loses precision for pieces lasting millions of years")]
+    fn total_duration(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f64(
+            self.end_frame as f64 / MIXER_SAMPLE_RATE as f64,
+        ))
+    }
+
+    /// Seeks by restarting every note which is sounding at the target position part way through.
+    /// This can't fail.
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.seek(pos);
+        Ok(())
+    }
+}
+
+impl MixerSource {
+    /// Jumps to the given position, restarting every note which is sounding there part way
+    /// through.
+    #[expect(clippy::arithmetic_side_effects, reason = "Manual bounds checking")]
+    pub(crate) fn seek(&mut self, pos: Duration) {
+        let target_frame = u64::try_from(pos.as_micros())
+            .unwrap_or(u64::MAX)
+            .saturating_mul(MIXER_SAMPLE_RATE as u64)
+            / 1_000_000;
+
+        self.active.clear();
+        self.levels.reset();
+        self.line_processors = new_line_processors(&self.line_effects);
+        self.next_note = self.schedule.partition_point(|note| note.start_frame < target_frame);
+
+        for index in 0..self.next_note {
+            let note = &self.schedule[index];
+            if note.end_frame > target_frame {
+                self.start_note(index, target_frame - note.start_frame);
+            }
+        }
+
+        self.frame = target_frame;
+        self.frame_fraction = 0.0;
+        self.channel = 0;
+        self.position.store(self.frame, Ordering::Relaxed);
+    }
+}
+
+impl Piece {
+    /// Turns the piece into a rodio source, which plays it as [`MusicPlayer::play`](crate::MusicPlayer::play) would.
+    ///
+    /// The source can be appended to a sink, mixed with other sources, or played on a stream
+    /// which is managed elsewhere, such as by a game. It plays in stereo at 48 kHz, with each line's
+    /// effects applied.
+    ///
+    /// # Parameters
+    /// - `tempo_bpm`: The tempo in beats per minute, where a beat is a sixteenth note
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    ///
+    /// let (_stream, handle) = rodio::OutputStream::try_default().unwrap();
+    /// let sink = rodio::Sink::try_new(&handle).unwrap();
+    ///
+    /// let piece = Piece::from(piano(quarter(C4) + quarter(E4) + half(G4)));
+    /// sink.append(piece.into_source(300));
+    /// sink.sleep_until_end();
+    /// ```
+    pub fn into_source(self, tempo_bpm: u32) -> impl Source<Item = f32> + Send {
+        let beat_duration_ms = 60_000u64.checked_div(u64::from(tempo_bpm)).unwrap_or(u64::MAX);
+        MixerSource::new(
+            &self,
+            beat_duration_ms,
+            LIVE_GAIN,
+            Rng::from_time().next_u64(),
+            &Mixer::new(),
+        )
+    }
+}
+
+#[test]
+fn test_mixer_plays_whole_piece() {
+    use crate::prelude::*;
+
+    // A trailing rest still has to be played, even once the note's release has finished
+    let line = piano(quarter(C4)) + whole(REST);
+    let samples: Vec<f32> = MixerSource::new(&line, 10, 1.0, 0, &Mixer::new()).collect();
+
+    assert_eq!(samples.len() as u64, ms_to_frames(200) * MIXER_CHANNELS as u64);
+    assert!(samples[..samples.len() / 2].iter().any(|&s| s != 0.0));
+    assert!(samples[samples.len() * 3 / 4..].iter().all(|&s| s == 0.0));
+}
+
+#[test]
+fn test_mixer_lets_effects_ring_on_after_the_piece() {
+    use crate::prelude::*;
+
+    let line = Line::from(piano(quarter(C4))).with_effect(crate::Reverb::default());
+    let mixer = MixerSource::new(&line, 10, 1.0, 0, &Mixer::new());
+    let release_end = mixer.schedule[0].end_frame * MIXER_CHANNELS as u64;
+    let samples: Vec<f32> = mixer.collect();
+
+    // The reverb is still heard after the note's release has finished
+    assert!(samples.len() as u64 > release_end + ms_to_frames(EFFECT_TAIL_MS) * MIXER_CHANNELS as u64 / 2);
+    assert!(samples
+        .iter()
+        .skip(usize::try_from(release_end).unwrap())
+        .any(|s| s.abs() > 1e-4));
+}
+
+#[test]
+fn test_mixer_seek_restarts_sounding_notes() {
+    use crate::prelude::*;
+
+    let line = piano(half(C4)) + whole(REST);
+    let mut mixer = MixerSource::new(&line, 10, 1.0, 0, &Mixer::new());
+
+    // Seek into the middle of the half note
+    mixer.try_seek(Duration::from_millis(40)).unwrap();
+    let samples: Vec<f32> = mixer.collect();
+
+    assert_eq!(samples.len() as u64, ms_to_frames(200) * MIXER_CHANNELS as u64);
+    assert!(samples[..samples.len() / 2].iter().any(|&s| s != 0.0));
+}
+
+#[test]
+fn test_mixer_pans_notes() {
+    use crate::prelude::*;
+
+    let line = piano(quarter(C4)).pan(-1.0);
+    let samples: Vec<f32> = MixerSource::new(&line, 10, 1.0, 0, &Mixer::new()).collect();
+
+    assert!(samples.iter().step_by(2).any(|&s| s != 0.0));
+    assert!(samples.iter().skip(1).step_by(2).all(|&s| s.abs() < 1e-6));
+}
+
+#[test]
+fn test_mixer_reports_line_levels() {
+    use crate::prelude::*;
+
+    // The first line plays, then the second
+    let piece = Line::from(piano(quarter(C4))) * (quarter(REST) + piano(quarter(C4)));
+    let mut mixer = MixerSource::new(&piece, 10, 1.0 / 64.0, 0, &Mixer::new());
+    let (sender, receiver) = crate::play::levels::level_channel();
+    mixer.level_subscribers().lock().unwrap().push(sender);
+
+    let _: Vec<f32> = mixer.by_ref().collect();
+    let levels: Vec<_> = receiver.iter().collect();
+
+    let at = |ms: u64| &levels.iter().find(|levels| levels.time_ms == ms).unwrap().lines;
+    assert!(at(20)[0] > 0.01 && at(20)[1] == 0.0);
+    assert!(at(60)[1] > 0.01);
+    // Levels aren't scaled down by the mixer's gain
+    assert!(at(20)[0] > 0.1);
+}
+
+#[test]
+fn test_mixer_skips_notes_by_probability() {
+    use crate::prelude::*;
+
+    let never = Line::from(piano(quarter(C4)).with_probability(0.0));
+    assert!(MixerSource::new(&never, 10, 1.0, 0, &Mixer::new()).all(|s| s == 0.0));
+
+    // Each seed plays the same notes every time, and different seeds play different notes
+    let maybe = Line::from((0..32).map(|_| sixteenth(C4).with_probability(0.5)).collect::<Vec<_>>());
+    let scheduled = |seed| MixerSource::new(&maybe, 10, 1.0, seed, &Mixer::new()).schedule.len();
+    assert_eq!(scheduled(1), scheduled(1));
+    assert!((1..10).any(|seed| scheduled(seed) != scheduled(0)));
+    assert!(scheduled(0) > 0 && scheduled(0) < 32);
+}
+
+#[test]
+fn test_mixer_schedules_each_retrigger_of_a_ratchet() {
+    use crate::prelude::*;
+
+    let piece = Line::from(piano(half(C4)).ratchet(4)) * piano(quarter(E4) + quarter(G4));
+    let schedule = MixerSource::new(&piece, 10, 1.0, 0, &Mixer::new()).schedule;
+    let starts: Vec<u64> = schedule.iter().map(|note| note.start_frame).collect();
+
+    // Four retriggers, each an eighth long, scheduled in order among the other line's notes
+    assert_eq!(schedule.len(), 6);
+    assert!(starts.windows(2).all(|pair| pair[0] <= pair[1]));
+    let eighth = ms_to_frames(ticks_to_ms(eighth(C4).0.ticks() as usize, 10));
+    let retriggers: Vec<u64> = schedule
+        .iter()
+        .filter(|note| note.line == 0)
+        .map(|note| note.start_frame)
+        .collect();
+    assert_eq!(retriggers, vec![0, eighth, 2 * eighth, 3 * eighth]);
+}
+
+#[test]
+fn test_mixer_steals_voices_past_its_limit() {
+    use crate::{prelude::*, VoiceStealing};
+
+    // Three held notes, each starting a sixteenth after the last, with the second quiet
+    let piece = Line::from(piano(whole(C4)))
+        * (sixteenth(REST) + piano(whole(E4)).volume(0.1))
+        * (eighth(REST) + piano(whole(G4)));
+    let sounding_lines = |stealing| {
+        let limit = VoiceLimit::new(2).with_stealing(stealing);
+        let mut mixer = MixerSource::new(&piece, 10, 1.0, 0, &Mixer::new()).with_voice_limit(Some(limit));
+        let _: Vec<f32> = mixer
+            .by_ref()
+            .take(usize::try_from(ms_to_frames(30) * MIXER_CHANNELS as u64).unwrap())
+            .collect();
+        assert_eq!(mixer.stolen_voices().load(Ordering::Relaxed), 1);
+        mixer.active.iter().map(|voice| voice.line).collect::<Vec<_>>()
+    };
+
+    assert_eq!(sounding_lines(VoiceStealing::Oldest), [1, 2]);
+    assert_eq!(sounding_lines(VoiceStealing::Quietest), [0, 2]);
+
+    // Without a limit, every note sounds
+    let mut mixer = MixerSource::new(&piece, 10, 1.0, 0, &Mixer::new());
+    let _: Vec<f32> = mixer
+        .by_ref()
+        .take(usize::try_from(ms_to_frames(30) * MIXER_CHANNELS as u64).unwrap())
+        .collect();
+    assert_eq!(mixer.active.len(), 3);
+    assert_eq!(mixer.stolen_voices().load(Ordering::Relaxed), 0);
+}
+
+#[test]
+fn test_piece_source_plays_the_whole_piece() {
+    use crate::prelude::*;
+
+    let source = Piece::from(piano(quarter(C4) + quarter(E4))).into_source(600);
+    assert_eq!(source.channels(), MIXER_CHANNELS);
+    // Two quarters are eight beats, of 100 ms each
+    assert_eq!(source.total_duration(), Some(Duration::from_millis(800)));
+    assert!(source.map(f32::abs).fold(0.0, f32::max) > 0.0);
+}
+
+#[cfg(feature = "wav-output")]
+#[test]
+fn test_mixer_decodes_files_before_playing() {
+    use crate::prelude::*;
+
+    let path = std::env::temp_dir().join(format!("symphoxy_mixer_prepare_{}.wav", std::process::id()));
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: 1000,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+    for _ in 0..100 {
+        writer.write_sample(i16::MAX / 2).unwrap();
+    }
+    writer.finalize().unwrap();
+
+    // The file is decoded when the piece is scheduled, so it plays even once it's gone
+    let line = quarter(C4).with_timbre(Timbre::custom_unpitched(&path));
+    let mixer = MixerSource::new(&line, 10, 1.0, 0, &Mixer::new());
+    std::fs::remove_file(&path).unwrap();
+    assert!(mixer.map(f32::abs).fold(0.0, f32::max) > 0.1);
+}
+
+#[test]
+fn test_mixer_follows_its_rate() {
+    use crate::prelude::*;
+
+    // At double speed, the note after two sixteenths of rest starts after one sixteenth's time
+    let line = sixteenth(REST) + sixteenth(REST) + piano(sixteenth(C4));
+    let rate: PlaybackRate = Arc::new(AtomicU64::new(2.0_f64.to_bits()));
+    let mixer = MixerSource::new(&line, 10, 1.0, 0, &Mixer::new()).with_rate(rate);
+    let samples: Vec<f32> = mixer.collect();
+
+    // A note's first sample can be silent
+    let first_sound = samples.iter().position(|&s| s != 0.0).unwrap() as u64 / MIXER_CHANNELS as u64;
+    assert!(first_sound.abs_diff(ms_to_frames(10)) <= 1);
+}
@@ -1,25 +1,111 @@
-#![expect(private_bounds, reason = "This is a public API, but the MusicOutput trait is private to prevent misuse")]
-
+#[cfg(feature = "wav-output")]
+use std::collections::HashMap;
+use std::sync::Arc;
 #[cfg(feature = "live-output")]
-use std::{
-    sync::Arc,
-    thread::{self, JoinHandle},
-    time::Duration,
-};
+use std::{sync::atomic::Ordering, thread, time::Duration};
 
+#[cfg(feature = "wav-output")]
+mod analysis;
+#[cfg(feature = "wav-output")]
+mod audio_buffer;
+mod backend;
+#[cfg(feature = "wav-output")]
+mod balance;
+mod beat_map;
+#[cfg(feature = "live-output")]
+mod clock_sync;
+mod decode_cache;
+pub(crate) mod fft;
+mod fm;
+#[cfg(feature = "live-output")]
+mod levels;
+#[cfg(feature = "midi-live")]
+mod midi_clock;
+#[cfg(feature = "midi-live")]
+mod midi_output;
+mod mix;
+#[cfg(feature = "live-output")]
+mod mixer;
+mod modulation;
+#[cfg(feature = "wav-output")]
+mod normalization;
+mod oscillator;
+#[cfg(feature = "live-output")]
+mod playback_handle;
+mod progress;
+#[cfg(feature = "wav-output")]
+mod regression;
+#[cfg(feature = "wav-output")]
+mod render_project;
+#[cfg(feature = "flac-output")]
+mod render_to_flac;
+#[cfg(feature = "mp3-output")]
+mod render_to_mp3;
+#[cfg(feature = "ogg-output")]
+mod render_to_ogg;
 #[cfg(feature = "wav-output")]
 mod render_to_wav;
+#[cfg(feature = "wav-output")]
+mod resampling;
+#[cfg(feature = "wav-output")]
+mod sample_pack;
 pub mod sources;
+#[cfg(feature = "wav-output")]
+mod spectrogram;
+#[cfg(feature = "wav-output")]
+mod stems;
+#[cfg(feature = "live-output")]
+mod voices;
 
 #[cfg(feature = "live-output")]
-use crate::{play::sources::get_source, NoteKind};
+pub use crate::play::clock_sync::ExternalClock;
+#[cfg(feature = "live-output")]
+pub use crate::play::levels::Levels;
+#[cfg(feature = "midi-live")]
+pub use crate::play::midi_clock::MidiClock;
+#[cfg(feature = "midi-live")]
+pub use crate::play::midi_output::{MidiChannel, MidiOutputConfig, MidiPlayback};
+#[cfg(feature = "live-output")]
+use crate::play::mixer::{frames_to_ms, MixerSource, PlaybackRate, LIVE_GAIN};
+#[cfg(feature = "live-output")]
+pub use crate::play::playback_handle::PlaybackHandle;
+#[cfg(feature = "live-output")]
+pub use crate::play::voices::{VoiceLimit, VoiceStealing};
 
-use crate::{Line, Note, Piece};
+#[cfg(feature = "wav-output")]
+pub use crate::play::analysis::{Loudness, RenderReport};
+#[cfg(feature = "wav-output")]
+pub use crate::play::audio_buffer::AudioBuffer;
+pub use crate::play::backend::{OutputBackend, OutputNote};
+#[cfg(feature = "wav-output")]
+pub use crate::play::balance::BalanceProfile;
+pub use crate::play::beat_map::{Beat, BeatMap};
+pub use crate::play::decode_cache::clear_decode_cache;
+pub use crate::play::mix::{LineStrip, Mixer};
+#[cfg(feature = "wav-output")]
+pub use crate::play::normalization::{Clipping, NormalizationMode};
+pub use crate::play::progress::RenderProgress;
+#[cfg(feature = "wav-output")]
+pub use crate::play::regression::{compare_renders, RenderDifference};
+#[cfg(feature = "wav-output")]
+pub use crate::play::render_to_wav::RenderTail;
+#[cfg(feature = "wav-output")]
+pub use crate::play::resampling::ResamplingQuality;
+#[cfg(feature = "wav-output")]
+pub use crate::play::spectrogram::SpectrogramOptions;
+#[cfg(feature = "wav-output")]
+pub use crate::play::stems::StemGrouping;
+#[cfg(feature = "live-output")]
+use crate::{note::chord::Chord, note::LengthFluid, NoteLength};
+use crate::{rng::Rng, Effect, Line, Note, Piece, TICKS_PER_SIXTEENTH};
+#[cfg(feature = "wav-output")]
+use crate::{Metadata, Timbre};
 
 /// Creates a configuration for this music library
 ///
 /// # Example
 /// ```no_run
+/// # #[cfg(feature = "wav-output")] {
 /// use symphoxy::MusicPlayer;
 /// use symphoxy::prelude::*;
 ///
@@ -28,11 +114,16 @@ use crate::{Line, Note, Piece};
 /// let player = MusicPlayer::new_file(300, 1.0, 44100);
 ///
 /// player.render_to_wav(piece, "path/to/output.wav");
+/// # }
 /// ```
 pub struct MusicPlayer<O: MusicOutput + Clone> {
     /// Tempo in beats per minute (default: 300 BPM which gives 200ms per beat)
     pub(crate) tempo_bpm: u32,
     pub(crate) output_config: O,
+    /// Decides which notes with a probability are played (default: a new seed every time)
+    pub(crate) seed: Option<u64>,
+    /// The gain, pan, mute, and solo of each line (default: every line as it is)
+    pub(crate) mixer: Mixer,
 }
 
 impl<O: MusicOutput + Clone> MusicPlayer<O> {
@@ -40,8 +131,113 @@ impl<O: MusicOutput + Clone> MusicPlayer<O> {
     pub(crate) fn beat_duration_ms(&self) -> u64 {
         60_000u64.checked_div(self.tempo_bpm as u64).unwrap_or(u64::MAX)
     }
+
+    /// Sets the seed which decides which notes with a probability are played.
+    ///
+    /// Without a seed, notes made with [`Note::with_probability`] are decided afresh every time
+    /// a piece is played or rendered. With one, every pass plays the same notes.
+    ///
+    /// # Arguments
+    /// * `seed` - Any number. The same seed always plays the same notes.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # #[cfg(feature = "wav-output")] {
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let fill = drums(eighth(C4) * 8).notes.iter().map(|note| note.with_probability(0.5)).collect::<Vec<_>>();
+    /// let player = MusicPlayer::new_file(300, 1.0, 44100).with_seed(7);
+    ///
+    /// // Both files have the same notes
+    /// player.render_to_wav(Line::from(fill.clone()), "take_1.wav");
+    /// player.render_to_wav(Line::from(fill), "take_2.wav");
+    /// # }
+    /// ```
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Sets the gain, pan, mute, and solo of each line, for both playback and rendering.
+    ///
+    /// # Arguments
+    /// * `mixer` - The settings of each line. See [`Mixer`] for an example.
+    pub fn with_mixer(mut self, mixer: Mixer) -> Self {
+        self.mixer = mixer;
+        self
+    }
+
+    /// Returns the mixer, to change the settings of a line.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # #[cfg(feature = "wav-output")] {
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let mut player = MusicPlayer::new_file(300, 1.0, 44100);
+    /// player.mixer_mut().strip_mut(0).solo = true;
+    /// # }
+    /// ```
+    pub fn mixer_mut(&mut self) -> &mut Mixer {
+        &mut self.mixer
+    }
+
+    /// Returns the seed for one pass of playing or rendering.
+    pub(crate) fn pass_seed(&self) -> u64 {
+        self.seed.unwrap_or_else(|| Rng::from_time().next_u64())
+    }
+}
+
+/// Decides whether a note plays in a pass with the given seed, given its chance of playing.
+///
+/// The decision only depends on the seed and where the note is, so every part of a pass,
+/// and every range rendered with the same seed, agrees.
+pub(crate) fn note_plays(seed: u64, instant: usize, index: usize, probability: f32) -> bool {
+    if probability >= 1.0 {
+        return true;
+    }
+    let position =
+        (instant as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ (index as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+    #[expect(clippy::cast_precision_loss, reason = "Only used for probabilities")]
+    let roll = Rng::new(seed ^ position).next_u64() as f32 / u64::MAX as f32;
+    roll < probability
+}
+
+/// Splits a note into the retriggers of its ratchet, returning the start (relative to the note)
+/// and length of each, in milliseconds.
+pub(crate) fn ratchet_hits(duration_ms: u64, ratchet: u8) -> impl Iterator<Item = (u64, u64)> {
+    let hits = u64::from(ratchet.max(1));
+    let boundary = move |hit: u64| duration_ms.saturating_mul(hit).checked_div(hits).unwrap_or_default();
+    (0..hits).map(move |hit| {
+        let start = boundary(hit);
+        (start, boundary(hit.saturating_add(1)).saturating_sub(start))
+    })
+}
+
+/// Returns the MIDI note number of a pitch, rounded to the nearest semitone.
+#[cfg(any(feature = "wav-output", feature = "midi-live"))]
+#[expect(clippy::cast_possible_truncation, reason = "Audible pitches are within a few hundred semitones")]
+pub(crate) fn midi_number(pitch: crate::NotePitch) -> i16 {
+    (69.0 + 12.0 * (pitch.0 / crate::A4.0).log2()).round() as i16
 }
 
+/// Converts a number of ticks to milliseconds, given the length of a beat (a sixteenth note).
+pub(crate) fn ticks_to_ms(ticks: usize, beat_duration_ms: u64) -> u64 {
+    (ticks as u64)
+        .saturating_mul(beat_duration_ms)
+        .checked_div(TICKS_PER_SIXTEENTH as u64)
+        .unwrap_or_default()
+}
+
+/// How long effects are given to decay after the last note stops, in milliseconds, as reverbs and
+/// delays can ring on long after the notes feeding them stop.
+pub(crate) const EFFECT_TAIL_MS: u64 = 5000;
+
+/// How often live playback checks its position, to report progress.
+#[cfg(feature = "live-output")]
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(10);
+
 #[cfg(feature = "live-output")]
 impl MusicPlayer<LiveOutputConfig> {
     /// Creates a new music player for live audio output.
@@ -62,20 +258,29 @@ impl MusicPlayer<LiveOutputConfig> {
     pub fn new_live(tempo_bpm: u32, output_handle: Arc<rodio::OutputStreamHandle>) -> Self {
         Self {
             tempo_bpm,
-            output_config: LiveOutputConfig { output_handle },
+            output_config: LiveOutputConfig {
+                output_handle,
+                voice_limit: None,
+            },
+            seed: None,
+            mixer: Mixer::new(),
         }
     }
 
     /// Plays a musical piece through the live audio output.
     ///
-    /// This method spawns a background thread to handle audio playback and returns
-    /// a join handle that can be used to wait for playback completion.
+    /// Every note is mixed into a single audio stream with sample-accurate start times,
+    /// so long or dense pieces stay in time. Playback happens in the background.
     ///
     /// # Arguments
     /// * `piece` - Any playable musical content (Note, Chord, Line, Piece, etc.)
     ///
     /// # Returns
-    /// A `JoinHandle` that resolves when playback is complete
+    /// A [`PlaybackHandle`] which can pause, resume, stop, or seek the playback,
+    /// or be joined to wait for playback to finish.
+    ///
+    /// # Panics
+    /// Panics if an audio sink can't be created on the output stream.
     ///
     /// # Example
     /// ```no_run
@@ -90,11 +295,280 @@ impl MusicPlayer<LiveOutputConfig> {
     /// let handle = player.play(note);
     /// handle.join().unwrap(); // Wait for playback to finish
     /// ```
-    pub fn play<T: Playable + Clone + Send + Sync + 'static>(&self, piece: T) -> std::thread::JoinHandle<()> {
-        piece.play(self.output_config.output_handle.clone(), self.beat_duration_ms())
+    pub fn play<T: Playable>(&self, piece: T) -> PlaybackHandle {
+        self.play_from(piece, 0)
+    }
+
+    /// Plays a musical piece through the live audio output, starting part way through.
+    ///
+    /// This is for auditioning a section without listening to everything before it. Notes which
+    /// started before the given beat, but are still sounding at it, are played from part way
+    /// through, so they sound as they would if the piece had been playing all along. To render
+    /// a section to a file instead, use `MusicPlayer::render_range_to_wav`.
+    ///
+    /// # Arguments
+    /// * `piece` - Any playable musical content (Note, Chord, Line, Piece, etc.)
+    /// * `start_beat` - The beat (sixteenth note) to start from. Starting past the end of the
+    ///   piece plays nothing.
+    ///
+    /// # Returns
+    /// A [`PlaybackHandle`] for the playback. Its position counts from the start of the piece,
+    /// not from `start_beat`.
+    ///
+    /// # Panics
+    /// Panics if an audio sink can't be created on the output stream.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    /// use std::sync::Arc;
+    ///
+    /// let (_stream, handle) = rodio::OutputStream::try_default().unwrap();
+    /// let player = MusicPlayer::new_live(300, Arc::new(handle));
+    ///
+    /// let meter = Meter::new(TimeSignature::COMMON_TIME);
+    /// let song = piano(whole(C4) * 39 + whole(G4) + whole(C4));
+    ///
+    /// // Start at bar 40, the G4
+    /// let bar_40 = meter.bar_start(40) / TICKS_PER_SIXTEENTH as usize;
+    /// player.play_from(song, bar_40).join().unwrap();
+    /// ```
+    pub fn play_from<T: Playable>(&self, piece: T, start_beat: usize) -> PlaybackHandle {
+        self.start_playback(piece, start_beat, None, None)
+    }
+
+    /// Plays a musical piece through the live audio output, reporting how far it has got as it plays.
+    ///
+    /// This is [`MusicPlayer::play`], which calls `progress` from the playback thread each time
+    /// a new beat starts, and once more when playback finishes or is stopped. It can drive a
+    /// progress bar without polling the [`PlaybackHandle`].
+    ///
+    /// # Arguments
+    /// * `piece` - Any playable musical content (Note, Chord, Line, Piece, etc.)
+    /// * `progress` - Called with how far playback has got
+    ///
+    /// # Returns
+    /// A [`PlaybackHandle`] for the playback. Joining it waits for the last call to `progress`.
+    ///
+    /// # Panics
+    /// Panics if an audio sink can't be created on the output stream.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    /// use std::sync::Arc;
+    ///
+    /// let (_stream, handle) = rodio::OutputStream::try_default().unwrap();
+    /// let player = MusicPlayer::new_live(300, Arc::new(handle));
+    ///
+    /// let playback = player.play_with_progress(piano(whole(C4) + whole(A4)), |progress| {
+    ///     println!("Beat {} of {}", progress.beat, progress.total_beats);
+    /// });
+    /// playback.join().unwrap();
+    /// ```
+    pub fn play_with_progress<T: Playable>(
+        &self,
+        piece: T,
+        mut progress: impl FnMut(RenderProgress) + Send + 'static,
+    ) -> PlaybackHandle {
+        let piece = piece.to_piece();
+        let total_ticks = piece.length();
+        let beat_duration_ms = self.beat_duration_ms();
+        let mut last = None;
+        let report = move |ms: u64| {
+            let ticks = ms
+                .saturating_mul(u64::from(TICKS_PER_SIXTEENTH))
+                .checked_div(beat_duration_ms)
+                .unwrap_or_default();
+            let now = RenderProgress::from_ticks(usize::try_from(ticks).unwrap_or(usize::MAX), total_ticks);
+            if last != Some(now) {
+                last = Some(now);
+                progress(now);
+            }
+        };
+        self.start_playback(piece, 0, Some(Box::new(report)), None)
+    }
+
+    /// Starts playing a piece from the given beat. If there's a progress callback, the playback
+    /// thread calls it with how far into the piece playback is, in milliseconds, as it plays.
+    /// If there's a rate, the piece moves through its notes at that rate, which can be changed as it plays.
+    fn start_playback<T: Playable>(
+        &self,
+        piece: T,
+        start_beat: usize,
+        progress: Option<Box<dyn FnMut(u64) + Send>>,
+        rate: Option<PlaybackRate>,
+    ) -> PlaybackHandle {
+        let mut source = MixerSource::new(
+            &piece,
+            self.beat_duration_ms(),
+            LIVE_GAIN,
+            self.pass_seed(),
+            &self.mixer,
+        )
+        .with_voice_limit(self.output_config.voice_limit);
+        if let Some(rate) = rate {
+            source = source.with_rate(rate);
+        }
+        if start_beat > 0 {
+            let start_ms = (start_beat as u64).saturating_mul(self.beat_duration_ms());
+            source.seek(std::time::Duration::from_millis(start_ms));
+        }
+        let position = source.position();
+        let stolen_voices = source.stolen_voices();
+        let end_frame = source.end_frame();
+        let level_subscribers = source.level_subscribers();
+
+        let sink =
+            Arc::new(rodio::Sink::try_new(&self.output_config.output_handle).expect("Failed to create an audio sink"));
+        sink.append(source);
+
+        let thread_sink = sink.clone();
+        let thread = match progress {
+            None => thread::spawn(move || thread_sink.sleep_until_end()),
+            Some(mut progress) => {
+                let position = position.clone();
+                thread::spawn(move || {
+                    while !thread_sink.empty() {
+                        progress(frames_to_ms(position.load(Ordering::Relaxed)));
+                        thread::sleep(PROGRESS_INTERVAL);
+                    }
+                    progress(frames_to_ms(position.load(Ordering::Relaxed)));
+                })
+            }
+        };
+
+        PlaybackHandle {
+            sink,
+            position,
+            end_frame,
+            beat_duration_ms: self.beat_duration_ms(),
+            level_subscribers,
+            stolen_voices,
+            thread,
+        }
+    }
+
+    /// Limits how many notes are played at once, to keep dense pieces from using more CPU than
+    /// the machine has.
+    ///
+    /// By default, there's no limit. With one, starting a note when every voice is in use cuts
+    /// another note off, as the limit says. [`PlaybackHandle::stolen_voices`] reports how often
+    /// that happened, so the limit can be tuned.
+    ///
+    /// # Arguments
+    /// * `voice_limit` - The most notes sounding at once, and which note is cut off to make room
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{MusicPlayer, VoiceLimit, VoiceStealing};
+    /// use std::sync::Arc;
+    ///
+    /// let (_stream, handle) = rodio::OutputStream::try_default().unwrap();
+    /// let player = MusicPlayer::new_live(300, Arc::new(handle))
+    ///     .with_voice_limit(VoiceLimit::new(16).with_stealing(VoiceStealing::Quietest));
+    ///
+    /// let cluster = Piece::from((0..24).map(|step| Line::from(piano(whole(C4.semitone(step))))).collect::<Vec<_>>());
+    /// let playback = player.play(cluster);
+    /// std::thread::sleep(std::time::Duration::from_millis(500));
+    /// println!("{} notes were cut off", playback.stolen_voices());
+    /// ```
+    pub fn with_voice_limit(mut self, voice_limit: VoiceLimit) -> Self {
+        self.output_config.voice_limit = Some(voice_limit);
+        self
+    }
+
+    /// Plays a single line on its own, at the player's tempo.
+    ///
+    /// This is a shortcut for auditioning part of a piece without building a new [`Piece`].
+    ///
+    /// # Arguments
+    /// * `line` - The line to play
+    ///
+    /// # Returns
+    /// A [`PlaybackHandle`] for the preview.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    /// use std::sync::Arc;
+    ///
+    /// let (_stream, handle) = rodio::OutputStream::try_default().unwrap();
+    /// let player = MusicPlayer::new_live(300, Arc::new(handle));
+    ///
+    /// let melody = piano(quarter(C4) + quarter(A4));
+    /// let bassline = bass(half(C4.octave(-2)));
+    /// let piece = melody.clone() * bassline;
+    ///
+    /// player.preview(&melody).join().unwrap();
+    /// ```
+    pub fn preview(&self, line: &Line) -> PlaybackHandle {
+        self.play(line.clone())
+    }
+
+    /// Plays a chord on its own for the given length, at the player's tempo.
+    ///
+    /// The chord is played with the default timbre. This is a shortcut for auditioning a voicing.
+    ///
+    /// # Arguments
+    /// * `chord` - The chord to play
+    /// * `length` - How long to hold the chord
+    ///
+    /// # Returns
+    /// A [`PlaybackHandle`] for the preview.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    /// use std::sync::Arc;
+    ///
+    /// let (_stream, handle) = rodio::OutputStream::try_default().unwrap();
+    /// let player = MusicPlayer::new_live(300, Arc::new(handle));
+    ///
+    /// let voicing = Chord::new(C4.semitones([0, 7, 16]));
+    /// player.preview_chord(&voicing, half(C4).0).join().unwrap();
+    /// ```
+    pub fn preview_chord(&self, chord: &Chord, length: NoteLength) -> PlaybackHandle {
+        self.play(chord.clone().with_length(length))
+    }
+
+    /// Plays only the notes which differ between two versions of a piece.
+    ///
+    /// Notes which were removed are panned hard left, and notes which were added are panned
+    /// hard right, so you can hear exactly what an edit changed. See [`Piece::diff`].
+    ///
+    /// # Arguments
+    /// * `old` - The piece before the edit
+    /// * `new` - The piece after the edit
+    ///
+    /// # Returns
+    /// A [`PlaybackHandle`] for the playback.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    /// use std::sync::Arc;
+    ///
+    /// let (_stream, handle) = rodio::OutputStream::try_default().unwrap();
+    /// let player = MusicPlayer::new_live(300, Arc::new(handle));
+    ///
+    /// let old = Piece::from(piano(quarter(C4) + quarter(E4) + half(G4)));
+    /// let new = Piece::from(piano(quarter(C4) + quarter(F4) + half(G4)));
+    ///
+    /// player.play_diff(&old, &new).join().unwrap(); // E4 on the left, then F4 on the right
+    /// ```
+    pub fn play_diff(&self, old: &Piece, new: &Piece) -> PlaybackHandle {
+        self.play(old.diff(new).to_piece())
     }
 }
 
+#[cfg(feature = "wav-output")]
 impl MusicPlayer<FileOutputConfig> {
     /// Creates a new music player for file output (WAV rendering).
     ///
@@ -120,27 +594,307 @@ impl MusicPlayer<FileOutputConfig> {
             output_config: FileOutputConfig {
                 output_gain,
                 sample_rate,
+                buses: HashMap::new(),
+                master_effects: vec![],
+                render_threads: None,
+                normalization: NormalizationMode::Peak,
+                clipping: Clipping::Hard,
+                resampling: ResamplingQuality::Cubic,
+                tail: RenderTail::Off,
+                metadata: Metadata::default(),
             },
+            seed: None,
+            mixer: Mixer::new(),
         }
     }
 
+    /// Routes every note of a timbre to its own output bus when rendering.
+    ///
+    /// The rendered WAV file has enough channels for every bus, so routing lets you deliver
+    /// stems (e.g. drums on channels 3-4) in a single render. Timbres which aren't routed
+    /// are mixed into the first channels as usual.
+    ///
+    /// # Arguments
+    /// * `timbre` - The timbre whose notes are routed
+    /// * `bus` - The channels those notes are written to
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{MusicPlayer, OutputBus};
+    ///
+    /// let piece = piano(quarter(C4) + quarter(A4)) * drums(quarter(C4) + quarter(C4.octave(1)));
+    ///
+    /// // Piano on channels 1-2, drums on channels 3-4
+    /// let player = MusicPlayer::new_file(300, 1.0, 44100)
+    ///     .with_bus(Timbre::Piano, OutputBus::new("Keys", 0, 2))
+    ///     .with_bus(Timbre::Drums, OutputBus::new("Drums", 2, 2));
+    ///
+    /// player.render_to_wav(piece, "stems.wav");
+    /// ```
+    pub fn with_bus(mut self, timbre: Timbre, bus: OutputBus) -> Self {
+        self.output_config.buses.insert(timbre, bus);
+        self
+    }
+
+    /// Adds an effect to the end of the master effects chain.
+    ///
+    /// Master effects are applied to the whole mix, after every line has been mixed together
+    /// and the mix has been normalized, and before the output gain. They're applied to every
+    /// channel of the file, including any buses. A [`Limiter`](crate::Limiter) at the end of
+    /// the chain keeps the other effects from clipping.
+    ///
+    /// # Arguments
+    /// * `effect` - The effect to apply to the mix
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let piece = piano(quarter(C4) + quarter(A4)) * bass(half(C4.octave(-1)));
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 44100)
+    ///     .with_master_effect(Equalizer { low_db: 2.0, mid_db: 0.0, high_db: -1.0 })
+    ///     .with_master_effect(Reverb { mix: 0.15, ..Reverb::default() })
+    ///     .with_master_effect(Limiter::default());
+    ///
+    /// player.render_to_wav(piece, "polished.wav");
+    /// ```
+    pub fn with_master_effect(mut self, effect: impl Effect + 'static) -> Self {
+        self.output_config.master_effects.push(Arc::new(effect));
+        self
+    }
+
+    /// Sets how many threads notes are rendered on.
+    ///
+    /// By default, notes are rendered on one thread per CPU core. Notes are always mixed in the
+    /// same order, so the rendered audio is exactly the same however many threads are used.
+    ///
+    /// # Arguments
+    /// * `threads` - The number of threads. 1 renders every note on the calling thread.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// // Leave the other cores free
+    /// let player = MusicPlayer::new_file(300, 1.0, 44100).with_render_threads(2);
+    /// player.render_to_wav(piano(quarter(C4) + quarter(A4)), "output.wav");
+    /// ```
+    pub fn with_render_threads(mut self, threads: usize) -> Self {
+        self.output_config.render_threads = Some(threads);
+        self
+    }
+
+    /// Sets how the mix is scaled before it's written.
+    ///
+    /// By default, the mix is scaled so its loudest sample is at full scale. The mix is normalized
+    /// before the master effects and the output gain are applied.
+    ///
+    /// # Arguments
+    /// * `normalization` - How the mix is scaled
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{Clipping, MusicPlayer, NormalizationMode};
+    ///
+    /// // Aim for the loudness of a streaming service, rounding off any peaks
+    /// let player = MusicPlayer::new_file(300, 1.0, 44100)
+    ///     .with_normalization(NormalizationMode::Lufs(-14.0))
+    ///     .with_clipping(Clipping::Soft);
+    /// player.render_to_wav(piano(quarter(C4) + quarter(A4)), "output.wav");
+    /// ```
+    pub fn with_normalization(mut self, normalization: NormalizationMode) -> Self {
+        self.output_config.normalization = normalization;
+        self
+    }
+
+    /// Sets how samples past full scale are handled when the mix is written.
+    ///
+    /// By default, they're cut off. Clipping is handled last, after the master effects and the output gain.
+    ///
+    /// # Arguments
+    /// * `clipping` - How samples past full scale are handled
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{Clipping, MusicPlayer, NormalizationMode};
+    ///
+    /// // Keep the notes' own loudness, and turn the mix down wherever it's too loud
+    /// let player = MusicPlayer::new_file(300, 1.0, 44100)
+    ///     .with_normalization(NormalizationMode::Off)
+    ///     .with_clipping(Clipping::Limit(Limiter::default()));
+    /// player.render_to_wav(piano(quarter(C4) + quarter(A4)), "output.wav");
+    /// ```
+    pub fn with_clipping(mut self, clipping: Clipping) -> Self {
+        self.output_config.clipping = clipping;
+        self
+    }
+
+    /// Sets how notes are resampled when their source's sample rate isn't the output's, such as
+    /// custom samples, which are pitched by resampling them.
+    ///
+    /// By default, notes are resampled with cubic interpolation. Use
+    /// [`ResamplingQuality::Linear`] for quicker previews, and [`ResamplingQuality::Sinc`] for
+    /// the cleanest masters.
+    ///
+    /// # Arguments
+    /// * `resampling` - How notes are resampled
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{MusicPlayer, ResamplingQuality};
+    ///
+    /// let master = MusicPlayer::new_file(300, 1.0, 48000).with_resampling(ResamplingQuality::Sinc);
+    /// master.render_to_wav(piano(quarter(C4) + quarter(A4)), "output.wav");
+    /// ```
+    pub fn with_resampling(mut self, resampling: ResamplingQuality) -> Self {
+        self.output_config.resampling = resampling;
+        self
+    }
+
+    /// Sets how far renders carry on past the end of the piece, so its last notes ring out.
+    ///
+    /// By default, renders stop exactly when the piece ends, which cuts off the release of any
+    /// notes still sounding, and the decay of any effects. Only renders which reach the end of
+    /// the piece get a tail.
+    ///
+    /// # Arguments
+    /// * `tail` - How far renders carry on past the end
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{MusicPlayer, RenderTail};
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 44100).with_tail(RenderTail::Auto);
+    /// player.render_to_wav(piano(quarter(C4) + whole(G4)), "output.wav");
+    /// ```
+    pub fn with_tail(mut self, tail: RenderTail) -> Self {
+        self.output_config.tail = tail;
+        self
+    }
+
+    /// Sets the metadata written into rendered files, such as the title and artist, so music
+    /// players and audio editors can show it.
+    ///
+    /// WAV files get it in a LIST INFO chunk. The key and tempo have no place of their own there,
+    /// so they're written as a comment.
+    ///
+    /// # Arguments
+    /// * `metadata` - The metadata of the piece being rendered
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{Metadata, MusicPlayer};
+    ///
+    /// let metadata = Metadata::new("Scale Study").with_artist("Symphoxy").with_key("C major");
+    /// let player = MusicPlayer::new_file(300, 1.0, 44100).with_metadata(metadata);
+    /// player.render_to_wav(piano(quarter(C4) + quarter(E4) + half(G4)), "scale_study.wav");
+    /// ```
+    pub fn with_metadata(mut self, metadata: Metadata) -> Self {
+        self.output_config.metadata = metadata;
+        self
+    }
+
+    /// Returns how many threads notes are rendered on.
+    pub(crate) fn render_threads(&self) -> usize {
+        self.output_config
+            .render_threads
+            .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, usize::from))
+    }
+
     /* See render_to_wav.rs for implementation */
 }
 
-trait MusicOutput {}
+/// The output a [`MusicPlayer`] plays to.
+///
+/// It's implemented by the file and live output configurations, for the outputs the crate
+/// supports, and by every [`OutputBackend`], for outputs it doesn't.
+pub trait MusicOutput {}
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg(feature = "wav-output")]
 pub struct FileOutputConfig {
     /// Gain applied to the output audio (default: 1.0)
     pub output_gain: f32,
     /// Sample rate for audio generation (default: 44100 Hz)
     pub sample_rate: u32,
+    /// The output bus each timbre is routed to (default: none, so everything is mixed together)
+    pub buses: HashMap<Timbre, OutputBus>,
+    /// The effects applied, in order, to the whole mix (default: none)
+    pub master_effects: Vec<Arc<dyn Effect>>,
+    /// How many threads notes are rendered on (default: `None`, for one per CPU core)
+    pub render_threads: Option<usize>,
+    /// How the mix is scaled before the master effects (default: to its peak)
+    pub normalization: NormalizationMode,
+    /// How samples past full scale are handled, after the output gain (default: hard clipping)
+    pub clipping: Clipping,
+    /// How notes are resampled when their source's sample rate isn't the output's (default: cubic)
+    pub resampling: ResamplingQuality,
+    /// How far renders carry on past the end of the piece (default: not at all)
+    pub tail: RenderTail,
+    /// The metadata written into rendered files, such as the title (default: none)
+    pub metadata: Metadata,
+}
+
+/// A named group of adjacent channels in a rendered file, which a timbre can be routed to.
+///
+/// Channels are numbered from zero, so a stereo bus on channels 3-4 starts at channel 2.
+///
+/// # Example
+/// ```
+/// use symphoxy::OutputBus;
+///
+/// let drums = OutputBus::new("Drums", 2, 2);
+/// assert_eq!(drums.channel_range(), 2..4);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg(feature = "wav-output")]
+pub struct OutputBus {
+    /// The name of the bus, such as "Drums"
+    pub name: String,
+    /// The first channel of the bus
+    pub first_channel: u16,
+    /// The number of channels in the bus. Stereo buses respect each note's pan.
+    pub channels: u16,
+}
+
+#[cfg(feature = "wav-output")]
+impl OutputBus {
+    /// Creates a new output bus.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the bus
+    /// * `first_channel` - The first channel of the bus, counting from zero
+    /// * `channels` - The number of channels in the bus. This is at least one.
+    pub fn new(name: impl Into<String>, first_channel: u16, channels: u16) -> Self {
+        OutputBus {
+            name: name.into(),
+            first_channel,
+            channels: channels.max(1),
+        }
+    }
+
+    /// Returns the range of channels this bus covers.
+    pub fn channel_range(&self) -> std::ops::Range<usize> {
+        let start = usize::from(self.first_channel);
+        start..start.saturating_add(usize::from(self.channels))
+    }
 }
 
 #[derive(Clone)]
 #[cfg(feature = "live-output")]
 pub struct LiveOutputConfig {
     pub output_handle: Arc<rodio::OutputStreamHandle>,
+    /// The most notes played at once, and how notes are cut off to make room (default: no limit)
+    pub voice_limit: Option<VoiceLimit>,
 }
 
 #[cfg(feature = "wav-output")]
@@ -152,6 +906,14 @@ impl Default for FileOutputConfig {
         FileOutputConfig {
             output_gain: 1.0,
             sample_rate: 44100,
+            buses: HashMap::new(),
+            master_effects: vec![],
+            render_threads: None,
+            normalization: NormalizationMode::Peak,
+            clipping: Clipping::Hard,
+            resampling: ResamplingQuality::Cubic,
+            tail: RenderTail::Off,
+            metadata: Metadata::default(),
         }
     }
 }
@@ -159,114 +921,70 @@ impl Default for FileOutputConfig {
 #[cfg(feature = "live-output")]
 impl MusicOutput for LiveOutputConfig {}
 
-pub(crate) trait Playable {
-    #[cfg(feature = "wav-output")]
-    fn length(&self) -> usize;
+#[cfg(feature = "midi-live")]
+impl MusicOutput for MidiOutputConfig {}
 
-    #[cfg(feature = "wav-output")]
-    fn get_notes_at_instant(&self, instant: usize) -> impl Iterator<Item = Note>;
-
-    #[cfg(feature = "live-output")]
-    fn play(&self, output_handle: Arc<rodio::OutputStreamHandle>, beat_duration_ms: u64) -> JoinHandle<()>
-    where
-        Self: Send + Sync + Clone + 'static;
+/// Music which can be played live by a [`MusicPlayer`] or rendered to a file.
+///
+/// It's implemented for [`Piece`], [`Line`], and [`Note`], and for references to anything
+/// playable. Implement it for your own types, such as a score format or a generator, to play
+/// them directly: all they need to do is say which piece they play.
+///
+/// # Example
+/// ```no_run
+/// # #[cfg(feature = "wav-output")] {
+/// use symphoxy::prelude::*;
+/// use symphoxy::{MusicPlayer, Playable};
+///
+/// /// A major arpeggio, repeated a number of times
+/// struct Arpeggio {
+///     root: NotePitch,
+///     repeats: usize,
+/// }
+///
+/// impl Playable for Arpeggio {
+///     fn to_piece(&self) -> Piece {
+///         let [root, third, fifth] = self.root.semitones([0, 4, 7]);
+///         Piece::from(piano(sixteenth(root) + sixteenth(third) + sixteenth(fifth)) * self.repeats)
+///     }
+/// }
+///
+/// let player = MusicPlayer::new_file(300, 1.0, 44100);
+/// player.render_to_wav(Arpeggio { root: C4, repeats: 4 }, "arpeggio.wav");
+/// # }
+/// ```
+pub trait Playable {
+    /// Returns the music as a piece, with each part that plays at the same time as a line.
+    fn to_piece(&self) -> Piece;
 }
 
 impl Playable for Piece {
-    #[cfg(feature = "wav-output")]
-    fn length(&self) -> usize {
-        self.length()
-    }
-
-    #[cfg(feature = "wav-output")]
-    fn get_notes_at_instant(&self, instant: usize) -> impl Iterator<Item = Note> {
-        self.get_notes_at_instant(instant)
-    }
-
-    #[cfg(feature = "live-output")]
-    fn play(&self, output_handle: Arc<rodio::OutputStreamHandle>, beat_duration_ms: u64) -> JoinHandle<()> {
-        let piece = self.clone();
-
-        thread::spawn(move || {
-            let mut handles = Vec::new();
-            for instant in 0..piece.length() {
-                for note in piece.get_notes_at_instant(instant) {
-                    handles.push(note.play(output_handle.clone(), beat_duration_ms));
-                }
-
-                thread::sleep(Duration::from_millis(beat_duration_ms));
-            }
-
-            for handle in handles {
-                let _ = handle.join();
-            }
-        })
+    fn to_piece(&self) -> Piece {
+        self.clone()
     }
 }
 
 impl Playable for Line {
-    /// Returns the length of this line without regard for the pickup
-    #[cfg(feature = "wav-output")]
-    fn length(&self) -> usize {
-        self.length()
-    }
-
-    #[cfg(feature = "wav-output")]
-    fn get_notes_at_instant(&self, instant: usize) -> impl Iterator<Item = Note> {
-        self.get_notes_at_instant(instant)
-    }
-
-    #[cfg(feature = "live-output")]
-    fn play(&self, output_handle: Arc<rodio::OutputStreamHandle>, beat_duration_ms: u64) -> JoinHandle<()> {
-        let line = self.clone();
-
-        thread::spawn(move || {
-            let mut handles = Vec::new();
-            for instant in 0..line.length() {
-                for note in line.get_notes_at_instant(instant) {
-                    handles.push(note.play(output_handle.clone(), beat_duration_ms));
-                }
-
-                thread::sleep(Duration::from_millis(beat_duration_ms));
-            }
-
-            for handle in handles {
-                let _ = handle.join();
-            }
-        })
+    fn to_piece(&self) -> Piece {
+        Piece::from(vec![self.clone()])
     }
 }
 
 impl Playable for Note {
-    #[cfg(feature = "wav-output")]
-    fn length(&self) -> usize {
-        self.0 .0 as usize
+    fn to_piece(&self) -> Piece {
+        Piece::from(vec![Line::from(self.clone())])
     }
+}
 
-    #[cfg(feature = "wav-output")]
-    fn get_notes_at_instant(&self, instant: usize) -> impl Iterator<Item = Note> {
-        if instant == 0 {
-            Some(*self).into_iter()
-        } else {
-            None.into_iter()
-        }
+impl<T: Playable + ?Sized> Playable for &T {
+    fn to_piece(&self) -> Piece {
+        (**self).to_piece()
     }
+}
 
-    #[cfg(feature = "live-output")]
-    fn play(&self, output_handle: Arc<rodio::OutputStreamHandle>, beat_duration_ms: u64) -> JoinHandle<()> {
-        if let Note(length, NoteKind::Pitched { pitch, timbre, volume }) = *self {
-            #[expect(clippy::arithmetic_side_effects, reason = "User's fault")]
-            let duration_ms = length.0 as u64 * beat_duration_ms;
-
-            thread::spawn(move || {
-                let sink = rodio::Sink::try_new(&output_handle.clone()).unwrap();
-                // For some reason, playing live is way louder than file output. 64 is arbitrary, but seems about right.
-                sink.append(get_source(duration_ms, pitch.0, timbre, volume / 64.0));
-                thread::sleep(Duration::from_millis(duration_ms));
-                sink.sleep_until_end();
-            })
-        } else {
-            thread::spawn(|| {})
-        }
+impl Piece {
+    /// Returns the effects applied to the line with the given index.
+    pub(crate) fn line_effects(&self, line: usize) -> &[Arc<dyn Effect>] {
+        self.0.get(line).map_or(&[], |line| &line.effects)
     }
 }
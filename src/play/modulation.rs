@@ -0,0 +1,169 @@
+use std::{f32::consts::TAU, time::Duration};
+
+use rodio::Source;
+
+use crate::{
+    note::{Lfo, LfoTarget},
+    play::sources::SymphoxySource,
+    TICKS_PER_SIXTEENTH,
+};
+
+/// Applies each LFO to a source, in order. `beat_duration_ms` is the length of a sixteenth note,
+/// which sets the speed of the LFOs.
+pub fn apply_lfos(source: SymphoxySource, lfos: &[Lfo], beat_duration_ms: u64) -> SymphoxySource {
+    #[expect(clippy::cast_precision_loss, reason = "Beat durations are far below f32's precision limit")]
+    let tick_secs = beat_duration_ms as f32 / 1000.0 / f32::from(TICKS_PER_SIXTEENTH);
+
+    lfos.iter().fold(source, |source, lfo| {
+        let cycle_secs = f32::from(lfo.rate.ticks()) * tick_secs;
+        Box::new(LfoSource::new(source, *lfo, cycle_secs))
+    })
+}
+
+/// Modulates a source with an LFO.
+struct LfoSource<S: Source<Item = f32>> {
+    input: S,
+    lfo: Lfo,
+    cycle_secs: f32,
+    channels: usize,
+    sample_rate: u32,
+    /// The number of frames output so far
+    frames: u64,
+    /// The frame being output
+    frame: Vec<f32>,
+    /// The index of the next sample of `frame` to output
+    channel: usize,
+    /// For pitch modulation, the input frames either side of the playback position,
+    /// and how far between them it is
+    previous: Vec<f32>,
+    next: Vec<f32>,
+    position: f32,
+    /// For filter modulation, the filter's output for each channel
+    filtered: Vec<f32>,
+}
+
+impl<S: Source<Item = f32>> LfoSource<S> {
+    fn new(input: S, lfo: Lfo, cycle_secs: f32) -> Self {
+        let channels = usize::from(input.channels().max(1));
+        let sample_rate = input.sample_rate();
+        LfoSource {
+            input,
+            lfo,
+            cycle_secs,
+            channels,
+            sample_rate,
+            frames: 0,
+            frame: vec![0.0; channels],
+            channel: 0,
+            previous: vec![],
+            next: vec![],
+            position: 0.0,
+            filtered: vec![0.0; channels],
+        }
+    }
+
+    /// Reads one frame from the input, or `None` if the input has ended.
+    fn read_frame(&mut self) -> Option<Vec<f32>> {
+        (0..self.channels).map(|_| self.input.next()).collect()
+    }
+
+    /// Computes the next frame of output.
+    #[expect(clippy::cast_precision_loss, reason = "Only loses precision for notes lasting days")]
+    fn advance(&mut self) -> Option<()> {
+        let elapsed_secs = self.frames as f32 / self.sample_rate.max(1) as f32;
+        let cycles = if self.cycle_secs > 0.0 {
+            elapsed_secs / self.cycle_secs
+        } else {
+            0.0
+        };
+        let value = self.lfo.value_at(cycles);
+        self.frames = self.frames.saturating_add(1);
+
+        match self.lfo.target {
+            LfoTarget::Amplitude => {
+                let gain = 1.0 - self.lfo.depth * (1.0 - value) / 2.0;
+                self.frame = self.read_frame()?.into_iter().map(|sample| sample * gain).collect();
+            }
+            LfoTarget::FilterCutoff { cutoff } => {
+                let cutoff = cutoff * 2.0_f32.powf(self.lfo.depth * value);
+                // A one-pole low-pass filter
+                let smoothing = 1.0 - (-TAU * cutoff / self.sample_rate.max(1) as f32).exp();
+                let input = self.read_frame()?;
+                for (filtered, sample) in self.filtered.iter_mut().zip(input) {
+                    *filtered += smoothing * (sample - *filtered);
+                }
+                self.frame.clone_from(&self.filtered);
+            }
+            LfoTarget::Pitch => {
+                if self.next.is_empty() {
+                    self.previous = self.read_frame()?;
+                    self.next = self.read_frame()?;
+                }
+                self.frame = self
+                    .previous
+                    .iter()
+                    .zip(&self.next)
+                    .map(|(previous, next)| previous + (next - previous) * self.position)
+                    .collect();
+
+                // Play faster or slower than the input to raise or lower the pitch
+                self.position += 2.0_f32.powf(self.lfo.depth * value / 12.0);
+                while self.position >= 1.0 {
+                    self.previous = std::mem::take(&mut self.next);
+                    self.next = self.read_frame()?;
+                    self.position -= 1.0;
+                }
+            }
+        }
+
+        Some(())
+    }
+}
+
+impl<S: Source<Item = f32>> Iterator for LfoSource<S> {
+    type Item = f32;
+
+    #[expect(clippy::arithmetic_side_effects, reason = "The channel index is always below the channel count")]
+    fn next(&mut self) -> Option<f32> {
+        if self.channel == 0 {
+            self.advance()?;
+        }
+
+        let sample = self.frame[self.channel];
+        self.channel = (self.channel + 1) % self.channels;
+        Some(sample)
+    }
+}
+
+impl<S: Source<Item = f32>> Source for LfoSource<S> {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[expect(clippy::cast_possible_truncation, reason = "The channel count came from a u16")]
+    fn channels(&self) -> u16 {
+        self.channels as u16
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+}
+
+#[test]
+fn test_amplitude_lfo_follows_shape() {
+    use crate::{note::LfoShape, NoteLength};
+
+    // One cycle of the LFO lasts a quarter note, which is one second at this tempo
+    let lfo = Lfo::new(LfoTarget::Amplitude, LfoShape::Square, NoteLength::new(4), 1.0);
+    let constant = rodio::buffer::SamplesBuffer::new(1, 1000, vec![1.0_f32; 1000]);
+    let samples: Vec<f32> = apply_lfos(Box::new(constant), &[lfo], 250).collect();
+
+    assert_eq!(samples.len(), 1000);
+    assert!(samples[..500].iter().all(|&s| s == 1.0));
+    assert!(samples[500..].iter().all(|&s| s == 0.0));
+}
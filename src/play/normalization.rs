@@ -0,0 +1,168 @@
+use std::sync::Arc;
+
+use crate::{
+    effects::apply_effects,
+    play::{analysis::integrated_loudness, render_to_wav::normalize},
+    Effect, Limiter,
+};
+
+/// How a rendered mix is scaled before it's written, set with
+/// [`MusicPlayer::with_normalization`](crate::MusicPlayer::with_normalization).
+///
+/// Normalizing keeps quiet pieces from being written too quietly, but it means a piece's loudness
+/// doesn't depend on how loud its notes are. Turn it off, or normalize to a loudness, to compare
+/// renders with each other.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum NormalizationMode {
+    /// The mix is written as it was mixed, so renders keep their loudness relative to each other.
+    /// Loud mixes may clip.
+    Off,
+    /// The mix is scaled so its loudest sample is at full scale. Every channel is scaled together,
+    /// so panning is kept.
+    #[default]
+    Peak,
+    /// Each channel is scaled so its own loudest sample is at full scale. This evens out the
+    /// channels, so panning isn't kept.
+    PerChannelPeak,
+    /// The mix is scaled so its integrated loudness is the given target, in LUFS, such as -14.0
+    /// for streaming services. Loud targets may clip.
+    Lufs(f32),
+}
+
+impl NormalizationMode {
+    /// Scales the samples, and returns how much each channel was scaled by.
+    pub(super) fn apply(self, samples: &mut [Vec<f32>], sample_rate: u32) -> Vec<f32> {
+        let gains = match self {
+            NormalizationMode::Off => vec![1.0; samples.len()],
+            NormalizationMode::Peak => {
+                let peak = normalize(samples);
+                return vec![if peak > 0.0 { 1.0 / peak } else { 1.0 }; samples.len()];
+            }
+            NormalizationMode::PerChannelPeak => {
+                return samples
+                    .iter_mut()
+                    .map(|channel| {
+                        let peak = normalize(std::slice::from_mut(channel));
+                        if peak > 0.0 {
+                            1.0 / peak
+                        } else {
+                            1.0
+                        }
+                    })
+                    .collect();
+            }
+            NormalizationMode::Lufs(target) => {
+                let loudness = integrated_loudness(samples, sample_rate);
+                #[expect(clippy::cast_possible_truncation, reason = "Gains are far inside f32's range")]
+                let gain = if loudness.is_finite() {
+                    10.0_f64.powf((f64::from(target) - loudness) / 20.0) as f32
+                } else {
+                    1.0
+                };
+                vec![gain; samples.len()]
+            }
+        };
+        for (channel, gain) in samples.iter_mut().zip(&gains) {
+            for sample in channel {
+                *sample *= gain;
+            }
+        }
+        gains
+    }
+}
+
+/// How samples past full scale are handled when a mix is written, set with
+/// [`MusicPlayer::with_clipping`](crate::MusicPlayer::with_clipping).
+///
+/// This is applied last, after the master effects and the output gain.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Clipping {
+    /// Samples past full scale are cut off, which can sound harsh
+    #[default]
+    Hard,
+    /// Samples near full scale are rounded off smoothly, like an analog tape or amplifier.
+    /// Quieter samples are left as they are.
+    Soft,
+    /// The mix is turned down just enough to keep it under the limiter's ceiling
+    Limit(Limiter),
+}
+
+/// How loud a sample can be before it's rounded off by [`Clipping::Soft`].
+const SOFT_CLIP_KNEE: f32 = 0.5;
+
+impl Clipping {
+    /// Keeps the samples from going past full scale, or leaves them to be cut off when they're written.
+    pub(super) fn apply(self, samples: &mut [Vec<f32>], sample_rate: u32) {
+        match self {
+            Clipping::Hard => {}
+            Clipping::Soft => {
+                for sample in samples.iter_mut().flatten() {
+                    *sample = soft_clip(*sample);
+                }
+            }
+            Clipping::Limit(limiter) => {
+                let limiter: Arc<dyn Effect> = Arc::new(limiter);
+                apply_effects(&[limiter], samples, sample_rate);
+            }
+        }
+    }
+}
+
+/// Rounds off a sample so it approaches, but never reaches, full scale.
+fn soft_clip(sample: f32) -> f32 {
+    let level = sample.abs();
+    if level <= SOFT_CLIP_KNEE {
+        return sample;
+    }
+    let headroom = 1.0 - SOFT_CLIP_KNEE;
+    let rounded = SOFT_CLIP_KNEE + headroom * ((level - SOFT_CLIP_KNEE) / headroom).tanh();
+    rounded.copysign(sample)
+}
+
+#[test]
+fn test_normalization_modes() {
+    use crate::{prelude::*, MusicPlayer};
+
+    // The bass is panned left, so the right channel is quieter
+    let piece = piano(quarter(C4) + quarter(E4)) * bass(half(C2)).pan(-0.8);
+    let peaks = |samples: &[Vec<f32>]| -> Vec<f32> {
+        samples
+            .iter()
+            .map(|channel| channel.iter().fold(0.0_f32, |peak, s| peak.max(s.abs())))
+            .collect()
+    };
+    let render = |mode| {
+        peaks(
+            &MusicPlayer::new_file(300, 1.0, 8000)
+                .with_normalization(mode)
+                .render_samples(piece.clone()),
+        )
+    };
+
+    let peak = render(NormalizationMode::Peak);
+    assert!((peak[0].max(peak[1]) - 1.0).abs() < 1e-6 && peak[1] < peak[0]);
+
+    let per_channel = render(NormalizationMode::PerChannelPeak);
+    assert!(per_channel.iter().all(|peak| (peak - 1.0).abs() < 1e-6));
+
+    // Turning normalization off keeps the notes' own volume
+    let off = render(NormalizationMode::Off);
+    let quieter = peaks(
+        &MusicPlayer::new_file(300, 1.0, 8000)
+            .with_normalization(NormalizationMode::Off)
+            .render_samples(piece.clone().volume(0.5)),
+    );
+    assert!((quieter[0] * 2.0 - off[0]).abs() < 1e-4);
+
+    let player = MusicPlayer::new_file(300, 1.0, 8000).with_normalization(NormalizationMode::Lufs(-20.0));
+    let lufs = player.analyze_render(piece.clone()).master.lufs;
+    assert!((lufs + 20.0).abs() < 0.1, "{lufs}");
+}
+
+#[test]
+fn test_soft_clipping_stays_under_full_scale() {
+    assert_eq!(soft_clip(0.25), 0.25);
+    assert!(soft_clip(3.0) < 1.0 && soft_clip(3.0) > 0.99);
+    assert_eq!(soft_clip(-3.0), -soft_clip(3.0));
+    assert!(soft_clip(0.6) < 0.6 && soft_clip(0.6) > soft_clip(0.55));
+}
@@ -0,0 +1,130 @@
+use std::time::Duration;
+
+use rodio::Source;
+
+/// The shape of a [`Oscillator`]'s wave.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Waveform {
+    Square,
+    Saw,
+    Triangle,
+}
+
+/// A band-limited oscillator, which plays a square, saw, or triangle wave without aliasing.
+///
+/// A naive square or saw wave jumps instantly, which creates harmonics above the Nyquist frequency.
+/// Those fold back down as inharmonic noise, especially on high notes. This smooths each jump with
+/// a polynomial band-limited step (`PolyBLEP`), and each corner of the triangle wave with its
+/// integral (`PolyBLAMP`), which removes most of the aliasing for very little work.
+pub struct Oscillator {
+    waveform: Waveform,
+    /// How far through the current cycle the wave is, from 0 to 1
+    phase: f32,
+    /// How far the phase moves each sample
+    increment: f32,
+}
+
+impl Oscillator {
+    /// The sample rate of every oscillator, which matches `SineWave`.
+    pub const SAMPLE_RATE: u32 = 48000;
+
+    pub fn new(waveform: Waveform, frequency: f32) -> Self {
+        #[expect(clippy::cast_precision_loss, reason = "The sample rate is exactly representable")]
+        let increment = (frequency / Self::SAMPLE_RATE as f32).clamp(0.0, 0.5);
+        Oscillator {
+            waveform,
+            phase: 0.0,
+            increment,
+        }
+    }
+}
+
+/// Smooths a jump of -2 at phase 0, given how far past the jump `phase` is.
+fn poly_blep(phase: f32, increment: f32) -> f32 {
+    if phase < increment {
+        let t = phase / increment;
+        t + t - t * t - 1.0
+    } else if phase > 1.0 - increment {
+        let t = (phase - 1.0) / increment;
+        t * t + t + t + 1.0
+    } else {
+        0.0
+    }
+}
+
+/// Smooths a corner at phase 0 where the slope rises by 2 per sample. This is the integral of [`poly_blep`].
+fn poly_blamp(phase: f32, increment: f32) -> f32 {
+    if phase < increment {
+        let t = phase / increment - 1.0;
+        -t * t * t / 3.0
+    } else if phase > 1.0 - increment {
+        let t = (phase - 1.0) / increment + 1.0;
+        t * t * t / 3.0
+    } else {
+        0.0
+    }
+}
+
+impl Iterator for Oscillator {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let (phase, increment) = (self.phase, self.increment);
+        let half_phase = (phase + 0.5).fract();
+
+        let sample = match self.waveform {
+            Waveform::Saw => 2.0 * phase - 1.0 - poly_blep(phase, increment),
+            Waveform::Square => {
+                let naive = if phase < 0.5 { 1.0 } else { -1.0 };
+                naive + poly_blep(phase, increment) - poly_blep(half_phase, increment)
+            }
+            Waveform::Triangle => {
+                // The slope changes by 8 per cycle at each corner: down at the peak, and up at the trough
+                let naive = 4.0 * (phase - 0.5).abs() - 1.0;
+                naive + 4.0 * increment * (poly_blamp(half_phase, increment) - poly_blamp(phase, increment))
+            }
+        };
+
+        self.phase = (phase + increment).fract();
+        Some(sample)
+    }
+}
+
+impl Source for Oscillator {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        Self::SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+#[test]
+fn test_oscillators_stay_in_range() {
+    for waveform in [Waveform::Square, Waveform::Saw, Waveform::Triangle] {
+        for frequency in [55.0, 440.0, 1760.0] {
+            let samples: Vec<f32> = Oscillator::new(waveform, frequency).take(48000).collect();
+            let peak = samples.iter().fold(0.0_f32, |peak, s| peak.max(s.abs()));
+            #[expect(clippy::cast_precision_loss, reason = "Test signal")]
+            let mean = samples.iter().sum::<f32>() / samples.len() as f32;
+
+            assert!(
+                (0.8..=1.1).contains(&peak),
+                "{waveform:?} at {frequency} Hz peaks at {peak}"
+            );
+            assert!(
+                mean.abs() < 0.01,
+                "{waveform:?} at {frequency} Hz has a DC offset of {mean}"
+            );
+        }
+    }
+}
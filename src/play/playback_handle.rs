@@ -0,0 +1,194 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::Receiver,
+        Arc,
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use rodio::{source::SeekError, Sink};
+
+use crate::{
+    play::{
+        levels::{level_channel, LevelSubscribers, Levels},
+        mixer::frames_to_ms,
+        ticks_to_ms,
+    },
+    Meter, TICKS_PER_SIXTEENTH,
+};
+
+/// A handle to a piece which is being played live.
+///
+/// Returned by `MusicPlayer::play`. It can be used to pause, resume, stop,
+/// or seek the playback, and to check how far along the playback is.
+///
+/// Dropping the handle does not stop playback.
+///
+/// # Example
+/// ```no_run
+/// use symphoxy::prelude::*;
+/// use symphoxy::MusicPlayer;
+/// use std::sync::Arc;
+///
+/// let (_stream, handle) = rodio::OutputStream::try_default().unwrap();
+/// let player = MusicPlayer::new_live(300, Arc::new(handle));
+///
+/// let playback = player.play(piano(whole(C4) + whole(A4)));
+/// playback.pause();
+/// playback.seek_to_beat(16).unwrap(); // Skip to the A4
+/// playback.resume();
+///
+/// println!("{:.0}% played", playback.progress() * 100.0);
+/// playback.join().unwrap();
+/// ```
+pub struct PlaybackHandle {
+    pub(crate) sink: Arc<Sink>,
+    pub(crate) position: Arc<AtomicU64>,
+    pub(crate) end_frame: u64,
+    pub(crate) beat_duration_ms: u64,
+    pub(crate) level_subscribers: LevelSubscribers,
+    pub(crate) stolen_voices: Arc<AtomicU64>,
+    pub(crate) thread: JoinHandle<()>,
+}
+
+impl PlaybackHandle {
+    /// Pauses playback. Has no effect if playback is already paused.
+    pub fn pause(&self) {
+        self.sink.pause();
+    }
+
+    /// Resumes paused playback. Has no effect if playback isn't paused.
+    pub fn resume(&self) {
+        self.sink.play();
+    }
+
+    /// Returns whether playback is currently paused.
+    pub fn is_paused(&self) -> bool {
+        self.sink.is_paused()
+    }
+
+    /// Stops playback. Stopped playback can't be resumed.
+    pub fn stop(&self) {
+        self.sink.stop();
+    }
+
+    /// Returns whether playback has finished, either by reaching the end of the piece or by being stopped.
+    pub fn is_finished(&self) -> bool {
+        self.thread.is_finished()
+    }
+
+    /// Jumps to the given beat (sixteenth note) of the piece.
+    ///
+    /// Notes which started before the given beat but are still sounding
+    /// are played from part way through. Seeking past the end of the piece ends playback.
+    ///
+    /// # Errors
+    /// Returns an error if the audio output couldn't seek.
+    pub fn seek_to_beat(&self, beat: usize) -> Result<(), SeekError> {
+        let ms = (beat as u64).saturating_mul(self.beat_duration_ms);
+        seek_sink(&self.sink, ms)
+    }
+
+    /// Jumps to the start of the given bar of the piece.
+    ///
+    /// Bars are numbered as a musician would number them: if the meter has an anacrusis,
+    /// it's bar 0, and bar 1 is the first full bar.
+    ///
+    /// # Errors
+    /// Returns an error if the audio output couldn't seek.
+    pub fn seek_to_bar(&self, bar: usize, meter: Meter) -> Result<(), SeekError> {
+        let ms = ticks_to_ms(meter.bar_start(bar), self.beat_duration_ms);
+        seek_sink(&self.sink, ms)
+    }
+
+    /// Returns the bar which is currently playing.
+    pub fn current_bar(&self, meter: Meter) -> usize {
+        let ms = frames_to_ms(self.position.load(Ordering::Relaxed));
+        let ticks = ms
+            .saturating_mul(u64::from(TICKS_PER_SIXTEENTH))
+            .checked_div(self.beat_duration_ms)
+            .unwrap_or_default();
+        meter.bar_at(usize::try_from(ticks).unwrap_or(usize::MAX))
+    }
+
+    /// Returns the beat (sixteenth note) which is currently playing.
+    pub fn current_beat(&self) -> usize {
+        let frame_ms = frames_to_ms(self.position.load(Ordering::Relaxed));
+        usize::try_from(frame_ms.checked_div(self.beat_duration_ms).unwrap_or_default()).unwrap_or(usize::MAX)
+    }
+
+    /// Returns how much of the piece has been played, from `0.0` to `1.0`.
+    #[expect(clippy::cast_precision_loss, reason = "Only used for display purposes")]
+    pub fn progress(&self) -> f32 {
+        if self.end_frame == 0 {
+            return 1.0;
+        }
+        let position = self.position.load(Ordering::Relaxed).min(self.end_frame);
+        position as f32 / self.end_frame as f32
+    }
+
+    /// Subscribes to the level of each line as it plays, measured from the audio itself.
+    ///
+    /// The levels follow each line's amplitude envelope: they jump up with each note,
+    /// then fall smoothly as it fades. They're sent about every 10 milliseconds while playback
+    /// runs (but not while it's paused), and the stream ends when playback finishes.
+    /// This can drive visuals which react to the music, such as meters or pulsing shapes.
+    ///
+    /// Each call returns its own stream, so several parts of an app can subscribe at once.
+    /// A stream holds about 160 milliseconds of levels, and levels sent while it's full are
+    /// dropped, so a subscriber which falls behind skips ahead rather than using more and more memory.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    /// use std::sync::Arc;
+    ///
+    /// let (_stream, handle) = rodio::OutputStream::try_default().unwrap();
+    /// let player = MusicPlayer::new_live(300, Arc::new(handle));
+    ///
+    /// let playback = player.play(piano(whole(C4)) * bass(whole(C4.octave(-2))));
+    /// for levels in playback.subscribe_levels() {
+    ///     let bars: Vec<String> = levels.lines.iter().map(|level| "#".repeat((level * 40.0) as usize)).collect();
+    ///     println!("{:>6} ms  {:<40} {}", levels.time_ms, bars[0], bars[1]);
+    /// }
+    /// ```
+    pub fn subscribe_levels(&self) -> Receiver<Levels> {
+        let (sender, receiver) = level_channel();
+        // Once playback has finished, the stream is already over
+        if !self.is_finished() {
+            if let Ok(mut subscribers) = self.level_subscribers.lock() {
+                subscribers.push(sender);
+            }
+        }
+        receiver
+    }
+
+    /// Returns the total length of the piece being played, in milliseconds.
+    pub fn duration_ms(&self) -> u64 {
+        frames_to_ms(self.end_frame)
+    }
+
+    /// Returns how many notes have been cut off so far to make room for others, because the
+    /// player's voice limit was reached. Always 0 if the player has no limit.
+    ///
+    /// See `MusicPlayer::with_voice_limit`.
+    pub fn stolen_voices(&self) -> u64 {
+        self.stolen_voices.load(Ordering::Relaxed)
+    }
+
+    /// Waits for playback to finish.
+    ///
+    /// # Errors
+    /// Returns an error if the playback thread panicked.
+    pub fn join(self) -> std::thread::Result<()> {
+        self.thread.join()
+    }
+}
+
+/// Moves the playback in a sink to the given number of milliseconds into the piece.
+pub(crate) fn seek_sink(sink: &Sink, ms: u64) -> Result<(), SeekError> {
+    sink.try_seek(Duration::from_millis(ms))
+}
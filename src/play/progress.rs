@@ -0,0 +1,44 @@
+use crate::TICKS_PER_SIXTEENTH;
+
+/// How far a render or a playback has got through a piece, reported to a progress callback.
+///
+/// Passed to the callback of `MusicPlayer::render_to_wav_with_progress` as notes are rendered,
+/// and to the callback of `MusicPlayer::play_with_progress` as the piece plays, such as to
+/// drive a progress bar.
+///
+/// # Example
+/// ```
+/// use symphoxy::RenderProgress;
+///
+/// let progress = RenderProgress { beat: 16, total_beats: 64 };
+/// assert_eq!(progress.fraction(), 0.25);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RenderProgress {
+    /// The beat (sixteenth note) reached so far, counted from the start of what's being rendered or played
+    pub beat: usize,
+    /// How many beats (sixteenth notes) there are in total
+    pub total_beats: usize,
+}
+
+impl RenderProgress {
+    /// Creates the progress of the given number of ticks through the given number of ticks.
+    /// Partial beats are rounded up in the total, so the last beat is always reached.
+    pub(crate) fn from_ticks(ticks: usize, total_ticks: usize) -> Self {
+        let ticks_per_beat = usize::from(TICKS_PER_SIXTEENTH);
+        let total_beats = total_ticks.div_ceil(ticks_per_beat);
+        RenderProgress {
+            beat: ticks.checked_div(ticks_per_beat).unwrap_or_default().min(total_beats),
+            total_beats,
+        }
+    }
+
+    /// Returns how much is done, from `0.0` to `1.0`. Nothing to do counts as done.
+    #[expect(clippy::cast_precision_loss, reason = "Only used for display purposes")]
+    pub fn fraction(&self) -> f32 {
+        if self.total_beats == 0 {
+            return 1.0;
+        }
+        self.beat.min(self.total_beats) as f32 / self.total_beats as f32
+    }
+}
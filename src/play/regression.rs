@@ -0,0 +1,157 @@
+use crate::{
+    play::{FileOutputConfig, Playable},
+    MusicPlayer,
+};
+
+/// How different two renders are, found with [`compare_renders`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RenderDifference {
+    /// The largest difference between any two samples
+    pub max_difference: f32,
+    /// The root mean square of the differences between samples
+    pub rms_difference: f32,
+}
+
+impl RenderDifference {
+    /// Returns whether no sample differs by more than `tolerance`.
+    ///
+    /// Samples range from -1.0 to 1.0, so a tolerance of `1e-3` allows changes of about -60 dB.
+    pub fn within(&self, tolerance: f32) -> bool {
+        self.max_difference <= tolerance
+    }
+}
+
+impl MusicPlayer<FileOutputConfig> {
+    /// Renders a piece without saving it, and returns one buffer of samples per channel.
+    ///
+    /// The samples are the same as [`MusicPlayer::render_to_wav`] would write, before they're
+    /// converted to 16-bit integers. They can be compared with [`compare_renders`].
+    ///
+    /// # Arguments
+    /// * `piece` - Any playable musical content (Note, Chord, Line, Piece, etc.)
+    pub fn render_samples<T: Playable>(&self, piece: T) -> Vec<Vec<f32>> {
+        let piece = piece.to_piece();
+        let length = piece.length();
+        let mut samples = self.render_range_samples(piece, 0..length);
+        self.apply_output_stage(&mut samples);
+        samples
+    }
+
+    /// Renders a piece without saving it, and returns a hash of the audio.
+    ///
+    /// The same piece rendered with the same settings always has the same checksum, so it can be
+    /// stored in a test to catch any change to how the piece sounds. The hash is of the 16-bit
+    /// samples a WAV file would hold, so changes too small to be heard in the file don't change it.
+    ///
+    /// Rendering isn't guaranteed to be bit-for-bit identical across platforms or compiler
+    /// versions, since it relies on floating point maths. Use [`compare_renders`] with a tolerance
+    /// for checks which should pass everywhere.
+    ///
+    /// # Arguments
+    /// * `piece` - Any playable musical content (Note, Chord, Line, Piece, etc.)
+    ///
+    /// # Example
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 8000);
+    /// let melody = piano(quarter(C4) + quarter(E4));
+    ///
+    /// let checksum = player.render_checksum(melody.clone());
+    /// assert_eq!(player.render_checksum(melody), checksum);
+    /// assert_ne!(player.render_checksum(piano(quarter(C4) + quarter(F4))), checksum);
+    /// ```
+    pub fn render_checksum<T: Playable>(&self, piece: T) -> u64 {
+        let samples = self.render_samples(piece);
+        let length = samples.first().map_or(0, Vec::len);
+
+        let mut hash = Fnv1a::default();
+        hash.write(&self.output_config.sample_rate.to_le_bytes());
+        hash.write(&u64::try_from(samples.len()).unwrap_or(u64::MAX).to_le_bytes());
+        // Interleaved, as in a WAV file
+        for index in 0..length {
+            for channel in &samples {
+                hash.write(&to_i16(channel[index]).to_le_bytes());
+            }
+        }
+        hash.0
+    }
+}
+
+/// Compares two renders sample by sample, such as the output of [`MusicPlayer::render_samples`]
+/// before and after a change.
+///
+/// If one render is longer or has more channels, the other is treated as silent there,
+/// so any extra audio counts as a difference.
+///
+/// # Parameters
+/// - `expected`: The samples of each channel of the reference render
+/// - `actual`: The samples of each channel of the render to check
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::{compare_renders, MusicPlayer};
+///
+/// let player = MusicPlayer::new_file(300, 1.0, 8000);
+/// let expected = player.render_samples(piano(quarter(C4)));
+///
+/// assert!(compare_renders(&expected, &player.render_samples(piano(quarter(C4)))).within(1e-4));
+/// assert!(!compare_renders(&expected, &player.render_samples(piano(quarter(D4)))).within(1e-4));
+/// ```
+pub fn compare_renders(expected: &[Vec<f32>], actual: &[Vec<f32>]) -> RenderDifference {
+    let channels = expected.len().max(actual.len());
+    let length = expected.iter().chain(actual).map(Vec::len).max().unwrap_or_default();
+    let sample = |render: &[Vec<f32>], channel: usize, index: usize| {
+        render
+            .get(channel)
+            .and_then(|samples| samples.get(index))
+            .copied()
+            .unwrap_or_default()
+    };
+
+    let mut max_difference = 0.0_f32;
+    let mut sum_of_squares = 0.0_f64;
+    for channel in 0..channels {
+        for index in 0..length {
+            let difference = (sample(expected, channel, index) - sample(actual, channel, index)).abs();
+            max_difference = max_difference.max(difference);
+            sum_of_squares += f64::from(difference) * f64::from(difference);
+        }
+    }
+
+    #[expect(clippy::cast_precision_loss, reason = "Sample counts are far below f64's precision limit")]
+    let count = channels.saturating_mul(length).max(1) as f64;
+    #[expect(clippy::cast_possible_truncation, reason = "The difference is at most a few units")]
+    let rms_difference = (sum_of_squares / count).sqrt() as f32;
+
+    RenderDifference {
+        max_difference,
+        rms_difference,
+    }
+}
+
+/// Converts a sample to 16 bits, as it's written to a WAV file.
+#[expect(clippy::cast_possible_truncation, reason = "It's clamped, so it should be safe")]
+fn to_i16(sample: f32) -> i16 {
+    (sample * f32::from(i16::MAX)).clamp(f32::from(i16::MIN), f32::from(i16::MAX)) as i16
+}
+
+/// The 64-bit FNV-1a hash. Unlike the standard library's hasher, its output is guaranteed
+/// never to change, so checksums can be stored.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
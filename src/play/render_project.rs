@@ -0,0 +1,161 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    play::{render_to_wav::write_wav, FileOutputConfig},
+    Metadata, MusicPlayer, Project,
+};
+
+impl MusicPlayer<FileOutputConfig> {
+    /// Renders every movement of a project to its own WAV file, then the whole project to one file,
+    /// with the project's gap of silence between movements.
+    ///
+    /// Each movement is played at its own tempo, or the project's if it doesn't have one, or the
+    /// player's if neither does. Each is normalized on its own, and the combined file is made of the
+    /// same audio as the movements' files, so they sound the same in both.
+    ///
+    /// Movements are named after their number and title, such as `01_spring.wav`, so they sort in
+    /// order, and the combined file is named after the project, such as `seasons.wav`.
+    /// The directory is created if it doesn't exist, and files already in it with the same names
+    /// are overwritten. Each file is tagged with the player's metadata, with its own title and
+    /// the project's artist.
+    ///
+    /// # Arguments
+    /// * `project` - The project to render
+    /// * `dir` - The directory the files are written to
+    ///
+    /// # Returns
+    /// The paths of the files written: each movement's in order, then the combined file.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{Movement, MusicPlayer, Project};
+    ///
+    /// let project = Project::new("Seasons")
+    ///     .with_tempo(300)
+    ///     .with_movement(Movement::new("Spring", piano(quarter(C4) + quarter(E4) + half(G4))))
+    ///     .with_movement(Movement::new("Winter", piano(half(A3) + half(C4))).with_tempo(200));
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 44100);
+    /// let files = player.render_project(&project, "seasons").unwrap();
+    /// assert_eq!(files.len(), 3); // 01_spring.wav, 02_winter.wav, and seasons.wav
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the directory can't be created, or a file can't be written.
+    pub fn render_project(&self, project: &Project, dir: impl AsRef<Path>) -> io::Result<Vec<PathBuf>> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        let sample_rate = self.output_config.sample_rate;
+        let metadata = &self.output_config.metadata;
+
+        let mut paths = vec![];
+        let mut renders: Vec<Vec<Vec<f32>>> = vec![];
+        for (number, movement) in project.movements.iter().enumerate() {
+            let player = MusicPlayer {
+                tempo_bpm: project.tempo_of(movement).unwrap_or(self.tempo_bpm),
+                output_config: self.output_config.clone(),
+                seed: self.seed,
+                mixer: self.mixer.clone(),
+            };
+            let mut samples = player.render_range_samples(movement.piece.clone(), 0..movement.piece.length());
+            player.apply_output_stage(&mut samples);
+
+            let path = dir.join(format!(
+                "{:02}_{}.wav",
+                number.saturating_add(1),
+                file_name(&movement.title)
+            ));
+            let metadata = Metadata {
+                title: Some(movement.title.clone()),
+                artist: project.artist.clone().or_else(|| metadata.artist.clone()),
+                tempo_bpm: Some(player.tempo_bpm),
+                ..metadata.clone()
+            };
+            write_wav(&path, &samples, sample_rate, &metadata)?;
+            paths.push(path);
+            renders.push(samples);
+        }
+
+        let gap = usize::try_from(u64::from(sample_rate).saturating_mul(project.gap_ms) / 1000).unwrap_or(usize::MAX);
+        let path = dir.join(format!("{}.wav", file_name(&project.title)));
+        let metadata = Metadata {
+            title: Some(project.title.clone()),
+            artist: project.artist.clone().or_else(|| metadata.artist.clone()),
+            ..metadata.clone()
+        };
+        write_wav(&path, &join_with_gaps(&renders, gap), sample_rate, &metadata)?;
+        paths.push(path);
+        Ok(paths)
+    }
+}
+
+/// Joins renders end to end, with `gap` samples of silence between each. Renders with fewer
+/// channels than the others have their last channel repeated, so mono movements play on every channel.
+fn join_with_gaps(renders: &[Vec<Vec<f32>>], gap: usize) -> Vec<Vec<f32>> {
+    let channels = renders.iter().map(Vec::len).max().unwrap_or(1).max(1);
+    let mut joined = vec![vec![]; channels];
+    for (index, render) in renders.iter().enumerate() {
+        if index > 0 {
+            for channel in &mut joined {
+                channel.resize(channel.len().saturating_add(gap), 0.0);
+            }
+        }
+        let length = render.iter().map(Vec::len).max().unwrap_or_default();
+        for (channel, output) in joined.iter_mut().enumerate() {
+            let start = output.len();
+            if let Some(source) = render.get(channel).or_else(|| render.last()) {
+                output.extend_from_slice(source);
+            }
+            output.resize(start.saturating_add(length), 0.0);
+        }
+    }
+    joined
+}
+
+/// Returns a title in lower case, with anything but letters and numbers replaced by underscores,
+/// for use in a file name.
+fn file_name(title: &str) -> String {
+    let name = title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("_");
+    if name.is_empty() {
+        "untitled".to_string()
+    } else {
+        name
+    }
+}
+
+#[test]
+fn test_project_renders_each_movement_and_the_whole() {
+    use crate::{prelude::*, Movement};
+
+    let dir = std::env::temp_dir().join(format!("symphoxy_project_{}", std::process::id()));
+    let project = Project::new("Night & Day")
+        .with_tempo(600)
+        .with_gap_ms(500)
+        .with_movement(Movement::new("I. Night", sine(whole(C4))))
+        .with_movement(Movement::new("II. Day", sine(whole(C4)).pan(1.0)).with_tempo(300));
+
+    let player = MusicPlayer::new_file(300, 1.0, 1000);
+    let paths = player.render_project(&project, &dir).unwrap();
+    let names: Vec<_> = paths.iter().map(|path| path.file_name().unwrap().to_owned()).collect();
+    assert_eq!(names, ["01_i_night.wav", "02_ii_day.wav", "night_day.wav"]);
+
+    let read = |path: &PathBuf| hound::WavReader::open(path).unwrap();
+    let lengths: Vec<u32> = paths.iter().map(|path| read(path).duration()).collect();
+    // A whole note is 16 sixteenths, which is 1.6 seconds at 600 and 3.2 seconds at 300
+    assert_eq!(lengths[..2], [1600, 3200]);
+    assert_eq!(lengths[2], 1600 + 500 + 3200);
+    // The mono movement is played on both channels of the combined file
+    assert_eq!(read(&paths[0]).spec().channels, 1);
+    assert_eq!(read(&paths[2]).spec().channels, 2);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
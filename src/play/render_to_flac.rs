@@ -0,0 +1,129 @@
+use std::{io, ops::Range, path::Path};
+
+use flacenc::{
+    bitsink::ByteSink,
+    component::{BitRepr, Stream},
+    error::Verify,
+    source::{Fill, FrameBuf},
+};
+
+use crate::{
+    play::{FileOutputConfig, Playable},
+    MusicPlayer,
+};
+
+impl MusicPlayer<FileOutputConfig> {
+    /// Renders a musical piece to a FLAC file.
+    ///
+    /// The piece is rendered exactly as [`MusicPlayer::render_to_wav`] would render it, and
+    /// written as 16-bit FLAC. FLAC is lossless, so the file sounds the same as the WAV file,
+    /// but is usually around half the size, which makes drafts easier to share.
+    ///
+    /// # Arguments
+    /// * `piece` - Any playable musical content (Note, Chord, Line, Piece, etc.)
+    /// * `path` - The file path where the FLAC file should be written
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 44100);
+    /// let note = piano(quarter(C4));
+    /// player.render_to_flac(note, "output.flac");
+    /// ```
+    ///
+    /// # Panics
+    /// This function panics if the file path is unable to be created or written to.
+    pub fn render_to_flac<T: Playable>(&self, piece: T, path: impl AsRef<Path>) {
+        let piece = piece.to_piece();
+        let length = piece.length();
+        self.render_range_to_flac(piece, 0..length, path);
+    }
+
+    /// Renders part of a musical piece to a FLAC file.
+    ///
+    /// See [`MusicPlayer::render_range_to_wav`] for how the range is rendered.
+    ///
+    /// # Arguments
+    /// * `piece` - Any playable musical content (Note, Chord, Line, Piece, etc.)
+    /// * `range` - The range of instants (in ticks) to render
+    /// * `path` - The file path where the FLAC file should be written
+    ///
+    /// # Panics
+    /// This function panics if the file path is unable to be created or written to.
+    pub fn render_range_to_flac<T: Playable>(&self, piece: T, range: Range<usize>, path: impl AsRef<Path>) {
+        let mut samples = self.render_range_samples(piece, range);
+        self.apply_output_stage(&mut samples);
+        write_flac(path.as_ref(), &samples, self.output_config.sample_rate).unwrap();
+    }
+}
+
+/// Writes one buffer of samples per channel to a 16-bit FLAC file.
+pub(super) fn write_flac(path: &Path, samples: &[Vec<f32>], sample_rate: u32) -> io::Result<()> {
+    let error = |message: String| io::Error::other(message);
+
+    #[expect(clippy::cast_possible_truncation, reason = "It's clamped, so it should be safe")]
+    let interleaved: Vec<i32> = (0..samples.first().map_or(0, Vec::len))
+        .flat_map(|index| samples.iter().map(move |channel| channel[index]))
+        .map(|sample| i32::from((sample * f32::from(i16::MAX)).clamp(f32::from(i16::MIN), f32::from(i16::MAX)) as i16))
+        .collect();
+    let channels = samples.len();
+    let config = flacenc::config::Encoder::default()
+        .into_verified()
+        .map_err(|(_, verify_error)| error(verify_error.to_string()))?;
+
+    // `flacenc::encode_with_fixed_block_size` pads the last frame with silence, so the frames
+    // are encoded here, with the last one shortened to the samples that are left.
+    let mut stream = Stream::new(usize::try_from(sample_rate).unwrap_or(usize::MAX), channels, 16)
+        .map_err(|verify_error| error(verify_error.to_string()))?;
+    let mut frame =
+        FrameBuf::with_size(channels, config.block_size).map_err(|verify_error| error(verify_error.to_string()))?;
+    for (frame_number, block) in interleaved
+        .chunks(config.block_size.saturating_mul(channels).max(1))
+        .enumerate()
+    {
+        frame.resize(block.len().checked_div(channels).unwrap_or_default());
+        frame
+            .fill_interleaved(block)
+            .map_err(|source_error| error(source_error.to_string()))?;
+        let encoded = flacenc::encode_fixed_size_frame(&config, &frame, frame_number, stream.stream_info())
+            .map_err(|encode_error| error(format!("{encode_error:?}")))?;
+        stream.add_frame(encoded);
+    }
+
+    let mut sink = ByteSink::new();
+    stream
+        .write(&mut sink)
+        .map_err(|output_error| error(output_error.to_string()))?;
+    std::fs::write(path, sink.as_slice())
+}
+
+#[test]
+fn test_flac_decodes_to_the_rendered_samples() {
+    use crate::prelude::*;
+
+    let path = std::env::temp_dir().join(format!("symphoxy_flac_{}.flac", std::process::id()));
+    let piece = piano(quarter(C4) + quarter(E4) + half(G4)) * bass(whole(C2)).pan(-0.5) * drums(quarter(C3) * 4);
+    let player = MusicPlayer::new_file(600, 0.8, 8000);
+    player.render_to_flac(piece.clone(), &path);
+
+    #[expect(clippy::cast_possible_truncation, reason = "It's clamped, so it should be safe")]
+    let expected: Vec<i32> = {
+        let channels = player.render_samples(piece);
+        (0..channels[0].len())
+            .flat_map(|index| channels.iter().map(move |channel| channel[index]))
+            .map(|sample| {
+                i32::from((sample * f32::from(i16::MAX)).clamp(f32::from(i16::MIN), f32::from(i16::MAX)) as i16)
+            })
+            .collect()
+    };
+
+    let mut reader = claxon::FlacReader::open(&path).unwrap();
+    assert_eq!(reader.streaminfo().sample_rate, 8000);
+    assert_eq!(reader.streaminfo().channels, 2);
+    let decoded: Vec<i32> = reader.samples().map(Result::unwrap).collect();
+    assert_eq!(decoded, expected);
+    assert!(std::fs::metadata(&path).unwrap().len() < expected.len() as u64 * 2);
+    std::fs::remove_file(&path).unwrap();
+}
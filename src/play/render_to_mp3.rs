@@ -0,0 +1,109 @@
+use std::{io, ops::Range, path::Path};
+
+use mp3lame_encoder::{Bitrate, Builder, DualPcm, FlushNoGap, MonoPcm, Quality};
+
+use crate::{
+    play::{FileOutputConfig, Playable},
+    MusicPlayer,
+};
+
+impl MusicPlayer<FileOutputConfig> {
+    /// Renders a musical piece to an MP3 file.
+    ///
+    /// The piece is rendered exactly as [`MusicPlayer::render_to_wav`] would render it, and
+    /// encoded at 192 kbps. MP3 is lossy, but almost every player can open it, which makes it
+    /// the safest format for sharing a finished piece.
+    ///
+    /// # Arguments
+    /// * `piece` - Any playable musical content (Note, Chord, Line, Piece, etc.)
+    /// * `path` - The file path where the MP3 file should be written
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 44100);
+    /// let note = piano(quarter(C4));
+    /// player.render_to_mp3(note, "output.mp3");
+    /// ```
+    ///
+    /// # Panics
+    /// This function panics if the file path is unable to be created or written to, or if the
+    /// player has more than two channels, which MP3 doesn't support.
+    pub fn render_to_mp3<T: Playable>(&self, piece: T, path: impl AsRef<Path>) {
+        let piece = piece.to_piece();
+        let length = piece.length();
+        self.render_range_to_mp3(piece, 0..length, path);
+    }
+
+    /// Renders part of a musical piece to an MP3 file.
+    ///
+    /// See [`MusicPlayer::render_range_to_wav`] for how the range is rendered.
+    ///
+    /// # Arguments
+    /// * `piece` - Any playable musical content (Note, Chord, Line, Piece, etc.)
+    /// * `range` - The range of instants (in ticks) to render
+    /// * `path` - The file path where the MP3 file should be written
+    ///
+    /// # Panics
+    /// This function panics if the file path is unable to be created or written to, or if the
+    /// player has more than two channels, which MP3 doesn't support.
+    pub fn render_range_to_mp3<T: Playable>(&self, piece: T, range: Range<usize>, path: impl AsRef<Path>) {
+        let mut samples = self.render_range_samples(piece, range);
+        self.apply_output_stage(&mut samples);
+        write_mp3(path.as_ref(), &samples, self.output_config.sample_rate).unwrap();
+    }
+}
+
+/// Writes one buffer of samples per channel to an MP3 file. MP3 only supports mono and stereo.
+pub(super) fn write_mp3(path: &Path, samples: &[Vec<f32>], sample_rate: u32) -> io::Result<()> {
+    let error = |message: String| io::Error::other(message);
+
+    let mut builder = Builder::new().ok_or_else(|| error("unable to start the MP3 encoder".to_string()))?;
+    let channels = u8::try_from(samples.len()).unwrap_or(u8::MAX);
+    builder
+        .set_num_channels(channels)
+        .and_then(|()| builder.set_sample_rate(sample_rate))
+        .and_then(|()| builder.set_brate(Bitrate::Kbps192))
+        .and_then(|()| builder.set_quality(Quality::Best))
+        .map_err(|build_error| error(build_error.to_string()))?;
+    let mut encoder = builder.build().map_err(|build_error| error(build_error.to_string()))?;
+
+    let length = samples.first().map_or(0, Vec::len);
+    let mut output = Vec::with_capacity(mp3lame_encoder::max_required_buffer_size(length));
+    let encoded = match samples {
+        [mono] => encoder.encode_to_vec(MonoPcm(mono.as_slice()), &mut output),
+        [left, right] => encoder.encode_to_vec(DualPcm { left, right }, &mut output),
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "MP3 files have one or two channels",
+            ))
+        }
+    };
+    encoded.map_err(|encode_error| error(encode_error.to_string()))?;
+    encoder
+        .flush_to_vec::<FlushNoGap>(&mut output)
+        .map_err(|encode_error| error(encode_error.to_string()))?;
+
+    std::fs::write(path, output)
+}
+
+#[test]
+fn test_mp3_decodes_to_the_rendered_format() {
+    use crate::prelude::*;
+    use rodio::Source;
+
+    let path = std::env::temp_dir().join(format!("symphoxy_mp3_{}.mp3", std::process::id()));
+    let piece = piano(quarter(C4) + quarter(E4) + half(G4)) * bass(whole(C2)).pan(-0.5);
+    let player = MusicPlayer::new_file(600, 0.8, 22050);
+    player.render_to_mp3(piece, &path);
+
+    let decoder = rodio::Decoder::new(std::fs::File::open(&path).unwrap()).unwrap();
+    assert_eq!(decoder.sample_rate(), 22050);
+    assert_eq!(decoder.channels(), 2);
+    let decoded: Vec<i16> = decoder.collect();
+    assert!(decoded.iter().any(|sample| sample.unsigned_abs() > 1000));
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,113 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter},
+    num::{NonZeroU32, NonZeroU8},
+    ops::Range,
+    path::Path,
+};
+
+use vorbis_rs::VorbisEncoderBuilder;
+
+use crate::{
+    play::{FileOutputConfig, Playable},
+    MusicPlayer,
+};
+
+/// The number of samples per channel given to the encoder at a time.
+const BLOCK_SIZE: usize = 4096;
+
+impl MusicPlayer<FileOutputConfig> {
+    /// Renders a musical piece to an Ogg Vorbis file.
+    ///
+    /// The piece is rendered exactly as [`MusicPlayer::render_to_wav`] would render it, and
+    /// encoded with Vorbis. Vorbis is lossy, so the file doesn't sound exactly the same as the
+    /// WAV file, but it's usually around a tenth of the size, which makes drafts easy to share.
+    ///
+    /// # Arguments
+    /// * `piece` - Any playable musical content (Note, Chord, Line, Piece, etc.)
+    /// * `path` - The file path where the Ogg file should be written
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 44100);
+    /// let note = piano(quarter(C4));
+    /// player.render_to_ogg(note, "output.ogg");
+    /// ```
+    ///
+    /// # Panics
+    /// This function panics if the file path is unable to be created or written to.
+    pub fn render_to_ogg<T: Playable>(&self, piece: T, path: impl AsRef<Path>) {
+        let piece = piece.to_piece();
+        let length = piece.length();
+        self.render_range_to_ogg(piece, 0..length, path);
+    }
+
+    /// Renders part of a musical piece to an Ogg Vorbis file.
+    ///
+    /// See [`MusicPlayer::render_range_to_wav`] for how the range is rendered.
+    ///
+    /// # Arguments
+    /// * `piece` - Any playable musical content (Note, Chord, Line, Piece, etc.)
+    /// * `range` - The range of instants (in ticks) to render
+    /// * `path` - The file path where the Ogg file should be written
+    ///
+    /// # Panics
+    /// This function panics if the file path is unable to be created or written to.
+    pub fn render_range_to_ogg<T: Playable>(&self, piece: T, range: Range<usize>, path: impl AsRef<Path>) {
+        let mut samples = self.render_range_samples(piece, range);
+        self.apply_output_stage(&mut samples);
+        write_ogg(path.as_ref(), &samples, self.output_config.sample_rate).unwrap();
+    }
+}
+
+/// Writes one buffer of samples per channel to an Ogg Vorbis file.
+pub(super) fn write_ogg(path: &Path, samples: &[Vec<f32>], sample_rate: u32) -> io::Result<()> {
+    let error = |message: String| io::Error::other(message);
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidInput, message.to_string());
+    let sample_rate = NonZeroU32::new(sample_rate).ok_or_else(|| invalid("the sample rate must be above 0"))?;
+    let channels = u8::try_from(samples.len())
+        .ok()
+        .and_then(NonZeroU8::new)
+        .ok_or_else(|| invalid("Ogg Vorbis files have between 1 and 255 channels"))?;
+
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = VorbisEncoderBuilder::new(sample_rate, channels, file)
+        .and_then(|mut builder| builder.build())
+        .map_err(|vorbis_error| error(vorbis_error.to_string()))?;
+
+    let length = samples.first().map_or(0, Vec::len);
+    for start in (0..length).step_by(BLOCK_SIZE) {
+        let end = start.saturating_add(BLOCK_SIZE).min(length);
+        let block: Vec<&[f32]> = samples.iter().map(|channel| &channel[start..end]).collect();
+        encoder
+            .encode_audio_block(&block)
+            .map_err(|vorbis_error| error(vorbis_error.to_string()))?;
+    }
+    encoder
+        .finish()
+        .map_err(|vorbis_error| error(vorbis_error.to_string()))?;
+    Ok(())
+}
+
+#[test]
+fn test_ogg_decodes_to_the_rendered_length() {
+    use crate::prelude::*;
+    use rodio::Source;
+
+    let path = std::env::temp_dir().join(format!("symphoxy_ogg_{}.ogg", std::process::id()));
+    let piece = piano(quarter(C4) + quarter(E4) + half(G4)) * bass(whole(C2)).pan(-0.5);
+    let player = MusicPlayer::new_file(600, 0.8, 22050);
+    player.render_to_ogg(piece.clone(), &path);
+
+    let rendered = player.render_samples(piece);
+    let decoder = rodio::Decoder::new(File::open(&path).unwrap()).unwrap();
+    assert_eq!(decoder.sample_rate(), 22050);
+    assert_eq!(decoder.channels(), 2);
+    let decoded: Vec<i16> = decoder.collect();
+    assert_eq!(decoded.len(), rendered[0].len() * 2);
+    assert!(decoded.iter().any(|sample| sample.unsigned_abs() > 1000));
+    std::fs::remove_file(&path).unwrap();
+}
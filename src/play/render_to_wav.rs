@@ -17,13 +17,36 @@
 )]
 #![allow(clippy::needless_range_loop, clippy::needless_collect, reason = "Complex audio processing code")]
 
-use std::ops::Div;
+use std::{
+    io::{self, Seek, SeekFrom, Write},
+    ops::{Div, Range},
+    path::Path,
+};
 
 use crate::{
-    play::{FileOutputConfig, Playable},
-    MusicPlayer,
+    play::{FileOutputConfig, Playable, RenderProgress, ResamplingQuality},
+    Metadata, MusicPlayer, NoteEvent, Piece,
 };
 
+/// How far a render carries on past the end of the piece, so its last notes can ring out,
+/// set with [`MusicPlayer::with_tail`].
+///
+/// Only renders which reach the end of the piece get a tail.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RenderTail {
+    /// The render stops exactly when the piece ends, cutting off anything still sounding
+    #[default]
+    Off,
+    /// The render carries on for the given number of milliseconds
+    Fixed(u64),
+    /// The render carries on until the last release has finished, with room for any effects to
+    /// decay, then the silence at the end is trimmed off
+    Auto,
+}
+
+/// Samples this quiet, about the smallest step of 16-bit audio, count as silence when a tail is trimmed.
+const SILENCE: f32 = 1.0 / 65536.0;
+
 impl MusicPlayer<FileOutputConfig> {
     /// Renders a musical piece to a WAV file.
     ///
@@ -31,6 +54,9 @@ impl MusicPlayer<FileOutputConfig> {
     /// to a WAV file at the specified path. The audio is rendered using the
     /// player's configured sample rate, gain, and tempo.
     ///
+    /// Timbres routed to an output bus with [`MusicPlayer::with_bus`] are written to that
+    /// bus's channels, and the file has enough channels for every bus.
+    ///
     /// # Arguments
     /// * `piece` - Any playable musical content (Note, Chord, Line, Piece, etc.)
     /// * `path` - The file path where the WAV file should be written
@@ -47,18 +73,260 @@ impl MusicPlayer<FileOutputConfig> {
     ///
     /// # Panics
     /// This function panics if the file path is unable to be created or written to.
-    #[expect(private_bounds, reason = "Only internal types should be playable")]
-    pub fn render_to_wav<T: Playable + Clone + Send + Sync + 'static>(&self, piece: T, path: &str) {
+    pub fn render_to_wav<T: Playable>(&self, piece: T, path: impl AsRef<Path>) {
+        let piece = piece.to_piece();
+        let length = piece.length();
+        self.render_range_to_wav(piece, 0..length, path);
+    }
+
+    /// Renders part of a musical piece to a WAV file.
+    ///
+    /// Only the given range of the timeline is rendered, so you can iterate on one section
+    /// without rendering the whole piece. Notes which started before the range, but are still
+    /// sounding at its start, are included. The audio is normalized to the loudest part of the range.
+    ///
+    /// Use [`MusicPlayer::render_to_wav_region`] to render a range of beats.
+    ///
+    /// # Arguments
+    /// * `piece` - Any playable musical content (Note, Chord, Line, Piece, etc.)
+    /// * `range` - The range of instants (in ticks) to render
+    /// * `path` - The file path where the WAV file should be written
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let verse = piano(whole(C4) * 4);
+    /// let bridge = piano(whole(C4.octave(1)) * 4);
+    /// let song = Piece::from(verse.clone() + bridge + verse);
+    ///
+    /// // Render just the bridge, from bar 5 to the start of bar 9
+    /// let bar = whole(C4).0.ticks() as usize;
+    /// let player = MusicPlayer::new_file(300, 1.0, 44100);
+    /// player.render_range_to_wav(song, 4 * bar..8 * bar, "bridge.wav");
+    /// ```
+    ///
+    /// # Panics
+    /// This function panics if the file path is unable to be created or written to.
+    pub fn render_range_to_wav<T: Playable>(&self, piece: T, range: Range<usize>, path: impl AsRef<Path>) {
+        self.render_range_to_wav_reporting(piece, range, path, &mut |_| {});
+    }
+
+    /// Renders the part of a musical piece between two beats to a WAV file.
+    ///
+    /// This is [`MusicPlayer::render_range_to_wav`], with the region given in beats (sixteenth
+    /// notes) rather than ticks. Notes which begin before the region, but are still sounding at
+    /// its start, are rendered from part way through, and the file ends at the end of the region.
+    ///
+    /// # Arguments
+    /// * `piece` - Any playable musical content (Note, Chord, Line, Piece, etc.)
+    /// * `path` - The file path where the WAV file should be written
+    /// * `beats` - The region to render, in beats (sixteenth notes)
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// // A held chord under a melody
+    /// let piece = piano(quarter(C4) * 8) * strings(double_whole(C4.octave(-1)));
+    ///
+    /// // The second bar, with the strings picking up half way through their note
+    /// let player = MusicPlayer::new_file(300, 1.0, 44100);
+    /// player.render_to_wav_region(piece, "bar_2.wav", 16..32);
+    /// ```
+    ///
+    /// # Panics
+    /// This function panics if the file path is unable to be created or written to.
+    pub fn render_to_wav_region<T: Playable>(&self, piece: T, path: impl AsRef<Path>, beats: Range<usize>) {
+        self.render_range_to_wav_reporting(piece, crate::beats_to_ticks(beats), path, &mut |_| {});
+    }
+
+    /// Renders a musical piece to a WAV file, reporting how far the render has got as it goes.
+    ///
+    /// This is [`MusicPlayer::render_to_wav`], which calls `progress` each time a batch of notes
+    /// has been rendered, and once more when the file has been written, with every beat done.
+    /// Long renders can use it to show a progress bar.
+    ///
+    /// # Arguments
+    /// * `piece` - Any playable musical content (Note, Chord, Line, Piece, etc.)
+    /// * `path` - The file path where the WAV file should be written
+    /// * `progress` - Called with how far the render has got
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 44100);
+    /// let piece = piano((quarter(C4) + quarter(E4) + quarter(G4) + quarter(C5)) * 64);
+    /// player.render_to_wav_with_progress(piece, "output.wav", |progress| {
+    ///     println!("{:.0}% rendered", progress.fraction() * 100.0);
+    /// });
+    /// ```
+    ///
+    /// # Panics
+    /// This function panics if the file path is unable to be created or written to.
+    pub fn render_to_wav_with_progress<T: Playable>(
+        &self,
+        piece: T,
+        path: impl AsRef<Path>,
+        mut progress: impl FnMut(RenderProgress),
+    ) {
+        let piece = piece.to_piece();
+        let length = piece.length();
+        self.render_range_to_wav_reporting(piece, 0..length, path, &mut progress);
+    }
+
+    /// Renders part of a piece to a WAV file, calling `progress` as it goes.
+    fn render_range_to_wav_reporting<T: Playable>(
+        &self,
+        piece: T,
+        range: Range<usize>,
+        path: impl AsRef<Path>,
+        progress: &mut dyn FnMut(RenderProgress),
+    ) {
+        let piece = piece.to_piece();
+        let total_ticks = range.end.min(piece.length()).saturating_sub(range.start);
+        let mut samples = self.render_range_samples_reporting(piece, range, progress);
+        self.apply_output_stage(&mut samples);
+        write_wav(
+            path.as_ref(),
+            &samples,
+            self.output_config.sample_rate,
+            &self.output_config.metadata,
+        )
+        .unwrap();
+        progress(RenderProgress::from_ticks(total_ticks, total_ticks));
+    }
+
+    /// Renders part of a piece into one buffer of samples per channel, at the player's sample rate.
+    ///
+    /// The samples are normalized, then the master effects are applied, but the output gain isn't.
+    pub(super) fn render_range_samples<T: Playable>(&self, piece: T, range: Range<usize>) -> Vec<Vec<f32>> {
+        self.render_range_samples_reporting(piece, range, &mut |_| {})
+    }
+
+    /// Renders part of a piece into one buffer of samples per channel, like
+    /// [`MusicPlayer::render_range_samples`], calling `progress` as notes are rendered.
+    fn render_range_samples_reporting<T: Playable>(
+        &self,
+        piece: T,
+        range: Range<usize>,
+        progress: &mut dyn FnMut(RenderProgress),
+    ) -> Vec<Vec<f32>> {
+        let piece = piece.to_piece();
+        let mut samples = self.render_range_mix_reporting(&piece, range.clone(), progress);
+        self.output_config
+            .normalization
+            .apply(&mut samples, self.output_config.sample_rate);
+        self.apply_master_effects(&mut samples);
+
+        if self.output_config.tail == RenderTail::Auto {
+            // The tail is trimmed after the effects, so it keeps their decay, but never into the piece
+            let length = range.end.min(piece.length());
+            let length_ms = super::ticks_to_ms(length.saturating_sub(range.start), self.beat_duration_ms());
+            let min_samples = (self.output_config.sample_rate as u64 * length_ms / 1000) as usize;
+            let end = samples
+                .iter()
+                .filter_map(|channel| channel.iter().rposition(|sample| sample.abs() > SILENCE))
+                .max()
+                .map_or(0, |last| last + 1)
+                .max(min_samples);
+            for channel in &mut samples {
+                channel.truncate(end);
+            }
+        }
+        samples
+    }
+
+    /// Returns how far past the end of the piece a render which reaches it carries on, in milliseconds.
+    fn tail_ms(&self, piece: &Piece, events: &[NoteEvent], beat_duration_ms: u64) -> u64 {
+        match self.output_config.tail {
+            RenderTail::Off => 0,
+            RenderTail::Fixed(tail_ms) => tail_ms,
+            RenderTail::Auto => {
+                let end_ms = super::ticks_to_ms(piece.length(), beat_duration_ms);
+                let release_ms = events
+                    .iter()
+                    .filter_map(|event| {
+                        let crate::note::NoteKind::Pitched { timbre, envelope, .. } = &event.note.1 else {
+                            return None;
+                        };
+                        let duration_ms = super::ticks_to_ms(event.note.0.ticks() as usize, beat_duration_ms);
+                        let sounding_ms = super::sources::sounding_ms(duration_ms, timbre, *envelope);
+                        Some(super::ticks_to_ms(event.start, beat_duration_ms) + sounding_ms)
+                    })
+                    .max()
+                    .unwrap_or(0)
+                    .saturating_sub(end_ms);
+                let has_effects = !self.output_config.master_effects.is_empty()
+                    || (0..piece.0.len()).any(|line| !piece.line_effects(line).is_empty());
+                release_ms + if has_effects { super::EFFECT_TAIL_MS } else { 0 }
+            }
+        }
+    }
+
+    /// Applies the output gain, then the clipping, to samples which have been through the master effects.
+    pub(super) fn apply_output_stage(&self, samples: &mut [Vec<f32>]) {
         let FileOutputConfig {
             output_gain,
             sample_rate,
+            clipping,
+            ..
+        } = self.output_config;
+        for sample in samples.iter_mut().flatten() {
+            *sample *= output_gain;
+        }
+        clipping.apply(samples, sample_rate);
+    }
+
+    /// Applies the master effects chain to normalized samples.
+    pub(super) fn apply_master_effects(&self, samples: &mut [Vec<f32>]) {
+        let FileOutputConfig {
+            sample_rate,
+            ref master_effects,
+            ..
+        } = self.output_config;
+        crate::effects::apply_effects(master_effects, samples, sample_rate);
+    }
+
+    /// Renders part of a piece into one buffer of samples per channel, at the player's sample rate.
+    ///
+    /// The samples are left as they were mixed, so they may go past full scale.
+    pub(super) fn render_range_mix<T: Playable>(&self, piece: T, range: Range<usize>) -> Vec<Vec<f32>> {
+        self.render_range_mix_reporting(piece, range, &mut |_| {})
+    }
+
+    /// Renders part of a piece into one buffer of samples per channel, like
+    /// [`MusicPlayer::render_range_mix`], calling `progress` as each batch of notes is mixed.
+    fn render_range_mix_reporting<T: Playable>(
+        &self,
+        piece: T,
+        range: Range<usize>,
+        progress: &mut dyn FnMut(RenderProgress),
+    ) -> Vec<Vec<f32>> {
+        let piece = piece.to_piece();
+        super::sources::prepare_files(&piece);
+        let FileOutputConfig {
+            sample_rate, ref buses, ..
         } = self.output_config;
 
         let beat_duration_ms = self.beat_duration_ms();
-        let length = piece.length();
+        // Notes can't start after the piece ends
+        let length = range.end.min(piece.length());
+        let range_start_ms = super::ticks_to_ms(range.start, beat_duration_ms);
+
+        let compiled = piece.compile();
 
-        // Compute total duration in ms
-        let total_ms = (length as u64).saturating_mul(beat_duration_ms);
+        // Compute total duration in ms, with room for the tail if the range reaches the end
+        let tail_ms = if range.end >= piece.length() {
+            self.tail_ms(&piece, compiled.events(), beat_duration_ms)
+        } else {
+            0
+        };
+        let total_ms = super::ticks_to_ms(length.saturating_sub(range.start), beat_duration_ms).saturating_add(tail_ms);
 
         let total_samples: usize = (sample_rate as u64)
             .saturating_mul(total_ms)
@@ -66,195 +334,587 @@ impl MusicPlayer<FileOutputConfig> {
             .try_into()
             .unwrap_or(usize::MAX);
 
-        // Step 1: Find max channel count
+        // Step 1: Find max channel count of the main mix. Panned notes need at least two channels.
+        // Notes routed to a bus don't count, as they get their own channels.
         let mut max_channels = 1;
 
-        // This could be more efficient if you made a Piece::get_all_notes() method,
-        // but creating wav files doesn't take eons at the moment, so this is fine.
-        for instant in 0..length {
-            let notes: Vec<_> = piece.get_notes_at_instant(instant).collect();
-            for note in notes {
-                if let crate::note::NoteKind::Pitched { pitch, timbre, volume } = note.1 {
-                    let duration_ms = (note.0 .0 as u64).saturating_mul(beat_duration_ms);
-                    let frequency = pitch.0;
-                    let src = super::sources::get_source(duration_ms, frequency, timbre, volume);
-                    let native_channels = src.channels() as usize;
-                    if native_channels > max_channels {
-                        max_channels = native_channels;
-                    }
+        let events = compiled.events().iter().take_while(|event| event.start < length);
+        for &NoteEvent {
+            line_index: line,
+            ref note,
+            ..
+        } in events
+        {
+            let Some(kind) = self.mixer.apply(line, &note.1) else {
+                continue;
+            };
+            if let crate::note::NoteKind::Pitched {
+                pitch,
+                timbre,
+                volume,
+                envelope,
+                pan,
+                ..
+            } = &kind
+            {
+                if buses.contains_key(timbre) {
+                    continue;
+                }
+                if *pan != 0.0 {
+                    max_channels = max_channels.max(2);
+                }
+                let duration_ms = super::ticks_to_ms(note.0.ticks() as usize, beat_duration_ms);
+                let frequency = pitch.0;
+                let src =
+                    super::sources::get_source(duration_ms, frequency, timbre, *volume, *envelope, beat_duration_ms);
+                let native_channels = src.channels() as usize;
+                if native_channels > max_channels {
+                    max_channels = native_channels;
                 }
             }
         }
 
+        // The file needs room for the main mix and every bus
+        let total_channels = buses
+            .values()
+            .map(|bus| bus.channel_range().end)
+            .fold(max_channels, usize::max);
+
         // Allocate output buffers
-        let mut samples: Vec<Vec<f32>> = vec![vec![0.0; total_samples]; max_channels];
+        let mut samples: Vec<Vec<f32>> = vec![vec![0.0; total_samples]; total_channels];
+        // Lines with effects are mixed on their own, so their effects can be applied before they're added in
+        let mut line_samples: Vec<Option<Vec<Vec<f32>>>> = (0..piece.0.len())
+            .map(|line| {
+                (!piece.line_effects(line).is_empty() && self.mixer.is_audible(line))
+                    .then(|| vec![vec![0.0; total_samples]; total_channels])
+            })
+            .collect();
 
         // Step 2: Render and mix
-        for instant in 0..length {
-            let notes: Vec<_> = piece.get_notes_at_instant(instant).collect();
-            let start_ms = (instant as u64).saturating_mul(beat_duration_ms);
-            for note in notes {
-                match note.1 {
-                    crate::note::NoteKind::Pitched { pitch, timbre, volume } => {
-                        let duration_ms = (note.0 .0 as u64).saturating_mul(beat_duration_ms);
-                        let frequency = pitch.0;
-                        let src = super::sources::get_source(duration_ms, frequency, timbre, volume);
-                        let native_sample_rate = src.sample_rate();
-                        let native_channels = src.channels() as usize;
-
-                        let note_samples = (sample_rate as u64)
-                            .saturating_mul(duration_ms)
-                            .div(1000)
-                            .try_into()
-                            .unwrap_or(usize::MAX);
+        // Notes are rendered in batches, spread across threads, then mixed one at a time in the
+        // order they're in the piece, so the result is the same however many threads there are.
+        let threads = self.render_threads();
+        let resampling = self.output_config.resampling;
+        let mut mix_batch = |batch: &[NoteJob]| {
+            let rendered = render_notes(batch, beat_duration_ms, sample_rate, resampling, threads);
+            for (job, chans) in batch.iter().zip(rendered) {
+                let native_channels = chans.len();
+                let channel_gains = super::sources::pan_gains(job.pan);
+                let samples = match line_samples.get_mut(job.line) {
+                    Some(Some(line_samples)) => line_samples,
+                    _ => &mut samples,
+                };
 
-                        let native_samples = (native_sample_rate as u64)
-                            .saturating_mul(duration_ms)
-                            .div(1000)
-                            .try_into()
-                            .unwrap_or(usize::MAX);
+                // The channels this note is written to: its bus, or the main mix
+                let (first_channel, max_channels) = match buses.get(&job.timbre) {
+                    Some(bus) => (bus.channel_range().start, bus.channel_range().len()),
+                    None => (0, max_channels),
+                };
 
-                        // Collect all channels
-                        let mut chans: Vec<Vec<f32>> = vec![vec![]; native_channels];
+                // For each input channel, determine which output channel(s) to map to
+                for (in_ch, buf) in chans.iter().enumerate() {
+                    // Map input channel to output channel(s)
+                    let out_ch = if native_channels == 1 {
+                        // Mono: spread to all output channels
+                        (first_channel..first_channel + max_channels).collect::<Vec<_>>()
+                    } else {
+                        // N-channel: map to proportional output channel
+                        let idx = ((in_ch as f32) * (max_channels as f32 - 1.0) / (native_channels as f32 - 1.0))
+                            .round() as usize;
+                        vec![first_channel + idx]
+                    };
 
-                        // To my understanding, the samples are interleaved. That's why we do this
-                        for (i, s) in src.take(native_samples * native_channels).enumerate() {
-                            chans[i % native_channels].push(s);
-                        }
+                    // Append all the samples to the output channels.
+                    // Notes which started before the range have their beginning cut off.
+                    let to_samples = |ms: u64| -> i64 {
+                        (sample_rate as u64)
+                            .saturating_mul(ms)
+                            .div(1000)
+                            .try_into()
+                            .unwrap_or(i64::MAX)
+                    };
+                    let start_idx = to_samples(job.start_ms) - to_samples(range_start_ms);
 
-                        // For each input channel, determine which output channel(s) to map to
-                        for in_ch in 0..native_channels {
-                            // Map input channel to output channel(s)
-                            let out_ch = if native_channels == 1 {
-                                // Mono: spread to all output channels
-                                (0..max_channels).collect::<Vec<_>>()
-                            } else {
-                                // N-channel: map to proportional output channel
-                                let idx = ((in_ch as f32) * (max_channels as f32 - 1.0)
-                                    / (native_channels as f32 - 1.0))
-                                    .round() as usize;
-                                vec![idx]
-                            };
-                            let buf = if sample_rate != native_sample_rate {
-                                // If you don't resample, the source will play slightly too fast / slow, causing pitch issues
-                                resample_to_target_rate(
-                                    chans[in_ch].clone().into_iter(),
-                                    native_sample_rate,
-                                    sample_rate,
-                                    note_samples,
-                                )
-                            } else {
-                                chans[in_ch].clone()
-                            };
-
-                            // Append all the samples to the output channels
-                            let start_idx = (sample_rate as u64)
-                                .saturating_mul(start_ms)
-                                .div(1000)
-                                .try_into()
-                                .unwrap_or(usize::MAX);
-
-                            for (i, &s) in buf.iter().enumerate() {
-                                if let Some(idx) = start_idx.checked_add(i) {
-                                    for &ch in &out_ch {
-                                        if idx < samples[ch].len() {
-                                            // For mono, divide by number of output channels to avoid boosting volume
-                                            let val = if native_channels == 1 {
-                                                s / max_channels as f32
-                                            } else {
-                                                s
-                                            };
-                                            samples[ch][idx] += val;
-                                        }
+                    for (i, &s) in buf.iter().enumerate() {
+                        if let Ok(idx) = usize::try_from(start_idx.saturating_add(i as i64)) {
+                            for &ch in &out_ch {
+                                if idx < samples[ch].len() {
+                                    // For mono, divide by number of output channels to avoid boosting volume
+                                    let mut val = if native_channels == 1 {
+                                        s / max_channels as f32
+                                    } else {
+                                        s
+                                    };
+                                    if max_channels == 2 {
+                                        val *= channel_gains[ch - first_channel];
                                     }
+                                    samples[ch][idx] += val;
                                 }
                             }
                         }
                     }
-                    crate::note::NoteKind::Rest => continue,
                 }
             }
+        };
+
+        let seed = self.pass_seed();
+        let mut batch = vec![];
+        for (instant, events) in compiled.instants().take_while(|(instant, _)| *instant < length) {
+            let note_start_ms = super::ticks_to_ms(instant, beat_duration_ms);
+            for (
+                index,
+                &NoteEvent {
+                    line_index: line,
+                    ref note,
+                    ..
+                },
+            ) in events.iter().enumerate()
+            {
+                let Some(kind) = self.mixer.apply(line, &note.1) else {
+                    continue;
+                };
+                let crate::note::NoteKind::Pitched {
+                    pitch,
+                    timbre,
+                    volume,
+                    envelope,
+                    pan,
+                    probability,
+                    ratchet,
+                } = kind
+                else {
+                    continue;
+                };
+                if !super::note_plays(seed, instant, index, probability) {
+                    continue;
+                }
+                let note_length_ms = super::ticks_to_ms(note.0.ticks() as usize, beat_duration_ms);
+                // Each retrigger of a ratchet is rendered as its own note
+                for (offset_ms, note_duration_ms) in super::ratchet_hits(note_length_ms, ratchet) {
+                    let start_ms = note_start_ms.saturating_add(offset_ms);
+                    // Notes keep sounding past their length while they are released
+                    let duration_ms = super::sources::sounding_ms(note_duration_ms, &timbre, envelope);
+                    if start_ms.saturating_add(duration_ms) <= range_start_ms {
+                        // This note has finished sounding before the range starts
+                        continue;
+                    }
+                    batch.push(NoteJob {
+                        line,
+                        start_ms,
+                        note_duration_ms,
+                        duration_ms,
+                        frequency: pitch.0,
+                        timbre: timbre.clone(),
+                        volume,
+                        envelope,
+                        pan,
+                    });
+                }
+            }
+            if batch.len() >= RENDER_BATCH_SIZE {
+                mix_batch(&batch);
+                batch.clear();
+                progress(RenderProgress::from_ticks(
+                    instant.saturating_sub(range.start),
+                    length.saturating_sub(range.start),
+                ));
+            }
         }
+        mix_batch(&batch);
 
-        // Normalize all channels
-        for ch in 0..max_channels {
-            // It seems like this normalizes all channels separately, which seems strange but I trust the process.
-            let max = samples[ch].iter().cloned().fold(0.0_f32, |a, b| a.abs().max(b.abs()));
-            if max > 0.0 {
-                for s in &mut samples[ch] {
-                    *s = (*s / max) * output_gain;
+        // Step 3: Apply each line's effects, and mix it in
+        for (line, line_samples) in line_samples.iter_mut().enumerate() {
+            if let Some(line_samples) = line_samples {
+                crate::effects::apply_effects(piece.line_effects(line), line_samples, sample_rate);
+                for (channel, line_channel) in samples.iter_mut().zip(line_samples.iter()) {
+                    for (sample, line_sample) in channel.iter_mut().zip(line_channel) {
+                        *sample += line_sample;
+                    }
                 }
             }
         }
 
-        // Write to WAV (interleaved)
-        let spec = hound::WavSpec {
-            channels: max_channels as u16,
-            sample_rate,
-            // This is apparently CD quality
-            bits_per_sample: 16,
-            sample_format: hound::SampleFormat::Int,
-        };
+        samples
+    }
+}
 
-        let mut writer = hound::WavWriter::create(path, spec).unwrap();
+/// How many notes are rendered before they're mixed. Rendered notes are held in memory until
+/// they're mixed, so this keeps long pieces from using too much memory.
+const RENDER_BATCH_SIZE: usize = 128;
 
-        // Convert to 16 bits per sample and int sample format
-        for i in 0..total_samples {
-            for ch in 0..max_channels {
-                #[expect(clippy::cast_possible_truncation, reason = "It's clamped, so it should be safe")]
-                let s: i16 = (samples[ch][i] * i16::MAX as f32).clamp(i16::MIN as f32, i16::MAX as f32) as i16;
-                writer.write_sample(s).unwrap();
-            }
-        }
+/// A note to be rendered, with everything needed to render it and mix it in.
+struct NoteJob {
+    line: usize,
+    /// When the note starts, in milliseconds from the start of the piece
+    start_ms: u64,
+    /// How long the note is held
+    note_duration_ms: u64,
+    /// How long the note sounds, including its release
+    duration_ms: u64,
+    frequency: f32,
+    timbre: crate::Timbre,
+    volume: f32,
+    envelope: Option<crate::Envelope>,
+    pan: f32,
+}
 
-        writer.finalize().unwrap();
+/// Renders notes on up to `threads` threads, returning each note's samples for each of its channels,
+/// in the same order as the notes.
+fn render_notes(
+    jobs: &[NoteJob],
+    beat_duration_ms: u64,
+    sample_rate: u32,
+    resampling: ResamplingQuality,
+    threads: usize,
+) -> Vec<Vec<Vec<f32>>> {
+    let render = |jobs: &[NoteJob]| -> Vec<Vec<Vec<f32>>> {
+        jobs.iter()
+            .map(|job| render_note(job, beat_duration_ms, sample_rate, resampling))
+            .collect()
+    };
+    if threads <= 1 || jobs.len() <= 1 {
+        return render(jobs);
     }
+
+    let chunk_size = jobs.len().div_ceil(threads);
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = jobs
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || render(chunk)))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic)))
+            .collect()
+    })
 }
 
-// This was originally a linear interpolation, but I changed it to cubic for better quality.
-fn cubic_interp(y0: f32, y1: f32, y2: f32, y3: f32, t: f32) -> f32 {
-    let a0 = y3 - y2 - y0 + y1;
-    let a1 = y0 - y1 - a0;
-    let a2 = y2 - y0;
-    let a3 = y1;
-    a0 * t * t * t + a1 * t * t + a2 * t + a3
+/// Renders one note at the given sample rate, returning its samples for each of its channels.
+fn render_note(job: &NoteJob, beat_duration_ms: u64, sample_rate: u32, resampling: ResamplingQuality) -> Vec<Vec<f32>> {
+    let src = super::sources::get_source(
+        job.note_duration_ms,
+        job.frequency,
+        &job.timbre,
+        job.volume,
+        job.envelope,
+        beat_duration_ms,
+    );
+    let native_sample_rate = src.sample_rate();
+    let native_channels = src.channels() as usize;
+
+    let note_samples = (sample_rate as u64)
+        .saturating_mul(job.duration_ms)
+        .div(1000)
+        .try_into()
+        .unwrap_or(usize::MAX);
+
+    let native_samples = (native_sample_rate as u64)
+        .saturating_mul(job.duration_ms)
+        .div(1000)
+        .try_into()
+        .unwrap_or(usize::MAX);
+
+    // Collect all channels
+    let mut chans: Vec<Vec<f32>> = vec![vec![]; native_channels];
+
+    // To my understanding, the samples are interleaved. That's why we do this
+    for (i, s) in src.take(native_samples * native_channels).enumerate() {
+        chans[i % native_channels].push(s);
+    }
+
+    if sample_rate == native_sample_rate {
+        return chans;
+    }
+    // If you don't resample, the source will play slightly too fast / slow, causing pitch issues
+    chans
+        .into_iter()
+        .map(|chan| resampling.resample(&chan, note_samples))
+        .collect()
 }
 
-#[expect(
-    clippy::cast_possible_truncation,
-    clippy::cast_sign_loss,
-    clippy::cast_precision_loss,
-    clippy::arithmetic_side_effects,
-    clippy::cast_possible_wrap,
-    reason = "Cubic interpolation and resampling require these conversions; safe for audio."
-)]
-// I assume this approximates inbetweening the samples using interpolation.
-fn resample_to_target_rate<I: Iterator<Item = f32>>(
-    input: I,
-    input_rate: u32,
-    output_rate: u32,
-    num_samples: usize,
-) -> Vec<f32> {
-    if input_rate == output_rate {
-        return input.take(num_samples).collect();
+/// Writes samples to a 16-bit WAV file, with one buffer of samples per channel, and any
+/// metadata in a LIST INFO chunk.
+pub(super) fn write_wav(path: &Path, samples: &[Vec<f32>], sample_rate: u32, metadata: &Metadata) -> io::Result<()> {
+    let to_io = |error: hound::Error| match error {
+        hound::Error::IoError(error) => error,
+        error => io::Error::other(error),
+    };
+    let total_channels = samples.len();
+    let total_samples = samples.first().map_or(0, Vec::len);
+
+    // Write to WAV (interleaved)
+    let spec = hound::WavSpec {
+        channels: total_channels as u16,
+        sample_rate,
+        // This is apparently CD quality
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    let mut writer = hound::WavWriter::create(path, spec).map_err(to_io)?;
+
+    // Convert to 16 bits per sample and int sample format
+    for i in 0..total_samples {
+        for ch in 0..total_channels {
+            #[expect(clippy::cast_possible_truncation, reason = "It's clamped, so it should be safe")]
+            let s: i16 = (samples[ch][i] * i16::MAX as f32).clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+            writer.write_sample(s).map_err(to_io)?;
+        }
     }
-    let input: Vec<f32> = input.collect();
-    let input_len = input.len();
-    let mut output = Vec::with_capacity(num_samples);
-    for i in 0..num_samples {
-        let t = i as f64 * (input_len as f64 - 1.0) / (num_samples as f64 - 1.0);
-        let idx = t.floor() as isize;
-        let frac = (t - idx as f64) as f32;
-        // Get four points for cubic interpolation
-        let y0 = *input.get((idx - 1).max(0) as usize).unwrap_or(&0.0);
-        let y1 = *input.get(idx.max(0) as usize).unwrap_or(&0.0);
-        let y2 = *input
-            .get((idx + 1).min((input_len - 1) as isize) as usize)
-            .unwrap_or(&0.0);
-        let y3 = *input
-            .get((idx + 2).min((input_len - 1) as isize) as usize)
-            .unwrap_or(&0.0);
-        output.push(cubic_interp(y0, y1, y2, y3, frac));
+
+    writer.finalize().map_err(to_io)?;
+    write_wav_info(path, metadata)
+}
+
+/// Appends a LIST INFO chunk with the metadata to a finished WAV file, then updates the size in
+/// its RIFF header. Nothing is written if there's no metadata.
+fn write_wav_info(path: &Path, metadata: &Metadata) -> io::Result<()> {
+    // The comment holds what INFO has no field for
+    let fields = [
+        (b"INAM", metadata.title.clone()),
+        (b"IART", metadata.artist.clone()),
+        (b"ICOP", metadata.copyright.clone()),
+        (b"ICMT", metadata.details()),
+    ];
+
+    let mut info = b"INFO".to_vec();
+    for (id, text) in fields {
+        let Some(text) = text else {
+            continue;
+        };
+        // Text is null-terminated, and chunks are padded to an even length
+        let mut data = text.into_bytes();
+        data.push(0);
+        info.extend_from_slice(id);
+        info.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        if data.len() % 2 == 1 {
+            data.push(0);
+        }
+        info.extend_from_slice(&data);
+    }
+    if info.len() == 4 {
+        return Ok(());
+    }
+
+    let mut file = std::fs::OpenOptions::new().read(true).write(true).open(path)?;
+    let end = file.seek(SeekFrom::End(0))?;
+    file.write_all(b"LIST")?;
+    file.write_all(&(info.len() as u32).to_le_bytes())?;
+    file.write_all(&info)?;
+
+    // The RIFF size counts everything after its own eight bytes, which the LIST header makes up for
+    let riff_size = end + info.len() as u64;
+    file.seek(SeekFrom::Start(4))?;
+    file.write_all(&(riff_size as u32).to_le_bytes())
+}
+
+/// Scales the samples so the loudest is at full scale, and returns how loud it was before.
+///
+/// All channels are normalized together, so the balance between them (and so any panning) is kept.
+pub(super) fn normalize(samples: &mut [Vec<f32>]) -> f32 {
+    let max = samples
+        .iter()
+        .flatten()
+        .cloned()
+        .fold(0.0_f32, |a, b| a.abs().max(b.abs()));
+    if max > 0.0 {
+        for s in samples.iter_mut().flatten() {
+            *s /= max;
+        }
     }
-    output
+    max
+}
+
+#[test]
+fn test_routed_timbre_renders_to_its_bus() {
+    use crate::{prelude::*, OutputBus};
+
+    let path = std::env::temp_dir().join("symphoxy_test_routed_timbre.wav");
+
+    let player = MusicPlayer::new_file(300, 1.0, 8000).with_bus(Timbre::Sine, OutputBus::new("Sines", 2, 2));
+    player.render_to_wav(quarter(C4), &path);
+
+    let mut reader = hound::WavReader::open(&path).unwrap();
+    assert_eq!(reader.spec().channels, 4);
+
+    let samples: Vec<i16> = reader.samples::<i16>().map(Result::unwrap).collect();
+    let peak = |channel: usize| samples.iter().skip(channel).step_by(4).map(|s| s.abs()).max().unwrap();
+    assert_eq!(peak(0), 0);
+    assert_eq!(peak(1), 0);
+    assert!(peak(2) > 0);
+    assert!(peak(3) > 0);
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_range_includes_notes_still_sounding() {
+    use crate::prelude::*;
+
+    let path = std::env::temp_dir().join("symphoxy_test_range.wav");
+
+    // The range starts halfway through the first note, so only the second half of it is rendered
+    let line = whole(C4) + whole(REST);
+    let half = half(C4).0.ticks() as usize;
+    let player = MusicPlayer::new_file(300, 1.0, 8000);
+    player.render_range_to_wav(line, half..2 * half, &path);
+
+    let mut reader = hound::WavReader::open(&path).unwrap();
+    let samples: Vec<i16> = reader.samples::<i16>().map(Result::unwrap).collect();
+    assert_eq!(samples.len(), 8000 * 8 * 200 / 1000);
+    assert!(samples[..100].iter().any(|&s| s != 0));
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_region_is_rendered_from_beats() {
+    use crate::prelude::*;
+
+    let path = std::env::temp_dir().join("symphoxy_test_region.wav");
+
+    // The region is the second half of the whole note and the first half of the rest
+    let line = whole(C4) + whole(REST);
+    let player = MusicPlayer::new_file(300, 1.0, 8000);
+    player.render_to_wav_region(line, &path, 8..24);
+
+    let mut reader = hound::WavReader::open(&path).unwrap();
+    let samples: Vec<i16> = reader.samples::<i16>().map(Result::unwrap).collect();
+    assert_eq!(samples.len(), 8000 * 16 * 200 / 1000);
+    assert!(samples[..100].iter().any(|&s| s != 0));
+    assert!(samples[samples.len() - 100..].iter().all(|&s| s == 0));
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_region_cuts_off_the_start_of_notes_sounding_into_it() {
+    use crate::prelude::*;
+
+    let path = std::env::temp_dir().join("symphoxy_test_region_cut.wav");
+
+    // A sine which takes a whole second to fade in, held for 16 beats of 200 ms
+    let line = whole(C4)
+        .with_timbre(Timbre::Sine)
+        .envelope(Envelope::from_millis(1000, 0, 1.0, 0));
+    let player = MusicPlayer::new_file(300, 1.0, 8000);
+    let opening_level = |beats: Range<usize>| {
+        player.render_to_wav_region(line.clone(), &path, beats);
+        let samples: Vec<i32> = hound::WavReader::open(&path)
+            .unwrap()
+            .samples::<i16>()
+            .map(|sample| i32::from(sample.unwrap()).abs())
+            .collect();
+        let peak = *samples.iter().max().unwrap();
+        *samples[..100].iter().max().unwrap() as f32 / peak as f32
+    };
+
+    // From the start, the note fades in, but from beat 8, it's already at full volume
+    assert!(opening_level(0..16) < 0.1);
+    assert!(opening_level(8..16) > 0.9);
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_tail_lets_last_note_ring_out() {
+    use crate::prelude::*;
+
+    // At 300 sixteenths a minute, the quarter note ends 800 ms in
+    let line = Line::from(piano(quarter(C4)));
+    let length = 8000 * 800 / 1000;
+    let render = |tail| {
+        MusicPlayer::new_file(300, 1.0, 8000)
+            .with_tail(tail)
+            .render_range_samples(line.clone(), 0..line.length())
+    };
+
+    assert_eq!(render(RenderTail::Off)[0].len(), length);
+    assert_eq!(render(RenderTail::Fixed(500))[0].len(), length + 4000);
+
+    // The piano's release rings on past the end, then the silence after it is trimmed
+    let auto = render(RenderTail::Auto);
+    assert!(auto[0].len() > length);
+    assert!(auto[0][length..].iter().any(|sample| sample.abs() > 0.01));
+    assert!(auto[0].last().unwrap().abs() > SILENCE);
+}
+
+#[test]
+fn test_render_reports_progress_up_to_the_last_beat() {
+    use crate::prelude::*;
+
+    let path = std::env::temp_dir().join("symphoxy_test_progress.wav");
+
+    // Enough notes for several batches
+    let line = Line::from(piano(sixteenth(C4))) * 300;
+    let mut reports = vec![];
+    MusicPlayer::new_file(300, 1.0, 8000).render_to_wav_with_progress(line, &path, |progress| reports.push(progress));
+
+    assert!(reports.len() > 2);
+    assert!(reports.windows(2).all(|pair| pair[0].beat <= pair[1].beat));
+    assert!(reports.iter().all(|progress| progress.total_beats == 300));
+    assert_eq!(reports.last().unwrap().fraction(), 1.0);
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_metadata_is_written_to_info_chunk() {
+    use crate::prelude::*;
+
+    let path = std::env::temp_dir().join("symphoxy_test_metadata.wav");
+
+    let metadata = Metadata::new("Test Tone").with_artist("Symphoxy").with_key("A major");
+    let player = MusicPlayer::new_file(300, 1.0, 8000).with_metadata(metadata);
+    player.render_to_wav(quarter(A4), &path);
+
+    let bytes = std::fs::read(&path).unwrap();
+    let riff_size = u32::from_le_bytes(bytes[4..8].try_into().unwrap());
+    assert_eq!(riff_size as usize, bytes.len() - 8);
+    let contains = |needle: &[u8]| bytes.windows(needle.len()).any(|window| window == needle);
+    assert!(contains(b"INAM\x0a\0\0\0Test Tone\0"));
+    assert!(contains(b"IART\x09\0\0\0Symphoxy\0"));
+    assert!(contains(b"A major\0"));
+
+    // Readers which don't know about the chunk still read the audio
+    let reader = hound::WavReader::open(&path).unwrap();
+    assert_eq!(reader.duration(), 8000 * 800 / 1000);
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_master_limiter_keeps_peaks_under_ceiling() {
+    use crate::prelude::*;
+
+    let piece = piano(quarter(C4) + quarter(E4)) * drums(quarter(C4) + quarter(C4));
+    let player = MusicPlayer::new_file(300, 1.0, 8000).with_master_effect(Limiter {
+        ceiling: 0.5,
+        ..Limiter::default()
+    });
+    let length = piece.length();
+    let samples = player.render_range_samples(piece, 0..length);
+
+    let peak = samples.iter().flatten().fold(0.0_f32, |peak, s| peak.max(s.abs()));
+    assert!(peak <= 0.5 && peak > 0.4);
+}
+
+#[test]
+fn test_threaded_render_matches_serial_render() {
+    use crate::prelude::*;
+
+    // More notes than fit in one batch, with different timbres and sample rates, some panned
+    let notes: Vec<_> = (0..140).map(|step| sixty_fourth(C4.semitone(step % 12))).collect();
+    let melody = sine(Line::from(notes));
+    let pad = square(whole(C3) * 5).pan(-0.4);
+    let beat = drums((eighth(C4) + eighth(C3)) * 5);
+    let piece = melody * pad * beat;
+
+    let player = |threads| {
+        MusicPlayer::new_file(600, 1.0, 8000)
+            .with_seed(3)
+            .with_render_threads(threads)
+    };
+    let (serial, threaded) = (player(1), player(4));
+    assert_eq!(serial.render_samples(piece.clone()), threaded.render_samples(piece));
 }
@@ -0,0 +1,156 @@
+use std::f64::consts::PI;
+
+/// How notes are resampled when their source's sample rate isn't the output's, set with
+/// [`MusicPlayer::with_resampling`](crate::MusicPlayer::with_resampling).
+///
+/// Custom samples are pitched by resampling them, so this matters most for pieces which use
+/// [`Timbre::Sample`](crate::Timbre::Sample) or [`Timbre::Sampled`](crate::Timbre::Sampled).
+/// Better quality is slower, so a quick preview can use [`ResamplingQuality::Linear`], and a
+/// final master [`ResamplingQuality::Sinc`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ResamplingQuality {
+    /// Straight lines between samples. The fastest, but dulls high notes and can alias.
+    Linear,
+    /// Smooth curves through four samples at a time
+    #[default]
+    Cubic,
+    /// A windowed sinc filter, which keeps the high end and filters out aliasing when samples are
+    /// pitched up. The slowest, for final masters.
+    Sinc,
+}
+
+/// How many input samples either side of each output sample the sinc filter reaches, at its
+/// narrowest. It reaches further when samples are pitched up, to filter out aliasing.
+const SINC_HALF_WIDTH: f64 = 8.0;
+
+impl ResamplingQuality {
+    /// Stretches or squeezes the samples to the given number of samples.
+    #[expect(clippy::cast_precision_loss, reason = "Notes are far shorter than the 2^52 samples where this matters")]
+    pub(super) fn resample(self, input: &[f32], num_samples: usize) -> Vec<f32> {
+        if input.len() == num_samples {
+            return input.to_vec();
+        }
+        let Some(last) = input.len().checked_sub(1) else {
+            return vec![0.0; num_samples];
+        };
+        // How far apart the output samples are, in input samples
+        let step = last as f64 / num_samples.saturating_sub(1).max(1) as f64;
+        (0..num_samples)
+            .map(|index| {
+                let position = index as f64 * step;
+                match self {
+                    ResamplingQuality::Linear => linear(input, position),
+                    ResamplingQuality::Cubic => cubic(input, position),
+                    ResamplingQuality::Sinc => sinc(input, position, step),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Returns the input sample at an index, holding the first and last samples past either end.
+#[expect(
+    clippy::cast_sign_loss,
+    clippy::cast_possible_truncation,
+    reason = "The index is clamped into the input first"
+)]
+fn held(input: &[f32], index: f64) -> f32 {
+    let last = input.len().saturating_sub(1);
+    input.get((index.max(0.0) as usize).min(last)).copied().unwrap_or(0.0)
+}
+
+#[expect(clippy::cast_possible_truncation, reason = "The fraction is between 0 and 1")]
+fn linear(input: &[f32], position: f64) -> f32 {
+    let index = position.floor();
+    let t = (position - index) as f32;
+    let (y0, y1) = (held(input, index), held(input, index + 1.0));
+    y0 + (y1 - y0) * t
+}
+
+// This was originally a linear interpolation, but I changed it to cubic for better quality.
+#[expect(clippy::cast_possible_truncation, reason = "The fraction is between 0 and 1")]
+fn cubic(input: &[f32], position: f64) -> f32 {
+    let index = position.floor();
+    let t = (position - index) as f32;
+    let y0 = held(input, index - 1.0);
+    let y1 = held(input, index);
+    let y2 = held(input, index + 1.0);
+    let y3 = held(input, index + 2.0);
+
+    let a0 = y3 - y2 - y0 + y1;
+    let a1 = y0 - y1 - a0;
+    let a2 = y2 - y0;
+    let a3 = y1;
+    a0 * t * t * t + a1 * t * t + a2 * t + a3
+}
+
+/// Filters the input around a position with a Blackman-windowed sinc. When the output samples
+/// are further apart than the input's, the cutoff is lowered to match, so nothing aliases.
+#[expect(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_precision_loss,
+    reason = "The filter is a few dozen samples wide, and samples are far inside f32's range"
+)]
+fn sinc(input: &[f32], position: f64, step: f64) -> f32 {
+    let cutoff = 1.0 / step.max(1.0);
+    let half_width = (SINC_HALF_WIDTH / cutoff).ceil();
+
+    let first = (position - half_width).floor();
+    let (sum, weights) = (0..=(2.0 * half_width) as usize)
+        .map(|offset| first + offset as f64)
+        .filter(|index| (index - position).abs() < half_width)
+        .fold((0.0, 0.0), |(sum, weights), index| {
+            let distance = index - position;
+            let window =
+                0.42 + 0.5 * (PI * distance / half_width).cos() + 0.08 * (2.0 * PI * distance / half_width).cos();
+            let weight = normalized_sinc(distance * cutoff) * window;
+            (sum + f64::from(held(input, index)) * weight, weights + weight)
+        });
+    // Dividing by the total weight keeps a constant signal at the same level
+    if weights == 0.0 {
+        0.0
+    } else {
+        (sum / weights) as f32
+    }
+}
+
+fn normalized_sinc(x: f64) -> f64 {
+    if x == 0.0 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+#[test]
+#[expect(clippy::cast_possible_truncation, clippy::cast_precision_loss, reason = "It's a short test signal")]
+fn test_sinc_resampling_is_most_accurate() {
+    // A high sine, resampled to 1.5 times as many samples, compared to the sine itself
+    let sine = |samples: usize| -> Vec<f32> {
+        let step = 999.0 / (samples as f64 - 1.0);
+        (0..samples)
+            .map(|index| (index as f64 * step * 0.3 * PI).sin() as f32)
+            .collect()
+    };
+    let input = sine(1000);
+    let expected = sine(1500);
+    let error = |quality: ResamplingQuality| -> f32 {
+        let output = quality.resample(&input, 1500);
+        // The middle is compared, as every resampler has to guess past the ends
+        (100..1400)
+            .map(|index| (output[index] - expected[index]).abs())
+            .fold(0.0, f32::max)
+    };
+
+    let (linear, cubic, sinc) = (
+        error(ResamplingQuality::Linear),
+        error(ResamplingQuality::Cubic),
+        error(ResamplingQuality::Sinc),
+    );
+    assert!(sinc < cubic && cubic < linear, "{linear} {cubic} {sinc}");
+    assert!(sinc < 0.01, "{sinc}");
+
+    // Resampling to the same length leaves the samples as they are
+    assert_eq!(ResamplingQuality::Sinc.resample(&input, 1000), input);
+}
@@ -0,0 +1,214 @@
+use std::{
+    io,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    note::{chord::Chord, whole, TimbreFluid},
+    play::{
+        midi_number, render_to_wav::write_wav, sources::sounding_ms, stems::timbre_name, ticks_to_ms, FileOutputConfig,
+    },
+    Line, Metadata, MusicPlayer, NotePitch, Piece, Tet12, Timbre, A4, REST,
+};
+
+/// Samples quieter than this at the end of a sample are trimmed off, which is just under the
+/// quietest level a 16-bit file can hold.
+const SILENCE: f32 = 1.0 / 65536.0;
+
+impl MusicPlayer<FileOutputConfig> {
+    /// Renders every semitone in a range, played by one timbre, to its own WAV file, so the timbre
+    /// can be used as a sample pack in other DAWs and trackers.
+    ///
+    /// Each note is held for a whole note at the player's tempo, then left to ring out until its
+    /// release ends, and the silence after it is trimmed. Each sample is normalized and clipped
+    /// on its own, as set by [`MusicPlayer::with_normalization`] and
+    /// [`MusicPlayer::with_clipping`]. Turn normalization off to keep the samples' loudness
+    /// consistent with each other.
+    ///
+    /// Files are named after the timbre, the MIDI note number, and the note name, with sharps
+    /// written as `s`, such as `piano_061_Cs4.wav`, so they sort from lowest to highest.
+    /// The directory is created if it doesn't exist, and files already in it with the same names
+    /// are overwritten.
+    ///
+    /// # Arguments
+    /// * `timbre` - The timbre to render
+    /// * `pitches` - The lowest and highest pitches to render. Every semitone from the lowest up is rendered.
+    /// * `dir` - The directory the samples are written to
+    ///
+    /// # Returns
+    /// The paths of the files written, from lowest to highest.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{MusicPlayer, NormalizationMode};
+    ///
+    /// let player = MusicPlayer::new_file(240, 1.0, 44100).with_normalization(NormalizationMode::Off);
+    ///
+    /// // Two octaves of the electric guitar, from C3 to C5
+    /// let samples = player.export_sample_pack(Timbre::ElectricGuitar, C3..=C5, "guitar_pack").unwrap();
+    /// assert_eq!(samples.len(), 25);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the directory can't be created, or a file can't be written.
+    pub fn export_sample_pack(
+        &self,
+        timbre: Timbre,
+        pitches: RangeInclusive<NotePitch>,
+        dir: impl AsRef<Path>,
+    ) -> io::Result<Vec<PathBuf>> {
+        self.export_pack(&timbre, pitches, dir.as_ref(), |root| {
+            let name = format!("{}_{:03}_{}", timbre_name(&timbre), midi_number(root), note_name(root));
+            (name, Chord::from(root))
+        })
+    }
+
+    /// Renders a chord, played by one timbre, rooted on every semitone in a range, each to its own
+    /// WAV file, so the chord can be used as a sample pack in other DAWs and trackers.
+    ///
+    /// The chord keeps its shape, and is moved so its lowest pitch is on each root. Samples are
+    /// rendered the same way as [`MusicPlayer::export_sample_pack`].
+    ///
+    /// Files are named after the timbre, the MIDI note number of the root, and the chord's
+    /// symbol, such as `strings_060_Cmaj7.wav`. Chords without a symbol are named `chord`, such as `strings_060_chord.wav`.
+    ///
+    /// # Arguments
+    /// * `timbre` - The timbre to render
+    /// * `chord` - The chord to render. Any voicing or inversion works.
+    /// * `roots` - The lowest and highest pitches the chord's lowest pitch is moved to
+    /// * `dir` - The directory the samples are written to
+    ///
+    /// # Returns
+    /// The paths of the files written, from lowest to highest.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let minor_seventh = Chord::from_degrees(&MinorScale(A4), &[1, 3, 5, 7]);
+    ///
+    /// let player = MusicPlayer::new_file(240, 1.0, 44100);
+    /// let samples = player.export_chord_pack(Timbre::SynthPad, &minor_seventh, C3..=B3, "pad_chords").unwrap();
+    /// assert!(samples[0].ends_with("synth_pad_048_Cm7.wav"));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the directory can't be created, or a file can't be written.
+    pub fn export_chord_pack(
+        &self,
+        timbre: Timbre,
+        chord: &Chord,
+        roots: RangeInclusive<NotePitch>,
+        dir: impl AsRef<Path>,
+    ) -> io::Result<Vec<PathBuf>> {
+        self.export_pack(&timbre, roots, dir.as_ref(), |root| {
+            let chord = chord.transpose_to(root);
+            let symbol = chord
+                .symbol()
+                .map_or_else(|| "chord".to_string(), |symbol| file_safe(&symbol));
+            let name = format!("{}_{:03}_{symbol}", timbre_name(&timbre), midi_number(root));
+            (name, chord)
+        })
+    }
+
+    /// Renders the chord returned for every semitone in a range, writing each to the file it names.
+    fn export_pack(
+        &self,
+        timbre: &Timbre,
+        roots: RangeInclusive<NotePitch>,
+        dir: &Path,
+        sample: impl Fn(NotePitch) -> (String, Chord),
+    ) -> io::Result<Vec<PathBuf>> {
+        std::fs::create_dir_all(dir)?;
+
+        let (lowest, highest) = roots.into_inner();
+        let semitones = midi_number(highest).saturating_sub(midi_number(lowest));
+        (0..=semitones)
+            .map(|semitone| {
+                let (name, chord) = sample(lowest.semitone(semitone));
+                let mut samples = self.render_range_samples(self.held(timbre, &chord), 0..usize::MAX);
+                self.apply_output_stage(&mut samples);
+                trim_silence(&mut samples);
+
+                let path = dir.join(format!("{name}.wav"));
+                write_wav(&path, &samples, self.output_config.sample_rate, &Metadata::default())?;
+                Ok(path)
+            })
+            .collect()
+    }
+
+    /// Returns a piece holding every pitch of a chord for a whole note, followed by enough rests
+    /// for the notes to ring out.
+    fn held(&self, timbre: &Timbre, chord: &Chord) -> Piece {
+        let held = whole(REST);
+        let held_ms = ticks_to_ms(held.0.ticks() as usize, self.beat_duration_ms());
+        let ring_ms = sounding_ms(held_ms, timbre, None).saturating_sub(held_ms);
+
+        let mut rests = vec![];
+        let mut rest_ms = 0;
+        while rest_ms < ring_ms {
+            rests.push(held.clone());
+            rest_ms = ticks_to_ms(
+                rests.len().saturating_mul(held.0.ticks() as usize),
+                self.beat_duration_ms(),
+            );
+        }
+
+        Piece(
+            chord
+                .iter()
+                .map(|&pitch| Line::from([vec![whole(pitch).with_timbre(timbre.clone())], rests.clone()].concat()))
+                .collect(),
+        )
+    }
+}
+
+/// Returns the name of a pitch, with its octave, that can be used in a file name, such as `Cs4` for C♯4.
+fn note_name(pitch: NotePitch) -> String {
+    file_safe(&crate::get_note_name_with_octave(pitch, A4))
+}
+
+/// Replaces the characters of a note name or chord symbol which don't belong in a file name.
+fn file_safe(name: &str) -> String {
+    name.replace('#', "s").replace('/', "_over_").replace(['(', ')'], "")
+}
+
+/// Removes the silence from the end of every channel.
+fn trim_silence(samples: &mut [Vec<f32>]) {
+    let length = samples
+        .iter()
+        .filter_map(|channel| channel.iter().rposition(|sample| sample.abs() > SILENCE))
+        .max()
+        .map_or(0, |last| last.saturating_add(1));
+    for channel in samples {
+        channel.truncate(length);
+    }
+}
+
+#[test]
+fn test_sample_pack_names_and_ring_out() {
+    use crate::prelude::*;
+
+    let dir = std::env::temp_dir().join(format!("symphoxy_samples_{}", std::process::id()));
+    let player = MusicPlayer::new_file(960, 1.0, 8000);
+
+    let samples = player.export_sample_pack(Timbre::Piano, C4..=D4, &dir).unwrap();
+    let names: Vec<String> = samples
+        .iter()
+        .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(names, ["piano_060_C4.wav", "piano_061_Cs4.wav", "piano_062_D4.wav"]);
+
+    // The release rings past the end of the held note
+    let held_ms = ticks_to_ms(usize::from(whole(C4).0.ticks()), player.beat_duration_ms());
+    let frames = hound::WavReader::open(&samples[0]).unwrap().duration();
+    assert!(u64::from(frames) * 1000 / 8000 > held_ms);
+
+    let chord = Chord::new([C4, C4.semitone(4), C4.semitone(7), C4.semitone(11)]);
+    let chords = player.export_chord_pack(Timbre::Piano, &chord, A3..=A3, &dir).unwrap();
+    assert!(chords[0].ends_with("piano_057_Amaj7.wav"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -1,24 +1,219 @@
-use std::{io::BufReader, path::Path, time::Duration};
+use std::{
+    f32::consts::{FRAC_PI_4, SQRT_2},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
-use rodio::{source::SineWave, Decoder, Source};
+use rodio::{buffer::SamplesBuffer, source::SineWave, Source};
 
-use crate::{note::Timbre, Tet12, C4};
+use crate::{
+    note::Timbre,
+    play::{
+        decode_cache::{decode, prepare, DecodeError, DecodedAudio},
+        fm::FmVoice,
+        modulation::apply_lfos,
+        oscillator::{Oscillator, Waveform},
+    },
+    Envelope, FmPatch, NoteKind, NotePitch, Piece, SampleInstrument, Tet12, C4,
+};
 
 pub type SymphoxySource = Box<dyn Source<Item = f32> + Send>;
 
-pub fn get_source(duration_ms: u64, frequency: f32, timbre: Timbre, volume: f32) -> SymphoxySource {
-    Box::new(get_dyn_source(duration_ms, frequency, timbre).amplify(volume))
+/// Creates the source for a note held for `duration_ms`.
+///
+/// If `envelope` is `None`, the timbre's default envelope is used. The returned source
+/// lasts for [`sounding_ms`], which includes the envelope's release.
+/// `beat_duration_ms` is the length of a sixteenth note, which sets the speed of custom timbres' LFOs.
+pub fn get_source(
+    duration_ms: u64,
+    frequency: f32,
+    timbre: &Timbre,
+    volume: f32,
+    envelope: Option<Envelope>,
+    beat_duration_ms: u64,
+) -> SymphoxySource {
+    if let Timbre::Layered(layers) = timbre {
+        return get_layered_source(duration_ms, frequency, layers, volume, envelope, beat_duration_ms);
+    }
+    if let Timbre::Reversed(timbre) = timbre {
+        return reverse_source(get_source(
+            duration_ms,
+            frequency,
+            timbre,
+            volume,
+            envelope,
+            beat_duration_ms,
+        ));
+    }
+
+    let envelope = envelope.or_else(|| timbre.default_envelope());
+    let sounding_duration_ms = sounding_ms(duration_ms, timbre, envelope);
+
+    let source = get_dyn_source(sounding_duration_ms, frequency, timbre);
+    let source = match timbre {
+        Timbre::Custom(synth) => apply_lfos(source, &synth.lfos(), beat_duration_ms),
+        _ => source,
+    };
+    let source: SymphoxySource = match envelope {
+        Some(envelope) => Box::new(EnvelopeSource::new(
+            source,
+            envelope,
+            Duration::from_millis(duration_ms),
+        )),
+        None => source,
+    };
+
+    Box::new(
+        source
+            .take_duration(Duration::from_millis(sounding_duration_ms))
+            .amplify(volume),
+    )
+}
+
+/// Mixes the sources of each layer of a layered timbre.
+fn get_layered_source(
+    duration_ms: u64,
+    frequency: f32,
+    layers: &[(Timbre, f32)],
+    volume: f32,
+    envelope: Option<Envelope>,
+    beat_duration_ms: u64,
+) -> SymphoxySource {
+    let sources = layers.iter().map(|(timbre, gain)| {
+        get_source(
+            duration_ms,
+            frequency,
+            timbre,
+            volume * gain,
+            envelope,
+            beat_duration_ms,
+        )
+    });
+
+    sources
+        .reduce(|mixed, source| Box::new(mixed.mix(source)))
+        .unwrap_or_else(|| Box::new(rodio::source::Zero::<f32>::new(1, 44100).take_duration(Duration::ZERO)))
+}
+
+/// Plays a source backwards, frame by frame. The whole source is rendered up front, so it must end.
+fn reverse_source(source: SymphoxySource) -> SymphoxySource {
+    let channels = source.channels().max(1);
+    let sample_rate = source.sample_rate();
+    let samples: Vec<f32> = source.collect();
+    let reversed: Vec<f32> = samples.chunks(usize::from(channels)).rev().flatten().copied().collect();
+    Box::new(SamplesBuffer::new(channels, sample_rate, reversed))
+}
+
+/// Returns how long a note held for `duration_ms` sounds for, including its envelope's release.
+pub fn sounding_ms(duration_ms: u64, timbre: &Timbre, envelope: Option<Envelope>) -> u64 {
+    if let Timbre::Reversed(timbre) = timbre {
+        return sounding_ms(duration_ms, timbre, envelope);
+    }
+    if let Timbre::Layered(layers) = timbre {
+        // The note sounds until its longest layer finishes
+        return layers
+            .iter()
+            .map(|(timbre, _)| sounding_ms(duration_ms, timbre, envelope))
+            .max()
+            .unwrap_or(duration_ms);
+    }
+
+    let release_ms = envelope.or_else(|| timbre.default_envelope()).map_or(0, |envelope| {
+        u64::try_from(envelope.release.as_millis()).unwrap_or(u64::MAX)
+    });
+    duration_ms.saturating_add(release_ms)
+}
+
+/// Returns the gains of the left and right channels for a note panned to `pan`.
+///
+/// This uses an equal-power pan law, scaled so that a centered note has a gain of 1.0 in both channels.
+pub fn pan_gains(pan: f32) -> [f32; 2] {
+    let angle = (pan.clamp(-1.0, 1.0) + 1.0) * FRAC_PI_4;
+    [angle.cos() * SQRT_2, angle.sin() * SQRT_2]
+}
+
+/// Applies an envelope to a source.
+struct EnvelopeSource<S: Source<Item = f32>> {
+    input: S,
+    envelope: Envelope,
+    note_duration_secs: f32,
+    /// Number of samples (not frames) taken from the input so far
+    samples_elapsed: u64,
+}
+
+impl<S: Source<Item = f32>> EnvelopeSource<S> {
+    fn new(input: S, envelope: Envelope, note_duration: Duration) -> Self {
+        EnvelopeSource {
+            input,
+            envelope,
+            note_duration_secs: note_duration.as_secs_f32(),
+            samples_elapsed: 0,
+        }
+    }
+}
+
+impl<S: Source<Item = f32>> Iterator for EnvelopeSource<S> {
+    type Item = f32;
+
+    #[expect(clippy::cast_precision_loss, reason = "Only loses precision for notes lasting days")]
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.input.next()?;
+        let samples_per_second = u64::from(self.input.sample_rate()).saturating_mul(u64::from(self.input.channels()));
+        let elapsed_secs = self.samples_elapsed as f32 / samples_per_second.max(1) as f32;
+        self.samples_elapsed = self.samples_elapsed.saturating_add(1);
+
+        Some(sample * self.envelope.amplitude_at_secs(elapsed_secs, self.note_duration_secs))
+    }
 }
 
-fn get_dyn_source(duration_ms: u64, frequency: f32, timbre: Timbre) -> SymphoxySource {
+impl<S: Source<Item = f32>> Source for EnvelopeSource<S> {
+    fn current_frame_len(&self) -> Option<usize> {
+        self.input.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+}
+
+fn get_dyn_source(duration_ms: u64, frequency: f32, timbre: &Timbre) -> SymphoxySource {
     match timbre {
         Timbre::Sine => get_sine_source(duration_ms, frequency),
+        Timbre::Square => get_oscillator_source(duration_ms, frequency, Waveform::Square),
+        Timbre::Saw => get_oscillator_source(duration_ms, frequency, Waveform::Saw),
+        Timbre::Triangle => get_oscillator_source(duration_ms, frequency, Waveform::Triangle),
         Timbre::Bass => get_bass_source(duration_ms, frequency),
         Timbre::Piano => get_piano_source(duration_ms, frequency),
         Timbre::ElectricGuitar => get_electric_guitar_source(duration_ms, frequency),
+        Timbre::Organ => get_organ_source(duration_ms, frequency),
+        Timbre::Strings => get_strings_source(duration_ms, frequency),
+        Timbre::SynthLead => get_synth_lead_source(duration_ms, frequency),
+        Timbre::SynthPad => get_synth_pad_source(duration_ms, frequency),
+        Timbre::Flute => get_flute_source(duration_ms, frequency),
+        Timbre::Brass => get_brass_source(duration_ms, frequency),
+        Timbre::Fm(patch) => get_fm_source(duration_ms, frequency, patch),
         Timbre::Drums => get_drum_source(duration_ms, frequency),
-        Timbre::CustomSourceUnpitched(file) => get_custom_source_unpitched(Path::new(file), duration_ms),
-        Timbre::CustomSourcePitched(file) => get_custom_source_pitched(Path::new(file), duration_ms, frequency),
+        Timbre::CustomSourceUnpitched(file) => get_custom_source_unpitched(file, duration_ms),
+        Timbre::CustomSourcePitched(file) => get_custom_source_pitched(file, duration_ms, frequency),
+        Timbre::Sample(sample) => get_sample_source(sample, duration_ms, frequency),
+        Timbre::Sampled(instrument) => match instrument.sample_for(NotePitch(frequency)) {
+            Some(sample) => get_sample_source(sample, duration_ms, frequency),
+            None => {
+                Box::new(rodio::source::Zero::<f32>::new(1, 44100).take_duration(Duration::from_millis(duration_ms)))
+            }
+        },
+        Timbre::Layered(layers) => get_layered_source(duration_ms, frequency, layers, 1.0, None, 0),
+        Timbre::Reversed(timbre) => reverse_source(get_dyn_source(duration_ms, frequency, timbre)),
+        Timbre::Custom(synth) => synth.source(frequency, duration_ms),
     }
 }
 
@@ -37,25 +232,117 @@ pub fn get_custom_source_pitched(file: &Path, duration_ms: u64, frequency: f32)
     )
 }
 
+/// Creates the source for a note played with a sample instrument, pitch-shifted from the sample's root pitch.
+pub fn get_sample_source(sample: &SampleInstrument, duration_ms: u64, frequency: f32) -> SymphoxySource {
+    let Some((start, end)) = sample.loop_points else {
+        let pitch_ratio = frequency / sample.root_pitch.0;
+        #[expect(clippy::cast_possible_truncation, clippy::cast_precision_loss, reason = "User's fault")]
+        #[expect(clippy::cast_sign_loss, reason = "Shouldn't happen")]
+        let unpitched_source = get_custom_source_unpitched(&sample.path, ((duration_ms as f32) * pitch_ratio) as u64);
+        return Box::new(
+            unpitched_source
+                .speed(pitch_ratio)
+                .take_duration(Duration::from_millis(duration_ms)),
+        );
+    };
+
+    let path = &*sample.path;
+    let Ok(audio) = decode(path) else {
+        eprintln!("Warning: Could not load sample file {path:?}, using silence");
+        return Box::new(rodio::source::Zero::<f32>::new(1, 44100).take_duration(Duration::from_millis(duration_ms)));
+    };
+
+    // Frames are converted to samples, and kept within the recording
+    let channel_count = usize::from(audio.channels);
+    let end = end.saturating_mul(channel_count).min(audio.samples.len());
+    let start = start.saturating_mul(channel_count).min(end);
+
+    // Play up to the end of the loop, then repeat the loop for as long as the note needs
+    let pitch_ratio = frequency / sample.root_pitch.0;
+    Box::new(
+        CachedSamples {
+            audio,
+            position: 0,
+            start,
+            end,
+        }
+        .speed(pitch_ratio)
+        .take_duration(Duration::from_millis(duration_ms)),
+    )
+}
+
+/// A recording read straight from the decode cache, so starting a note doesn't copy it.
+///
+/// It plays up to `end`, then repeats the loop from `start` forever, if the loop isn't empty.
+struct CachedSamples {
+    audio: Arc<DecodedAudio>,
+    /// The index of the next sample
+    position: usize,
+    /// The index of the first sample of the loop
+    start: usize,
+    /// The index just past the last sample played, which is the end of the loop
+    end: usize,
+}
+
+impl Iterator for CachedSamples {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.position >= self.end {
+            if self.start >= self.end {
+                return None;
+            }
+            self.position = self.start;
+        }
+        let sample = *self.audio.samples.get(self.position)?;
+        self.position = self.position.saturating_add(1);
+        Some(sample)
+    }
+}
+
+impl Source for CachedSamples {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        self.audio.channels
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.audio.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
 pub fn get_custom_source_unpitched(file: &Path, duration_ms: u64) -> SymphoxySource {
     let path = Path::new(file);
-    match std::fs::File::open(path) {
-        Ok(file) => match Decoder::new(BufReader::new(file)) {
-            Ok(decoder) => Box::new(
-                decoder
+    match decode(path) {
+        Ok(audio) => {
+            // The file is played once through, without a loop
+            let length = audio.samples.len();
+            Box::new(
+                CachedSamples {
+                    audio,
+                    position: 0,
+                    start: length,
+                    end: length,
+                }
+                .take_duration(Duration::from_millis(duration_ms)),
+            )
+        }
+        Err(DecodeError::Decode) => {
+            eprintln!("Warning: Could not decode audio file {path:?}, using silence");
+            Box::new(
+                rodio::source::Zero::<f32>::new(1, 44100)
                     .convert_samples()
                     .take_duration(Duration::from_millis(duration_ms)),
-            ),
-            Err(_) => {
-                eprintln!("Warning: Could not decode audio file {path:?}, using silence");
-                Box::new(
-                    rodio::source::Zero::<f32>::new(1, 44100)
-                        .convert_samples()
-                        .take_duration(Duration::from_millis(duration_ms)),
-                )
-            }
-        },
-        Err(_) => {
+            )
+        }
+        Err(DecodeError::Open) => {
             eprintln!("Warning: Could not find custom source file {path:?}, using silence");
             Box::new(
                 rodio::source::Zero::<f32>::new(1, 44100)
@@ -67,24 +354,34 @@ pub fn get_custom_source_unpitched(file: &Path, duration_ms: u64) -> SymphoxySou
 }
 
 pub fn get_sine_source(duration_ms: u64, frequency: f32) -> SymphoxySource {
-    let sources: Vec<Box<dyn Source<Item = f32> + Send>> = vec![
-        Box::new(
-            SineWave::new(frequency)
-                .take_duration(Duration::from_millis(duration_ms.saturating_sub(40)))
-                .fade_in(Duration::from_millis(40)),
-        ),
-        Box::new(SineWave::new(frequency).fade_out(Duration::from_millis(40))),
-    ];
+    Box::new(
+        SineWave::new(frequency)
+            .take_duration(Duration::from_millis(duration_ms))
+            .amplify((3.0 * 44.0 / frequency).clamp(0.0, 1.0)),
+    )
+}
 
-    Box::new(rodio::source::from_iter(sources).amplify((3.0 * 44.0 / frequency).clamp(0.0, 1.0)))
+pub fn get_oscillator_source(duration_ms: u64, frequency: f32, waveform: Waveform) -> SymphoxySource {
+    // Brighter waves sound louder, so they're turned down to match
+    let gain = match waveform {
+        Waveform::Square => 0.35,
+        Waveform::Saw => 0.45,
+        Waveform::Triangle => 0.9,
+    };
+    Box::new(
+        Oscillator::new(waveform, frequency)
+            .take_duration(Duration::from_millis(duration_ms))
+            .amplify(gain * (3.0 * 44.0 / frequency).clamp(0.0, 1.0)),
+    )
 }
 
 fn decibels_to_amplitude_ratio(dec: f32) -> f32 {
     10.0f32.powf(dec / 20.0)
 }
 
-pub fn get_drum_source(duration_ms: u64, frequency: f32) -> SymphoxySource {
-    let kind = if frequency > C4.octave(1).semitone(6).0 {
+/// Returns which drum of the kit a pitch plays: "kick", "snare", "hi-hat", or "crash".
+pub fn drum_kind(frequency: f32) -> &'static str {
+    if frequency > C4.octave(1).semitone(6).0 {
         "crash"
     } else if frequency > C4.semitone(6).0 {
         "hi-hat"
@@ -92,10 +389,48 @@ pub fn get_drum_source(duration_ms: u64, frequency: f32) -> SymphoxySource {
         "kick"
     } else {
         "snare"
+    }
+}
+
+/// Returns the audio file of a drum of the kit.
+fn drum_path(kind: &str) -> PathBuf {
+    Path::new("src/assets").join(format!("{kind}.mp3"))
+}
+
+/// Whether a timbre plays the drum kit, on its own or as part of a layered or reversed timbre.
+fn plays_drums(timbre: &Timbre) -> bool {
+    match timbre {
+        Timbre::Drums => true,
+        Timbre::Layered(layers) => layers.iter().any(|(layer, _)| plays_drums(layer)),
+        Timbre::Reversed(timbre) => plays_drums(timbre),
+        _ => false,
+    }
+}
+
+/// Decodes every audio file the piece plays which isn't cached yet, or has changed since it was,
+/// so starting its notes doesn't touch the filesystem. Call this before each session of playing or rendering.
+pub(super) fn prepare_files(piece: &Piece) {
+    let drums = piece
+        .0
+        .iter()
+        .flat_map(|line| line.pickup.iter().chain(&line.notes))
+        .any(|note| matches!(&note.1, NoteKind::Pitched { timbre, .. } if plays_drums(timbre)));
+    let drum_paths: Vec<PathBuf> = if drums {
+        ["kick", "snare", "hi-hat", "crash"].map(drum_path).into()
+    } else {
+        vec![]
     };
+    prepare(
+        piece
+            .collect_assets()
+            .into_iter()
+            .chain(drum_paths.iter().map(PathBuf::as_path)),
+    );
+}
 
-    let path = Path::new("src/assets").join(format!("{kind}.mp3"));
-    let base = get_custom_source_unpitched(&path, duration_ms);
+pub fn get_drum_source(duration_ms: u64, frequency: f32) -> SymphoxySource {
+    let kind = drum_kind(frequency);
+    let base = get_custom_source_unpitched(&drum_path(kind), duration_ms);
     if kind == "snare" {
         Box::new(base.amplify(5.0))
     } else {
@@ -116,8 +451,7 @@ pub fn get_electric_guitar_source(duration_ms: u64, frequency: f32) -> SymphoxyS
             .mix(SineWave::new(frequency * 7.0).amplify(decibels_to_amplitude_ratio(-8.0)))
             .mix(SineWave::new(frequency * 8.0).amplify(decibels_to_amplitude_ratio(-10.0)))
             .take_duration(Duration::from_millis(duration_ms))
-            .amplify((3.0 * 44.0 / frequency).clamp(0.0, 1.0))
-            .fade_out(Duration::from_millis(duration_ms)),
+            .amplify((3.0 * 44.0 / frequency).clamp(0.0, 1.0)),
     )
 }
 
@@ -134,8 +468,7 @@ pub fn get_bass_source(duration_ms: u64, frequency: f32) -> SymphoxySource {
             .mix(SineWave::new(frequency * 7.0).amplify(1.0 / 3.0))
             .mix(SineWave::new(frequency * 8.0).amplify(1.0 / 10.0))
             .take_duration(Duration::from_millis(duration_ms))
-            .amplify(12.0 * (3.0 * 44.0 / frequency).clamp(0.0, 1.0))
-            .fade_out(Duration::from_millis(duration_ms)),
+            .amplify(12.0 * (3.0 * 44.0 / frequency).clamp(0.0, 1.0)),
     )
 }
 
@@ -152,8 +485,164 @@ pub fn get_piano_source(duration_ms: u64, frequency: f32) -> SymphoxySource {
             .mix(SineWave::new(frequency * 7.0).amplify(1.0 / 36.0))
             .mix(SineWave::new(frequency * 8.0).amplify(1.0 / 72.0))
             .take_duration(Duration::from_millis(duration_ms))
-            .amplify((12.0 * 44.0 / frequency).clamp(0.0, 1.0))
-            .fade_in(Duration::from_millis(5))
-            .fade_out(Duration::from_millis(duration_ms)),
+            .amplify((12.0 * 44.0 / frequency).clamp(0.0, 1.0)),
+    )
+}
+
+/// The highest harmonic added by additive synthesis. `SineWave` runs at 48 kHz, so anything
+/// above its Nyquist frequency would alias.
+const MAX_HARMONIC_FREQUENCY: f32 = 20_000.0;
+
+/// Mixes sine waves at multiples of `frequency`, given as (multiple, amplitude).
+/// Harmonics too high to be reproduced are left out.
+fn additive(frequency: f32, harmonics: impl IntoIterator<Item = (f32, f32)>) -> SymphoxySource {
+    harmonics
+        .into_iter()
+        .filter(|(multiple, _)| frequency * multiple < MAX_HARMONIC_FREQUENCY)
+        .fold(
+            Box::new(rodio::source::Zero::new(1, 48000)),
+            |mix, (multiple, amplitude)| Box::new(mix.mix(SineWave::new(frequency * multiple).amplify(amplitude))),
+        )
+}
+
+/// A band-limited sawtooth wave.
+fn sawtooth(frequency: f32) -> SymphoxySource {
+    Box::new(Oscillator::new(Waveform::Saw, frequency).amplify(0.6))
+}
+
+/// Several slightly detuned sawtooth waves, which beat against each other like an ensemble.
+fn detuned_sawtooths(frequency: f32, detune_cents: &[f32]) -> SymphoxySource {
+    let voices = detune_cents.len().max(1);
+    #[expect(clippy::cast_precision_loss, reason = "There are only a few voices")]
+    let gain = 1.0 / voices as f32;
+    detune_cents
+        .iter()
+        .fold(Box::new(rodio::source::Zero::new(1, 48000)), |mix, cents| {
+            let detuned = frequency * 2.0_f32.powf(cents / 1200.0);
+            Box::new(mix.mix(sawtooth(detuned).amplify(gain)))
+        })
+}
+
+/// Converts a frequency to a filter cutoff, kept within the range the filter can handle.
+#[expect(clippy::cast_possible_truncation, reason = "The cutoff is clamped")]
+#[expect(clippy::cast_sign_loss, reason = "The cutoff is clamped")]
+fn cutoff(frequency: f32) -> u32 {
+    frequency.clamp(20.0, MAX_HARMONIC_FREQUENCY) as u32
+}
+
+pub fn get_organ_source(duration_ms: u64, frequency: f32) -> SymphoxySource {
+    // Drawbars at 16', 8', 4', 2 2/3', 2', 1 1/3', and 1'
+    let drawbars = [
+        (0.5, 0.6),
+        (1.0, 1.0),
+        (2.0, 0.8),
+        (3.0, 0.5),
+        (4.0, 0.4),
+        (6.0, 0.25),
+        (8.0, 0.2),
+    ];
+    Box::new(
+        additive(frequency, drawbars)
+            .take_duration(Duration::from_millis(duration_ms))
+            .amplify((4.0 * 44.0 / frequency).clamp(0.0, 0.3)),
+    )
+}
+
+pub fn get_strings_source(duration_ms: u64, frequency: f32) -> SymphoxySource {
+    Box::new(
+        detuned_sawtooths(frequency, &[-8.0, 0.0, 7.0])
+            .low_pass(cutoff(frequency * 5.0))
+            .take_duration(Duration::from_millis(duration_ms))
+            .amplify((6.0 * 44.0 / frequency).clamp(0.0, 0.5)),
+    )
+}
+
+pub fn get_synth_lead_source(duration_ms: u64, frequency: f32) -> SymphoxySource {
+    Box::new(
+        Oscillator::new(Waveform::Square, frequency)
+            .amplify(0.6)
+            .mix(sawtooth(frequency * 1.005).amplify(0.5))
+            .low_pass_with_q(cutoff(frequency * 12.0), 1.5)
+            .take_duration(Duration::from_millis(duration_ms))
+            .amplify((4.0 * 44.0 / frequency).clamp(0.0, 0.4)),
+    )
+}
+
+pub fn get_synth_pad_source(duration_ms: u64, frequency: f32) -> SymphoxySource {
+    Box::new(
+        detuned_sawtooths(frequency, &[-12.0, -4.0, 5.0, 13.0])
+            .mix(SineWave::new(frequency * 0.5).amplify(0.4))
+            .low_pass(cutoff(frequency * 3.0))
+            .take_duration(Duration::from_millis(duration_ms))
+            .amplify((6.0 * 44.0 / frequency).clamp(0.0, 0.5)),
+    )
+}
+
+pub fn get_flute_source(duration_ms: u64, frequency: f32) -> SymphoxySource {
+    Box::new(
+        additive(
+            frequency,
+            [(1.0, 1.0), (2.0, 0.3), (3.0, 0.12), (4.0, 0.04), (5.0, 0.02)],
+        )
+        .take_duration(Duration::from_millis(duration_ms))
+        .amplify((12.0 * 44.0 / frequency).clamp(0.0, 0.8)),
     )
 }
+
+pub fn get_brass_source(duration_ms: u64, frequency: f32) -> SymphoxySource {
+    Box::new(
+        detuned_sawtooths(frequency, &[-3.0, 3.0])
+            .low_pass_with_q(cutoff(frequency * 8.0), 1.2)
+            .take_duration(Duration::from_millis(duration_ms))
+            .amplify((6.0 * 44.0 / frequency).clamp(0.0, 0.5)),
+    )
+}
+
+pub fn get_fm_source(duration_ms: u64, frequency: f32, patch: &FmPatch) -> SymphoxySource {
+    Box::new(
+        FmVoice::new(patch, frequency)
+            .take_duration(Duration::from_millis(duration_ms))
+            .amplify((12.0 * 44.0 / frequency).clamp(0.0, 0.8)),
+    )
+}
+
+#[cfg(feature = "wav-output")]
+#[test]
+fn test_sample_loops_while_held() {
+    let path = std::env::temp_dir().join("symphoxy_test_sample_loop.wav");
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: 1000,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+    for _ in 0..100 {
+        writer.write_sample(i16::MAX / 2).unwrap();
+    }
+    writer.finalize().unwrap();
+
+    // The sample lasts a tenth of a second, but loops for the whole half-second note
+    let sample = SampleInstrument::new(&path, C4).with_loop(50, 100);
+    let samples: Vec<f32> = get_sample_source(&sample, 500, C4.0).collect();
+
+    // Changing the speed can round away the last sample
+    assert!((499..=500).contains(&samples.len()));
+    assert!(samples.iter().all(|&s| s > 0.4));
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_reversed_note_swells_to_its_end() {
+    let peak = |samples: &[f32]| samples.iter().fold(0.0_f32, |peak, s| peak.max(s.abs()));
+
+    let forwards: Vec<f32> = get_source(500, C4.0, &Timbre::Piano, 1.0, None, 100).collect();
+    let backwards: Vec<f32> = get_source(500, C4.0, &Timbre::Piano.reversed(), 1.0, None, 100).collect();
+    assert_eq!(forwards.len(), backwards.len());
+
+    // A piano note dies away, so played backwards it gets louder
+    let tenth = backwards.len() / 10;
+    assert!(peak(&forwards[..tenth]) > peak(&forwards[forwards.len() - 2 * tenth..]));
+    assert!(peak(&backwards[backwards.len() - 2 * tenth..]) > peak(&backwards[..tenth]));
+}
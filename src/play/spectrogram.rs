@@ -0,0 +1,221 @@
+use std::{f32::consts::PI, fs::File, io::Write, path::Path};
+
+use crate::{play::fft::fft, MusicPlayer, Piece};
+
+/// Settings for rendering a spectrogram with [`Piece::render_spectrogram`].
+///
+/// # Example
+/// ```
+/// use symphoxy::SpectrogramOptions;
+///
+/// // Finer frequency detail, up to 8 kHz
+/// let options = SpectrogramOptions {
+///     window_size: 4096,
+///     max_frequency: 8000.0,
+///     ..SpectrogramOptions::default()
+/// };
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpectrogramOptions {
+    /// Tempo the piece is rendered at, in beats per minute (default: 300)
+    pub tempo_bpm: u32,
+    /// Sample rate the piece is rendered at, in Hz (default: 44100)
+    pub sample_rate: u32,
+    /// Number of samples analysed for each column of the image. This is rounded up to a
+    /// power of two. Larger windows show finer frequencies, but blur timing (default: 2048)
+    pub window_size: usize,
+    /// Number of samples between the start of each column of the image (default: 512)
+    pub hop_size: usize,
+    /// Highest frequency shown, in Hz. It's limited to half the sample rate (default: 22050)
+    pub max_frequency: f32,
+    /// How far below the loudest point, in decibels, is drawn as silence (default: 90)
+    pub dynamic_range_db: f32,
+}
+
+impl Default for SpectrogramOptions {
+    fn default() -> Self {
+        SpectrogramOptions {
+            tempo_bpm: 300,
+            sample_rate: 44100,
+            window_size: 2048,
+            hop_size: 512,
+            max_frequency: 22050.0,
+            dynamic_range_db: 90.0,
+        }
+    }
+}
+
+impl Piece {
+    /// Renders the piece and saves a spectrogram of the mix as a BMP image.
+    ///
+    /// Time runs from left to right, one column per `hop_size` samples, and frequency from
+    /// bottom to top, one row per frequency band. Louder bands are brighter. All channels are
+    /// mixed together before the analysis.
+    ///
+    /// This is useful for checking what's actually in the rendered audio, such as aliasing
+    /// above a note's harmonics, or whether a filter is cutting what it should.
+    ///
+    /// # Parameters
+    /// - `path`: The file path where the image should be written
+    /// - `options`: How the piece is rendered and analysed
+    ///
+    /// # Errors
+    /// Returns an error if the file can't be created or written to.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::SpectrogramOptions;
+    ///
+    /// let piece = Piece::from(piano(quarter(C4) + quarter(E4) + half(G4)));
+    /// piece.render_spectrogram("spectrogram.bmp", &SpectrogramOptions::default()).unwrap();
+    /// ```
+    pub fn render_spectrogram(&self, path: impl AsRef<Path>, options: &SpectrogramOptions) -> std::io::Result<()> {
+        let player = MusicPlayer::new_file(options.tempo_bpm, 1.0, options.sample_rate);
+        let channels = player.render_range_samples(self.clone(), 0..self.length());
+
+        // Mix every channel down to mono
+        let length = channels.first().map_or(0, Vec::len);
+        let mono: Vec<f32> = (0..length)
+            .map(|index| channels.iter().map(|channel| channel[index]).sum())
+            .collect();
+
+        let columns = spectrogram(&mono, options);
+        write_bmp(path.as_ref(), &columns, options.dynamic_range_db)
+    }
+}
+
+/// Computes the loudness of each frequency band (in decibels) for each window of the samples.
+#[expect(clippy::cast_precision_loss, reason = "Window sizes are small")]
+#[expect(clippy::cast_possible_truncation, reason = "Bin counts are small")]
+#[expect(clippy::cast_sign_loss, reason = "The maximum frequency is clamped to be positive")]
+#[expect(clippy::arithmetic_side_effects, reason = "Signal processing, with checked bounds")]
+fn spectrogram(samples: &[f32], options: &SpectrogramOptions) -> Vec<Vec<f32>> {
+    let window_size = options.window_size.max(2).next_power_of_two();
+    let hop_size = options.hop_size.max(1);
+
+    let nyquist = options.sample_rate as f32 / 2.0;
+    let max_frequency = options.max_frequency.clamp(0.0, nyquist);
+    let bins = ((max_frequency / nyquist) * (window_size / 2) as f32).ceil() as usize;
+
+    // A Hann window, which stops the edges of each window from smearing across every frequency
+    let window: Vec<f32> = (0..window_size)
+        .map(|index| 0.5 - 0.5 * (2.0 * PI * index as f32 / window_size as f32).cos())
+        .collect();
+
+    let column_count = samples.len().saturating_sub(window_size).div_ceil(hop_size) + 1;
+    (0..column_count)
+        .map(|column| {
+            let start = column * hop_size;
+            let mut real: Vec<f32> = (0..window_size)
+                .map(|index| samples.get(start + index).copied().unwrap_or_default() * window[index])
+                .collect();
+            let mut imaginary = vec![0.0; window_size];
+            fft(&mut real, &mut imaginary);
+
+            // A full scale sine wave peaks at a quarter of the window size, once windowed
+            let full_scale = window_size as f32 / 4.0;
+            (0..bins)
+                .map(|bin| {
+                    let magnitude = real[bin].hypot(imaginary[bin]) / full_scale;
+                    20.0 * magnitude.max(f32::MIN_POSITIVE).log10()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Writes the spectrogram as a 24-bit BMP image, with low frequencies at the bottom.
+#[expect(clippy::arithmetic_side_effects, reason = "Image sizes are small")]
+fn write_bmp(path: &Path, columns: &[Vec<f32>], dynamic_range_db: f32) -> std::io::Result<()> {
+    let width = columns.len();
+    let height = columns.first().map_or(0, Vec::len);
+
+    // The loudest point is drawn at full brightness
+    let loudest = columns.iter().flatten().copied().fold(f32::MIN, f32::max);
+
+    // Each row is padded to a multiple of four bytes
+    let row_size = (width * 3).div_ceil(4) * 4;
+    let image_size = row_size * height;
+    let too_large = || std::io::Error::new(std::io::ErrorKind::InvalidInput, "spectrogram is too large for a BMP");
+    let as_u32 = |value: usize| u32::try_from(value).map_err(|_| too_large());
+
+    let mut bytes = Vec::with_capacity(54 + image_size);
+    // File header
+    bytes.extend_from_slice(b"BM");
+    bytes.extend_from_slice(&as_u32(54 + image_size)?.to_le_bytes());
+    bytes.extend_from_slice(&[0; 4]);
+    bytes.extend_from_slice(&54_u32.to_le_bytes());
+    // Image header
+    bytes.extend_from_slice(&40_u32.to_le_bytes());
+    bytes.extend_from_slice(&as_u32(width)?.to_le_bytes());
+    bytes.extend_from_slice(&as_u32(height)?.to_le_bytes());
+    bytes.extend_from_slice(&1_u16.to_le_bytes());
+    bytes.extend_from_slice(&24_u16.to_le_bytes());
+    bytes.extend_from_slice(&[0; 4]);
+    bytes.extend_from_slice(&as_u32(image_size)?.to_le_bytes());
+    bytes.extend_from_slice(&[0; 16]);
+
+    // Rows are stored from the bottom of the image up
+    for bin in 0..height {
+        let row_start = bytes.len();
+        for column in columns {
+            let level = 1.0 - (loudest - column[bin]) / dynamic_range_db.max(f32::EPSILON);
+            let [red, green, blue] = heat_colour(level.clamp(0.0, 1.0));
+            bytes.extend_from_slice(&[blue, green, red]);
+        }
+        bytes.resize(row_start + row_size, 0);
+    }
+
+    File::create(path)?.write_all(&bytes)
+}
+
+/// Colours a spectrogram is drawn with, from silent to loudest.
+const HEAT_COLOURS: [[f32; 3]; 5] = [
+    [0.0, 0.0, 0.0],
+    [0.3, 0.0, 0.5],
+    [0.9, 0.1, 0.1],
+    [1.0, 0.85, 0.0],
+    [1.0, 1.0, 1.0],
+];
+
+/// Maps a level from 0 to 1 onto black, through purple, red, and yellow, to white.
+#[expect(clippy::cast_possible_truncation, reason = "Colours are clamped to 0..=255")]
+#[expect(clippy::cast_sign_loss, reason = "Colours are clamped to 0..=255")]
+#[expect(clippy::cast_precision_loss, reason = "There are only a few colours")]
+#[expect(clippy::arithmetic_side_effects, reason = "Indices are clamped")]
+fn heat_colour(level: f32) -> [u8; 3] {
+    let position = level * (HEAT_COLOURS.len() - 1) as f32;
+    let lower = (position.floor() as usize).min(HEAT_COLOURS.len() - 2);
+    let blend = position - lower as f32;
+
+    let [from, to] = [HEAT_COLOURS[lower], HEAT_COLOURS[lower + 1]];
+    [0, 1, 2].map(|channel| {
+        let value = from[channel] + (to[channel] - from[channel]) * blend;
+        (value * 255.0).round().clamp(0.0, 255.0) as u8
+    })
+}
+
+#[test]
+fn test_spectrogram_finds_sine_frequency() {
+    let options = SpectrogramOptions {
+        sample_rate: 8000,
+        window_size: 1024,
+        max_frequency: 4000.0,
+        ..SpectrogramOptions::default()
+    };
+    let samples: Vec<f32> = (0..8000_u16)
+        .map(|index| (2.0 * PI * 1000.0 * f32::from(index) / 8000.0).sin())
+        .collect();
+
+    let columns = spectrogram(&samples, &options);
+    let loudest_bin = columns[0]
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(bin, _)| bin);
+
+    // 1000 Hz is 1/8 of the sample rate, so it's 1/8 of the way through the window
+    assert_eq!(loudest_bin, Some(128));
+    assert!(columns[0][128] > -7.0);
+}
@@ -0,0 +1,232 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    play::{mix::Mixer, render_to_wav::write_wav, FileOutputConfig},
+    Line, MusicPlayer, Note, NoteKind, Piece, Timbre,
+};
+
+/// How a piece is split into stems by [`MusicPlayer::render_stems`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StemGrouping {
+    /// One stem per line, named `line_1.wav`, `line_2.wav`, and so on
+    #[default]
+    ByLine,
+    /// One stem per timbre, named after the timbre, such as `piano.wav`. Notes of the same timbre
+    /// are put in the same stem, whichever line they're in.
+    ByTimbre,
+}
+
+impl MusicPlayer<FileOutputConfig> {
+    /// Renders each part of a piece to its own WAV file, so it can be mixed and mastered in a DAW.
+    ///
+    /// Every stem is as long as the whole piece, so they line up when they're all started at the
+    /// same time. Stems are scaled by the same amount as the full mix would be, and the player's mixer
+    /// and each line's effects are applied to them, so together they add up to the mix written by
+    /// [`MusicPlayer::render_to_wav`]. The master effects and clipping aren't applied, as they're meant to be
+    /// applied after the stems are mixed.
+    ///
+    /// The directory is created if it doesn't exist, and files already in it with the same names
+    /// are overwritten.
+    ///
+    /// # Arguments
+    /// * `piece` - The piece to render. Notes and lines are treated as pieces with one line.
+    /// * `dir` - The directory the stems are written to
+    /// * `grouping` - Whether there's a stem for each line or for each timbre
+    ///
+    /// # Returns
+    /// The paths of the files written, in the order of the lines or timbres in the piece.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{MusicPlayer, StemGrouping};
+    ///
+    /// let piece = piano(quarter(C4) + quarter(E4)) * bass(half(C2)) * drums(quarter(C4) + quarter(C3));
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 44100);
+    /// let stems = player.render_stems(piece, "stems", StemGrouping::ByLine).unwrap();
+    /// assert_eq!(stems.len(), 3);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the directory can't be created, or a file can't be written.
+    pub fn render_stems(
+        &self,
+        piece: impl Into<Piece>,
+        dir: impl AsRef<Path>,
+        grouping: StemGrouping,
+    ) -> io::Result<Vec<PathBuf>> {
+        let piece: Piece = piece.into();
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+
+        // Every render uses the same seed, so each stem has the same notes as the mix
+        let seed = self.pass_seed();
+        let player = |mixer: Mixer| MusicPlayer {
+            tempo_bpm: self.tempo_bpm,
+            output_config: self.output_config.clone(),
+            seed: Some(seed),
+            mixer,
+        };
+        let render = |player: MusicPlayer<FileOutputConfig>, piece: Piece| {
+            let length = piece.length();
+            player.render_range_mix(piece, 0..length)
+        };
+
+        let sample_rate = self.output_config.sample_rate;
+        let gains = self
+            .output_config
+            .normalization
+            .apply(&mut render(player(self.mixer.clone()), piece.clone()), sample_rate);
+
+        // Stems are rendered as part of the whole piece, with everything else silenced, so the
+        // mixer treats each part the same as in the mix
+        let stems: Vec<(String, Vec<Vec<f32>>)> = match grouping {
+            StemGrouping::ByLine => (0..piece.0.len())
+                .map(|line| {
+                    let name = format!("line_{}", line.saturating_add(1));
+                    (name, render(player(self.mixer.isolate(line)), piece.clone()))
+                })
+                .collect(),
+            StemGrouping::ByTimbre => {
+                let mut names: Vec<String> = vec![];
+                timbres_in(&piece)
+                    .into_iter()
+                    .map(|timbre| {
+                        let name = unique_name(timbre_name(&timbre), &names);
+                        names.push(name.clone());
+                        (name, render(player(self.mixer.clone()), only_timbre(&piece, &timbre)))
+                    })
+                    .collect()
+            }
+        };
+
+        stems
+            .into_iter()
+            .map(|(name, mut samples)| {
+                for (channel, gain) in samples.iter_mut().zip(&gains) {
+                    for sample in channel {
+                        *sample *= gain * self.output_config.output_gain;
+                    }
+                }
+                let path = dir.join(format!("{name}.wav"));
+                write_wav(&path, &samples, sample_rate, &self.output_config.metadata)?;
+                Ok(path)
+            })
+            .collect()
+    }
+}
+
+/// Returns every timbre played in a piece, in the order they're first played.
+fn timbres_in(piece: &Piece) -> Vec<Timbre> {
+    let mut timbres: Vec<Timbre> = vec![];
+    for note in piece.0.iter().flat_map(|line| line.pickup.iter().chain(&line.notes)) {
+        if let NoteKind::Pitched { timbre, .. } = &note.1 {
+            if !timbres.contains(timbre) {
+                timbres.push(timbre.clone());
+            }
+        }
+    }
+    timbres
+}
+
+/// Returns the piece with every note not played by the timbre replaced with a rest.
+fn only_timbre(piece: &Piece, timbre: &Timbre) -> Piece {
+    let keep = |notes: &[Note]| {
+        notes
+            .iter()
+            .map(|note| match &note.1 {
+                NoteKind::Pitched { timbre: played, .. } if played != timbre => Note(note.0, NoteKind::Rest),
+                _ => note.clone(),
+            })
+            .collect()
+    };
+    Piece(
+        piece
+            .0
+            .iter()
+            .map(|line| Line {
+                notes: keep(&line.notes),
+                pickup: keep(&line.pickup),
+                ..line.clone()
+            })
+            .collect(),
+    )
+}
+
+/// Returns a name for a timbre which can be used in a file name.
+pub(super) fn timbre_name(timbre: &Timbre) -> String {
+    match timbre {
+        Timbre::Sine => "sine".to_string(),
+        Timbre::Square => "square".to_string(),
+        Timbre::Saw => "saw".to_string(),
+        Timbre::Triangle => "triangle".to_string(),
+        Timbre::Bass => "bass".to_string(),
+        Timbre::Piano => "piano".to_string(),
+        Timbre::ElectricGuitar => "electric_guitar".to_string(),
+        Timbre::Organ => "organ".to_string(),
+        Timbre::Strings => "strings".to_string(),
+        Timbre::SynthLead => "synth_lead".to_string(),
+        Timbre::SynthPad => "synth_pad".to_string(),
+        Timbre::Flute => "flute".to_string(),
+        Timbre::Brass => "brass".to_string(),
+        Timbre::Fm(_) => "fm".to_string(),
+        Timbre::Drums => "drums".to_string(),
+        Timbre::CustomSourceUnpitched(path) | Timbre::CustomSourcePitched(path) => path
+            .file_stem()
+            .map_or_else(|| "custom".to_string(), |stem| stem.to_string_lossy().into_owned()),
+        Timbre::Sample(_) => "sample".to_string(),
+        Timbre::Sampled(_) => "sampled".to_string(),
+        Timbre::Layered(_) => "layered".to_string(),
+        Timbre::Reversed(timbre) => format!("reversed_{}", timbre_name(timbre)),
+        Timbre::Custom(_) => "custom".to_string(),
+    }
+}
+
+/// Adds a number to the end of a name if it's already taken.
+fn unique_name(name: String, taken: &[String]) -> String {
+    if !taken.contains(&name) {
+        return name;
+    }
+    (2..)
+        .map(|number: usize| format!("{name}_{number}"))
+        .find(|numbered| !taken.contains(numbered))
+        .unwrap_or(name)
+}
+
+#[test]
+fn test_stems_add_up_to_the_mix() {
+    use crate::prelude::*;
+
+    let dir = std::env::temp_dir().join(format!("symphoxy_stems_{}", std::process::id()));
+    let piece = piano(quarter(C4) + quarter(E4) + half(G4)) * sine(whole(C3)) * piano(half(G3) + half(C4));
+    let player = MusicPlayer::new_file(300, 0.8, 8000);
+
+    let read = |path: &Path| -> Vec<i32> {
+        hound::WavReader::open(path)
+            .unwrap()
+            .samples::<i16>()
+            .map(|sample| i32::from(sample.unwrap()))
+            .collect()
+    };
+    let mix_path = dir.join("mix.wav");
+    std::fs::create_dir_all(&dir).unwrap();
+    player.render_to_wav(piece.clone(), &mix_path);
+    let mix = read(&mix_path);
+
+    for grouping in [StemGrouping::ByLine, StemGrouping::ByTimbre] {
+        let stems = player.render_stems(piece.clone(), &dir, grouping).unwrap();
+        let stems: Vec<Vec<i32>> = stems.iter().map(|path| read(path)).collect();
+        assert_eq!(stems.len(), if grouping == StemGrouping::ByLine { 3 } else { 2 });
+        for (index, sample) in mix.iter().enumerate() {
+            let sum: i32 = stems.iter().map(|stem| stem[index]).sum();
+            // Each stem is rounded on its own
+            assert!((sum - sample).abs() <= 3);
+        }
+    }
+    assert!(dir.join("piano.wav").exists() && dir.join("sine.wav").exists());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
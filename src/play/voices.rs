@@ -0,0 +1,66 @@
+/// Which sounding note is cut off to make room for a new one when live playback runs out of voices.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum VoiceStealing {
+    /// The note which started first
+    #[default]
+    Oldest,
+    /// The note which is quietest at that moment, such as one far into its release
+    Quietest,
+}
+
+/// The most notes live playback sounds at once, and how it makes room for more.
+///
+/// Every sounding note costs CPU, including notes in their release, so dense pieces can play
+/// more notes at once than the machine can keep up with, and the audio breaks up. With a limit,
+/// starting a note when every voice is in use cuts one of the others off instead. Cut notes stop
+/// immediately, so a limit which is often reached can be heard as clicks.
+///
+/// Set with `MusicPlayer::with_voice_limit`, and see how often notes were cut off with
+/// `PlaybackHandle::stolen_voices`.
+///
+/// # Example
+/// ```
+/// use symphoxy::{VoiceLimit, VoiceStealing};
+///
+/// let limit = VoiceLimit::new(32).with_stealing(VoiceStealing::Quietest);
+/// assert_eq!(limit.max_voices, 32);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct VoiceLimit {
+    /// The most notes sounding at once. At least one note can always sound.
+    pub max_voices: usize,
+    /// Which note is cut off when a note starts and every voice is in use
+    pub stealing: VoiceStealing,
+}
+
+impl VoiceLimit {
+    /// Creates a limit of the given number of voices, which cuts off the oldest note to make room.
+    pub fn new(max_voices: usize) -> Self {
+        VoiceLimit {
+            max_voices,
+            stealing: VoiceStealing::Oldest,
+        }
+    }
+
+    /// Sets which note is cut off to make room for a new one.
+    pub fn with_stealing(mut self, stealing: VoiceStealing) -> Self {
+        self.stealing = stealing;
+        self
+    }
+
+    /// Returns whether a note can start without cutting another off, given the number sounding.
+    pub(super) fn has_room(&self, sounding: usize) -> bool {
+        sounding < self.max_voices.max(1)
+    }
+
+    /// Returns the index of the note to cut off, given the frame each sounding note started
+    /// on and its current level, or `None` if nothing is sounding.
+    pub(super) fn victim(&self, voices: impl Iterator<Item = (u64, f32)>) -> Option<usize> {
+        let voices = voices.enumerate();
+        match self.stealing {
+            VoiceStealing::Oldest => voices.min_by_key(|(_, (start_frame, _))| *start_frame),
+            VoiceStealing::Quietest => voices.min_by(|(_, (_, a)), (_, (_, b))| a.total_cmp(b)),
+        }
+        .map(|(index, _)| index)
+    }
+}
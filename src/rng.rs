@@ -0,0 +1,55 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A small, seedable pseudo-random number generator (`SplitMix64`).
+///
+/// This is not suitable for anything security-related, but it is fast, has no dependencies,
+/// and gives the same sequence for the same seed on every platform, so generated music is reproducible.
+#[derive(Clone, Debug)]
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    /// Creates a generator which always produces the same sequence for the same seed.
+    pub(crate) fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    /// Creates a generator seeded from the system clock.
+    pub(crate) fn from_time() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos())
+            .unwrap_or_default();
+        #[expect(clippy::cast_possible_truncation, reason = "Only the low bits are needed for a seed")]
+        Rng::new(nanos as u64)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a random number in `0..bound`, or 0 if `bound` is 0.
+    pub(crate) fn below(&mut self, bound: usize) -> usize {
+        let value = self.next_u64().checked_rem(bound as u64).unwrap_or_default();
+        usize::try_from(value).unwrap_or_default()
+    }
+
+    /// Returns a random number in `low..=high`.
+    #[expect(clippy::arithmetic_side_effects, reason = "Manual bounds checking")]
+    pub(crate) fn range_inclusive(&mut self, low: i32, high: i32) -> i32 {
+        if high <= low {
+            return low;
+        }
+        let span = u64::try_from(i64::from(high) - i64::from(low) + 1).unwrap_or(1);
+        let offset = i64::try_from(self.next_u64() % span).unwrap_or_default();
+        i32::try_from(i64::from(low) + offset).unwrap_or(low)
+    }
+
+    /// Returns a random element of a slice, or `None` if it is empty.
+    pub(crate) fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        items.get(self.below(items.len()))
+    }
+}
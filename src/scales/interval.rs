@@ -0,0 +1,406 @@
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    ops::{Add, Neg, Sub},
+};
+
+use crate::{Chord, NotePitch, Scale};
+
+/// The names of the intervals up to an octave, by their number of semitones.
+const INTERVAL_NAMES: [&str; 13] = [
+    "unison",
+    "minor second",
+    "major second",
+    "minor third",
+    "major third",
+    "perfect fourth",
+    "tritone",
+    "perfect fifth",
+    "minor sixth",
+    "major sixth",
+    "minor seventh",
+    "major seventh",
+    "octave",
+];
+
+/// The distance between two pitches, measured in cents (hundredths of an equal-tempered semitone).
+///
+/// Intervals say what a distance means, where a raw number of semitones doesn't: `C4 +
+/// Interval::PERFECT_FIFTH` reads as what it is. Common intervals are named as constants, and
+/// any other distance, including microtonal ones, can be made with [`Interval::semitones`] or
+/// [`Interval::cents`]. Intervals can be added to and subtracted from pitches and each other,
+/// and stacked to build chords and scales.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let g4 = C4 + Interval::PERFECT_FIFTH;
+/// assert!((g4.0 - G4.0).abs() < 0.01);
+///
+/// let interval = Interval::between(C4, E4);
+/// assert_eq!(interval.to_string(), "major third");
+/// assert!((interval.as_semitones() - 4.0).abs() < 0.01);
+///
+/// // A quarter tone above A4
+/// let quarter_sharp = A4 + Interval::cents(50.0);
+/// assert_eq!(Interval::between(A4, quarter_sharp).to_string(), "50 cents");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Default)]
+pub struct Interval(pub f32);
+
+impl Interval {
+    /// The same pitch
+    pub const UNISON: Interval = Interval(0.0);
+    /// One semitone
+    pub const MINOR_SECOND: Interval = Interval(100.0);
+    /// Two semitones, a whole step
+    pub const MAJOR_SECOND: Interval = Interval(200.0);
+    /// Three semitones
+    pub const MINOR_THIRD: Interval = Interval(300.0);
+    /// Four semitones
+    pub const MAJOR_THIRD: Interval = Interval(400.0);
+    /// Five semitones
+    pub const PERFECT_FOURTH: Interval = Interval(500.0);
+    /// Six semitones, half an octave
+    pub const TRITONE: Interval = Interval(600.0);
+    /// Seven semitones
+    pub const PERFECT_FIFTH: Interval = Interval(700.0);
+    /// Eight semitones
+    pub const MINOR_SIXTH: Interval = Interval(800.0);
+    /// Nine semitones
+    pub const MAJOR_SIXTH: Interval = Interval(900.0);
+    /// Ten semitones
+    pub const MINOR_SEVENTH: Interval = Interval(1000.0);
+    /// Eleven semitones
+    pub const MAJOR_SEVENTH: Interval = Interval(1100.0);
+    /// Twelve semitones, double the frequency
+    pub const OCTAVE: Interval = Interval(1200.0);
+
+    /// Creates an interval of the given number of equal-tempered semitones, such as `-3.0` for
+    /// a minor third down. Fractions of a semitone are allowed.
+    pub fn semitones(semitones: f32) -> Self {
+        Interval(semitones * 100.0)
+    }
+
+    /// Creates an interval of the given number of cents (hundredths of a semitone).
+    pub fn cents(cents: f32) -> Self {
+        Interval(cents)
+    }
+
+    /// Returns the interval from one pitch to another, which is negative if the second pitch is lower.
+    ///
+    /// # Parameters
+    /// - `from`: The pitch the interval starts at
+    /// - `to`: The pitch the interval ends at
+    pub fn between(from: NotePitch, to: NotePitch) -> Self {
+        Interval(1200.0 * (to.0 / from.0).log2())
+    }
+
+    /// Returns the size of the interval in semitones.
+    pub fn as_semitones(&self) -> f32 {
+        self.0 / 100.0
+    }
+
+    /// Returns the size of the interval in cents.
+    pub fn as_cents(&self) -> f32 {
+        self.0
+    }
+
+    /// Returns the ratio between the frequencies of the two pitches of the interval, such as
+    /// `2.0` for an octave.
+    pub fn ratio(&self) -> f32 {
+        2.0_f32.powf(self.0 / 1200.0)
+    }
+
+    /// Returns the pitch this interval above the given pitch. This is the same as `pitch + interval`.
+    pub fn above(&self, pitch: NotePitch) -> NotePitch {
+        NotePitch(pitch.0 * self.ratio())
+    }
+
+    /// Returns the pitch this interval below the given pitch. This is the same as `pitch - interval`.
+    pub fn below(&self, pitch: NotePitch) -> NotePitch {
+        NotePitch(pitch.0 / self.ratio())
+    }
+}
+
+impl Display for Interval {
+    /// Writes the name of the interval, such as "perfect fifth", if it's within a cent of an
+    /// interval up to an octave, and its size in cents otherwise.
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let semitones = self.as_semitones().round();
+        #[expect(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            reason = "Only whole semitones from 0 to 12 are looked up"
+        )]
+        let name = ((self.0 - semitones * 100.0).abs() < 1.0 && (0.0..=12.0).contains(&semitones))
+            .then(|| INTERVAL_NAMES.get(semitones as usize))
+            .flatten();
+        match name {
+            Some(name) => write!(f, "{name}"),
+            None => write!(f, "{} cents", self.0.round()),
+        }
+    }
+}
+
+impl Add for Interval {
+    type Output = Interval;
+
+    fn add(self, rhs: Interval) -> Self::Output {
+        Interval(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Interval {
+    type Output = Interval;
+
+    fn sub(self, rhs: Interval) -> Self::Output {
+        Interval(self.0 - rhs.0)
+    }
+}
+
+impl Neg for Interval {
+    type Output = Interval;
+
+    fn neg(self) -> Self::Output {
+        Interval(-self.0)
+    }
+}
+
+impl Add<Interval> for NotePitch {
+    type Output = NotePitch;
+
+    /// Raises the pitch by the interval.
+    fn add(self, rhs: Interval) -> Self::Output {
+        rhs.above(self)
+    }
+}
+
+impl Sub<Interval> for NotePitch {
+    type Output = NotePitch;
+
+    /// Lowers the pitch by the interval.
+    fn sub(self, rhs: Interval) -> Self::Output {
+        rhs.below(self)
+    }
+}
+
+impl Chord {
+    /// Creates a chord by stacking intervals, each above the pitch before it, starting from a root.
+    ///
+    /// # Parameters
+    /// - `root`: The lowest pitch of the chord
+    /// - `intervals`: The interval from each pitch of the chord to the next
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// // A major triad is a major third with a minor third on top
+    /// let c_major = Chord::from_intervals(C4, [Interval::MAJOR_THIRD, Interval::MINOR_THIRD]);
+    /// assert_eq!(c_major.symbol().as_deref(), Some("C"));
+    ///
+    /// // Stacked fourths
+    /// let quartal = Chord::from_intervals(D4, [Interval::PERFECT_FOURTH; 3]);
+    /// assert_eq!(quartal.len(), 4);
+    /// ```
+    pub fn from_intervals(root: NotePitch, intervals: impl IntoIterator<Item = Interval>) -> Self {
+        let mut pitches = vec![root];
+        let mut pitch = root;
+        for interval in intervals {
+            pitch = interval.above(pitch);
+            pitches.push(pitch);
+        }
+        Chord(pitches)
+    }
+}
+
+/// A scale made from the intervals between its degrees, such as whole and half steps.
+///
+/// The steps climb from the root to the top of the scale, and the scale repeats from there,
+/// so steps adding up to an octave give a scale which repeats every octave. This allows scales
+/// beyond the built-in modes, such as pentatonic, whole-tone, or microtonal scales.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let [w, m3] = [Interval::MAJOR_SECOND, Interval::MINOR_THIRD];
+/// let pentatonic = IntervalScale::new(C4, [w, w, m3, w, m3]);
+///
+/// assert!((pentatonic.get_degree(4).0 - G4.0).abs() < 0.01);
+/// assert!((pentatonic.get_degree(6).0 - C4.octave(1).0).abs() < 0.01);
+/// assert!((pentatonic.get_degree(-1).0 - A4.octave(-1).0).abs() < 0.01);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct IntervalScale {
+    /// The pitch of the first degree
+    pub root: NotePitch,
+    /// The interval from each degree to the next, from the root up to the top of the scale
+    pub steps: Vec<Interval>,
+}
+
+/// A user-defined scale, made from the steps between its degrees.
+///
+/// This is another name for [`IntervalScale`], which also has constructors taking the steps as plain numbers of
+/// [semitones](IntervalScale::from_semitones) or [cents](IntervalScale::from_cents).
+pub type CustomScale = IntervalScale;
+
+impl IntervalScale {
+    /// Creates a scale from its root and the intervals between its degrees.
+    ///
+    /// The steps aren't checked. If there are none, or they don't add up to more than zero, the
+    /// scale never rises, and every degree is the root. [`IntervalScale::from_semitones`] and
+    /// [`IntervalScale::from_cents`] reject such steps.
+    pub fn new(root: NotePitch, steps: impl IntoIterator<Item = Interval>) -> Self {
+        IntervalScale {
+            root,
+            steps: steps.into_iter().collect(),
+        }
+    }
+
+    /// Creates a scale from its root and the number of semitones between each of its degrees.
+    ///
+    /// # Parameters
+    /// - `root`: The pitch of the first degree
+    /// - `steps`: The semitones from each degree to the next, which may be fractions
+    ///
+    /// # Errors
+    /// Returns an error if there are no steps, or they don't add up to more than zero, since
+    /// the scale would never rise.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// // The Hirajōshi scale
+    /// let hirajoshi = CustomScale::from_semitones(A4, &[2.0, 1.0, 4.0, 1.0, 4.0]).unwrap();
+    /// assert!((hirajoshi.get_degree(4).0 - E5.0).abs() < 0.01);
+    /// assert!((hirajoshi.get_degree(6).0 - A4.octave(1).0).abs() < 0.01);
+    ///
+    /// assert_eq!(CustomScale::from_semitones(A4, &[]), Err(ScaleStepsError::Empty));
+    /// ```
+    pub fn from_semitones(root: NotePitch, steps: &[f32]) -> Result<Self, ScaleStepsError> {
+        IntervalScale::checked(root, steps.iter().map(|&semitones| Interval::semitones(semitones)))
+    }
+
+    /// Creates a scale from its root and the number of cents between each of its degrees.
+    ///
+    /// # Parameters
+    /// - `root`: The pitch of the first degree
+    /// - `steps`: The cents from each degree to the next
+    ///
+    /// # Errors
+    /// Returns an error if there are no steps, or they don't add up to more than zero, since
+    /// the scale would never rise.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// // Seven equal steps to the octave, as in some Thai music
+    /// let thai = CustomScale::from_cents(C4, &[1200.0 / 7.0; 7]).unwrap();
+    /// assert!((thai.get_degree(8).0 - C4.octave(1).0).abs() < 0.01);
+    /// assert_eq!(thai.demo_line(1).notes.len(), 15);
+    /// ```
+    pub fn from_cents(root: NotePitch, steps: &[f32]) -> Result<Self, ScaleStepsError> {
+        IntervalScale::checked(root, steps.iter().map(|&cents| Interval::cents(cents)))
+    }
+
+    /// Creates a scale, if its steps are a scale which rises.
+    fn checked(root: NotePitch, steps: impl IntoIterator<Item = Interval>) -> Result<Self, ScaleStepsError> {
+        let scale = IntervalScale::new(root, steps);
+        let period: f32 = scale.steps.iter().map(|step| step.0).sum();
+        if scale.steps.is_empty() {
+            Err(ScaleStepsError::Empty)
+        } else if period > 0.0 {
+            Ok(scale)
+        } else {
+            Err(ScaleStepsError::NotRising)
+        }
+    }
+}
+
+/// The error returned when the steps of a custom scale don't make a scale.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScaleStepsError {
+    /// There were no steps
+    Empty,
+    /// The steps didn't add up to more than zero, so the scale would never rise
+    NotRising,
+}
+
+impl Display for ScaleStepsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            ScaleStepsError::Empty => write!(f, "a scale needs at least one step"),
+            ScaleStepsError::NotRising => write!(f, "a scale's steps must add up to more than zero"),
+        }
+    }
+}
+
+impl std::error::Error for ScaleStepsError {}
+
+impl Scale for IntervalScale {
+    #[expect(clippy::cast_precision_loss, reason = "Degrees are small")]
+    fn get_degree(&self, degree: isize) -> NotePitch {
+        // Like the built-in modes, degree 1 is the root and degree 0 is the same as degree 1
+        let adjusted_degree = if degree > 0 { degree.saturating_sub(1) } else { degree };
+        let Ok(count) = isize::try_from(self.steps.len()) else {
+            return self.root;
+        };
+        if count == 0 {
+            return self.root;
+        }
+
+        let period: f32 = self.steps.iter().map(|step| step.0).sum();
+        let within: f32 = self
+            .steps
+            .iter()
+            .take(usize::try_from(adjusted_degree.rem_euclid(count)).unwrap_or_default())
+            .map(|step| step.0)
+            .sum();
+        Interval(adjusted_degree.div_euclid(count) as f32 * period + within).above(self.root)
+    }
+}
+
+#[test]
+fn test_intervals_round_trip_through_pitches() {
+    use crate::prelude::*;
+
+    let fifth_below = C4 - Interval::PERFECT_FIFTH;
+    assert!((Interval::between(C4, fifth_below).as_semitones() + 7.0).abs() < 0.001);
+    assert!((Interval::OCTAVE.ratio() - 2.0).abs() < f32::EPSILON);
+    assert_eq!(Interval::MAJOR_THIRD + Interval::MINOR_THIRD, Interval::PERFECT_FIFTH);
+    assert_eq!(-Interval::TRITONE, Interval::semitones(-6.0));
+    assert_eq!(Interval::cents(-1200.0).to_string(), "-1200 cents");
+
+    let whole_tone = IntervalScale::new(C4, [Interval::MAJOR_SECOND; 6]);
+    assert!((whole_tone.get_degree(7).0 - C4.octave(1).0).abs() < 0.01);
+    assert_eq!(whole_tone.get_degree(0), whole_tone.get_degree(1));
+    assert_eq!(IntervalScale::new(C4, []).get_degree(5), C4);
+}
+
+#[test]
+fn test_custom_scales_reject_steps_which_never_rise() {
+    use crate::prelude::*;
+
+    assert_eq!(CustomScale::from_semitones(C4, &[]), Err(ScaleStepsError::Empty));
+    assert_eq!(CustomScale::from_semitones(C4, &[0.0]), Err(ScaleStepsError::NotRising));
+    assert_eq!(
+        CustomScale::from_cents(C4, &[700.0, -700.0]),
+        Err(ScaleStepsError::NotRising)
+    );
+    assert_eq!(
+        CustomScale::from_cents(C4, &[-100.0; 12]),
+        Err(ScaleStepsError::NotRising)
+    );
+    assert_eq!(
+        CustomScale::from_cents(C4, &[f32::NAN]),
+        Err(ScaleStepsError::NotRising)
+    );
+
+    // A step may go down, as long as the scale rises overall
+    let scale = CustomScale::from_semitones(C4, &[7.0, -3.0, 8.0]).unwrap();
+    assert!((scale.get_degree(4).0 - C4.octave(1).0).abs() < 0.01);
+}
@@ -1,11 +1,15 @@
-use crate::{note::NotePitch, scales::tet12::A4};
+use crate::{eighth, note::NotePitch, quarter, scales::tet12::A4, Line, Note};
 
 /// 12-tone equal temperament system and related scales.
 ///
 /// Contains scale implementations and pitch manipulation functions.
 pub mod tet12;
 
+mod interval;
+
+pub use interval::{CustomScale, Interval, IntervalScale, ScaleStepsError};
 pub use tet12::modes::*;
+pub use tet12::{Key, Mode};
 
 /// A trait for musical scales that can generate pitches from scale degrees.
 ///
@@ -46,6 +50,36 @@ pub trait Scale {
     /// ```
     fn get_degree(&self, degree: isize) -> NotePitch;
 
+    /// Creates a melody from scale degrees, each with a note length function.
+    ///
+    /// This saves getting the pitches of a phrase with [`Scale::get_degrees`] and then giving
+    /// each one its length. Length functions like `quarter` can be
+    /// given directly, and other lengths as closures, such as `|pitch| dotted(quarter)(pitch)`.
+    /// Notes have the default timbre, which can be changed with functions like
+    /// [`piano`](crate::prelude::piano).
+    ///
+    /// # Parameters
+    /// - `notes`: The degree and length function of each note, in order
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let scale = MajorScale(C4);
+    /// let phrase = piano(scale.melody(&[(1, quarter), (3, quarter), (5, |pitch| dotted(quarter)(pitch)), (8, eighth)]));
+    ///
+    /// let [c4, e4, g4, c5] = scale.get_degrees([1, 3, 5, 8]);
+    /// assert_eq!(phrase, piano(quarter(c4) + quarter(e4) + dotted(quarter)(g4) + eighth(c5)));
+    /// ```
+    fn melody(&self, notes: &[DegreeNote]) -> Line {
+        Line::from(
+            notes
+                .iter()
+                .map(|(degree, length)| length(self.get_degree(*degree)))
+                .collect::<Vec<_>>(),
+        )
+    }
+
     /// Gets multiple pitches at once from an array of scale degrees.
     ///
     /// This is a convenience method for getting several scale degrees
@@ -66,4 +100,60 @@ pub trait Scale {
         }
         out
     }
+    /// Creates a run up and back down the scale, for hearing how it sounds.
+    ///
+    /// The run starts on the first degree, climbs until it's the given number of octaves higher,
+    /// and comes back down, in eighth notes with a quarter note at the end. The number of degrees
+    /// in an octave is found by listening to the scale, so it works for any scale, including
+    /// ones with more or fewer than seven notes. Notes have the default timbre, which can be
+    /// changed with functions like [`piano`](crate::prelude::piano).
+    ///
+    /// # Parameters
+    /// - `octaves`: How many octaves the run climbs
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let run = piano(MajorScale(C4).demo_line(1));
+    ///
+    /// // C D E F G A B C B A G F E D C
+    /// assert_eq!(run.notes.len(), 15);
+    /// assert_eq!(run.notes[7], piano(eighth(C4.octave(1))));
+    /// ```
+    fn demo_line(&self, octaves: u8) -> Line {
+        let top = self.get_degree(1).0 * 2.0_f32.powi(i32::from(octaves));
+        let mut degrees: Vec<isize> = vec![1];
+        // Scales are expected to climb, but one which never reaches the top still ends
+        while let Some(&degree) = degrees.last() {
+            if self.get_degree(degree).0 >= top * DEMO_TOLERANCE || degree >= DEMO_MAX_DEGREES {
+                break;
+            }
+            degrees.push(degree.saturating_add(1));
+        }
+        let descent: Vec<isize> = degrees.iter().rev().skip(1).copied().collect();
+        degrees.extend(descent);
+
+        let last = degrees.len().saturating_sub(1);
+        Line::from(
+            degrees
+                .into_iter()
+                .enumerate()
+                .map(|(index, degree)| match index == last {
+                    true => quarter(self.get_degree(degree)),
+                    false => eighth(self.get_degree(degree)),
+                })
+                .collect::<Vec<_>>(),
+        )
+    }
 }
+
+/// A note of a melody built with [`Scale::melody`]: a degree of the scale, and the note length
+/// function which gives it its length.
+type DegreeNote = (isize, fn(NotePitch) -> Note);
+
+/// The most degrees [`Scale::demo_line`] climbs, in case a scale never reaches the top.
+const DEMO_MAX_DEGREES: isize = 256;
+
+/// How close a pitch has to be to the top of [`Scale::demo_line`] to count as reaching it.
+const DEMO_TOLERANCE: f32 = 0.999;
@@ -0,0 +1,348 @@
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
+};
+
+use crate::{
+    scales::tet12::{
+        format_note_name, format_note_name_with_octave, get_pitch_from_name, modes::*, pitch_class_and_octave,
+        KeySpelling, NoteNameOptions, Spelling,
+    },
+    NotePitch, Scale, Tet12, A4,
+};
+
+/// The number of sharps (positive) or flats (negative) in the signature of each major key,
+/// by the pitch class of its tonic, from 0 for C to 11 for B.
+const MAJOR_SIGNATURES: [i8; 12] = [0, -5, 2, -3, 4, -1, -6, 1, -4, 3, -2, 5];
+
+/// One of the seven modes of the major scale, which together with a tonic make a [`Key`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// The major scale, also known as Ionian
+    #[default]
+    Major,
+    /// The natural minor scale, also known as Aeolian
+    Minor,
+    /// A minor mode with a raised 6th degree
+    Dorian,
+    /// A minor mode with a flattened 2nd degree
+    Phrygian,
+    /// A major mode with a raised 4th degree
+    Lydian,
+    /// A major mode with a flattened 7th degree
+    Mixolydian,
+    /// A diminished mode with flattened 2nd and 5th degrees
+    Locrian,
+}
+
+impl Mode {
+    /// Every mode, in the order they're listed in.
+    pub const ALL: [Mode; 7] = [
+        Mode::Major,
+        Mode::Minor,
+        Mode::Dorian,
+        Mode::Phrygian,
+        Mode::Lydian,
+        Mode::Mixolydian,
+        Mode::Locrian,
+    ];
+
+    /// Returns how many semitones the mode's tonic is above the tonic of the major key with the
+    /// same signature, such as 9 for minor, since A minor shares its signature with C major.
+    pub fn semitones_above_relative_major(&self) -> usize {
+        match self {
+            Mode::Major => 0,
+            Mode::Dorian => 2,
+            Mode::Phrygian => 4,
+            Mode::Lydian => 5,
+            Mode::Mixolydian => 7,
+            Mode::Minor => 9,
+            Mode::Locrian => 11,
+        }
+    }
+}
+
+impl Display for Mode {
+    /// Writes the mode's name in lower case, such as "major" or "dorian".
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let name = match self {
+            Mode::Major => "major",
+            Mode::Minor => "minor",
+            Mode::Dorian => "dorian",
+            Mode::Phrygian => "phrygian",
+            Mode::Lydian => "lydian",
+            Mode::Mixolydian => "mixolydian",
+            Mode::Locrian => "locrian",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A key: a tonic and a mode, such as B♭ major or F♯ minor.
+///
+/// Pitches in this crate are frequencies, so they don't know whether they're a D♯ or an E♭.
+/// A key does: its signature says whether its notes are written with sharps or flats, and
+/// [`Key::note_names`] gives the options for writing note names that way. Keys are also
+/// scales, so they can be used anywhere a [`Scale`] can, such as to build chords.
+///
+/// Keys with six sharps or flats, such as F♯ and G♭ major, sound the same, and are written
+/// with flats.
+///
+/// A key can be attached to a piece through its [`Metadata`](crate::Metadata), by setting it
+/// with [`Metadata::with_key`](crate::Metadata::with_key), and read back with
+/// [`Metadata::parsed_key`](crate::Metadata::parsed_key).
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::scales::tet12::get_note_name_in_key;
+///
+/// let b_flat_major: Key = "Bb major".parse().unwrap();
+/// assert_eq!(b_flat_major.signature(), -2);
+/// assert_eq!(get_note_name_in_key(DS4, A4, &b_flat_major), "Eb");
+///
+/// let e_major = Key::new(E4, Mode::Major);
+/// assert_eq!(get_note_name_in_key(DS4, A4, &e_major), "D#");
+/// assert_eq!(e_major.get_degree(5), MajorScale(E4).get_degree(5));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Key {
+    /// The pitch of the first degree
+    pub tonic: NotePitch,
+    /// The mode of the key
+    pub mode: Mode,
+}
+
+impl Key {
+    /// Creates a key from its tonic and mode.
+    pub fn new(tonic: NotePitch, mode: Mode) -> Self {
+        Key { tonic, mode }
+    }
+
+    /// Returns the number of sharps in the key signature, or minus the number of flats, such
+    /// as `3` for A major and `-1` for D minor.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// assert_eq!(Key::new(A4, Mode::Major).signature(), 3);
+    /// assert_eq!(Key::new(D4, Mode::Minor).signature(), -1);
+    /// assert_eq!(Key::new(D4, Mode::Dorian).signature(), 0);
+    /// ```
+    pub fn signature(&self) -> i8 {
+        let (pitch_class, _) = pitch_class_and_octave(self.tonic, A4);
+        let relative_major = pitch_class
+            .saturating_add(12)
+            .saturating_sub(self.mode.semitones_above_relative_major())
+            % 12;
+        MAJOR_SIGNATURES.get(relative_major).copied().unwrap_or_default()
+    }
+
+    /// Returns whether the key's signature has sharps or flats. Keys with no sharps or flats
+    /// count as sharp keys.
+    ///
+    /// See [`Key::note_names`] for how each note is spelled in the key.
+    pub fn spelling(&self) -> Spelling {
+        if self.signature() < 0 {
+            Spelling::Flats
+        } else {
+            Spelling::Sharps
+        }
+    }
+
+    /// Returns the options for writing note names in the key, in scientific pitch notation with
+    /// the key's spelling.
+    ///
+    /// Notes of the key are spelled by their letter in its scale, so the fourth of G♭ major is
+    /// Cb rather than B. In minor keys, the raised 6th and 7th degrees of the melodic and harmonic
+    /// minor scales are spelled as sharpened degrees, so the leading tone of G minor is F#.
+    /// Other notes are spelled with the signature's sharps or flats.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let key = Key::new(F4, Mode::Major);
+    /// assert_eq!(AS4.name(&key.note_names()), "Bb4");
+    ///
+    /// let g_flat_major = Key::new(FS4, Mode::Major);
+    /// assert_eq!(B4.name(&g_flat_major.note_names()), "Cb5");
+    /// ```
+    pub fn note_names(&self) -> NoteNameOptions {
+        let (tonic, _) = pitch_class_and_octave(self.tonic, A4);
+        let mut spelling = KeySpelling::new(self.spelling());
+        let (tonic_letter, _) = spelling.names.get(tonic).copied().unwrap_or_default();
+
+        let pitch_class = |pitch: NotePitch| pitch_class_and_octave(pitch, A4).0;
+        for (step, degree) in (1..=7).enumerate() {
+            spelling.set(pitch_class(self.get_degree(degree)), tonic_letter.saturating_add(step));
+        }
+        if self.mode == Mode::Minor {
+            for (step, degree) in [(5, 6), (6, 7)] {
+                let raised = self.get_degree(degree).semitone(1);
+                spelling.set(pitch_class(raised), tonic_letter.saturating_add(step));
+            }
+        }
+
+        NoteNameOptions {
+            spelling: Spelling::InKey(spelling),
+            ..NoteNameOptions::default()
+        }
+    }
+}
+
+impl Scale for Key {
+    fn get_degree(&self, degree: isize) -> NotePitch {
+        let tonic = self.tonic;
+        match self.mode {
+            Mode::Major => MajorScale(tonic).get_degree(degree),
+            Mode::Minor => MinorScale(tonic).get_degree(degree),
+            Mode::Dorian => DorianScale(tonic).get_degree(degree),
+            Mode::Phrygian => PhrygianScale(tonic).get_degree(degree),
+            Mode::Lydian => LydianScale(tonic).get_degree(degree),
+            Mode::Mixolydian => MixolydianScale(tonic).get_degree(degree),
+            Mode::Locrian => LocrianScale(tonic).get_degree(degree),
+        }
+    }
+}
+
+impl Display for Key {
+    /// Writes the key's tonic, spelled as the key spells it, and its mode, such as "Bb major".
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "{} {}",
+            format_note_name(self.tonic, A4, &self.note_names()),
+            self.mode
+        )
+    }
+}
+
+impl FromStr for Key {
+    type Err = ParseKeyError;
+
+    /// Reads a key from its tonic and mode, such as "Bb major", "f# minor", or "D dorian".
+    /// The mode may be left out for a major key, and "ionian" and "aeolian" are read as major
+    /// and minor. The tonic is placed in the octave starting at C4.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseKeyError(s.to_string());
+        let mut words = s.split_whitespace();
+        let tonic = words
+            .next()
+            .and_then(|name| get_pitch_from_name(&format!("{name}4"), A4))
+            .ok_or_else(error)?;
+        let mode = match words.next().map(str::to_lowercase).as_deref() {
+            None | Some("major" | "ionian") => Mode::Major,
+            Some("minor" | "aeolian") => Mode::Minor,
+            Some(name) => Mode::ALL
+                .into_iter()
+                .find(|mode| mode.to_string() == name)
+                .ok_or_else(error)?,
+        };
+        match words.next() {
+            Some(_) => Err(error()),
+            None => Ok(Key::new(tonic, mode)),
+        }
+    }
+}
+
+/// Gets the note name (without octave) for a given pitch, spelled as the key spells it
+/// (see [`Key::note_names`]).
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::scales::tet12::get_note_name_in_key;
+///
+/// let g_minor = Key::new(G4, Mode::Minor);
+/// assert_eq!(get_note_name_in_key(AS4, A4, &g_minor), "Bb");
+/// assert_eq!(get_note_name_in_key(FS4, A4, &g_minor), "F#"); // The leading tone
+/// assert_eq!(get_note_name_in_key(E4, A4, &g_minor), "E"); // The raised 6th
+/// assert_eq!(get_note_name_in_key(CS4, A4, &g_minor), "Db"); // Outside the key
+/// ```
+pub fn get_note_name_in_key(note: NotePitch, a4: NotePitch, key: &Key) -> String {
+    format_note_name(note, a4, &key.note_names())
+}
+
+/// Gets the note name with octave number for a given pitch, spelled as the key spells it
+/// (see [`Key::note_names`]).
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::scales::tet12::get_note_name_with_octave_in_key;
+///
+/// let a_flat_major = Key::new(GS4, Mode::Major);
+/// assert_eq!(get_note_name_with_octave_in_key(CS5, A4, &a_flat_major), "Db5");
+/// ```
+pub fn get_note_name_with_octave_in_key(note: NotePitch, a4: NotePitch, key: &Key) -> String {
+    format_note_name_with_octave(note, a4, &key.note_names())
+}
+
+/// The error returned when a [`Key`] can't be read from text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseKeyError(pub String);
+
+impl Display for ParseKeyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "invalid key: \"{}\"", self.0)
+    }
+}
+
+impl std::error::Error for ParseKeyError {}
+
+#[test]
+fn test_keys_spell_with_their_signature() {
+    use crate::prelude::*;
+
+    for (name, signature) in [
+        ("C major", 0),
+        ("A minor", 0),
+        ("Bb major", -2),
+        ("C# minor", 4),
+        ("Eb minor", -6),
+        ("E phrygian", 0),
+        ("F lydian", 0),
+        ("Ab", -4),
+    ] {
+        let key: Key = name.parse().unwrap();
+        assert_eq!(key.signature(), signature, "{name}");
+        let written = if name.contains(' ') {
+            name.to_string()
+        } else {
+            format!("{name} major")
+        };
+        assert_eq!(key.to_string(), written);
+    }
+
+    // F# major sounds the same as Gb major, and is written with flats
+    assert_eq!("F# major".parse::<Key>().unwrap().to_string(), "Gb major");
+    assert_eq!("D minor".parse::<Key>().unwrap().spelling(), Spelling::Flats);
+    assert!("H major".parse::<Key>().is_err());
+    assert!("C majestic".parse::<Key>().is_err());
+}
+
+#[test]
+fn test_keys_spell_notes_by_their_scale_letter() {
+    use crate::prelude::*;
+
+    let names = |key: &str| {
+        let key: Key = key.parse().unwrap();
+        (1..=7)
+            .map(|degree| format_note_name(key.get_degree(degree), A4, &key.note_names()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names("Gb major"), ["Gb", "Ab", "Bb", "Cb", "Db", "Eb", "F"]);
+    assert_eq!(names("Eb minor"), ["Eb", "F", "Gb", "Ab", "Bb", "Cb", "Db"]);
+    assert_eq!(names("D dorian"), ["D", "E", "F", "G", "A", "B", "C"]);
+
+    // Raised 7ths of minor keys are sharpened, even when that's a B# or an F##
+    let leading_tone = |key: &str| {
+        let key: Key = key.parse().unwrap();
+        key.get_degree(7).semitone(1).name(&key.note_names())
+    };
+    assert_eq!(leading_tone("D minor"), "C#5");
+    assert_eq!(leading_tone("C# minor"), "B#4");
+    assert_eq!(leading_tone("G# minor"), "F##5");
+}
@@ -8,15 +8,27 @@ pub mod modes;
 
 pub use modes::*;
 
+mod key;
+mod note_names;
+mod pitch_class_set;
+
+pub use key::*;
+pub use note_names::*;
+pub use pitch_class_set::*;
+
+/// Named pitch constants, such as `G3` or `DS5`.
+pub mod pitches;
+
 use crate::{
     instrument_tools::strings::StringTuning,
     note::{chord::Chord, NotePitch},
+    Line, Note, NoteKind, Piece, Timbre,
 };
 
 /// Gets the note name (without octave) for a given pitch.
 ///
 /// Returns the note name in standard Western notation (C, C#, D, D#, E, F, F#, G, G#, A, A#, B)
-/// relative to the provided A4 reference pitch.
+/// relative to the provided A4 reference pitch. Use [`format_note_name`] for flats and other naming systems.
 ///
 /// # Examples
 /// ```
@@ -30,14 +42,14 @@ use crate::{
 /// assert_eq!(sharp_name, "C#");
 /// ```
 pub fn get_note_name(note: NotePitch, a4: NotePitch) -> String {
-    let name = get_note_name_with_octave(note, a4);
-    name.trim_end_matches(char::is_numeric).to_string()
+    format_note_name(note, a4, &NoteNameOptions::default())
 }
 
 /// Gets the note name with octave number for a given pitch.
 ///
 /// Returns the note name with octave in standard Western notation (e.g., "C4", "A#5")
-/// relative to the provided A4 reference pitch.
+/// relative to the provided A4 reference pitch. Use [`format_note_name_with_octave`] for flats
+/// and other naming systems.
 ///
 /// # Examples
 /// ```
@@ -51,25 +63,82 @@ pub fn get_note_name(note: NotePitch, a4: NotePitch) -> String {
 /// assert_eq!(higher_note, "A5");
 /// ```
 pub fn get_note_name_with_octave(note: NotePitch, a4: NotePitch) -> String {
-    let c4 = a4.semitone(3).octave(-1);
-
-    let note_names = ["C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B"];
+    format_note_name_with_octave(note, a4, &NoteNameOptions::default())
+}
 
+/// Returns the pitch class of a pitch, from 0 for C to 11 for B, and its octave number.
+pub(crate) fn pitch_class_and_octave(note: NotePitch, a4: NotePitch) -> (usize, i16) {
+    let c4 = a4.semitone(3).octave(-1);
     let diff = f32::log2(note.0 / c4.0);
 
+    // The octave is found from the rounded semitone, so a C slightly flat of its frequency
+    // isn't named as the C an octave below
     #[expect(clippy::cast_possible_truncation, reason = "log_2 of a non-infinite f32 has at most 7 bits")]
-    let (octave_diff, semitone_diff) = (diff.floor() as i16, ((diff * 12.0).round() as i16).rem_euclid(12));
+    let semitones = (diff * 12.0).round() as i16;
 
-    #[expect(clippy::cast_sign_loss, reason = "semitone_diff is always in range 0..12")]
-    let note_name = String::from(note_names[semitone_diff as usize]);
+    let pitch_class = semitones.rem_euclid(12) as usize;
 
     #[expect(clippy::arithmetic_side_effects, reason = "This is guaranteed to fit in i16.")]
-    let octave_number = octave_diff + 4;
+    let octave = semitones.div_euclid(12) + 4;
+
+    (pitch_class, octave)
+}
+
+/// Gets the pitch of a note name with octave number, such as "C#4" or "Bb2".
+///
+/// This is the inverse of [`get_note_name_with_octave`]. The letter may be upper or lower case,
+/// and may be followed by any number of sharps (`#` or `♯`) or flats (`b` or `♭`).
+/// As in scientific pitch notation, the octave number belongs to the letter, so "Cb4" is B3.
+///
+/// Returns `None` if the name can't be parsed.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::scales::tet12::{get_note_name_with_octave, get_pitch_from_name};
+///
+/// let g_sharp = get_pitch_from_name("G#2", A4).unwrap();
+/// assert_eq!(get_note_name_with_octave(g_sharp, A4), "G#2");
+///
+/// let b_flat = get_pitch_from_name("Bb-1", A4).unwrap();
+/// assert_eq!(get_note_name_with_octave(b_flat, A4), "A#-1");
+///
+/// assert_eq!(get_pitch_from_name("A4", A4), Some(A4));
+/// assert_eq!(get_pitch_from_name("H4", A4), None);
+/// ```
+pub fn get_pitch_from_name(name: &str, a4: NotePitch) -> Option<NotePitch> {
+    let mut chars = name.trim().chars().peekable();
+
+    let letter_semitones: i32 = match chars.next()?.to_ascii_uppercase() {
+        'C' => -9,
+        'D' => -7,
+        'E' => -5,
+        'F' => -4,
+        'G' => -2,
+        'A' => 0,
+        'B' => 2,
+        _ => return None,
+    };
 
-    #[expect(clippy::arithmetic_side_effects, reason = "This is a simple string concatenation")]
-    let out = note_name + &(octave_number).to_string();
+    let mut accidentals = 0_i32;
+    while let Some(accidental) = chars.peek() {
+        match accidental {
+            '#' | '♯' => accidentals = accidentals.checked_add(1)?,
+            'b' | '♭' => accidentals = accidentals.checked_sub(1)?,
+            _ => break,
+        }
+        chars.next();
+    }
 
-    out
+    let octave: i32 = chars.collect::<String>().parse().ok()?;
+
+    let semitones = octave
+        .checked_sub(4)?
+        .checked_mul(12)?
+        .checked_add(letter_semitones)?
+        .checked_add(accidentals)?;
+
+    Some(a4.semitone(i16::try_from(semitones).ok()?))
 }
 
 #[test]
@@ -84,6 +153,10 @@ fn test_get_note_name() {
         let name = get_note_name_with_octave(*note, A4);
         assert_eq!(name, *expected_name);
     }
+
+    // A C slightly flat of its frequency is still named in its own octave
+    let flat_c5 = NotePitch(C4.0 * 2.0 * 0.999);
+    assert_eq!(get_note_name_with_octave(flat_c5, A4), "C5");
 }
 
 /// Standard pitch reference - A above middle C at 440 Hz.
@@ -95,18 +168,26 @@ pub const A4: NotePitch = NotePitch(440.0);
 /// This is a common reference point for musical compositions.
 pub const C4: NotePitch = NotePitch(261.626);
 
+/// Gets a degree of a scale which repeats every octave, from the semitones between each of its
+/// degrees in the first octave. The steps should add up to 12.
 #[expect(
     clippy::cast_possible_truncation,
     clippy::cast_precision_loss,
     reason = "Willing to accept some precision loss here"
 )]
-fn get_degree_with_pattern_and_root(degree: isize, root: NotePitch, pattern: [f64; 7]) -> NotePitch {
+fn get_degree_with_pattern_and_root(degree: isize, root: NotePitch, pattern: &[f64]) -> NotePitch {
     #[expect(clippy::arithmetic_side_effects, reason = "Manual overflow checking")]
     let adjusted_degree = if degree > 0 { degree - 1 } else { degree };
-    let octave_power = adjusted_degree.div_euclid(7) as f64;
+    let Ok(steps) = isize::try_from(pattern.len()) else {
+        return root;
+    };
+    if steps == 0 {
+        return root;
+    }
+    let octave_power = adjusted_degree.div_euclid(steps) as f64;
 
     let mut interval_power = 0.0f64;
-    for &step_size in pattern.iter().take(adjusted_degree.rem_euclid(7) as usize) {
+    for &step_size in pattern.iter().take(adjusted_degree.rem_euclid(steps) as usize) {
         interval_power += step_size / 12.0
     }
 
@@ -210,3 +291,86 @@ impl Tet12 for Chord {
         Chord::new(self.0.iter().map(|&note| note.semitone(change)))
     }
 }
+
+/// Transposes a note with the given function. Rests, and notes whose timbre
+/// plays a fixed sound regardless of pitch (such as drums), are left as they are.
+pub(crate) fn transpose_kind(kind: &NoteKind, transpose: impl Fn(NotePitch) -> NotePitch) -> NoteKind {
+    match kind {
+        NoteKind::Pitched { pitch, timbre, .. }
+            if !matches!(timbre.unreversed(), Timbre::Drums | Timbre::CustomSourceUnpitched(_)) =>
+        {
+            let mut kind = kind.clone();
+            if let NoteKind::Pitched { pitch: new_pitch, .. } = &mut kind {
+                *new_pitch = transpose(*pitch);
+            }
+            kind
+        }
+        _ => kind.clone(),
+    }
+}
+
+/// Rests and unpitched notes, such as drums, are not transposed.
+impl Tet12 for NoteKind {
+    fn octave(&self, change: i32) -> Self {
+        transpose_kind(self, |pitch| pitch.octave(change))
+    }
+
+    fn semitone(&self, change: i16) -> Self {
+        transpose_kind(self, |pitch| pitch.semitone(change))
+    }
+}
+
+/// Rests and unpitched notes, such as drums, are not transposed.
+impl Tet12 for Note {
+    fn octave(&self, change: i32) -> Self {
+        Note(self.0, self.1.octave(change))
+    }
+
+    fn semitone(&self, change: i16) -> Self {
+        Note(self.0, self.1.semitone(change))
+    }
+}
+
+/// Transposes every note of the line, including its pickup.
+/// Rests and unpitched notes, such as drums, are not transposed.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let melody = piano(quarter(C4) + quarter(E4) + half(G4));
+/// assert_eq!(melody.semitone(2), piano(quarter(C4.semitone(2)) + quarter(E4.semitone(2)) + half(G4.semitone(2))));
+///
+/// let beat = drums(quarter(C5) + quarter(C4));
+/// assert_eq!(beat.octave(-1), beat); // Drums keep their sounds
+/// ```
+impl Tet12 for Line {
+    fn octave(&self, change: i32) -> Self {
+        self.map_notes(|note| note.octave(change))
+    }
+
+    fn semitone(&self, change: i16) -> Self {
+        self.map_notes(|note| note.semitone(change))
+    }
+}
+
+/// Transposes every line of the piece.
+/// Rests and unpitched notes, such as drums, are not transposed.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let piece = piano(quarter(C4) + quarter(E4)) * bass(half(C2));
+/// let lower = piece.octave(-1);
+/// assert_eq!(lower.0[1], Line::from(bass(half(C2.octave(-1)))));
+/// ```
+impl Tet12 for Piece {
+    fn octave(&self, change: i32) -> Self {
+        Piece(self.0.iter().map(|line| line.octave(change)).collect())
+    }
+
+    fn semitone(&self, change: i16) -> Self {
+        Piece(self.0.iter().map(|line| line.semitone(change)).collect())
+    }
+}
@@ -7,6 +7,9 @@ macro_rules! scale_pattern {
     [$($steps:literal,)* h $($etc:tt)*] => {
         scale_pattern![$($steps,)* 1.0, $($etc)*]
     };
+    [$($steps:literal,)* m3 $($etc:tt)*] => {
+        scale_pattern![$($steps,)* 3.0, $($etc)*]
+    };
     [$($steps:literal,)*] => {[$($steps,)*]};
 }
 
@@ -17,7 +20,7 @@ macro_rules! implement_scale {
 
         impl Scale for $name {
             fn get_degree(&self, degree: isize) -> NotePitch {
-                get_degree_with_pattern_and_root(degree, self.0, $pattern)
+                get_degree_with_pattern_and_root(degree, self.0, &$pattern)
             }
         }
     };
@@ -58,6 +61,74 @@ implement_scale!(
     scale_pattern![h w w h w w w],
     "Locrian mode - a diminished-type scale with both flattened 2nd and 5th degrees."
 );
+implement_scale!(
+    HarmonicMinorScale,
+    scale_pattern![w h w w h m3 h],
+    "Harmonic minor scale - the natural minor scale with a raised 7th degree, which gives it a leading tone \
+    and an augmented second between its 6th and 7th degrees."
+);
+implement_scale!(
+    MelodicMinorScale,
+    scale_pattern![w h w w w w h],
+    "Melodic minor scale - the natural minor scale with raised 6th and 7th degrees, as it's played ascending \
+    in classical music, and in both directions in jazz."
+);
+implement_scale!(
+    MajorPentatonicScale,
+    scale_pattern![w w m3 w m3],
+    "Major pentatonic scale - a five-note scale made from the major scale without its 4th and 7th degrees. \
+    It repeats every 5 degrees, so degree 6 is an octave above the root."
+);
+implement_scale!(
+    MinorPentatonicScale,
+    scale_pattern![m3 w w m3 w],
+    "Minor pentatonic scale - a five-note scale made from the natural minor scale without its 2nd and 6th \
+    degrees. It repeats every 5 degrees, so degree 6 is an octave above the root."
+);
+implement_scale!(
+    BluesScale,
+    scale_pattern![m3 w h h m3 w],
+    "Blues scale - the minor pentatonic scale with an added flattened 5th, the \"blue note\". \
+    It repeats every 6 degrees, so degree 7 is an octave above the root."
+);
+implement_scale!(
+    WholeToneScale,
+    scale_pattern![w w w w w w],
+    "Whole tone scale - six notes a whole step apart, with no half steps, giving a dreamy, unresolved sound. \
+    It repeats every 6 degrees, so degree 7 is an octave above the root."
+);
+implement_scale!(
+    ChromaticScale,
+    scale_pattern![h h h h h h h h h h h h],
+    "Chromatic scale - all twelve notes, a half step apart. \
+    It repeats every 12 degrees, so degree 13 is an octave above the root."
+);
 
 pub use MajorScale as IonianScale;
 pub use MinorScale as AeolianScale;
+
+#[test]
+fn test_scales_repeat_every_octave() {
+    use crate::prelude::*;
+
+    fn check(scale: impl Scale, degrees: isize) {
+        let close = |a: NotePitch, b: NotePitch| (a.0 - b.0).abs() < 0.01;
+        assert!(close(scale.get_degree(degrees.saturating_add(1)), C4.octave(1)));
+        assert!(close(scale.get_degree(-degrees), C4.octave(-1)));
+        let run_length = degrees.saturating_mul(2).saturating_add(1);
+        assert_eq!(scale.demo_line(1).notes.len(), usize::try_from(run_length).unwrap());
+    }
+    check(MajorPentatonicScale(C4), 5);
+    check(MinorPentatonicScale(C4), 5);
+    check(BluesScale(C4), 6);
+    check(HarmonicMinorScale(C4), 7);
+    check(MelodicMinorScale(C4), 7);
+    check(WholeToneScale(C4), 6);
+    check(ChromaticScale(C4), 12);
+
+    let close = |a: NotePitch, b: NotePitch| (a.0 - b.0).abs() < 0.01;
+    assert!(close(BluesScale(C4).get_degree(4), C4.semitone(6)));
+    assert!(close(HarmonicMinorScale(C4).get_degree(7), C4.semitone(11)));
+    assert!(close(MelodicMinorScale(C4).get_degree(6), C4.semitone(9)));
+    assert!(close(ChromaticScale(C4).get_degree(-1), C4.semitone(-1)));
+}
@@ -0,0 +1,239 @@
+use crate::note::NotePitch;
+
+/// The pitch class of each natural note, from 0 for C to 6 for B.
+const NATURALS: [usize; 7] = [0, 2, 4, 5, 7, 9, 11];
+
+/// Whether notes between the natural notes are spelled with sharps or flats.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Spelling {
+    /// C#, D#, F#, G#, and A#
+    #[default]
+    Sharps,
+    /// Db, Eb, Gb, Ab, and Bb
+    Flats,
+    /// Spelled as a key spells them, from [`Key::note_names`](super::Key::note_names)
+    InKey(KeySpelling),
+}
+
+/// How each pitch class is spelled in a key, such as Cb rather than B in G♭ major, or F# rather
+/// than Gb as the leading tone of G minor.
+///
+/// Created by [`Key::note_names`](super::Key::note_names).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeySpelling {
+    /// The letter of each pitch class, from 0 for C to 6 for B, and how many sharps (positive)
+    /// or flats (negative) it has
+    pub(crate) names: [(usize, i8); 12],
+}
+
+impl KeySpelling {
+    /// Creates a spelling which spells every pitch class with sharps or flats, to be changed
+    /// with [`KeySpelling::set`] for the notes of a key.
+    pub(crate) fn new(spelling: Spelling) -> Self {
+        let mut names = [(0, 0); 12];
+        for (pitch_class, name) in names.iter_mut().enumerate() {
+            *name = letter_and_accidental(pitch_class, spelling);
+        }
+        KeySpelling { names }
+    }
+
+    /// Spells a pitch class with the given letter, from 0 for C to 6 for B.
+    #[expect(clippy::arithmetic_side_effects, reason = "Pitch classes and letters are small")]
+    pub(crate) fn set(&mut self, pitch_class: usize, letter: usize) {
+        let letter = letter % 7;
+        let natural = NATURALS.get(letter).copied().unwrap_or_default();
+        // The accidental which moves the letter's natural to the pitch class the shortest way
+        #[expect(clippy::cast_possible_truncation, reason = "This is below 12")]
+        let accidental = ((pitch_class % 12 + 18 - natural) % 12) as i8 - 6;
+        if let Some(name) = self.names.get_mut(pitch_class) {
+            *name = (letter, accidental);
+        }
+    }
+}
+
+/// Returns the letter of a pitch class, from 0 for C to 6 for B, and how many sharps (positive)
+/// or flats (negative) it's spelled with.
+fn letter_and_accidental(pitch_class: usize, spelling: Spelling) -> (usize, i8) {
+    let natural_at = |letter: usize| NATURALS.get(letter).copied().unwrap_or_default();
+    #[expect(clippy::cast_possible_truncation, reason = "Pitch classes are at most 11")]
+    let difference = |letter: usize| (pitch_class as i8).saturating_sub(natural_at(letter) as i8);
+    match spelling {
+        Spelling::Sharps => {
+            let letter = NATURALS
+                .iter()
+                .rposition(|&natural| natural <= pitch_class)
+                .unwrap_or_default();
+            (letter, difference(letter))
+        }
+        Spelling::Flats => {
+            let letter = NATURALS
+                .iter()
+                .position(|&natural| natural >= pitch_class)
+                .unwrap_or_default();
+            (letter, difference(letter))
+        }
+        Spelling::InKey(key) => key.names.get(pitch_class).copied().unwrap_or_default(),
+    }
+}
+
+/// The naming system note names are written in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum NoteNameStyle {
+    /// English letter names with the octave as a number, as in scientific pitch notation: `C#4`, `Bb2`
+    #[default]
+    Scientific,
+    /// German names, where B natural is H and B flat is B, and accidentals are suffixes: `Cis4`, `Es4`, `H3`
+    German,
+    /// Fixed-do solfège, where C is always Do: `Do4`, `Fa#3`, `Sib2`
+    Solfege,
+    /// Helmholtz notation, where the octave is shown by the letter's case and by marks: C4 is `c'`,
+    /// C3 is `c`, C2 is `C`, and C1 is `C,`
+    Helmholtz,
+}
+
+/// How note names are written.
+///
+/// Used by [`format_note_name`], [`format_note_name_with_octave`], [`NotePitch::name`], and scores
+/// displayed with [`ScoreDisplay::with_note_names`](crate::piece::ScoreDisplay::with_note_names).
+/// The default is what [`get_note_name_with_octave`](super::get_note_name_with_octave) writes:
+/// scientific pitch notation with sharps.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::scales::tet12::{NoteNameOptions, NoteNameStyle, Spelling};
+///
+/// let b_flat = C4.semitone(-2);
+/// let name = |style, spelling| b_flat.name(&NoteNameOptions { style, spelling });
+///
+/// assert_eq!(name(NoteNameStyle::Scientific, Spelling::Sharps), "A#3");
+/// assert_eq!(name(NoteNameStyle::Scientific, Spelling::Flats), "Bb3");
+/// assert_eq!(name(NoteNameStyle::German, Spelling::Flats), "B3");
+/// assert_eq!(name(NoteNameStyle::Solfege, Spelling::Flats), "Sib3");
+/// assert_eq!(name(NoteNameStyle::Helmholtz, Spelling::Flats), "bb");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct NoteNameOptions {
+    /// The naming system
+    pub style: NoteNameStyle,
+    /// Whether sharps or flats are used
+    pub spelling: Spelling,
+}
+
+/// Gets the note name (without octave) for a given pitch, written as the options say.
+///
+/// In Helmholtz notation, the octave is part of the name, so names without it are written
+/// in scientific pitch notation instead.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::scales::tet12::{format_note_name, NoteNameOptions, NoteNameStyle, Spelling};
+///
+/// let german = NoteNameOptions { style: NoteNameStyle::German, spelling: Spelling::Sharps };
+/// assert_eq!(format_note_name(C4.semitone(-1), A4, &german), "H");
+/// assert_eq!(format_note_name(C4.semitone(1), A4, &german), "Cis");
+/// ```
+pub fn format_note_name(note: NotePitch, a4: NotePitch, options: &NoteNameOptions) -> String {
+    let (pitch_class, _) = super::pitch_class_and_octave(note, a4);
+    let style = match options.style {
+        NoteNameStyle::Helmholtz => NoteNameStyle::Scientific,
+        style => style,
+    };
+    pitch_class_name(pitch_class, style, options.spelling).0
+}
+
+/// Gets the note name with octave for a given pitch, written as the options say.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::scales::tet12::{format_note_name_with_octave, NoteNameOptions, NoteNameStyle, Spelling};
+///
+/// let helmholtz = NoteNameOptions { style: NoteNameStyle::Helmholtz, spelling: Spelling::Sharps };
+/// assert_eq!(format_note_name_with_octave(C4, A4, &helmholtz), "c'");
+/// assert_eq!(format_note_name_with_octave(C4.semitone(13), A4, &helmholtz), "c#''");
+/// assert_eq!(format_note_name_with_octave(C4.octave(-3), A4, &helmholtz), "C,");
+///
+/// let solfege = NoteNameOptions { style: NoteNameStyle::Solfege, spelling: Spelling::Sharps };
+/// assert_eq!(format_note_name_with_octave(C4.semitone(7), A4, &solfege), "Sol4");
+/// ```
+pub fn format_note_name_with_octave(note: NotePitch, a4: NotePitch, options: &NoteNameOptions) -> String {
+    let (pitch_class, octave) = super::pitch_class_and_octave(note, a4);
+    let (name, octave_change) = pitch_class_name(pitch_class, options.style, options.spelling);
+    let octave = octave.saturating_add(octave_change);
+    if options.style != NoteNameStyle::Helmholtz {
+        return format!("{name}{octave}");
+    }
+
+    // The small octave, starting at C3, is written in lower case, and octaves above it get a
+    // prime each. The great octave, starting at C2, is written in upper case, and octaves below
+    // it get a comma each.
+    let marks = |count: i16, mark: &str| mark.repeat(usize::try_from(count).unwrap_or_default());
+    if octave >= 3 {
+        format!("{}{}", name.to_lowercase(), marks(octave.saturating_sub(3), "'"))
+    } else {
+        format!("{name}{}", marks(2_i16.saturating_sub(octave), ","))
+    }
+}
+
+/// Returns the name of a pitch class, from 0 for C to 11 for B, without an octave, and how much
+/// the octave number changes when written with that name, such as 1 for B spelled as Cb.
+fn pitch_class_name(pitch_class: usize, style: NoteNameStyle, spelling: Spelling) -> (String, i16) {
+    let (letter, accidental) = letter_and_accidental(pitch_class, spelling);
+    let count = usize::from(accidental.unsigned_abs());
+    let name = match style {
+        NoteNameStyle::Scientific | NoteNameStyle::Helmholtz => {
+            let letter = ["C", "D", "E", "F", "G", "A", "B"]
+                .get(letter)
+                .copied()
+                .unwrap_or_default();
+            let mark = if accidental > 0 { "#" } else { "b" };
+            format!("{letter}{}", mark.repeat(count))
+        }
+        NoteNameStyle::German => {
+            let letter = ["C", "D", "E", "F", "G", "A", "H"]
+                .get(letter)
+                .copied()
+                .unwrap_or_default();
+            match (letter, accidental) {
+                (_, 0) => letter.to_string(),
+                (_, 1..) => format!("{letter}{}", "is".repeat(count)),
+                ("H", -1) => "B".to_string(),
+                // Vowels take an "s" for their first flat, as in "Es" and "As"
+                ("E" | "A", _) => format!("{letter}s{}", "es".repeat(count.saturating_sub(1))),
+                _ => format!("{letter}{}", "es".repeat(count)),
+            }
+        }
+        NoteNameStyle::Solfege => {
+            let letter = ["Do", "Re", "Mi", "Fa", "Sol", "La", "Si"]
+                .get(letter)
+                .copied()
+                .unwrap_or_default();
+            let mark = if accidental > 0 { "#" } else { "b" };
+            format!("{letter}{}", mark.repeat(count))
+        }
+    };
+
+    // As in scientific pitch notation, the octave belongs to the letter, so B#3 is C4
+    let spelled = i16::try_from(NATURALS.get(letter).copied().unwrap_or_default()).unwrap_or_default();
+    let octave_change = spelled.saturating_add(i16::from(accidental)).div_euclid(12);
+    (name, octave_change.saturating_neg())
+}
+
+impl NotePitch {
+    /// Returns the name of the pitch with its octave, written as the options say, tuned relative to A4 at 440 Hz.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::scales::tet12::{NoteNameOptions, Spelling};
+    ///
+    /// let flats = NoteNameOptions { spelling: Spelling::Flats, ..Default::default() };
+    /// assert_eq!(C4.semitone(3).name(&flats), "Eb4");
+    /// assert_eq!(C4.semitone(3).name(&NoteNameOptions::default()), "D#4");
+    /// ```
+    pub fn name(&self, options: &NoteNameOptions) -> String {
+        format_note_name_with_octave(*self, crate::A4, options)
+    }
+}
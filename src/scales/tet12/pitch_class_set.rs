@@ -0,0 +1,258 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use itertools::Itertools;
+
+use crate::{note::chord::Chord, NotePitch, Tet12, C4};
+
+/// A set of pitch classes, as used in musical set theory.
+///
+/// A pitch class is a pitch without its octave, numbered in semitones above C, so C is 0,
+/// C#/Db is 1, and B is 11. A pitch class set ignores octaves, doublings, and voicing,
+/// so every voicing of a C major chord has the same set, `{0, 4, 7}`.
+///
+/// This is handy for analysing atonal music, and for comparing chord shapes regardless of
+/// how they're voiced, with [`PitchClassSet::prime_form`].
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let c_major = PitchClassSet::from(&Chord::new([C4, E4, G4]));
+/// let spread_c_major = PitchClassSet::from(&Chord::new([C3, G3, E4, C5]));
+/// assert_eq!(c_major, spread_c_major);
+///
+/// // Major and minor triads are inversions of each other, so they share a prime form
+/// let a_minor = PitchClassSet::from(&Chord::new([A3, C4, E4]));
+/// assert_eq!(c_major.prime_form(), vec![0, 3, 7]);
+/// assert_eq!(a_minor.prime_form(), c_major.prime_form());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub struct PitchClassSet(u16);
+
+impl PitchClassSet {
+    /// Creates a set from pitch classes. Numbers of 12 and above wrap around, so 12 is C again.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let set = PitchClassSet::new([7, 0, 4, 12]);
+    /// assert_eq!(set.pitch_classes(), vec![0, 4, 7]);
+    /// ```
+    pub fn new(pitch_classes: impl IntoIterator<Item = u8>) -> Self {
+        PitchClassSet(
+            pitch_classes
+                .into_iter()
+                .fold(0, |bits, pitch_class| bits | 1 << (pitch_class % 12)),
+        )
+    }
+
+    /// Returns whether the set contains a pitch class.
+    pub fn contains(&self, pitch_class: u8) -> bool {
+        self.0 & 1 << (pitch_class % 12) != 0
+    }
+
+    /// Returns the number of pitch classes in the set.
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Returns whether the set has no pitch classes.
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the pitch classes in the set, from lowest to highest.
+    pub fn pitch_classes(&self) -> Vec<u8> {
+        (0..12).filter(|&pitch_class| self.contains(pitch_class)).collect()
+    }
+
+    /// Transposes every pitch class in the set by a number of semitones.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let c_major = PitchClassSet::new([0, 4, 7]);
+    /// assert_eq!(c_major.transpose(2), PitchClassSet::new([2, 6, 9])); // D major
+    /// assert_eq!(c_major.transpose(-1), PitchClassSet::new([11, 3, 6])); // B major
+    /// ```
+    pub fn transpose(&self, semitones: i16) -> Self {
+        let shift = u32::from(semitones.rem_euclid(12).unsigned_abs());
+        let bits = u32::from(self.0);
+        let rotated = (bits << shift | bits >> (12_u32.saturating_sub(shift))) & 0xfff;
+        PitchClassSet(u16::try_from(rotated).unwrap_or_default())
+    }
+
+    /// Inverts the set around C, so each pitch class `n` becomes `12 - n`.
+    ///
+    /// Inverting turns a major triad into a minor one. Combine with [`PitchClassSet::transpose`]
+    /// to invert around other pitch classes.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let c_major = PitchClassSet::new([0, 4, 7]);
+    /// assert_eq!(c_major.invert(), PitchClassSet::new([0, 8, 5])); // F minor
+    /// ```
+    pub fn invert(&self) -> Self {
+        PitchClassSet::new(
+            self.pitch_classes()
+                .into_iter()
+                .map(|pitch_class| (12_u8.saturating_sub(pitch_class)) % 12),
+        )
+    }
+
+    /// Returns the normal form of the set: its pitch classes in the most compact ascending order,
+    /// as defined by John Rahn.
+    ///
+    /// Of every rotation of the pitch classes, the normal form spans the smallest interval from
+    /// first to last. Ties are broken by the smallest interval from first to second-last, and so on,
+    /// and then by the lowest first pitch class.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let set = PitchClassSet::new([0, 4, 7]);
+    /// assert_eq!(set.normal_form(), vec![0, 4, 7]);
+    ///
+    /// // C, E, G, B spans 11 semitones, but B, C, E, G spans only 8
+    /// let c_major_seventh = PitchClassSet::new([0, 4, 7, 11]);
+    /// assert_eq!(c_major_seventh.normal_form(), vec![11, 0, 4, 7]);
+    /// ```
+    pub fn normal_form(&self) -> Vec<u8> {
+        let pitch_classes = self.pitch_classes();
+        let count = pitch_classes.len();
+        (0..count)
+            .map(|start| {
+                let rotation: Vec<u8> = pitch_classes.iter().cycle().skip(start).take(count).copied().collect();
+                (packing(&rotation), rotation)
+            })
+            .min()
+            .map(|(_, rotation)| rotation)
+            .unwrap_or_default()
+    }
+
+    /// Returns the prime form of the set: the most compact form of the set, or of its inversion,
+    /// transposed to start on 0.
+    ///
+    /// Sets with the same prime form are related by transposition or inversion, so they
+    /// have the same intervals between their pitch classes.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// // Every major and minor triad has the prime form [0, 3, 7]
+    /// assert_eq!(PitchClassSet::new([2, 6, 9]).prime_form(), vec![0, 3, 7]);
+    ///
+    /// // The "Viennese trichord"
+    /// assert_eq!(PitchClassSet::new([4, 10, 11]).prime_form(), vec![0, 1, 6]);
+    /// ```
+    pub fn prime_form(&self) -> Vec<u8> {
+        [*self, self.invert()]
+            .iter()
+            .map(|set| {
+                let normal_form = set.normal_form();
+                let first = normal_form.first().copied().unwrap_or_default();
+                normal_form
+                    .iter()
+                    .map(|pitch_class| (pitch_class.saturating_add(12).saturating_sub(first)) % 12)
+                    .collect::<Vec<u8>>()
+            })
+            .min_by_key(|form| (packing(form), form.clone()))
+            .unwrap_or_default()
+    }
+
+    /// Returns the interval vector of the set: how many pairs of pitch classes are 1, 2, 3, 4, 5,
+    /// and 6 semitones apart. Intervals larger than 6 semitones count as their inversions, so
+    /// a fifth (7) counts as a fourth (5).
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// // A major triad has one minor third, one major third, and one fourth/fifth
+    /// assert_eq!(PitchClassSet::new([0, 4, 7]).interval_vector(), [0, 0, 1, 1, 1, 0]);
+    /// ```
+    pub fn interval_vector(&self) -> [u8; 6] {
+        let mut vector = [0_u8; 6];
+        for (low, high) in self.pitch_classes().into_iter().tuple_combinations() {
+            let interval = high
+                .saturating_sub(low)
+                .min(12_u8.saturating_sub(high.saturating_sub(low)));
+            if let Some(count) = vector.get_mut(usize::from(interval).wrapping_sub(1)) {
+                *count = count.saturating_add(1);
+            }
+        }
+        vector
+    }
+}
+
+/// How compactly a rotation of pitch classes is packed: the intervals from its first pitch class
+/// to each of the others, from the last to the second. Smaller is more compact.
+fn packing(rotation: &[u8]) -> Vec<u8> {
+    let first = rotation.first().copied().unwrap_or_default();
+    rotation
+        .iter()
+        .skip(1)
+        .rev()
+        .map(|pitch_class| (pitch_class.saturating_add(12).saturating_sub(first)) % 12)
+        .collect()
+}
+
+impl From<&Chord> for PitchClassSet {
+    /// Finds the pitch classes of a chord's pitches, rounded to the nearest semitone.
+    fn from(chord: &Chord) -> Self {
+        #[expect(clippy::cast_possible_truncation, reason = "Audible pitches are within a few hundred semitones")]
+        let pitch_classes = chord.0.iter().map(|pitch| {
+            let semitones = (12.0 * f32::log2(pitch.0 / C4.0)).round() as i16;
+            u8::try_from(semitones.rem_euclid(12)).unwrap_or_default()
+        });
+        PitchClassSet::new(pitch_classes)
+    }
+}
+
+impl From<Chord> for PitchClassSet {
+    fn from(chord: Chord) -> Self {
+        PitchClassSet::from(&chord)
+    }
+}
+
+impl From<PitchClassSet> for Chord {
+    /// Voices the set as a close chord in normal form, starting in the octave above C4.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let c_major = Chord::from(PitchClassSet::new([0, 4, 7]));
+    /// assert_eq!(c_major.symbol().as_deref(), Some("C"));
+    ///
+    /// // The normal form of C major seventh starts on B
+    /// let c_major_seventh = Chord::from(PitchClassSet::new([0, 4, 7, 11]));
+    /// assert_eq!(c_major_seventh.symbol().as_deref(), Some("Cmaj7/B"));
+    /// ```
+    fn from(set: PitchClassSet) -> Self {
+        let normal_form = set.normal_form();
+        let first = normal_form.first().copied().unwrap_or_default();
+        Chord(
+            normal_form
+                .iter()
+                .map(|&pitch_class| {
+                    let above_first = (pitch_class.saturating_add(12).saturating_sub(first)) % 12;
+                    C4.semitone(i16::from(first).saturating_add(i16::from(above_first)))
+                })
+                .collect::<Vec<NotePitch>>(),
+        )
+    }
+}
+
+impl Display for PitchClassSet {
+    /// Writes the set as its pitch classes in braces, such as `{0, 4, 7}`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{{{}}}", self.pitch_classes().iter().join(", "))
+    }
+}
@@ -0,0 +1,200 @@
+//! Named pitch constants for every note in octaves 0 to 8, tuned to A4 at 440 Hz.
+//!
+//! Sharps are written with an `S` and flats with an `F`, so D#5 is `DS5` and E♭5 is `EF5`.
+//! Both spellings of a note have the same pitch.
+//!
+//! # Examples
+//! ```
+//! use symphoxy::prelude::*;
+//! use symphoxy::scales::tet12::get_note_name_with_octave;
+//!
+//! let melody = piano(quarter(E4) + quarter(D4) + quarter(C4) + quarter(D4));
+//! let bass_note = bass(whole(G2)); // Instead of C4.semitone(7).octave(-2)
+//!
+//! assert_eq!(DS5, EF5);
+//! assert_eq!(get_note_name_with_octave(GS2, A4), "G#2");
+//! ```
+
+use crate::NotePitch;
+
+pub use super::{A4, C4};
+
+macro_rules! pitches {
+    ($($ident:ident = $frequency:literal, $name:literal;)*) => {
+        $(
+            #[doc = concat!("The pitch ", $name, ", at ", stringify!($frequency), " Hz.")]
+            pub const $ident: NotePitch = NotePitch($frequency);
+        )*
+    };
+}
+
+pitches! {
+    // Octave 0
+    C0 = 16.352, "C0";
+    CS0 = 17.324, "C#0";
+    DF0 = 17.324, "Db0";
+    D0 = 18.354, "D0";
+    DS0 = 19.445, "D#0";
+    EF0 = 19.445, "Eb0";
+    E0 = 20.602, "E0";
+    F0 = 21.827, "F0";
+    FS0 = 23.125, "F#0";
+    GF0 = 23.125, "Gb0";
+    G0 = 24.5, "G0";
+    GS0 = 25.957, "G#0";
+    AF0 = 25.957, "Ab0";
+    A0 = 27.5, "A0";
+    AS0 = 29.135, "A#0";
+    BF0 = 29.135, "Bb0";
+    B0 = 30.868, "B0";
+
+    // Octave 1
+    C1 = 32.703, "C1";
+    CS1 = 34.648, "C#1";
+    DF1 = 34.648, "Db1";
+    D1 = 36.708, "D1";
+    DS1 = 38.891, "D#1";
+    EF1 = 38.891, "Eb1";
+    E1 = 41.203, "E1";
+    F1 = 43.654, "F1";
+    FS1 = 46.249, "F#1";
+    GF1 = 46.249, "Gb1";
+    G1 = 48.999, "G1";
+    GS1 = 51.913, "G#1";
+    AF1 = 51.913, "Ab1";
+    A1 = 55.0, "A1";
+    AS1 = 58.27, "A#1";
+    BF1 = 58.27, "Bb1";
+    B1 = 61.735, "B1";
+
+    // Octave 2
+    C2 = 65.406, "C2";
+    CS2 = 69.296, "C#2";
+    DF2 = 69.296, "Db2";
+    D2 = 73.416, "D2";
+    DS2 = 77.782, "D#2";
+    EF2 = 77.782, "Eb2";
+    E2 = 82.407, "E2";
+    F2 = 87.307, "F2";
+    FS2 = 92.499, "F#2";
+    GF2 = 92.499, "Gb2";
+    G2 = 97.999, "G2";
+    GS2 = 103.826, "G#2";
+    AF2 = 103.826, "Ab2";
+    A2 = 110.0, "A2";
+    AS2 = 116.541, "A#2";
+    BF2 = 116.541, "Bb2";
+    B2 = 123.471, "B2";
+
+    // Octave 3
+    C3 = 130.813, "C3";
+    CS3 = 138.591, "C#3";
+    DF3 = 138.591, "Db3";
+    D3 = 146.832, "D3";
+    DS3 = 155.563, "D#3";
+    EF3 = 155.563, "Eb3";
+    E3 = 164.814, "E3";
+    F3 = 174.614, "F3";
+    FS3 = 184.997, "F#3";
+    GF3 = 184.997, "Gb3";
+    G3 = 195.998, "G3";
+    GS3 = 207.652, "G#3";
+    AF3 = 207.652, "Ab3";
+    A3 = 220.0, "A3";
+    AS3 = 233.082, "A#3";
+    BF3 = 233.082, "Bb3";
+    B3 = 246.942, "B3";
+
+    // Octave 4
+    CS4 = 277.183, "C#4";
+    DF4 = 277.183, "Db4";
+    D4 = 293.665, "D4";
+    DS4 = 311.127, "D#4";
+    EF4 = 311.127, "Eb4";
+    E4 = 329.628, "E4";
+    F4 = 349.228, "F4";
+    FS4 = 369.994, "F#4";
+    GF4 = 369.994, "Gb4";
+    G4 = 391.995, "G4";
+    GS4 = 415.305, "G#4";
+    AF4 = 415.305, "Ab4";
+    AS4 = 466.164, "A#4";
+    BF4 = 466.164, "Bb4";
+    B4 = 493.883, "B4";
+
+    // Octave 5
+    C5 = 523.251, "C5";
+    CS5 = 554.365, "C#5";
+    DF5 = 554.365, "Db5";
+    D5 = 587.33, "D5";
+    DS5 = 622.254, "D#5";
+    EF5 = 622.254, "Eb5";
+    E5 = 659.255, "E5";
+    F5 = 698.456, "F5";
+    FS5 = 739.989, "F#5";
+    GF5 = 739.989, "Gb5";
+    G5 = 783.991, "G5";
+    GS5 = 830.609, "G#5";
+    AF5 = 830.609, "Ab5";
+    A5 = 880.0, "A5";
+    AS5 = 932.328, "A#5";
+    BF5 = 932.328, "Bb5";
+    B5 = 987.767, "B5";
+
+    // Octave 6
+    C6 = 1046.502, "C6";
+    CS6 = 1108.731, "C#6";
+    DF6 = 1108.731, "Db6";
+    D6 = 1174.659, "D6";
+    DS6 = 1244.508, "D#6";
+    EF6 = 1244.508, "Eb6";
+    E6 = 1318.51, "E6";
+    F6 = 1396.913, "F6";
+    FS6 = 1479.978, "F#6";
+    GF6 = 1479.978, "Gb6";
+    G6 = 1567.982, "G6";
+    GS6 = 1661.219, "G#6";
+    AF6 = 1661.219, "Ab6";
+    A6 = 1760.0, "A6";
+    AS6 = 1864.655, "A#6";
+    BF6 = 1864.655, "Bb6";
+    B6 = 1975.533, "B6";
+
+    // Octave 7
+    C7 = 2093.005, "C7";
+    CS7 = 2217.461, "C#7";
+    DF7 = 2217.461, "Db7";
+    D7 = 2349.318, "D7";
+    DS7 = 2489.016, "D#7";
+    EF7 = 2489.016, "Eb7";
+    E7 = 2637.02, "E7";
+    F7 = 2793.826, "F7";
+    FS7 = 2959.955, "F#7";
+    GF7 = 2959.955, "Gb7";
+    G7 = 3135.963, "G7";
+    GS7 = 3322.438, "G#7";
+    AF7 = 3322.438, "Ab7";
+    A7 = 3520.0, "A7";
+    AS7 = 3729.31, "A#7";
+    BF7 = 3729.31, "Bb7";
+    B7 = 3951.066, "B7";
+
+    // Octave 8
+    C8 = 4186.009, "C8";
+    CS8 = 4434.922, "C#8";
+    DF8 = 4434.922, "Db8";
+    D8 = 4698.636, "D8";
+    DS8 = 4978.032, "D#8";
+    EF8 = 4978.032, "Eb8";
+    E8 = 5274.041, "E8";
+    F8 = 5587.652, "F8";
+    FS8 = 5919.911, "F#8";
+    GF8 = 5919.911, "Gb8";
+    G8 = 6271.927, "G8";
+    GS8 = 6644.875, "G#8";
+    AF8 = 6644.875, "Ab8";
+    A8 = 7040.0, "A8";
+    AS8 = 7458.62, "A#8";
+    BF8 = 7458.62, "Bb8";
+    B8 = 7902.133, "B8";
+}
@@ -0,0 +1,370 @@
+mod sight_reading;
+
+pub use sight_reading::*;
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use crate::{
+    eighth, half, note::chord::Chord, piano, quarter, rng::Rng, scales::tet12::modes::*, whole, Line, NotePitch, Piece,
+    Scale, Tet12, C4,
+};
+
+/// A generated ear-training exercise: a piece to play, and the answer the listener should give.
+///
+/// # Examples
+/// ```
+/// use symphoxy::training::{EarTrainer, Exercise, IntervalAnswer};
+///
+/// let mut trainer = EarTrainer::with_seed(42);
+/// let Exercise { piece, answer } = trainer.interval();
+///
+/// // Play `piece` to the user, then compare their guess with `answer`
+/// println!("That was a {answer}");
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Exercise<A> {
+    /// The music to play to the listener
+    pub piece: Piece,
+    /// The correct answer for the exercise
+    pub answer: A,
+}
+
+/// The answer to an interval exercise.
+///
+/// # Examples
+/// ```
+/// use symphoxy::training::IntervalAnswer;
+///
+/// let fifth = IntervalAnswer { semitones: 7 };
+/// assert_eq!(fifth.name(), "perfect fifth");
+/// assert_eq!(fifth.to_string(), "perfect fifth");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct IntervalAnswer {
+    /// The size of the interval in semitones, from 1 (minor second) to 12 (octave)
+    pub semitones: u8,
+}
+
+impl IntervalAnswer {
+    /// Returns the name of the interval, e.g. "minor third".
+    pub fn name(&self) -> &'static str {
+        match self.semitones {
+            0 => "unison",
+            1 => "minor second",
+            2 => "major second",
+            3 => "minor third",
+            4 => "major third",
+            5 => "perfect fourth",
+            6 => "tritone",
+            7 => "perfect fifth",
+            8 => "minor sixth",
+            9 => "major sixth",
+            10 => "minor seventh",
+            11 => "major seventh",
+            12 => "octave",
+            _ => "compound interval",
+        }
+    }
+}
+
+impl Display for IntervalAnswer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(self.name())
+    }
+}
+
+/// The quality of a chord, used as the answer to a chord exercise.
+///
+/// # Examples
+/// ```
+/// use symphoxy::training::ChordQuality;
+///
+/// assert_eq!(ChordQuality::Minor.semitone_offsets(), &[3, 7]);
+/// assert_eq!(ChordQuality::DominantSeventh.to_string(), "dominant seventh");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChordQuality {
+    /// Major triad - root, major third, perfect fifth
+    Major,
+    /// Minor triad - root, minor third, perfect fifth
+    Minor,
+    /// Diminished triad - root, minor third, diminished fifth
+    Diminished,
+    /// Augmented triad - root, major third, augmented fifth
+    Augmented,
+    /// Major seventh chord - major triad plus a major seventh
+    MajorSeventh,
+    /// Minor seventh chord - minor triad plus a minor seventh
+    MinorSeventh,
+    /// Dominant seventh chord - major triad plus a minor seventh
+    DominantSeventh,
+}
+
+impl ChordQuality {
+    /// Every chord quality, in the order they are listed above.
+    pub const ALL: [ChordQuality; 7] = [
+        ChordQuality::Major,
+        ChordQuality::Minor,
+        ChordQuality::Diminished,
+        ChordQuality::Augmented,
+        ChordQuality::MajorSeventh,
+        ChordQuality::MinorSeventh,
+        ChordQuality::DominantSeventh,
+    ];
+
+    /// Returns the semitone offsets of the chord tones above the root.
+    pub fn semitone_offsets(&self) -> &'static [u8] {
+        match self {
+            ChordQuality::Major => &[4, 7],
+            ChordQuality::Minor => &[3, 7],
+            ChordQuality::Diminished => &[3, 6],
+            ChordQuality::Augmented => &[4, 8],
+            ChordQuality::MajorSeventh => &[4, 7, 11],
+            ChordQuality::MinorSeventh => &[3, 7, 10],
+            ChordQuality::DominantSeventh => &[4, 7, 10],
+        }
+    }
+
+    /// Builds a chord of this quality on the given root.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::training::ChordQuality;
+    ///
+    /// let c_major = ChordQuality::Major.chord(C4);
+    /// assert_eq!(c_major, Chord::new([C4, C4.semitone(4), C4.semitone(7)]));
+    /// ```
+    pub fn chord(&self, root: NotePitch) -> Chord {
+        Chord::new(
+            std::iter::once(root).chain(
+                self.semitone_offsets()
+                    .iter()
+                    .map(|&offset| root.semitone(i16::from(offset))),
+            ),
+        )
+    }
+
+    /// Returns the name of the chord quality, e.g. "minor seventh".
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChordQuality::Major => "major",
+            ChordQuality::Minor => "minor",
+            ChordQuality::Diminished => "diminished",
+            ChordQuality::Augmented => "augmented",
+            ChordQuality::MajorSeventh => "major seventh",
+            ChordQuality::MinorSeventh => "minor seventh",
+            ChordQuality::DominantSeventh => "dominant seventh",
+        }
+    }
+}
+
+impl Display for ChordQuality {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(self.name())
+    }
+}
+
+/// The kind of scale, used as the answer to a scale exercise.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::training::ScaleKind;
+///
+/// assert_eq!(ScaleKind::Dorian.degree(C4, 1), C4);
+/// assert_eq!(ScaleKind::Major.degree(C4, 5), MajorScale(C4).get_degree(5));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ScaleKind {
+    /// The major scale (Ionian mode)
+    Major,
+    /// The natural minor scale (Aeolian mode)
+    Minor,
+    /// The Dorian mode
+    Dorian,
+    /// The Phrygian mode
+    Phrygian,
+    /// The Lydian mode
+    Lydian,
+    /// The Mixolydian mode
+    Mixolydian,
+    /// The Locrian mode
+    Locrian,
+}
+
+impl ScaleKind {
+    /// Every scale kind, in the order they are listed above.
+    pub const ALL: [ScaleKind; 7] = [
+        ScaleKind::Major,
+        ScaleKind::Minor,
+        ScaleKind::Dorian,
+        ScaleKind::Phrygian,
+        ScaleKind::Lydian,
+        ScaleKind::Mixolydian,
+        ScaleKind::Locrian,
+    ];
+
+    /// Gets the pitch at a degree of this kind of scale, starting on `root`.
+    ///
+    /// See [`Scale::get_degree`] for how degrees are numbered.
+    pub fn degree(&self, root: NotePitch, degree: isize) -> NotePitch {
+        match self {
+            ScaleKind::Major => MajorScale(root).get_degree(degree),
+            ScaleKind::Minor => MinorScale(root).get_degree(degree),
+            ScaleKind::Dorian => DorianScale(root).get_degree(degree),
+            ScaleKind::Phrygian => PhrygianScale(root).get_degree(degree),
+            ScaleKind::Lydian => LydianScale(root).get_degree(degree),
+            ScaleKind::Mixolydian => MixolydianScale(root).get_degree(degree),
+            ScaleKind::Locrian => LocrianScale(root).get_degree(degree),
+        }
+    }
+
+    /// Returns the name of the scale, e.g. "dorian".
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScaleKind::Major => "major",
+            ScaleKind::Minor => "natural minor",
+            ScaleKind::Dorian => "dorian",
+            ScaleKind::Phrygian => "phrygian",
+            ScaleKind::Lydian => "lydian",
+            ScaleKind::Mixolydian => "mixolydian",
+            ScaleKind::Locrian => "locrian",
+        }
+    }
+}
+
+impl Display for ScaleKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(self.name())
+    }
+}
+
+/// Generates random ear-training exercises.
+///
+/// Every exercise is played on the piano, with a root chosen at random between C3 and C5.
+/// Use `TimbreFluid::with_timbre` on the exercise's piece to play it with a different sound.
+///
+/// Trainers created with the same seed generate the same exercises, which is useful for tests
+/// and for sharing a set of exercises between users.
+///
+/// # Examples
+/// ```
+/// use symphoxy::training::EarTrainer;
+///
+/// let mut trainer = EarTrainer::with_seed(7);
+/// let interval = trainer.interval();
+/// let chord = trainer.chord();
+/// let scale = trainer.scale();
+///
+/// assert!((1..=12).contains(&interval.answer.semitones));
+///
+/// // The same seed produces the same exercises
+/// assert_eq!(EarTrainer::with_seed(7).interval(), interval);
+/// ```
+#[derive(Clone, Debug)]
+pub struct EarTrainer {
+    rng: Rng,
+}
+
+impl Default for EarTrainer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EarTrainer {
+    /// The lowest root, in semitones from C4
+    const LOWEST_ROOT: i32 = -12;
+    /// The highest root, in semitones from C4
+    const HIGHEST_ROOT: i32 = 12;
+
+    /// Creates a trainer with a seed taken from the system clock.
+    pub fn new() -> Self {
+        EarTrainer { rng: Rng::from_time() }
+    }
+
+    /// Creates a trainer which always generates the same sequence of exercises for the same seed.
+    pub fn with_seed(seed: u64) -> Self {
+        EarTrainer { rng: Rng::new(seed) }
+    }
+
+    fn random_root(&mut self) -> NotePitch {
+        let semitones = self.rng.range_inclusive(Self::LOWEST_ROOT, Self::HIGHEST_ROOT);
+        C4.semitone(i16::try_from(semitones).unwrap_or_default())
+    }
+
+    /// Generates an ascending interval of up to an octave.
+    ///
+    /// The two notes are played one after the other, then together.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::training::EarTrainer;
+    ///
+    /// let exercise = EarTrainer::with_seed(1).interval();
+    /// assert_eq!(exercise.piece.length(), whole(C4).0.ticks() as usize);
+    /// ```
+    pub fn interval(&mut self) -> Exercise<IntervalAnswer> {
+        let semitones = u8::try_from(self.rng.range_inclusive(1, 12)).unwrap_or(1);
+        let lower = self.random_root();
+        let upper = lower.semitone(i16::from(semitones));
+
+        #[expect(clippy::arithmetic_side_effects, reason = "Arithmetic implementation")]
+        let piece = piano(quarter(lower) + quarter(upper)) + piano(half(Chord::new([lower, upper])));
+
+        Exercise {
+            piece,
+            answer: IntervalAnswer { semitones },
+        }
+    }
+
+    /// Generates a chord of a random quality in root position.
+    ///
+    /// The chord is arpeggiated upwards, then played as a block chord.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::training::EarTrainer;
+    ///
+    /// let exercise = EarTrainer::with_seed(1).chord();
+    /// let chord_tones = exercise.answer.semitone_offsets().len() + 1;
+    /// assert_eq!(exercise.piece.0.len(), chord_tones);
+    /// ```
+    pub fn chord(&mut self) -> Exercise<ChordQuality> {
+        let quality = *self.rng.choose(&ChordQuality::ALL).unwrap_or(&ChordQuality::Major);
+        let chord = quality.chord(self.random_root());
+
+        let arpeggio = Line::from(chord.0.iter().map(|&pitch| piano(eighth(pitch))).collect::<Vec<_>>());
+        let block = piano(whole(chord));
+
+        #[expect(clippy::arithmetic_side_effects, reason = "Arithmetic implementation")]
+        let piece = arpeggio + block;
+
+        Exercise { piece, answer: quality }
+    }
+
+    /// Generates a random kind of scale, played ascending for one octave.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::training::EarTrainer;
+    ///
+    /// let exercise = EarTrainer::with_seed(1).scale();
+    /// assert_eq!(exercise.piece.0[0].notes.len(), 8);
+    /// ```
+    pub fn scale(&mut self) -> Exercise<ScaleKind> {
+        let kind = *self.rng.choose(&ScaleKind::ALL).unwrap_or(&ScaleKind::Major);
+        let root = self.random_root();
+
+        let notes = (1..=8)
+            .map(|degree| piano(eighth(kind.degree(root, degree))))
+            .collect::<Vec<_>>();
+
+        Exercise {
+            piece: Piece::from(Line::from(notes)),
+            answer: kind,
+        }
+    }
+}
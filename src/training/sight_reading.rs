@@ -0,0 +1,210 @@
+use crate::{piano, rng::Rng, training::ScaleKind, Line, Note, NoteLength, NotePitch, Piece, C4};
+
+/// The difficulty of a sight-reading exercise.
+///
+/// Each grade allows every rhythm of the grades below it, along with some new ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Difficulty {
+    /// Whole and half notes only
+    Beginner,
+    /// Adds quarter notes
+    Elementary,
+    /// Adds eighth notes and dotted half notes
+    Intermediate,
+    /// Adds dotted rhythms, sixteenth notes, and syncopation
+    Advanced,
+}
+
+impl Difficulty {
+    /// The one-bar rhythms introduced at this difficulty, as note lengths in sixteenth notes.
+    fn rhythms(self) -> &'static [&'static [u16]] {
+        match self {
+            Difficulty::Beginner => &[&[16], &[8, 8]],
+            Difficulty::Elementary => &[&[4, 4, 4, 4], &[8, 4, 4], &[4, 4, 8], &[4, 8, 4]],
+            Difficulty::Intermediate => &[
+                &[2, 2, 4, 4, 4],
+                &[4, 2, 2, 4, 4],
+                &[4, 4, 2, 2, 4],
+                &[2, 2, 2, 2, 8],
+                &[12, 4],
+            ],
+            Difficulty::Advanced => &[
+                &[6, 2, 4, 4],
+                &[4, 6, 2, 4],
+                &[6, 2, 6, 2],
+                &[2, 4, 2, 8],
+                &[3, 1, 4, 8],
+                &[4, 3, 1, 8],
+            ],
+        }
+    }
+}
+
+/// Options for generating a sight-reading exercise.
+///
+/// Start from the defaults for a difficulty with [`SightReadingOptions::new`], then change
+/// whichever options you need.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::training::{Difficulty, ScaleKind, SightReadingOptions};
+///
+/// let options = SightReadingOptions {
+///     root: C4.semitone(7), // G
+///     scale: ScaleKind::Minor,
+///     bars: 2,
+///     ..SightReadingOptions::new(Difficulty::Elementary)
+/// };
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct SightReadingOptions {
+    /// The tonic of the key. The melody starts and ends on this pitch.
+    pub root: NotePitch,
+    /// The scale the melody is taken from
+    pub scale: ScaleKind,
+    /// How many scale degrees above the tonic the melody may reach (4 keeps it within a fifth)
+    pub range: isize,
+    /// The largest leap between consecutive notes, in scale degrees
+    pub max_leap: isize,
+    /// The most complex rhythms which may be used
+    pub rhythm: Difficulty,
+    /// The length of the melody, in bars of 4/4
+    pub bars: usize,
+}
+
+impl SightReadingOptions {
+    /// Creates options suitable for the given difficulty, in C major.
+    ///
+    /// Harder grades use a wider range, larger leaps, more complex rhythms, and more bars.
+    pub fn new(difficulty: Difficulty) -> Self {
+        let (range, max_leap, bars) = match difficulty {
+            Difficulty::Beginner => (4, 1, 4),
+            Difficulty::Elementary => (4, 2, 4),
+            Difficulty::Intermediate => (7, 3, 8),
+            Difficulty::Advanced => (9, 5, 8),
+        };
+
+        SightReadingOptions {
+            root: C4,
+            scale: ScaleKind::Major,
+            range,
+            max_leap,
+            rhythm: difficulty,
+            bars,
+        }
+    }
+}
+
+impl Default for SightReadingOptions {
+    fn default() -> Self {
+        Self::new(Difficulty::Beginner)
+    }
+}
+
+/// Generates random melodies for sight-reading practice.
+///
+/// The generated melodies are played on the piano, and can be exported as sheet music
+/// with `Piece::to_lilypond`, so the same exercise can be both read and heard.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::training::{Difficulty, SightReader, SightReadingOptions};
+///
+/// let mut reader = SightReader::with_seed(3);
+/// let options = SightReadingOptions::new(Difficulty::Intermediate);
+///
+/// let melody = reader.melody(&options);
+/// assert_eq!(melody.length(), whole(C4).0.ticks() as usize * options.bars);
+///
+/// // Give the student the sheet music, then play `melody` so they can check themselves
+/// let sheet_music = melody.to_lilypond();
+/// ```
+#[derive(Clone, Debug)]
+pub struct SightReader {
+    rng: Rng,
+}
+
+impl Default for SightReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SightReader {
+    /// Creates a generator with a seed taken from the system clock.
+    pub fn new() -> Self {
+        SightReader { rng: Rng::from_time() }
+    }
+
+    /// Creates a generator which always generates the same sequence of melodies for the same seed.
+    pub fn with_seed(seed: u64) -> Self {
+        SightReader { rng: Rng::new(seed) }
+    }
+
+    /// Generates a melody with the given options.
+    ///
+    /// The melody moves around the scale within the allowed range and leap size,
+    /// and ends on the tonic with a whole or two half notes.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::training::{Difficulty, SightReader, SightReadingOptions};
+    ///
+    /// let options = SightReadingOptions::new(Difficulty::Beginner);
+    /// let melody = SightReader::with_seed(1).melody(&options);
+    ///
+    /// // Beginner melodies only use whole and half notes
+    /// assert!(melody.0[0].notes.iter().all(|note| note.0 == NoteLength::new(16) || note.0 == NoteLength::new(8)));
+    /// ```
+    pub fn melody(&mut self, options: &SightReadingOptions) -> Piece {
+        let rhythms: Vec<&[u16]> = [
+            Difficulty::Beginner,
+            Difficulty::Elementary,
+            Difficulty::Intermediate,
+            Difficulty::Advanced,
+        ]
+        .into_iter()
+        .filter(|&difficulty| difficulty <= options.rhythm)
+        .flat_map(|difficulty| difficulty.rhythms().iter().copied())
+        .collect();
+        let ending_rhythms = Difficulty::Beginner.rhythms();
+
+        let top_degree = options.range.max(0).saturating_add(1);
+        let mut degree: isize = 1;
+        let mut notes = vec![];
+
+        for bar in 0..options.bars {
+            let is_last_bar = bar.saturating_add(1) == options.bars;
+            let rhythm: &[u16] = if is_last_bar {
+                self.rng.choose(ending_rhythms)
+            } else {
+                self.rng.choose(&rhythms)
+            }
+            .copied()
+            .unwrap_or(&[16]);
+
+            for (index, &length) in rhythm.iter().enumerate() {
+                let is_first_note = bar == 0 && index == 0;
+                if is_last_bar && index.saturating_add(1) == rhythm.len() {
+                    degree = 1;
+                } else if !is_first_note {
+                    let leap = self.rng.range_inclusive(
+                        i32::try_from(options.max_leap.saturating_neg()).unwrap_or(-1),
+                        i32::try_from(options.max_leap).unwrap_or(1),
+                    );
+                    degree = degree
+                        .saturating_add(isize::try_from(leap).unwrap_or_default())
+                        .clamp(1, top_degree);
+                }
+
+                let pitch = options.scale.degree(options.root, degree);
+                notes.push(piano(Note(NoteLength::new(length), pitch.into())));
+            }
+        }
+
+        Piece::from(Line::from(notes))
+    }
+}